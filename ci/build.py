@@ -25,3 +25,6 @@ if __name__ == "__main__":
     cibase.permute_features_parallel(
         build_features, with_full=False, with_empty=False
     )
+
+    step("Build - Targeted features (parallel)")
+    cibase.targeted_features_parallel(build_features)
@@ -0,0 +1,14 @@
+#!/usr/bin/env python
+import os
+from cibase import step, run
+
+
+if __name__ == "__main__":
+    step("Build - thumbv6m-none-eabi (portable-atomic)")
+    # The target lacks atomic read-modify-writes, which portable-atomic then emulates by disabling
+    # interrupts on its single core.
+    os.environ["RUSTFLAGS"] = "--cfg portable_atomic_unsafe_assume_single_core"
+    run(
+        "cargo +nightly build -Z build-std=core,alloc --target thumbv6m-none-eabi"
+        ' --features "mutex,portable-atomic"'
+    )
@@ -7,7 +7,37 @@ import typing as t
 import collections.abc as c
 import sys
 
-features = {"mutex", "rwlock", "std"}
+# Permuted against each other in full, as each changes how the rest of the crate builds.
+features = {
+    "alloc",
+    "mutex",
+    "no-poison",
+    "registry",
+    "rwlock",
+    "std",
+}
+
+# Only add code of their own, or swap out one part of a lock, so they are each built on their own
+# and along with the locks and `std`, rather than in every permutation.
+targeted_features = [
+    "embassy-sync",
+    "hle",
+    "labels",
+    "multi",
+    "parking_lot",
+    "portable-atomic",
+    "powerlocks-derive",
+    "proptest",
+    "rwlock-state-u16",
+    "rwlock-state-u32",
+    "spin",
+    "strategy-observer",
+    "test-support",
+]
+
+
+def targeted_feature_sets(feature: str) -> list[tuple[str, ...]]:
+    return [(feature,), (feature, "rwlock", "std")]
 
 
 Steps = c.Callable[[], c.Iterable]
@@ -62,6 +92,15 @@ def permute_features_parallel(
     )
 
 
+def targeted_features_parallel(target: c.Callable[[c.Iterable[str]], None]):
+    parallel_params(
+        target,
+        itertools.chain(
+            *[targeted_feature_sets(feature) for feature in targeted_features]
+        ),
+    )
+
+
 T = t.TypeVar("T")
 
 
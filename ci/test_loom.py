@@ -0,0 +1,9 @@
+#!/usr/bin/env python
+import os
+from cibase import step, run
+
+
+if __name__ == "__main__":
+    step("Test - Loom")
+    os.environ["RUSTFLAGS"] = "--cfg loom"
+    run("cargo test --release --features rwlock --test loom")
@@ -25,3 +25,6 @@ if __name__ == "__main__":
     cibase.permute_features_parallel(
         test_test_features, with_empty=True, with_full=True
     )
+
+    step("Test - Targeted features (parallel)")
+    cibase.targeted_features_parallel(test_test_features)
@@ -0,0 +1,15 @@
+#!/usr/bin/env python
+import sys
+import cibase
+from cibase import step, run
+
+
+if __name__ == "__main__":
+    feature = sys.argv[1]
+    if feature not in cibase.targeted_features:
+        cibase.echo(f'"{feature}" is not a targeted feature.')
+        raise cibase.ScriptError()
+
+    for features in cibase.targeted_feature_sets(feature):
+        step(f'Test - Features "{",".join(features)}"')
+        run(f'cargo test --features "{",".join(features)}" --no-fail-fast')
@@ -0,0 +1,101 @@
+//! Derive macros for `powerlocks`. Use them through the `powerlocks-derive` feature of
+//! `powerlocks`, rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Index, parse_macro_input, parse_quote};
+
+/// Generates a `<Name>Project` trait, with a `project_<field>` method for every field of the
+/// struct, that maps a guard of the struct to a
+/// [`MappedGuard`](../powerlocks/primitives/struct.MappedGuard.html) of the field.
+///
+/// The trait is implemented for every guard of `powerlocks` that dereferences mutably to the
+/// struct, see [`StableGuard`](../powerlocks/primitives/trait.StableGuard.html), and has the
+/// same visibility as the struct. Fields of tuple structs are projected with `project_0`,
+/// `project_1` and so on.
+#[proc_macro_derive(LockProject)]
+pub fn derive_lock_project(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    lock_project(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn lock_project(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            Span::call_site(),
+            "`LockProject` can only be derived for structs",
+        ));
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let trait_name = format_ident!("{name}Project");
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let methods = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                let method = format_ident!("project_{}", ident);
+                let ty = &field.ty;
+                let doc = format!("Maps this guard to the `{ident}` field.");
+                quote! {
+                    #[doc = #doc]
+                    fn #method(self) -> ::powerlocks::primitives::MappedGuard<Self, #ty> {
+                        ::powerlocks::primitives::MappedGuard::new(self, |this| &mut this.#ident)
+                    }
+                }
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let method = format_ident!("project_{}", index);
+                let index = Index::from(index);
+                let ty = &field.ty;
+                let doc = format!("Maps this guard to field `{}`.", index.index);
+                quote! {
+                    #[doc = #doc]
+                    fn #method(self) -> ::powerlocks::primitives::MappedGuard<Self, #ty> {
+                        ::powerlocks::primitives::MappedGuard::new(self, |this| &mut this.#index)
+                    }
+                }
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let mut guard_generics = input.generics.clone();
+    guard_generics.params.push(parse_quote!(__PowerlocksGuard));
+    guard_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(
+            __PowerlocksGuard: ::powerlocks::primitives::StableGuard<Target = #name #type_generics>
+        ));
+    let (guard_impl_generics, _, guard_where_clause) = guard_generics.split_for_impl();
+
+    let doc = format!("Projects guards of [`{name}`] to its fields.");
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #trait_name #impl_generics:
+            ::powerlocks::primitives::StableGuard<Target = #name #type_generics>
+            + ::core::marker::Sized
+        #where_clause
+        {
+            #(#methods)*
+        }
+
+        impl #guard_impl_generics #trait_name #type_generics for __PowerlocksGuard
+        #guard_where_clause
+        {
+        }
+    })
+}
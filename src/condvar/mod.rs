@@ -0,0 +1,344 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicUsize, Ordering},
+    const_fn,
+};
+
+use crate::{
+    mutex::{BaseMutexGuard, MutexHook},
+    primitives::{CoreThreadEnv, PoisonPolicy, ThreadEnv, WaitQueue},
+};
+
+#[cfg(feature = "rwlock")]
+use crate::{
+    mutex::CoreUnpoisonedMutex,
+    primitives::{Handle, HandleId, OnDrop, sync::atomic::AtomicBool},
+    rwlock::{BaseRwLockReadGuard, BaseRwLockWriteGuard, RwLockHook},
+    strategied_mutex::MutexStrategy,
+};
+
+#[cfg(feature = "rwlock")]
+extern crate alloc;
+#[cfg(feature = "rwlock")]
+use alloc::{boxed::Box, vec::Vec};
+
+/// A thread waiting on a [`BaseCondvar`] with a wake strategy.
+#[cfg(feature = "rwlock")]
+struct Waiter<H: Handle> {
+    handle: H,
+    priority: u32,
+    notified: AtomicBool,
+}
+
+/// The waiters of a [`BaseCondvar`] with a wake strategy, oldest first, and the strategy that
+/// picks which of them [`notify_one`](BaseCondvar::notify_one) wakes.
+#[cfg(feature = "rwlock")]
+struct WakeOrder<H: Handle> {
+    strategy: Box<dyn MutexStrategy>,
+    // Waiters live on the stack of the waiting thread, which doesn't return before a notifier
+    // popped it and left the lock.
+    waiters: Vec<*const Waiter<H>>,
+}
+
+#[cfg(feature = "rwlock")]
+impl<H: Handle> WakeOrder<H> {
+    /// Pops the waiter picked by the strategy and wakes it. Returns `false` if none is waiting.
+    fn wake_one(&mut self) -> bool {
+        if self.waiters.is_empty() {
+            return false;
+        }
+
+        let entries: Vec<(HandleId, u32)> = self
+            .waiters
+            .iter()
+            // SAFETY: Every queued waiter is alive until it is popped.
+            .map(|&waiter| unsafe { ((*waiter).handle.id(), (*waiter).priority) })
+            .collect();
+        let index = (self.strategy)(&mut entries.iter());
+        assert!(
+            index < self.waiters.len(),
+            "The provided `MutexStrategy` picked a thread that isn't waiting."
+        );
+        // SAFETY: As above.
+        unsafe { Self::wake(self.waiters.remove(index)) };
+        true
+    }
+
+    fn wake_all(&mut self) {
+        // SAFETY: Every queued waiter is alive until it is popped.
+        self.waiters
+            .drain(..)
+            .for_each(|waiter| unsafe { Self::wake(waiter) });
+    }
+
+    /// # Safety
+    /// `waiter` must have just been popped from the queue, with the lock still held.
+    unsafe fn wake(waiter: *const Waiter<H>) {
+        // SAFETY: The waiter stays alive until it has seen `notified` and then taken the lock,
+        // which we still hold.
+        let waiter = unsafe { &*waiter };
+        waiter.notified.store(true, Ordering::Release);
+        waiter.handle.unpark();
+    }
+}
+
+// SAFETY: The waiters are only accessed under the lock around the `WakeOrder`, while they are
+// alive, and their handles are `Sync`. The strategy is only ever called under that lock, as in the
+// strategied locks.
+#[cfg(feature = "rwlock")]
+unsafe impl<H: Handle + Sync> Send for WakeOrder<H> {}
+
+/// A condition variable, which blocks threads until some condition on data protected by a lock
+/// becomes true.
+///
+/// Unlike [`std::sync::Condvar`], it can wait on the guards of both mutexes and read-write locks,
+/// so state machines guarded by a [`BaseRwLock`](crate::rwlock::BaseRwLock) don't need a separate
+/// mutex just to block on a condition.
+///
+/// Like `std`'s condition variables, waiting threads may wake up spuriously, so the condition
+/// should always be re-checked in a loop.
+///
+/// With the `rwlock` feature, [`new_strategied`](BaseCondvar::new_strategied) builds a condition
+/// variable whose [`notify_one`](BaseCondvar::notify_one) wakes the waiter picked by a
+/// [`MutexStrategy`], e.g. the one with the highest priority, instead of the oldest one.
+///
+/// See also: [`std::sync::Condvar`].
+pub struct BaseCondvar<Env: ThreadEnv> {
+    // Bumped by every notification, so that a waiter can tell whether it was notified after it
+    // released the lock.
+    sequence: AtomicUsize,
+    waiters: WaitQueue<Env::Handle>,
+    // Only set for condition variables with a wake strategy, whose waiters queue here instead.
+    #[cfg(feature = "rwlock")]
+    wake_order: CoreUnpoisonedMutex<Option<WakeOrder<Env::Handle>>>,
+    thread_env: PhantomData<Env>,
+}
+
+impl<Env: ThreadEnv> BaseCondvar<Env> {
+    const_fn! {
+        pub const fn new() -> Self {
+            Self {
+                sequence: AtomicUsize::new(0),
+                waiters: WaitQueue::new(),
+                #[cfg(feature = "rwlock")]
+                wake_order: CoreUnpoisonedMutex::new_unhooked(None),
+                thread_env: PhantomData,
+            }
+        }
+    }
+
+    /// Creates a condition variable whose [`notify_one`](BaseCondvar::notify_one) wakes the waiter
+    /// picked by `strategy`, such as
+    /// [`strategied_mutex::strategies::priority`](crate::strategied_mutex::strategies::priority).
+    ///
+    /// The strategy is passed the waiters oldest first, with the priority they wait with, see
+    /// [`wait_with_priority`](BaseCondvar::wait_with_priority).
+    #[cfg(feature = "rwlock")]
+    pub fn new_strategied(strategy: Box<dyn MutexStrategy>) -> Self {
+        let condvar = Self::new();
+        condvar.set_wake_strategy(strategy);
+        condvar
+    }
+
+    /// Replaces the strategy that picks which waiter [`notify_one`](BaseCondvar::notify_one)
+    /// wakes, keeping the threads that are already waiting.
+    #[cfg(feature = "rwlock")]
+    pub fn set_wake_strategy(&self, strategy: Box<dyn MutexStrategy>) {
+        let mut wake_order = self.wake_order.lock();
+        match &mut *wake_order {
+            Some(wake_order) => wake_order.strategy = strategy,
+            None => {
+                *wake_order = Some(WakeOrder {
+                    strategy,
+                    waiters: Vec::new(),
+                })
+            }
+        }
+    }
+
+    fn park(&self, sequence: usize) {
+        // `wait_until` fences between enqueueing and checking, which pairs with the fence in
+        // `notify_*`, so a relaxed load can't miss a notification.
+        self.waiters
+            .wait_until(|| self.sequence.load(Ordering::Relaxed) != sequence);
+    }
+
+    /// Queues the current thread as a waiter, then calls `relock_after` with a function that
+    /// blocks until the thread is notified.
+    ///
+    /// The waiter is queued before `relock_after` releases the lock, so that a notification sent
+    /// right after the release isn't missed.
+    #[cfg_attr(
+        not(feature = "rwlock"),
+        allow(unused_variables, reason = "Only wake strategies see the priority.")
+    )]
+    fn wait_with<R>(&self, priority: u32, relock_after: impl FnOnce(&dyn Fn()) -> R) -> R {
+        #[cfg(feature = "rwlock")]
+        if self.wake_order.lock().is_some() {
+            let waiter = Waiter {
+                handle: Env::Handle::new(),
+                priority,
+                notified: AtomicBool::new(false),
+            };
+            // A wake strategy, once set, is never removed.
+            if let Some(wake_order) = &mut *self.wake_order.lock() {
+                wake_order.waiters.push(&waiter);
+            }
+            // Takes `waiter` out of the queue before it is dropped, in case `relock_after` panics
+            // before it was notified. Otherwise, this waits for the notifier to release the lock,
+            // as it may still be unparking `waiter`.
+            let _unqueue = OnDrop(|| {
+                if let Some(wake_order) = &mut *self.wake_order.lock() {
+                    wake_order
+                        .waiters
+                        .retain(|&queued| !core::ptr::eq(queued, &waiter));
+                }
+            });
+            return relock_after(&|| {
+                while !waiter.notified.load(Ordering::Acquire) {
+                    waiter.handle.park();
+                }
+            });
+        }
+
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        relock_after(&|| self.park(sequence))
+    }
+
+    /// Releases the mutex behind `guard` and blocks until this condition variable is notified,
+    /// then locks the mutex again.
+    ///
+    /// See also: [`std::sync::Condvar::wait`].
+    pub fn wait<'a, T, Hook, Policy, Marker>(
+        &self,
+        guard: BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>,
+    ) -> Policy::Result<BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>>
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Policy: PoisonPolicy,
+    {
+        self.wait_with(0, |park| guard.relock_after(park))
+    }
+
+    /// Waits like [`wait`](BaseCondvar::wait), with the given `priority`.
+    ///
+    /// The priority is passed on to the wake strategy, see
+    /// [`new_strategied`](BaseCondvar::new_strategied). Without one, it is ignored.
+    pub fn wait_with_priority<'a, T, Hook, Policy, Marker>(
+        &self,
+        guard: BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>,
+        priority: u32,
+    ) -> Policy::Result<BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>>
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Policy: PoisonPolicy,
+    {
+        self.wait_with(priority, |park| guard.relock_after(park))
+    }
+
+    /// Releases the read lock behind `guard` and blocks until this condition variable is notified,
+    /// then read-locks the lock again.
+    #[cfg(feature = "rwlock")]
+    pub fn wait_read<'a, T, Hook, Policy>(
+        &self,
+        guard: BaseRwLockReadGuard<'a, T, Hook, Env, Policy>,
+    ) -> Policy::Result<BaseRwLockReadGuard<'a, T, Hook, Env, Policy>>
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Policy: PoisonPolicy,
+    {
+        self.wait_with(0, |park| guard.relock_after(park))
+    }
+
+    /// Releases the write lock behind `guard` and blocks until this condition variable is
+    /// notified, then write-locks the lock again.
+    #[cfg(feature = "rwlock")]
+    pub fn wait_write<'a, T, Hook, Policy>(
+        &self,
+        guard: BaseRwLockWriteGuard<'a, T, Hook, Env, Policy>,
+    ) -> Policy::Result<BaseRwLockWriteGuard<'a, T, Hook, Env, Policy>>
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Policy: PoisonPolicy,
+    {
+        self.wait_with(0, |park| guard.relock_after(park))
+    }
+
+    /// Wakes up one thread blocked on this condition variable, if any.
+    ///
+    /// With a wake strategy, the thread is picked by the strategy, and otherwise it is the thread
+    /// that has waited the longest.
+    ///
+    /// See also: [`std::sync::Condvar::notify_one`].
+    pub fn notify_one(&self) {
+        #[cfg(feature = "rwlock")]
+        if let Some(wake_order) = &mut *self.wake_order.lock()
+            && wake_order.wake_one()
+        {
+            return;
+        }
+
+        self.sequence.fetch_add(1, Ordering::Relaxed);
+        self.waiters.notify_one();
+    }
+
+    /// Wakes up every thread blocked on this condition variable.
+    ///
+    /// See also: [`std::sync::Condvar::notify_all`].
+    pub fn notify_all(&self) {
+        #[cfg(feature = "rwlock")]
+        if let Some(wake_order) = &mut *self.wake_order.lock() {
+            wake_order.wake_all();
+        }
+
+        self.sequence.fetch_add(1, Ordering::Relaxed);
+        self.waiters.notify_all();
+    }
+}
+
+impl<Env: ThreadEnv> Default for BaseCondvar<Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Env: ThreadEnv> Debug for BaseCondvar<Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condvar").finish_non_exhaustive()
+    }
+}
+
+pub type CoreCondvar = BaseCondvar<CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseCondvar;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdCondvar = BaseCondvar<StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreCondvar;
+    pub type Condvar = CoreCondvar;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdCondvar;
+    pub type Condvar = StdCondvar;
+}
+
+pub use types::*;
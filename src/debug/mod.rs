@@ -0,0 +1,231 @@
+//! A global registry of locks, for inspecting the state of a program at runtime, e.g. to find out
+//! which locks are involved in a deadlock.
+//!
+//! Locks register themselves while a thread is blocked acquiring them, which is the only time
+//! their address is known not to change, and are then reported by [`dump`]. This covers every lock
+//! involved in a deadlock. Locks with a fixed address, such as those in `static`s or leaked
+//! [`Box`](alloc::boxed::Box)es, can also be [`register`]ed explicitly, to be reported all along.
+
+extern crate alloc;
+use alloc::{boxed::Box, vec::Vec};
+
+use core::{
+    any,
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    mem, ptr,
+};
+
+use crate::{mutex::Mutex, primitives::LockResultExt};
+
+/// The state of a lock at the time it was inspected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LockState {
+    /// The lock is not held.
+    Unlocked,
+    /// The lock is held by the given number of readers.
+    Read(usize),
+    /// The lock is held exclusively, by a writer or a mutex guard.
+    Written,
+}
+
+/// A snapshot of a lock, as reported by [`dump`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LockInfo {
+    /// The label the lock was [`register`]ed with, or else the label it was created with, if any.
+    pub label: Option<&'static str>,
+    /// The kind of lock, such as `"Mutex"` or `"RwLock"`.
+    pub kind: &'static str,
+    pub state: LockState,
+    pub poisoned: bool,
+    /// The number of threads that are waiting to acquire the lock.
+    pub waiters: usize,
+}
+
+impl LockInfo {
+    /// Creates an unlabeled snapshot, to be returned from [`Inspect::inspect`].
+    pub const fn new(kind: &'static str, state: LockState, poisoned: bool, waiters: usize) -> Self {
+        Self {
+            label: None,
+            kind,
+            state,
+            poisoned,
+            waiters,
+        }
+    }
+}
+
+impl Display for LockInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:?}: ",
+            self.kind,
+            self.label.unwrap_or("<unlabeled>")
+        )?;
+        match self.state {
+            LockState::Unlocked => write!(f, "unlocked")?,
+            LockState::Read(readers) => write!(f, "read by {readers}")?,
+            LockState::Written => write!(f, "written")?,
+        }
+        if self.poisoned {
+            write!(f, ", poisoned")?;
+        }
+        write!(f, ", {} waiting", self.waiters)
+    }
+}
+
+/// A lock whose state can be reported by [`dump`].
+///
+/// The returned snapshot doesn't need to be synchronized, as it is only used for debugging, and
+/// may be out of date by the time it is reported anyway. It may be taken on any thread, and must
+/// only read the state of the lock, never the data it protects.
+pub trait Inspect {
+    fn inspect(&self) -> LockInfo;
+}
+
+struct Entry {
+    lock: &'static (dyn Inspect + Sync),
+    label: Option<&'static str>,
+}
+
+/// A lock that threads are blocked on, registered by their [`Waiting`]s.
+struct WaitedOn {
+    address: *const (),
+    // Tells apart a lock from the lock it may hold at the same address.
+    type_name: &'static str,
+    // Borrows the lock for as long as any thread is blocked on it, see `Waiting::register`.
+    inspect: Box<dyn Fn() -> LockInfo>,
+    waiting: usize,
+}
+
+// SAFETY: `inspect` only reads the state of the lock, which is synchronized whatever the data is.
+unsafe impl Send for WaitedOn {}
+
+struct Registry {
+    registered: Vec<Entry>,
+    waited_on: Vec<WaitedOn>,
+}
+
+// Locked with `lock_unregistered`, so that blocking on the registry doesn't register it in itself.
+static REGISTRY: Mutex<Registry> = Mutex::new_unhooked(Registry {
+    registered: Vec::new(),
+    waited_on: Vec::new(),
+});
+
+/// Registers a lock in the registry while the current thread is blocked acquiring it, and
+/// unregisters it once dropped.
+pub(crate) struct Waiting<'a, L: ?Sized> {
+    address: *const (),
+    lock: PhantomData<&'a L>,
+}
+
+impl<'a, L: ?Sized + Inspect> Waiting<'a, L> {
+    pub(crate) fn register(lock: &'a L) -> Self {
+        let address = ptr::from_ref(lock).cast::<()>();
+        let type_name = any::type_name::<L>();
+        let mut registry = REGISTRY.lock_unregistered().ignore_poison();
+        match registry
+            .waited_on
+            .iter_mut()
+            .find(|entry| entry.address == address && entry.type_name == type_name)
+        {
+            Some(entry) => entry.waiting += 1,
+            None => {
+                let inspect: Box<dyn Fn() -> LockInfo + 'a> = Box::new(move || lock.inspect());
+                // SAFETY: The entry is removed by the last `Waiting` on the lock to be dropped.
+                // Until then, a thread is blocked acquiring the lock, so the lock stays borrowed,
+                // alive, and in place. Any thread blocked on the lock keeps it so, not only this
+                // one, since they all borrow the same lock.
+                let inspect = unsafe {
+                    mem::transmute::<Box<dyn Fn() -> LockInfo + 'a>, Box<dyn Fn() -> LockInfo>>(
+                        inspect,
+                    )
+                };
+                registry.waited_on.push(WaitedOn {
+                    address,
+                    type_name,
+                    inspect,
+                    waiting: 1,
+                });
+            }
+        }
+        Self {
+            address,
+            lock: PhantomData,
+        }
+    }
+}
+
+impl<L: ?Sized> Drop for Waiting<'_, L> {
+    fn drop(&mut self) {
+        let type_name = any::type_name::<L>();
+        let mut registry = REGISTRY.lock_unregistered().ignore_poison();
+        let index = registry
+            .waited_on
+            .iter()
+            .position(|entry| entry.address == self.address && entry.type_name == type_name)
+            .unwrap_or_else(|| unreachable!());
+        registry.waited_on[index].waiting -= 1;
+        if registry.waited_on[index].waiting == 0 {
+            registry.waited_on.remove(index);
+        }
+    }
+}
+
+/// Registers `lock`, so that it is reported by [`dump`] under the given `label`.
+///
+/// Registering the same lock more than once reports it more than once.
+pub fn register<L: Inspect + Sync>(lock: &'static L, label: Option<&'static str>) {
+    let mut registry = REGISTRY.lock_unregistered().ignore_poison();
+    registry.registered.push(Entry { lock, label });
+}
+
+/// Unregisters every registration of `lock`. Returns `false` if `lock` wasn't registered.
+pub fn unregister<L: Inspect + Sync>(lock: &'static L) -> bool {
+    let mut registry = REGISTRY.lock_unregistered().ignore_poison();
+    let registered = registry.registered.len();
+    registry
+        .registered
+        .retain(|entry| !ptr::addr_eq(entry.lock, lock));
+    registry.registered.len() != registered
+}
+
+/// Returns a snapshot of every registered lock, in the order they were registered, followed by
+/// every other lock that a thread is blocked on.
+///
+/// # Examples
+/// ```
+/// # use powerlocks::{debug, mutex::Mutex};
+/// static CONFIG: Mutex<u32> = Mutex::new_unhooked(0);
+///
+/// debug::register(&CONFIG, Some("config"));
+/// let _guard = CONFIG.lock().unwrap();
+/// for lock in debug::dump() {
+///     println!("{lock}");
+/// }
+/// # debug::unregister(&CONFIG);
+/// ```
+pub fn dump() -> Vec<LockInfo> {
+    // The registry stays locked while the locks are inspected, which keeps the locks that threads
+    // are blocked on alive.
+    let registry = REGISTRY.lock_unregistered().ignore_poison();
+    let registered = registry.registered.iter().map(|entry| {
+        let info = entry.lock.inspect();
+        LockInfo {
+            label: entry.label.or(info.label),
+            ..info
+        }
+    });
+    let waited_on = registry
+        .waited_on
+        .iter()
+        .filter(|waited_on| {
+            !registry
+                .registered
+                .iter()
+                .any(|entry| ptr::addr_eq(entry.lock, waited_on.address))
+        })
+        .map(|waited_on| (waited_on.inspect)());
+    registered.chain(waited_on).collect()
+}
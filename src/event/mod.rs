@@ -0,0 +1,113 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicBool, Ordering},
+    const_fn,
+};
+
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::primitives::park_until;
+use crate::primitives::{CoreThreadEnv, ThreadEnv, WaitQueue};
+
+/// A manual-reset event, for signaling threads that some condition has come true, such as a
+/// shutdown request or a resource becoming ready.
+///
+/// Once [`set`](BaseEvent::set), the event stays set and lets every waiting thread through, until
+/// it is [`reset`](BaseEvent::reset) again.
+pub struct BaseEvent<Env: ThreadEnv> {
+    set: AtomicBool,
+    waiters: WaitQueue<Env::Handle>,
+    thread_env: PhantomData<Env>,
+}
+
+impl<Env: ThreadEnv> BaseEvent<Env> {
+    const_fn! {
+        /// Creates a new event that is not set.
+        pub const fn new() -> Self {
+            Self {
+                set: AtomicBool::new(false),
+                waiters: WaitQueue::new(),
+                thread_env: PhantomData,
+            }
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.set.load(Ordering::Acquire)
+    }
+
+    /// Sets the event, waking up every thread that is waiting on it.
+    pub fn set(&self) {
+        self.set.store(true, Ordering::Release);
+        self.waiters.notify_all();
+    }
+
+    /// Resets the event, so that threads calling [`wait`](BaseEvent::wait) block again.
+    pub fn reset(&self) {
+        self.set.store(false, Ordering::Relaxed);
+    }
+
+    /// Blocks the current thread until the event is set.
+    pub fn wait(&self) {
+        if !self.is_set() {
+            self.waiters.wait_until(|| self.is_set());
+        }
+    }
+
+    /// Blocks the current thread until the event is set, or until `timeout` has elapsed. Returns
+    /// whether the event was set.
+    #[cfg(feature = "std")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.is_set()
+            || self
+                .waiters
+                .wait_until_or(|| self.is_set(), park_until(timeout))
+    }
+}
+
+impl<Env: ThreadEnv> Default for BaseEvent<Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Env: ThreadEnv> Debug for BaseEvent<Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Event")
+            .field("set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
+
+pub type CoreEvent = BaseEvent<CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseEvent;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdEvent = BaseEvent<StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreEvent;
+    pub type Event = CoreEvent;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdEvent;
+    pub type Event = StdEvent;
+}
+
+pub use types::*;
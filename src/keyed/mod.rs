@@ -0,0 +1,444 @@
+//! Locks created on demand for each key, such as one lock per user or per file.
+//!
+//! A [`BaseKeyedMutex`] or [`BaseKeyedRwLock`] keeps a lock for every key that is currently locked
+//! or waited on. The lock of a key is created when the key is first locked, and dropped again once
+//! the last guard of the key is gone, so keys that are no longer used take no memory.
+//!
+//! The locks guard no data themselves. They only serialize the threads that work on the same key,
+//! e.g. on a resource stored elsewhere.
+//!
+//! # Examples
+//! ```
+//! # use powerlocks::keyed::KeyedMutex;
+//! let files = KeyedMutex::new();
+//! let report = files.lock("report.txt");
+//! assert!(files.try_lock("report.txt").is_none());
+//! assert!(files.try_lock("notes.txt").is_some());
+//!
+//! drop(report);
+//! assert_eq!(files.len(), 0);
+//! ```
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    mem,
+};
+
+extern crate alloc;
+use alloc::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    mutex::BaseMutex,
+    primitives::{CoreThreadEnv, NoPoison, ThreadEnv, sync::const_fn},
+};
+
+#[cfg(feature = "rwlock")]
+use crate::rwlock::BaseRwLock;
+
+/// The locks of every key in use, shared by both kinds of keyed locks.
+struct LockMap<K, L, Env: ThreadEnv> {
+    locks: BaseMutex<BTreeMap<K, Arc<L>>, (), Env, NoPoison>,
+}
+
+impl<K, L, Env: ThreadEnv> LockMap<K, L, Env> {
+    const_fn! {
+        const fn new() -> Self {
+            Self {
+                locks: BaseMutex::new_unhooked(BTreeMap::new()),
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.locks.lock().len()
+    }
+}
+
+impl<K: Ord + Clone, L: Default, Env: ThreadEnv> LockMap<K, L, Env> {
+    /// Returns the lock of `key`, creating it if no other thread uses the key.
+    fn get(&self, key: &K) -> Arc<L> {
+        let mut locks = self.locks.lock();
+        match locks.get(key) {
+            Some(lock) => Arc::clone(lock),
+            None => Arc::clone(locks.entry(key.clone()).or_default()),
+        }
+    }
+
+    /// Gives back a lock returned by `get`, dropping it if no other thread uses the key anymore.
+    fn put(&self, key: &K, lock: Arc<L>) {
+        let mut locks = self.locks.lock();
+        // Every other thread using the key holds a clone of `lock`, which it can only get or give
+        // back while holding `locks`, so the count can't change under us.
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(key);
+        }
+    }
+}
+
+impl<K, L, Env: ThreadEnv> Debug for LockMap<K, L, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockMap")
+            .field("keys", &self.len())
+            .finish()
+    }
+}
+
+type KeyMutex<Env> = BaseMutex<(), (), Env, NoPoison>;
+
+/// A set of mutexes, one for each key, that are created and dropped as the keys are locked and
+/// unlocked.
+///
+/// See the [module documentation](self) for details.
+pub struct BaseKeyedMutex<K, Env: ThreadEnv> {
+    map: LockMap<K, KeyMutex<Env>, Env>,
+}
+
+impl<K, Env: ThreadEnv> BaseKeyedMutex<K, Env> {
+    const_fn! {
+        pub const fn new() -> Self {
+            Self { map: LockMap::new() }
+        }
+    }
+
+    /// Returns the number of keys that are currently locked or waited on.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no key is currently locked or waited on.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Ord + Clone, Env: ThreadEnv> BaseKeyedMutex<K, Env> {
+    /// Locks `key`, blocking until no other thread holds it.
+    pub fn lock(&self, key: K) -> BaseKeyedMutexGuard<'_, K, Env> {
+        let lock = self.map.get(&key);
+        mem::forget(lock.lock());
+        BaseKeyedMutexGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        }
+    }
+
+    /// Attempts to lock `key` without blocking, returning [`None`] if another thread holds it.
+    pub fn try_lock(&self, key: K) -> Option<BaseKeyedMutexGuard<'_, K, Env>> {
+        let lock = self.map.get(&key);
+        if lock.try_lock().map(mem::forget).is_err() {
+            self.map.put(&key, lock);
+            return None;
+        }
+        Some(BaseKeyedMutexGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        })
+    }
+}
+
+impl<K, Env: ThreadEnv> Default for BaseKeyedMutex<K, Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, Env: ThreadEnv> Debug for BaseKeyedMutex<K, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedMutex")
+            .field("keys", &self.len())
+            .finish()
+    }
+}
+
+/// Holds the lock of a key of a [`BaseKeyedMutex`], until dropped.
+#[must_use = "if unused the key will immediately unlock"]
+pub struct BaseKeyedMutexGuard<'a, K: Ord + Clone, Env: ThreadEnv> {
+    owner: &'a BaseKeyedMutex<K, Env>,
+    key: K,
+    // Only taken on drop.
+    lock: Option<Arc<KeyMutex<Env>>>,
+}
+
+impl<'a, K: Ord + Clone, Env: ThreadEnv> BaseKeyedMutexGuard<'a, K, Env> {
+    /// Returns the key that this guard holds.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the keyed mutex that this guard was acquired from.
+    pub fn keyed_mutex(&self) -> &'a BaseKeyedMutex<K, Env> {
+        self.owner
+    }
+}
+
+impl<K: Ord + Clone, Env: ThreadEnv> Drop for BaseKeyedMutexGuard<'_, K, Env> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            // SAFETY: We locked the mutex when creating the guard, and forgot its own guard.
+            unsafe { lock.force_unlock() };
+            self.owner.map.put(&self.key, lock);
+        }
+    }
+}
+
+impl<K: Ord + Clone + Debug, Env: ThreadEnv> Debug for BaseKeyedMutexGuard<'_, K, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedMutexGuard")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "rwlock")]
+type KeyRwLock<Env> = BaseRwLock<(), (), Env, NoPoison>;
+
+/// A set of read-write locks, one for each key, that are created and dropped as the keys are
+/// locked and unlocked.
+///
+/// See the [module documentation](self) for details.
+#[cfg(feature = "rwlock")]
+pub struct BaseKeyedRwLock<K, Env: ThreadEnv> {
+    map: LockMap<K, KeyRwLock<Env>, Env>,
+}
+
+#[cfg(feature = "rwlock")]
+impl<K, Env: ThreadEnv> BaseKeyedRwLock<K, Env> {
+    const_fn! {
+        pub const fn new() -> Self {
+            Self { map: LockMap::new() }
+        }
+    }
+
+    /// Returns the number of keys that are currently locked or waited on.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no key is currently locked or waited on.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K: Ord + Clone, Env: ThreadEnv> BaseKeyedRwLock<K, Env> {
+    /// Read-locks `key`, blocking while another thread write-locks it.
+    pub fn read(&self, key: K) -> BaseKeyedRwLockReadGuard<'_, K, Env> {
+        let lock = self.map.get(&key);
+        mem::forget(lock.read());
+        BaseKeyedRwLockReadGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        }
+    }
+
+    /// Write-locks `key`, blocking while any other thread holds it.
+    pub fn write(&self, key: K) -> BaseKeyedRwLockWriteGuard<'_, K, Env> {
+        let lock = self.map.get(&key);
+        mem::forget(lock.write());
+        BaseKeyedRwLockWriteGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        }
+    }
+
+    /// Attempts to read-lock `key` without blocking, returning [`None`] if another thread
+    /// write-locks it.
+    pub fn try_read(&self, key: K) -> Option<BaseKeyedRwLockReadGuard<'_, K, Env>> {
+        let lock = self.map.get(&key);
+        if lock.try_read().map(mem::forget).is_err() {
+            self.map.put(&key, lock);
+            return None;
+        }
+        Some(BaseKeyedRwLockReadGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        })
+    }
+
+    /// Attempts to write-lock `key` without blocking, returning [`None`] if any other thread holds
+    /// it.
+    pub fn try_write(&self, key: K) -> Option<BaseKeyedRwLockWriteGuard<'_, K, Env>> {
+        let lock = self.map.get(&key);
+        if lock.try_write().map(mem::forget).is_err() {
+            self.map.put(&key, lock);
+            return None;
+        }
+        Some(BaseKeyedRwLockWriteGuard {
+            owner: self,
+            key,
+            lock: Some(lock),
+        })
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K, Env: ThreadEnv> Default for BaseKeyedRwLock<K, Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K, Env: ThreadEnv> Debug for BaseKeyedRwLock<K, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRwLock")
+            .field("keys", &self.len())
+            .finish()
+    }
+}
+
+/// Holds a read lock of a key of a [`BaseKeyedRwLock`], until dropped.
+#[cfg(feature = "rwlock")]
+#[must_use = "if unused the key will immediately unlock"]
+pub struct BaseKeyedRwLockReadGuard<'a, K: Ord + Clone, Env: ThreadEnv> {
+    owner: &'a BaseKeyedRwLock<K, Env>,
+    key: K,
+    // Only taken on drop.
+    lock: Option<Arc<KeyRwLock<Env>>>,
+}
+
+#[cfg(feature = "rwlock")]
+impl<'a, K: Ord + Clone, Env: ThreadEnv> BaseKeyedRwLockReadGuard<'a, K, Env> {
+    /// Returns the key that this guard holds.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the keyed lock that this guard was acquired from.
+    pub fn keyed_rwlock(&self) -> &'a BaseKeyedRwLock<K, Env> {
+        self.owner
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K: Ord + Clone, Env: ThreadEnv> Drop for BaseKeyedRwLockReadGuard<'_, K, Env> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            // SAFETY: We read-locked the lock when creating the guard, and forgot its own guard.
+            unsafe { lock.force_unlock_read() };
+            self.owner.map.put(&self.key, lock);
+        }
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K: Ord + Clone + Debug, Env: ThreadEnv> Debug for BaseKeyedRwLockReadGuard<'_, K, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRwLockReadGuard")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Holds the write lock of a key of a [`BaseKeyedRwLock`], until dropped.
+#[cfg(feature = "rwlock")]
+#[must_use = "if unused the key will immediately unlock"]
+pub struct BaseKeyedRwLockWriteGuard<'a, K: Ord + Clone, Env: ThreadEnv> {
+    owner: &'a BaseKeyedRwLock<K, Env>,
+    key: K,
+    // Only taken on drop.
+    lock: Option<Arc<KeyRwLock<Env>>>,
+}
+
+#[cfg(feature = "rwlock")]
+impl<'a, K: Ord + Clone, Env: ThreadEnv> BaseKeyedRwLockWriteGuard<'a, K, Env> {
+    /// Returns the key that this guard holds.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the keyed lock that this guard was acquired from.
+    pub fn keyed_rwlock(&self) -> &'a BaseKeyedRwLock<K, Env> {
+        self.owner
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K: Ord + Clone, Env: ThreadEnv> Drop for BaseKeyedRwLockWriteGuard<'_, K, Env> {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            // SAFETY: We write-locked the lock when creating the guard, and forgot its own guard.
+            unsafe { lock.force_unlock_write() };
+            self.owner.map.put(&self.key, lock);
+        }
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<K: Ord + Clone + Debug, Env: ThreadEnv> Debug for BaseKeyedRwLockWriteGuard<'_, K, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRwLockWriteGuard")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+pub type CoreKeyedMutex<K> = BaseKeyedMutex<K, CoreThreadEnv>;
+pub type CoreKeyedMutexGuard<'a, K> = BaseKeyedMutexGuard<'a, K, CoreThreadEnv>;
+#[cfg(feature = "rwlock")]
+pub type CoreKeyedRwLock<K> = BaseKeyedRwLock<K, CoreThreadEnv>;
+#[cfg(feature = "rwlock")]
+pub type CoreKeyedRwLockReadGuard<'a, K> = BaseKeyedRwLockReadGuard<'a, K, CoreThreadEnv>;
+#[cfg(feature = "rwlock")]
+pub type CoreKeyedRwLockWriteGuard<'a, K> = BaseKeyedRwLockWriteGuard<'a, K, CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::{BaseKeyedMutex, BaseKeyedMutexGuard};
+    use crate::primitives::StdThreadEnv;
+
+    #[cfg(feature = "rwlock")]
+    use super::{BaseKeyedRwLock, BaseKeyedRwLockReadGuard, BaseKeyedRwLockWriteGuard};
+
+    pub type StdKeyedMutex<K> = BaseKeyedMutex<K, StdThreadEnv>;
+    pub type StdKeyedMutexGuard<'a, K> = BaseKeyedMutexGuard<'a, K, StdThreadEnv>;
+    #[cfg(feature = "rwlock")]
+    pub type StdKeyedRwLock<K> = BaseKeyedRwLock<K, StdThreadEnv>;
+    #[cfg(feature = "rwlock")]
+    pub type StdKeyedRwLockReadGuard<'a, K> = BaseKeyedRwLockReadGuard<'a, K, StdThreadEnv>;
+    #[cfg(feature = "rwlock")]
+    pub type StdKeyedRwLockWriteGuard<'a, K> = BaseKeyedRwLockWriteGuard<'a, K, StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::{CoreKeyedMutex, CoreKeyedMutexGuard};
+    #[cfg(feature = "rwlock")]
+    use super::{CoreKeyedRwLock, CoreKeyedRwLockReadGuard, CoreKeyedRwLockWriteGuard};
+
+    pub type KeyedMutex<K> = CoreKeyedMutex<K>;
+    pub type KeyedMutexGuard<'a, K> = CoreKeyedMutexGuard<'a, K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLock<K> = CoreKeyedRwLock<K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLockReadGuard<'a, K> = CoreKeyedRwLockReadGuard<'a, K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLockWriteGuard<'a, K> = CoreKeyedRwLockWriteGuard<'a, K>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::{StdKeyedMutex, StdKeyedMutexGuard};
+    #[cfg(feature = "rwlock")]
+    use super::{StdKeyedRwLock, StdKeyedRwLockReadGuard, StdKeyedRwLockWriteGuard};
+
+    pub type KeyedMutex<K> = StdKeyedMutex<K>;
+    pub type KeyedMutexGuard<'a, K> = StdKeyedMutexGuard<'a, K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLock<K> = StdKeyedRwLock<K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLockReadGuard<'a, K> = StdKeyedRwLockReadGuard<'a, K>;
+    #[cfg(feature = "rwlock")]
+    pub type KeyedRwLockWriteGuard<'a, K> = StdKeyedRwLockWriteGuard<'a, K>;
+}
+
+pub use types::*;
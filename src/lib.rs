@@ -1,12 +1,60 @@
 #![no_std]
 
+#[cfg(all(loom, feature = "registry"))]
+compile_error!("The `registry` feature can't be used under `cfg(loom)`.");
+
 pub mod primitives;
 
 #[cfg(feature = "mutex")]
 pub mod mutex;
 
+#[cfg(feature = "mutex")]
+pub mod condvar;
+
+#[cfg(feature = "mutex")]
+pub mod event;
+
+#[cfg(feature = "mutex")]
+pub mod wait_group;
+
+#[cfg(all(feature = "mutex", feature = "alloc"))]
+pub mod notify;
+
+#[cfg(feature = "multi")]
+pub mod multi;
+
+#[cfg(feature = "mutex")]
+pub mod local_lock;
+
+#[cfg(feature = "mutex")]
+pub mod once_lock;
+
+#[cfg(feature = "mutex")]
+pub mod seq_lock;
+
+#[cfg(feature = "registry")]
+pub mod debug;
+
 #[cfg(feature = "rwlock")]
 pub mod strategied_rwlock;
 
 #[cfg(feature = "rwlock")]
 pub mod rwlock;
+
+#[cfg(feature = "rwlock")]
+pub mod lock_table;
+
+#[cfg(all(feature = "mutex", feature = "alloc"))]
+pub mod keyed;
+
+#[cfg(all(feature = "mutex", feature = "alloc"))]
+pub mod swap_lock;
+
+#[cfg(feature = "rwlock")]
+pub mod strategied_mutex;
+
+#[cfg(feature = "test-support")]
+pub mod test_support;
+
+#[cfg(feature = "powerlocks-derive")]
+pub use powerlocks_derive::LockProject;
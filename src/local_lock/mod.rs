@@ -0,0 +1,184 @@
+use core::{
+    cell::{Ref, RefCell, RefMut},
+    fmt::{self, Debug, Formatter},
+};
+
+use crate::{
+    mutex::{MutexApi, MutexGuardApi},
+    primitives::{LockResult, TryLockError, TryLockResult},
+};
+
+#[cfg(feature = "rwlock")]
+use crate::rwlock::{RwLockApi, RwLockReadGuardApi, RwLockWriteGuardApi};
+
+/// A lock for a single thread, backed by a [`RefCell`].
+///
+/// It implements [`MutexApi`] and, with the `rwlock` feature, `RwLockApi`, so code that is generic
+/// over the lock APIs can run single-threaded without paying for atomics. Being `!Sync`, it can't
+/// be shared between threads, so there is nobody to wait for: `lock`, `read` and `write` panic if
+/// the data is already borrowed in a conflicting way, like [`RefCell::borrow_mut`] does, instead
+/// of blocking forever. It never poisons.
+///
+#[derive(Default)]
+pub struct LocalLock<T: ?Sized> {
+    data: RefCell<T>,
+}
+
+impl<T> LocalLock<T> {
+    pub const fn new(data: T) -> Self {
+        Self {
+            data: RefCell::new(data),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> LocalLock<T> {
+    /// Borrows the data exclusively.
+    ///
+    /// # Panics
+    /// Panics if the data is already borrowed.
+    pub fn lock(&self) -> RefMut<'_, T> {
+        self.data.borrow_mut()
+    }
+
+    /// Borrows the data exclusively, or returns [`TryLockError::WouldBlock`] if it is already
+    /// borrowed.
+    pub fn try_lock(&self) -> TryLockResult<RefMut<'_, T>> {
+        self.data
+            .try_borrow_mut()
+            .map_err(|_| TryLockError::WouldBlock)
+    }
+
+    /// Borrows the data shared with other reads.
+    ///
+    /// # Panics
+    /// Panics if the data is already borrowed exclusively.
+    pub fn read(&self) -> Ref<'_, T> {
+        self.data.borrow()
+    }
+
+    /// Borrows the data shared with other reads, or returns [`TryLockError::WouldBlock`] if it is
+    /// already borrowed exclusively.
+    pub fn try_read(&self) -> TryLockResult<Ref<'_, T>> {
+        self.data.try_borrow().map_err(|_| TryLockError::WouldBlock)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T> From<T> for LocalLock<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for LocalLock<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("LocalLock");
+        match self.try_read() {
+            Ok(data) => d.field("data", &&*data),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: 'a + ?Sized> MutexGuardApi<'a, T> for RefMut<'a, T> {}
+
+impl<T: ?Sized> MutexApi<T> for LocalLock<T> {
+    fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_lock()
+    }
+
+    fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        Ok(self.lock())
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        Ok(self.get_mut())
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::new(t)
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Ok(self.into_inner())
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<'a, T: 'a + ?Sized> RwLockReadGuardApi<'a, T> for Ref<'a, T> {}
+#[cfg(feature = "rwlock")]
+impl<'a, T: 'a + ?Sized> RwLockWriteGuardApi<'a, T> for RefMut<'a, T> {}
+
+#[cfg(feature = "rwlock")]
+impl<T: ?Sized> RwLockApi<T> for LocalLock<T> {
+    fn try_read<'a>(&'a self) -> TryLockResult<impl RwLockReadGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_read()
+    }
+
+    fn read<'a>(&'a self) -> LockResult<impl RwLockReadGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        Ok(self.read())
+    }
+
+    fn try_write<'a>(&'a self) -> TryLockResult<impl RwLockWriteGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_lock()
+    }
+
+    fn write<'a>(&'a self) -> LockResult<impl RwLockWriteGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        Ok(self.lock())
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        Ok(self.get_mut())
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::new(t)
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Ok(self.into_inner())
+    }
+}
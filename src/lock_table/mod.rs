@@ -0,0 +1,253 @@
+//! Lock striping: a fixed set of read-write locks, picked by hashing a key.
+//!
+//! Guarding a whole map with a single lock serializes every access to it. A [`BaseLockTable`]
+//! spreads the keys over `SHARDS` locks instead, so that threads accessing different keys rarely
+//! wait on each other, without a lock per key.
+//!
+//! # Examples
+//! ```
+//! # use std::collections::HashMap;
+//! # use powerlocks::lock_table::LockTable;
+//! let sessions: LockTable<str, HashMap<String, u32>, 16> = LockTable::new();
+//! sessions.write("alice").unwrap().insert("alice".into(), 1);
+//! assert_eq!(sessions.read("alice").unwrap().get("alice"), Some(&1));
+//! ```
+
+use core::{
+    array,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{
+    primitives::{CoreThreadEnv, LockResult, PoisonError, ThreadEnv, TryLockResult},
+    rwlock::{BaseRwLock, BaseRwLockReadGuard, BaseRwLockWriteGuard},
+};
+
+/// A fast, non-cryptographic [`Hasher`] for picking shards, after rustc's `FxHasher`.
+///
+/// Keys chosen by an adversary can all be sent to the same shard. Pass a
+/// [`RandomState`](https://doc.rust-lang.org/std/hash/struct.RandomState.html) to
+/// [`with_hasher`](BaseLockTable::with_hasher) where that matters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShardHasher(u64);
+
+impl ShardHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for ShardHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Builds [`ShardHasher`]s, the default hasher of a [`BaseLockTable`].
+pub type BuildShardHasher = BuildHasherDefault<ShardHasher>;
+
+/// A fixed array of `SHARDS` read-write locks, each guarding its own `T`, where every key maps to
+/// one of the locks by its hash.
+///
+/// Keys with the same hash always map to the same shard, but different keys may share a shard too,
+/// so a shard usually holds a collection of the entries of all of its keys. Locking two keys at
+/// once may thus deadlock, if they happen to share a shard. Use [`shard_index`] to tell.
+///
+/// `SHARDS` must not be zero, which is checked when the table is built.
+///
+/// [`shard_index`]: BaseLockTable::shard_index
+pub struct BaseLockTable<K, T, const SHARDS: usize, Env, S = BuildShardHasher>
+where
+    K: ?Sized,
+    Env: ThreadEnv,
+{
+    shards: [BaseRwLock<T, (), Env>; SHARDS],
+    hasher: S,
+    keys: PhantomData<fn(&K)>,
+}
+
+impl<K, T, const SHARDS: usize, Env, S> BaseLockTable<K, T, SHARDS, Env, S>
+where
+    K: ?Sized + Hash,
+    Env: ThreadEnv,
+    S: BuildHasher,
+{
+    /// Creates a table whose shards all start out as [`T::default`](Default::default).
+    pub fn new() -> Self
+    where
+        T: Default,
+        S: Default,
+    {
+        Self::from_fn(|_| T::default())
+    }
+
+    /// Creates a table whose shard at each index starts out as `f(index)`.
+    pub fn from_fn(f: impl FnMut(usize) -> T) -> Self
+    where
+        S: Default,
+    {
+        Self::from_fn_with_hasher(S::default(), f)
+    }
+
+    /// Creates a table that maps keys to shards with `hasher`, and whose shards all start out as
+    /// [`T::default`](Default::default).
+    pub fn with_hasher(hasher: S) -> Self
+    where
+        T: Default,
+    {
+        Self::from_fn_with_hasher(hasher, |_| T::default())
+    }
+
+    /// Creates a table that maps keys to shards with `hasher`, and whose shard at each index starts
+    /// out as `f(index)`.
+    pub fn from_fn_with_hasher(hasher: S, mut f: impl FnMut(usize) -> T) -> Self {
+        const { assert!(SHARDS > 0, "a `LockTable` needs at least one shard") };
+        Self {
+            shards: array::from_fn(|index| BaseRwLock::new(f(index))),
+            hasher,
+            keys: PhantomData,
+        }
+    }
+
+    /// Returns the index of the shard that `key` maps to.
+    pub fn shard_index(&self, key: &K) -> usize {
+        let hash = self.hasher.hash_one(key);
+        // Take the high bits of the hash, which are better mixed than the low ones.
+        ((u128::from(hash) * SHARDS as u128) >> 64) as usize
+    }
+
+    /// Returns the lock of the shard that `key` maps to.
+    pub fn shard(&self, key: &K) -> &BaseRwLock<T, (), Env> {
+        &self.shards[self.shard_index(key)]
+    }
+
+    /// Read-locks the shard that `key` maps to.
+    pub fn read(&self, key: &K) -> LockResult<BaseRwLockReadGuard<'_, T, (), Env>> {
+        self.shard(key).read()
+    }
+
+    /// Write-locks the shard that `key` maps to.
+    pub fn write(&self, key: &K) -> LockResult<BaseRwLockWriteGuard<'_, T, (), Env>> {
+        self.shard(key).write()
+    }
+
+    /// Attempts to read-lock the shard that `key` maps to, without blocking.
+    pub fn try_read(&self, key: &K) -> TryLockResult<BaseRwLockReadGuard<'_, T, (), Env>> {
+        self.shard(key).try_read()
+    }
+
+    /// Attempts to write-lock the shard that `key` maps to, without blocking.
+    pub fn try_write(&self, key: &K) -> TryLockResult<BaseRwLockWriteGuard<'_, T, (), Env>> {
+        self.shard(key).try_write()
+    }
+}
+
+impl<K, T, const SHARDS: usize, Env, S> BaseLockTable<K, T, SHARDS, Env, S>
+where
+    K: ?Sized,
+    Env: ThreadEnv,
+{
+    /// Returns the locks of every shard, e.g. to visit every entry of the table.
+    pub fn shards(&self) -> &[BaseRwLock<T, (), Env>; SHARDS] {
+        &self.shards
+    }
+
+    /// Returns the hasher that maps keys to shards.
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// Consumes the table, returning the data of every shard, even if poisoned.
+    pub fn into_inner(self) -> [T; SHARDS] {
+        self.shards
+            .map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<K, T, const SHARDS: usize, Env, S> Default for BaseLockTable<K, T, SHARDS, Env, S>
+where
+    K: ?Sized + Hash,
+    T: Default,
+    Env: ThreadEnv,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T, const SHARDS: usize, Env, S> Debug for BaseLockTable<K, T, SHARDS, Env, S>
+where
+    K: ?Sized,
+    T: Debug,
+    Env: ThreadEnv,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockTable")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+pub type CoreLockTable<K, T, const SHARDS: usize, S = BuildShardHasher> =
+    BaseLockTable<K, T, SHARDS, CoreThreadEnv, S>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::{BaseLockTable, BuildShardHasher};
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdLockTable<K, T, const SHARDS: usize, S = BuildShardHasher> =
+        BaseLockTable<K, T, SHARDS, StdThreadEnv, S>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::{BuildShardHasher, CoreLockTable};
+    pub type LockTable<K, T, const SHARDS: usize, S = BuildShardHasher> =
+        CoreLockTable<K, T, SHARDS, S>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::{BuildShardHasher, StdLockTable};
+    pub type LockTable<K, T, const SHARDS: usize, S = BuildShardHasher> =
+        StdLockTable<K, T, SHARDS, S>;
+}
+
+pub use types::*;
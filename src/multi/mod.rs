@@ -0,0 +1,265 @@
+//! Acquiring several locks at once without deadlocking.
+//!
+//! Two threads that lock the same pair of mutexes in opposite orders can deadlock, each holding
+//! one mutex and waiting for the other. [`lock_all`] avoids this by always acquiring locks in a
+//! canonical order, and by backing off and retrying instead of blocking while holding other locks.
+//!
+//! # Examples
+//! ```
+//! # use powerlocks::{multi, mutex::Mutex};
+//! let from = Mutex::new(10);
+//! let to = Mutex::new(0);
+//!
+//! let (mut from, mut to) = multi::lock_all((&from, &to)).unwrap();
+//! *from -= 5;
+//! *to += 5;
+//! ```
+
+use crate::{
+    mutex::{
+        BaseMcsMutex, BaseMcsMutexGuard, BaseMutex, BaseMutexGuard, BaseTicketMutex,
+        BaseTicketMutexGuard, MutexHook,
+    },
+    primitives::{
+        Backoff, LockResult, PoisonError, PoisonPolicy, ThreadEnv, TryLockError, TryLockResult,
+    },
+};
+
+/// A lock that can be acquired as part of [`lock_all`].
+///
+/// Implemented for references to every lock in this crate, where read-write locks are
+/// write-locked unless wrapped in [`Read`]. Locks from other crates, such as `std::sync::Mutex`,
+/// have nowhere to keep the stable id that [`lock_id`](Lockable::lock_id) needs, so they can't
+/// take part.
+pub trait Lockable<'a> {
+    type Guard;
+
+    /// The environment that [`lock_all`] waits in before it retries, once this lock turned out to
+    /// be unavailable.
+    type Env: ThreadEnv;
+
+    /// An id that orders this lock relative to others. It must stay the same for as long as the
+    /// lock is alive, and differ from the id of every other lock that is alive.
+    ///
+    /// The locks in this crate draw their id from a global counter when it is first asked for.
+    fn lock_id(&self) -> u64;
+
+    fn acquire(&self) -> LockResult<Self::Guard>;
+
+    fn try_acquire(&self) -> TryLockResult<Self::Guard>;
+}
+
+/// Read-locks the wrapped read-write lock as part of [`lock_all`], instead of write-locking it.
+#[derive(Debug, Clone, Copy)]
+pub struct Read<'a, L: ?Sized>(pub &'a L);
+
+macro_rules! impl_lockable {
+    (
+        [$($generics:tt)*] $lock:ty => $guard:ty, $env:ty,
+        $acquire:ident, $try_acquire:ident, $into_lock_result:expr
+    ) => {
+        impl<'a, $($generics)*> Lockable<'a> for $lock {
+            type Guard = $guard;
+            type Env = $env;
+
+            fn lock_id(&self) -> u64 {
+                self.lock().lock_id()
+            }
+
+            fn acquire(&self) -> LockResult<Self::Guard> {
+                $into_lock_result(self.lock().$acquire())
+            }
+
+            fn try_acquire(&self) -> TryLockResult<Self::Guard> {
+                self.lock().$try_acquire()
+            }
+        }
+    };
+}
+
+/// Gives uniform access to the lock behind the implementors of [`Lockable`].
+trait LockRef<'a> {
+    type Target: ?Sized + 'a;
+    fn lock(&self) -> &'a Self::Target;
+}
+
+impl<'a, L: ?Sized> LockRef<'a> for &'a L {
+    type Target = L;
+    fn lock(&self) -> &'a L {
+        self
+    }
+}
+
+impl<'a, L: ?Sized> LockRef<'a> for Read<'a, L> {
+    type Target = L;
+    fn lock(&self) -> &'a L {
+        self.0
+    }
+}
+
+impl_lockable!(
+    [
+        T: ?Sized + 'a,
+        Hook: MutexHook + 'a,
+        Env: ThreadEnv + 'a,
+        Policy: PoisonPolicy + 'a,
+        Marker: 'a
+    ]
+    &'a BaseMutex<T, Hook, Env, Policy, Marker> => BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>, Env,
+    lock, try_lock, Policy::into_lock_result
+);
+impl_lockable!(
+    [T: ?Sized + 'a, Env: ThreadEnv + 'a, Policy: PoisonPolicy + 'a]
+    &'a BaseTicketMutex<T, Env, Policy> => BaseTicketMutexGuard<'a, T, Env, Policy>, Env,
+    lock, try_lock, Policy::into_lock_result
+);
+impl_lockable!(
+    [T: ?Sized + 'a, Env: ThreadEnv + 'a, Policy: PoisonPolicy + 'a]
+    &'a BaseMcsMutex<T, Env, Policy> => BaseMcsMutexGuard<'a, T, Env, Policy>, Env,
+    lock, try_lock, Policy::into_lock_result
+);
+
+#[cfg(feature = "rwlock")]
+mod rwlock_impls {
+    use super::{LockRef, Lockable, Read};
+    use crate::{
+        primitives::{Handle, LockResult, PoisonPolicy, ThreadEnv, TryLockResult},
+        rwlock::{BaseRwLock, BaseRwLockReadGuard, BaseRwLockWriteGuard, RwLockHook},
+        strategied_rwlock,
+    };
+
+    impl_lockable!(
+        [T: ?Sized + 'a, Hook: RwLockHook + 'a, Env: ThreadEnv + 'a, Policy: PoisonPolicy + 'a]
+        &'a BaseRwLock<T, Hook, Env, Policy> => BaseRwLockWriteGuard<'a, T, Hook, Env, Policy>, Env,
+        write, try_write, Policy::into_lock_result
+    );
+    impl_lockable!(
+        [T: ?Sized + 'a, Hook: RwLockHook + 'a, Env: ThreadEnv + 'a, Policy: PoisonPolicy + 'a]
+        Read<'a, BaseRwLock<T, Hook, Env, Policy>>
+            => BaseRwLockReadGuard<'a, T, Hook, Env, Policy>, Env,
+        read, try_read, Policy::into_lock_result
+    );
+    impl_lockable!(
+        [T: ?Sized + 'a, H: Handle + 'a]
+        &'a strategied_rwlock::BaseRwLock<T, H>
+            => strategied_rwlock::BaseRwLockWriteGuard<'a, T, H>, H,
+        write, try_write, core::convert::identity::<LockResult<_>>
+    );
+    impl_lockable!(
+        [T: ?Sized + 'a, H: Handle + 'a]
+        Read<'a, strategied_rwlock::BaseRwLock<T, H>>
+            => strategied_rwlock::BaseRwLockReadGuard<'a, T, H>, H,
+        read, try_read, core::convert::identity::<LockResult<_>>
+    );
+}
+
+/// A tuple of [`Lockable`]s that can be acquired together by [`lock_all`].
+pub trait LockAll<'a> {
+    type Guards;
+
+    fn lock_all(self) -> LockResult<Self::Guards>;
+}
+
+/// Acquires a single lock for `lock_all`, setting `poisoned` if it was poisoned. Returns `None` if
+/// the lock was not acquired.
+fn acquire_one<'a, L: Lockable<'a>>(
+    lock: &L,
+    block: bool,
+    poisoned: &mut bool,
+) -> Option<L::Guard> {
+    let result = if block {
+        lock.acquire().map_err(TryLockError::Poisoned)
+    } else {
+        lock.try_acquire()
+    };
+
+    match result {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(poison)) => {
+            *poisoned = true;
+            Some(poison.into_inner())
+        }
+        Err(TryLockError::WouldBlock | TryLockError::Cancelled | TryLockError::TimedOut) => None,
+    }
+}
+
+macro_rules! impl_lock_all {
+    ($count:literal; $($name:ident $index:tt),+) => {
+        impl<'a, $($name: Lockable<'a>),+> LockAll<'a> for ($($name,)+) {
+            type Guards = ($($name::Guard,)+);
+
+            fn lock_all(self) -> LockResult<Self::Guards> {
+                let ids = [$(self.$index.lock_id()),+];
+                let mut order: [usize; $count] = core::array::from_fn(|index| index);
+                order.sort_unstable_by_key(|&index| ids[index]);
+                assert!(
+                    order.windows(2).all(|pair| ids[pair[0]] != ids[pair[1]]),
+                    "`lock_all` called with the same lock more than once"
+                );
+
+                let mut backoff = Backoff::new();
+                // The position in `order` of the lock to block on, which starts out as the first
+                // one, and then is whichever lock was unavailable in the last attempt.
+                let mut blocking = 0;
+                loop {
+                    let mut guards = ($(None::<$name::Guard>,)+);
+                    let mut poisoned = false;
+
+                    // Only block while holding no other lock, and merely try the rest in canonical
+                    // order. Blocking on a lock while holding others could deadlock against
+                    // threads that don't go through `lock_all`.
+                    let failed = core::iter::once(blocking)
+                        .chain((0..$count).filter(|&position| position != blocking))
+                        .find(|&position| match order[position] {
+                            $($index => {
+                                guards.$index =
+                                    acquire_one(&self.$index, position == blocking, &mut poisoned);
+                                guards.$index.is_none()
+                            })+
+                            _ => unreachable!(),
+                        });
+
+                    let Some(failed) = failed else {
+                        let guards = ($(guards.$index.unwrap_or_else(|| unreachable!()),)+);
+                        return match poisoned {
+                            true => Err(PoisonError::new(guards)),
+                            false => Ok(guards),
+                        };
+                    };
+
+                    // Back off, releasing everything acquired so far, and try again, this time
+                    // blocking on the lock that was unavailable.
+                    drop(guards);
+                    match order[failed] {
+                        $($index => backoff.wait::<$name::Env>(),)+
+                        _ => unreachable!(),
+                    }
+                    blocking = failed;
+                }
+            }
+        }
+    };
+}
+
+impl_lock_all!(1; A 0);
+impl_lock_all!(2; A 0, B 1);
+impl_lock_all!(3; A 0, B 1, C 2);
+impl_lock_all!(4; A 0, B 1, C 2, D 3);
+impl_lock_all!(5; A 0, B 1, C 2, D 3, E 4);
+impl_lock_all!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Acquires every lock in the tuple `locks`, returning a tuple of their guards in the same order.
+///
+/// The locks are acquired in a canonical order given by [`Lockable::lock_id`], and only the first
+/// one is waited on. If any other lock is unavailable, everything acquired so far is released, and
+/// after backing off, the next attempt waits on that lock before trying the others. This way,
+/// `lock_all` never blocks while holding a lock, so it never deadlocks against other threads, no
+/// matter in which order they acquire the same locks.
+///
+/// If any of the locks is poisoned, the guards are returned inside a [`PoisonError`].
+///
+/// # Panics
+/// Panics if the same lock appears in `locks` more than once.
+pub fn lock_all<'a, L: LockAll<'a>>(locks: L) -> LockResult<L::Guards> {
+    locks.lock_all()
+}
@@ -1,13 +1,47 @@
 use core::ops::{Deref, DerefMut};
 
-use crate::primitives::{LockResult, ShouldBlock, TryLockError, TryLockResult};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+use crate::primitives::{
+    HookContext, LockResult, LockResultExt, ShouldBlock, TryLockError, TryLockResult,
+};
+
+/// Callbacks invoked by a [`BaseMutex`](super::BaseMutex) around each lock operation.
+///
+/// A successful acquisition calls, in order: [`try_lock`](MutexHook::try_lock) until it returns
+/// [`ShouldBlock::Ok`], [`before_lock`](MutexHook::before_lock), [`on_contended`] if the mutex
+/// was held by another thread, then [`before_unlock`](MutexHook::before_unlock) and
+/// [`after_lock`](MutexHook::after_lock) once the guard is dropped. A failed `try_lock` on the
+/// mutex, or a cancelled [`lock_until`](super::BaseMutex::lock_until), ends with
+/// [`on_contended`] instead.
+///
+/// [`on_contended`]: MutexHook::on_contended
 pub trait MutexHook {
-    fn try_lock(&self) -> ShouldBlock {
+    /// Decides whether the current thread may attempt to acquire the mutex.
+    fn try_lock(&self, _context: &HookContext) -> ShouldBlock {
         ShouldBlock::Ok
     }
 
-    fn after_lock(&self) {}
+    /// Called once the attempt is admitted, right before the mutex is acquired.
+    fn before_lock(&self, _context: &HookContext) {}
+
+    /// Called at most once per attempt, when the mutex turns out to be held by another thread.
+    fn on_contended(&self, _context: &HookContext) {}
+
+    /// Called while the mutex is still held, right before it is released.
+    fn before_unlock(&self, _context: &HookContext) {}
+
+    /// Called after the mutex has been released.
+    fn after_lock(&self, _context: &HookContext) {}
+
+    /// Called when a guard that is dropped while its thread panics poisons the mutex, right after
+    /// the mutex has been released and before [`after_lock`](MutexHook::after_lock). Panics while
+    /// the mutex is poisoned already don't call it again.
+    ///
+    /// This is never called under a [`PoisonPolicy`](crate::primitives::PoisonPolicy) that doesn't
+    /// poison, such as [`NoPoison`](crate::primitives::NoPoison).
+    fn on_poison(&self, _context: &HookContext) {}
 
     fn new() -> Self
     where
@@ -37,11 +71,23 @@ pub trait MutexApi<T: ?Sized> {
             match self.try_lock() {
                 Ok(guard) => break Ok(guard),
                 Err(TryLockError::Poisoned(poison)) => break Err(poison),
-                Err(TryLockError::WouldBlock) => continue,
+                Err(
+                    TryLockError::WouldBlock | TryLockError::Cancelled | TryLockError::TimedOut,
+                ) => continue,
             };
         }
     }
 
+    /// Locks the mutex, runs `f` on the protected data, and unlocks the mutex again, returning the
+    /// result of `f`.
+    ///
+    /// As the guard never escapes `f`, the mutex is held for exactly the duration of the call. If
+    /// the mutex is poisoned, `f` still runs, and its result is returned inside the
+    /// [`PoisonError`](crate::primitives::PoisonError).
+    fn with_lock<U>(&self, f: impl FnOnce(&mut T) -> U) -> LockResult<U> {
+        self.lock().map_guard(|mut guard| f(&mut guard))
+    }
+
     fn get_mut(&mut self) -> LockResult<&mut T>;
 
     fn new(t: T) -> Self
@@ -61,6 +107,99 @@ pub trait MutexApi<T: ?Sized> {
     fn clear_poison(&self) {}
 }
 
+/// Forwards the locking methods of [`MutexApi`] to the lock `L` behind a pointer.
+#[cfg(feature = "alloc")]
+macro_rules! forward_mutex_api {
+    () => {
+        fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::try_lock(self)
+        }
+
+        fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::lock(self)
+        }
+
+        fn is_poisoned(&self) -> bool {
+            L::is_poisoned(self)
+        }
+
+        fn clear_poison(&self) {
+            L::clear_poison(self)
+        }
+    };
+}
+
+// References don't implement `MutexApi`, as they can't own the lock for the owning methods such
+// as `new` and `into_inner`. Generic code can take `&impl MutexApi<T>` instead.
+#[cfg(feature = "alloc")]
+impl<T, L> MutexApi<T> for alloc::boxed::Box<L>
+where
+    T: ?Sized,
+    L: MutexApi<T>,
+{
+    forward_mutex_api!();
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        L::get_mut(self)
+    }
+
+    fn new(t: T) -> Self
+    where
+        T: Sized,
+    {
+        alloc::boxed::Box::new(L::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        L::into_inner(*self)
+    }
+}
+
+/// Locks through shared ownership.
+///
+/// # Panics
+/// [`get_mut`](MutexApi::get_mut) and [`into_inner`](MutexApi::into_inner) panic if the lock is
+/// shared with other `Arc`s.
+#[cfg(feature = "alloc")]
+impl<T, L> MutexApi<T> for alloc::sync::Arc<L>
+where
+    T: ?Sized,
+    L: MutexApi<T>,
+{
+    forward_mutex_api!();
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        let lock = alloc::sync::Arc::get_mut(self)
+            .expect("`MutexApi::get_mut` was called on a shared `Arc`");
+        L::get_mut(lock)
+    }
+
+    fn new(t: T) -> Self
+    where
+        T: Sized,
+    {
+        alloc::sync::Arc::new(L::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        let lock = alloc::sync::Arc::into_inner(self)
+            .expect("`MutexApi::into_inner` was called on a shared `Arc`");
+        L::into_inner(lock)
+    }
+}
+
 #[cfg(feature = "std")]
 pub mod std_mutex_api {
     #[cfg(feature = "std")]
@@ -115,3 +254,93 @@ pub mod std_mutex_api {
         }
     }
 }
+
+#[cfg(feature = "parking_lot")]
+pub mod parking_lot_mutex_api {
+    use super::{MutexApi, MutexGuardApi};
+    use crate::primitives::{LockResult, TryLockError, TryLockResult};
+
+    impl<'a, T: 'a + ?Sized> MutexGuardApi<'a, T> for parking_lot::MutexGuard<'a, T> {}
+
+    /// `parking_lot`'s mutexes never poison, so `lock` always succeeds.
+    impl<T: ?Sized> MutexApi<T> for parking_lot::Mutex<T> {
+        fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            self.try_lock().ok_or(TryLockError::WouldBlock)
+        }
+
+        fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            Ok(self.lock())
+        }
+
+        fn get_mut(&mut self) -> LockResult<&mut T> {
+            Ok(self.get_mut())
+        }
+
+        fn new(t: T) -> Self
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Self::new(t)
+        }
+
+        fn into_inner(self) -> LockResult<T>
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Ok(self.into_inner())
+        }
+    }
+}
+
+#[cfg(feature = "spin")]
+pub mod spin_mutex_api {
+    use super::{MutexApi, MutexGuardApi};
+    use crate::primitives::{LockResult, TryLockError, TryLockResult};
+
+    impl<'a, T: 'a + ?Sized> MutexGuardApi<'a, T> for spin::mutex::MutexGuard<'a, T> {}
+
+    /// `spin`'s mutexes never poison, so `lock` always succeeds.
+    impl<T: ?Sized, R: spin::RelaxStrategy> MutexApi<T> for spin::mutex::Mutex<T, R> {
+        fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            self.try_lock().ok_or(TryLockError::WouldBlock)
+        }
+
+        fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            Ok(self.lock())
+        }
+
+        fn get_mut(&mut self) -> LockResult<&mut T> {
+            Ok(self.get_mut())
+        }
+
+        fn new(t: T) -> Self
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Self::new(t)
+        }
+
+        fn into_inner(self) -> LockResult<T>
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Ok(self.into_inner())
+        }
+    }
+}
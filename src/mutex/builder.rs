@@ -0,0 +1,135 @@
+use core::marker::PhantomData;
+
+use crate::primitives::{
+    GuardSend, Label, Poison, PoisonPolicy, ThreadEnv, sync::atomic::AtomicU8,
+};
+
+use super::{BaseMutex, FAIR, MutexHook, UNLOCKED};
+
+/// Configures a [`BaseMutex`] one option at a time, as created by [`BaseMutex::builder`].
+///
+/// Options that change the type of the mutex, such as the hook, the thread environment, and the
+/// poison policy, return a builder for the new type, so that e.g. a hooked mutex doesn't have to be
+/// named with all of its generic parameters up front.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::{mutex::Mutex, primitives::NoPoison};
+/// let mutex = Mutex::builder()
+///     .fair()
+///     .poison_policy::<NoPoison>()
+///     .build(Vec::<u32>::new());
+/// assert!(mutex.is_fair());
+/// mutex.lock().push(1);
+/// # }
+/// ```
+///
+#[must_use = "a builder does nothing until it is built"]
+pub struct MutexBuilder<T, Hook, Env, Policy = Poison, Marker = GuardSend>
+where
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    hook: Option<Hook>,
+    label: Label,
+    fair: bool,
+    marker: PhantomData<fn(T) -> (Env, Policy)>,
+    guard_marker: PhantomData<fn() -> Marker>,
+}
+
+impl<T, Hook, Env, Policy, Marker> MutexBuilder<T, Hook, Env, Policy, Marker>
+where
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    pub(super) fn new() -> Self {
+        Self {
+            hook: None,
+            label: Label::NONE,
+            fair: false,
+            marker: PhantomData,
+            guard_marker: PhantomData,
+        }
+    }
+
+    /// Uses `hook` instead of [`MutexHook::new`], like [`BaseMutex::with_hook`].
+    pub fn hook<NewHook: MutexHook>(
+        self,
+        hook: NewHook,
+    ) -> MutexBuilder<T, NewHook, Env, Policy, Marker> {
+        MutexBuilder {
+            hook: Some(hook),
+            label: self.label,
+            fair: self.fair,
+            marker: PhantomData,
+            guard_marker: PhantomData,
+        }
+    }
+
+    /// Labels the mutex, like [`BaseMutex::new_named`].
+    #[cfg(feature = "labels")]
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Label::new(label);
+        self
+    }
+
+    /// Makes the mutex hand itself over to the thread that has waited the longest on every
+    /// release, instead of letting any thread take it.
+    ///
+    /// Parked threads are then served in arrival order, and can't be starved by threads that keep
+    /// locking the mutex again. This costs throughput under contention, as the mutex stays locked
+    /// until the woken thread gets to run, and every release takes the lock of the wait queue.
+    pub fn fair(mut self) -> Self {
+        self.fair = true;
+        self
+    }
+
+    /// Blocks threads according to `NewEnv` instead, which decides how waiting threads back off,
+    /// e.g. by only yielding, by sleeping, or by parking.
+    pub fn thread_env<NewEnv: ThreadEnv>(self) -> MutexBuilder<T, Hook, NewEnv, Policy, Marker> {
+        MutexBuilder {
+            hook: self.hook,
+            label: self.label,
+            fair: self.fair,
+            marker: PhantomData,
+            guard_marker: PhantomData,
+        }
+    }
+
+    /// Handles panics of lock holders according to `NewPolicy` instead.
+    pub fn poison_policy<NewPolicy: PoisonPolicy>(
+        self,
+    ) -> MutexBuilder<T, Hook, Env, NewPolicy, Marker> {
+        MutexBuilder {
+            hook: self.hook,
+            label: self.label,
+            fair: self.fair,
+            marker: PhantomData,
+            guard_marker: PhantomData,
+        }
+    }
+
+    /// Marks the guards of the mutex with `NewMarker` instead, see
+    /// [`GuardNoSend`](crate::primitives::GuardNoSend).
+    pub fn guard_marker<NewMarker>(self) -> MutexBuilder<T, Hook, Env, Policy, NewMarker> {
+        MutexBuilder {
+            hook: self.hook,
+            label: self.label,
+            fair: self.fair,
+            marker: PhantomData,
+            guard_marker: PhantomData,
+        }
+    }
+
+    /// Creates the mutex, protecting `data`.
+    pub fn build(self, data: T) -> BaseMutex<T, Hook, Env, Policy, Marker> {
+        BaseMutex {
+            lock: AtomicU8::new(if self.fair { FAIR } else { UNLOCKED }),
+            label: self.label,
+            ..BaseMutex::with_hook(data, self.hook.unwrap_or_else(Hook::new))
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use embassy_sync::blocking_mutex::raw::RawMutex;
+
+use super::BaseMutex;
+use crate::primitives::{PoisonError, PoisonPolicy, ThreadEnv};
+
+/// A data-less mutex can back embassy's blocking `Mutex`, and through it embassy's channels,
+/// signals and async mutexes, e.g. `embassy_sync::channel::Channel<CoreUnpoisonedMutex<()>, T, N>`.
+///
+/// Unlike embassy's `CriticalSectionRawMutex`, interrupts stay enabled while the lock is held, and
+/// other threads wait according to `Env`. The lock isn't reentrant, so `lock` must not be nested
+/// on the same mutex.
+// SAFETY: `f` only runs while the mutex is held, so no other thread can be in `lock` at once.
+unsafe impl<Env, Policy, Marker> RawMutex for BaseMutex<(), (), Env, Policy, Marker>
+where
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    #[allow(
+        clippy::declare_interior_mutable_const,
+        reason = "Embassy moves `INIT` into each new mutex."
+    )]
+    const INIT: Self = Self::new_unhooked(());
+
+    fn lock<R>(&self, f: impl FnOnce() -> R) -> R {
+        // With no data to protect, a panic in an earlier `f` leaves nothing inconsistent behind.
+        let _guard =
+            Policy::into_lock_result(BaseMutex::lock(self)).unwrap_or_else(PoisonError::into_inner);
+        f()
+    }
+}
@@ -0,0 +1,451 @@
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    panic::{RefUnwindSafe, UnwindSafe},
+    ptr,
+};
+
+#[cfg(feature = "registry")]
+use crate::primitives::sync::atomic::AtomicUsize;
+use crate::primitives::sync::{
+    atomic::{AtomicBool, AtomicPtr, Ordering},
+    const_fn,
+};
+
+use super::{MutexApi, MutexGuardApi};
+#[cfg(feature = "multi")]
+use crate::primitives::LockId;
+use crate::primitives::{
+    LockResult, Poison, PoisonPolicy, ThreadEnv, TryLockError, TryLockResult, Waiting,
+    forward_guard_mut_traits, forward_guard_traits, panicking, stable_guard,
+};
+
+#[derive(Debug)]
+#[must_use = "if unused the `BaseMcsMutex` will immediately unlock"]
+pub struct BaseMcsMutexGuard<'a, T, Env, Policy = Poison>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    lock: &'a BaseMcsMutex<T, Env, Policy>,
+    // See `BaseMutexGuard` for why this is a raw pointer.
+    data: *mut T,
+}
+
+impl<'a, T, Env, Policy> BaseMcsMutexGuard<'a, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    unsafe fn new(lock: &'a BaseMcsMutex<T, Env, Policy>) -> Self {
+        Self {
+            lock,
+            data: lock.data.get(),
+        }
+    }
+
+    /// Returns the mutex that this guard was acquired from.
+    pub fn mutex(&self) -> &'a BaseMcsMutex<T, Env, Policy> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the mutex locked forever and returning a mutable reference to the
+    /// protected data that lives as long as the mutex itself.
+    ///
+    /// See also: [`std::sync::MutexGuard::leak`].
+    pub fn leak(self) -> &'a mut T {
+        let data = self.data;
+        core::mem::forget(self);
+        // SAFETY: Forgetting the guard means that the lock is never released, so we keep our
+        // exclusive access to `data` for the rest of `'a`.
+        unsafe { &mut *data }
+    }
+}
+
+impl<T, Env, Policy> Drop for BaseMcsMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn drop(&mut self) {
+        // SAFETY: We're dropping, so we won't use `data` again.
+        unsafe { self.lock.unlock(panicking::<Env>()) };
+    }
+}
+
+impl<T, Env, Policy> Deref for BaseMcsMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `data` is aligned and is guaranteed to point to valid memory via
+        // `UnsafeCell::get`. Caller of `new` must guarantee that we have no writing access.
+        unsafe { &*self.data }
+    }
+}
+
+impl<T, Env, Policy> DerefMut for BaseMcsMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: `data` is aligned and is guaranteed to point to valid memory via
+        // `UnsafeCell::get`. Caller of `new` must guarantee that we have exclusive access.
+        unsafe { &mut *self.data }
+    }
+}
+
+forward_guard_traits! {
+    impl<T, Env, Policy> BaseMcsMutexGuard<'_, T, Env, Policy> => T
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_mut_traits! {
+    impl<T, Env, Policy> BaseMcsMutexGuard<'_, T, Env, Policy> => T
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+stable_guard! {
+    impl<T, Env, Policy> BaseMcsMutexGuard<'_, T, Env, Policy>
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+// SAFETY: Like `BaseMutexGuard`, the MCS mutex does not care which thread releases it.
+unsafe impl<T, Env, Policy> Send for BaseMcsMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+unsafe impl<T, Env, Policy> Sync for BaseMcsMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized + Sync,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+struct McsNode {
+    next: AtomicPtr<McsNode>,
+    ready: AtomicBool,
+}
+
+/// A queue-based mutex, where each waiting thread spins on its own node rather than on the lock
+/// itself.
+///
+/// Waiters join a queue with a single atomic swap, and only the thread at the front of the queue
+/// competes for the lock. This avoids the cache line contention of a global compare-and-swap loop
+/// on machines with many cores. Nodes live on the stack of the waiting thread, so no allocation is
+/// needed.
+#[derive(Debug)]
+pub struct BaseMcsMutex<T, Env, Policy = Poison>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    locked: AtomicBool,
+    tail: AtomicPtr<McsNode>,
+    // The number of threads in the queue, which can't be counted by walking it, as its nodes may go
+    // away at any time.
+    #[cfg(feature = "registry")]
+    queued: AtomicUsize,
+    poison: Policy,
+    #[cfg(feature = "multi")]
+    id: LockId,
+    thread_env: PhantomData<Env>,
+    data: UnsafeCell<T>,
+}
+
+impl<T, Env, Policy> BaseMcsMutex<T, Env, Policy>
+where
+    T: Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    const_fn! {
+        pub const fn new(data: T) -> Self {
+            Self {
+                locked: AtomicBool::new(false),
+                tail: AtomicPtr::new(ptr::null_mut()),
+                #[cfg(feature = "registry")]
+                queued: AtomicUsize::new(0),
+                poison: Policy::UNPOISONED,
+                #[cfg(feature = "multi")]
+                id: LockId::new(),
+                thread_env: PhantomData,
+                data: UnsafeCell::new(data),
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> Policy::Result<T> {
+        let poison = self.poison;
+        poison.wrap(self.data.into_inner())
+    }
+}
+
+impl<T, Env, Policy> BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Returns the id that [`multi::lock_all`](crate::multi::lock_all) orders this lock by.
+    #[cfg(feature = "multi")]
+    pub(crate) fn lock_id(&self) -> u64 {
+        self.id.get()
+    }
+
+    /// Returns a raw pointer to the underlying data.
+    ///
+    /// See also: [`std::sync::Mutex::data_ptr`]
+    pub fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+
+    pub fn get_mut(&mut self) -> Policy::Result<&mut T> {
+        self.poison.wrap(self.data.get_mut())
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.poison.clear_poison();
+    }
+
+    unsafe fn unlock(&self, poison: bool) {
+        self.poison.poison(poison);
+        self.locked.store(false, Ordering::Release);
+    }
+
+    fn try_acquire(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    pub fn lock(&self) -> Policy::Result<BaseMcsMutexGuard<'_, T, Env, Policy>> {
+        if !(self.tail.load(Ordering::Relaxed).is_null() && self.try_acquire()) {
+            self.lock_queued();
+        }
+        // SAFETY: We acquired `locked`, so we have exclusive access until we release it.
+        self.poison.wrap(unsafe { BaseMcsMutexGuard::new(self) })
+    }
+
+    #[cold]
+    fn lock_queued(&self) {
+        let _waiting = Waiting::register(self);
+        #[cfg(feature = "registry")]
+        self.queued.fetch_add(1, Ordering::Relaxed);
+
+        let node = McsNode {
+            next: AtomicPtr::new(ptr::null_mut()),
+            ready: AtomicBool::new(false),
+        };
+        let node_ptr = ptr::from_ref(&node).cast_mut();
+
+        let previous = self.tail.swap(node_ptr, Ordering::AcqRel);
+        if !previous.is_null() {
+            // SAFETY: A node stays alive until it has handed the front of the queue over to its
+            // successor, which cannot happen before we link ourselves in.
+            unsafe { (*previous).next.store(node_ptr, Ordering::Release) };
+            while !node.ready.load(Ordering::Acquire) {
+                Env::yield_now();
+            }
+        }
+
+        // We are at the front of the queue, so only newcomers on the fast path compete with us.
+        while !self.try_acquire() {
+            Env::yield_now();
+        }
+        #[cfg(feature = "registry")]
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+
+        // Leave the queue, handing the front over to our successor if there is one.
+        if self
+            .tail
+            .compare_exchange(
+                node_ptr,
+                ptr::null_mut(),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            let next = loop {
+                let next = node.next.load(Ordering::Acquire);
+                if !next.is_null() {
+                    break next;
+                }
+                core::hint::spin_loop();
+            };
+            // SAFETY: Our successor is waiting on its `ready` flag, so it is still alive.
+            unsafe { (*next).ready.store(true, Ordering::Release) };
+        }
+    }
+
+    pub fn try_lock(&self) -> TryLockResult<BaseMcsMutexGuard<'_, T, Env, Policy>> {
+        // Never overtake queued threads.
+        if self.tail.load(Ordering::Relaxed).is_null() && self.try_acquire() {
+            // SAFETY: We acquired `locked`, so we have exclusive access until we release it.
+            self.poison
+                .try_wrap(unsafe { BaseMcsMutexGuard::new(self) })
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+}
+
+impl<T, Env, Policy> Default for BaseMcsMutex<T, Env, Policy>
+where
+    T: Default,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, Env, Policy> crate::debug::Inspect for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let state = match self.locked.load(Ordering::Relaxed) {
+            true => LockState::Written,
+            false => LockState::Unlocked,
+        };
+        LockInfo::new(
+            "McsMutex",
+            state,
+            self.is_poisoned(),
+            self.queued.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl<T, Env, Policy> From<T> for BaseMcsMutex<T, Env, Policy>
+where
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+// `T` needs to be `Send` for the mutex to be `Sync`, for the same reasons as `BaseMutex`. The
+// queue nodes are only ever touched by the threads that own them and their neighbours.
+unsafe impl<T, Env, Policy> Send for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+unsafe impl<T, Env, Policy> Sync for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<T, Env, Policy> UnwindSafe for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+impl<T, Env, Policy> RefUnwindSafe for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<'a, T, Env, Policy> MutexGuardApi<'a, T> for BaseMcsMutexGuard<'a, T, Env, Policy>
+where
+    T: 'a + ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<T, Env, Policy> MutexApi<T> for BaseMcsMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_lock()
+    }
+
+    fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        Policy::into_lock_result(self.lock())
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.is_poisoned()
+    }
+
+    fn clear_poison(&self) {
+        self.clear_poison();
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        Policy::into_lock_result(self.get_mut())
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::new(t)
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Policy::into_lock_result(self.into_inner())
+    }
+}
@@ -1,26 +1,70 @@
 mod api;
 pub use api::*;
 
+mod ticket;
+pub use ticket::*;
+
+mod mcs;
+pub use mcs::*;
+
+mod reentrant;
+pub use reentrant::*;
+
+mod builder;
+pub use builder::*;
+
+#[cfg(all(feature = "embassy-sync", not(loom)))]
+mod embassy;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{alloc::Layout, boxed::Box, sync::Arc};
+#[cfg(feature = "alloc")]
+use core::ptr;
+
+#[cfg(feature = "multi")]
+use crate::primitives::LockId;
 use crate::primitives::{
-    CoreThreadEnv, LockResult, PoisonError, ShouldBlock, ThreadEnv, TryLockError, TryLockResult,
+    Backoff, CoreThreadEnv, GuardSend, HookContext, Label, LockResult, LockResultExt, MappedGuard,
+    Method, NoPoison, OnDrop, Poison, PoisonPolicy, ShouldBlock, ThreadEnv, TryLockError,
+    TryLockResult, WaitQueue, Waiting, forward_guard_io, forward_guard_mut_traits,
+    forward_guard_traits, panicking, stable_guard,
 };
 use core::{
     cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
     marker::PhantomData,
+    mem,
     ops::{Deref, DerefMut},
     panic::{RefUnwindSafe, UnwindSafe},
-    sync::atomic::{AtomicBool, Ordering},
+    pin::Pin,
+};
+
+use crate::primitives::sync::{
+    CancelFlag,
+    atomic::{AtomicU8, Ordering},
+    const_fn,
 };
 
+const UNLOCKED: u8 = 0;
+const LOCKED: u8 = 1;
+// Released by a fair mutex to the waiter it woke up, which is the only thread that may take it.
+const HANDED_OFF: u8 = 2;
+// Set alongside the states above for the whole life of a fair mutex, rather than in a field of its
+// own, which would grow every mutex by a byte.
+const FAIR: u8 = 1 << 7;
+
 #[derive(Debug)]
 #[must_use = "if unused the `BaseMutex` will immediately unlock"]
-pub struct BaseMutexGuard<'a, T, Hook, Env>
+pub struct BaseMutexGuard<'a, T, Hook, Env, Policy = Poison, Marker = GuardSend>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    lock: &'a BaseMutex<T, Hook, Env>,
+    lock: &'a BaseMutex<T, Hook, Env, Policy, Marker>,
     // It may seem as if we could get away with `&mut`, but no! While we are `drop`ping this guard,
     // `data` may still be live and some other thread could immediately lock the mutex while we are
     // dropping this guard (since we are releasing the lock during `drop`) and then create another
@@ -29,41 +73,114 @@ where
     data: *mut T,
 }
 
-impl<'a, T, Hook, Env> BaseMutexGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy, Marker> BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    unsafe fn new(lock: &'a BaseMutex<T, Hook, Env>) -> Self {
+    unsafe fn new(lock: &'a BaseMutex<T, Hook, Env, Policy, Marker>) -> Self {
         Self {
             lock,
             data: lock.data.get(),
         }
     }
+
+    /// Releases the mutex, runs `wait`, and then locks the mutex again.
+    pub(crate) fn relock_after(self, wait: impl FnOnce()) -> Policy::Result<Self> {
+        let lock = self.lock;
+        drop(self);
+        wait();
+        lock.lock()
+    }
+
+    /// Temporarily unlocks the mutex to run `f`, and then locks it again, so that other threads
+    /// get a turn while `f` runs. Returns what `f` returns, wrapped according to whether the mutex
+    /// was poisoned once it was locked again.
+    ///
+    /// The mutex is locked again even if `f` panics.
+    pub fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> Policy::Result<U> {
+        let lock = self.lock;
+        self.release();
+        // Keep our exclusive access from being lost for good when `f` panics, since `self` would
+        // still be dropped afterwards.
+        let relock = OnDrop(|| core::mem::forget(lock.lock()));
+        let value = f();
+        drop(relock);
+        lock.poison.wrap_labeled(value, lock.label.get())
+    }
+
+    /// Temporarily unlocks the mutex and yields, giving waiting threads a chance to lock it, and
+    /// then locks it again.
+    pub fn bump(&mut self) -> Policy::Result<()> {
+        self.unlocked(Env::yield_now)
+    }
+
+    fn release(&self) {
+        let context = self.lock.hook_context(!self.lock.waiters.is_empty());
+        self.lock.hook.before_unlock(&context);
+
+        // SAFETY: Callers won't use `data` again until the mutex is locked again.
+        let poisoned = unsafe { self.lock.unlock(panicking::<Env>()) };
+
+        let context = self.lock.hook_context(!self.lock.waiters.is_empty());
+        if poisoned {
+            self.lock.hook.on_poison(&context);
+        }
+        self.lock.hook.after_lock(&context);
+    }
+
+    /// Returns the mutex that this guard was acquired from.
+    pub fn mutex(&self) -> &'a BaseMutex<T, Hook, Env, Policy, Marker> {
+        self.lock
+    }
+
+    /// Returns a pinned mutable reference to the protected data, for data that isn't [`Unpin`].
+    ///
+    /// # Safety
+    /// The caller must uphold the pinning guarantees: from now on, the data must not be moved out
+    /// of the mutex until it is dropped. See [`BaseMutex::get_pin_mut`].
+    pub unsafe fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: Caller guarantees that the data is never moved again.
+        unsafe { Pin::new_unchecked(&mut **self) }
+    }
+
+    /// Leaks this guard, keeping the mutex locked forever and returning a mutable reference to the
+    /// protected data that lives as long as the mutex itself.
+    ///
+    /// This is useful for one-time initialization of global data, where the data is never
+    /// unlocked again. Any further attempt to lock the mutex will block (or fail with
+    /// [`TryLockError::WouldBlock`]).
+    ///
+    /// See also: [`std::sync::MutexGuard::leak`].
+    pub fn leak(self) -> &'a mut T {
+        let data = self.data;
+        core::mem::forget(self);
+        // SAFETY: Forgetting the guard means that the lock is never released, so we keep our
+        // exclusive access to `data` for the rest of `'a`.
+        unsafe { &mut *data }
+    }
 }
 
-impl<T, Hook, Env> Drop for BaseMutexGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> Drop for BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn drop(&mut self) {
-        // SAFETY: We're dropping, so we won't use `data` again.
-        unsafe {
-            self.lock.unlock(Env::panicking());
-        };
-
-        self.lock.hook.after_lock();
+        self.release();
     }
 }
 
-impl<T, Hook, Env> Deref for BaseMutexGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> Deref for BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -73,11 +190,12 @@ where
     }
 }
 
-impl<T, Hook, Env> DerefMut for BaseMutexGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> DerefMut for BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         // SAFETY: `data` is aligned and is guaranteed to point to valid memory via
@@ -86,227 +204,706 @@ where
     }
 }
 
+forward_guard_traits! {
+    impl<T, Hook, Env, Policy, Marker> BaseMutexGuard<'_, T, Hook, Env, Policy, Marker> => T
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_mut_traits! {
+    impl<T, Hook, Env, Policy, Marker> BaseMutexGuard<'_, T, Hook, Env, Policy, Marker> => T
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+stable_guard! {
+    impl<T, Hook, Env, Policy, Marker> BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_io! {
+    impl<T, Hook, Env, Policy, Marker> BaseMutexGuard<'_, T, Hook, Env, Policy, Marker> => T
+    where
+        T: ?Sized,
+        Hook: MutexHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
 // SAFETY: Unlike `MutexGuard`, we are `Send`. The primary reason why `MutexGuard` is not `Send` is
 // because it uses the C `pthread_mutex_unlock` call that requires locks to be released on the same
 // thread that called `pthread_mutex_lock`. Unlike `MutexGuard` though, it is safe to release our
 // `BaseMutexGuard` on another thread, as we don't depend on the `pthread` library.
-// Furthermore, we only care about if we are locked, not which thread has locked us.
-unsafe impl<T, Hook, Env> Send for BaseMutexGuard<'_, T, Hook, Env>
+// Furthermore, we only care about if we are locked, not which thread has locked us. Users that
+// want `MutexGuard`'s behaviour anyway can opt out with the `GuardNoSend` marker.
+unsafe impl<T, Hook, Env, Policy, Marker> Send for BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized + Send,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
+    Marker: Send,
 {
 }
-unsafe impl<T, Hook, Env> Sync for BaseMutexGuard<'_, T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy, Marker> Sync for BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>
 where
     T: ?Sized + Sync,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-#[derive(Debug)]
-pub struct BaseMutex<T, Hook, Env>
+pub struct BaseMutex<T, Hook, Env, Policy = Poison, Marker = GuardSend>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    lock: AtomicBool,
-    poison: AtomicBool,
+    lock: AtomicU8,
+    waiters: WaitQueue<Env::Handle>,
+    poison: Policy,
     hook: Hook,
+    label: Label,
+    #[cfg(feature = "multi")]
+    id: LockId,
     thread_env: PhantomData<Env>,
+    guard_marker: PhantomData<fn() -> Marker>,
     data: UnsafeCell<T>,
 }
 
-fn wrap_lock_result<T>(poisoned: bool, t: T) -> LockResult<T> {
-    if poisoned {
-        Err(PoisonError::new(t))
-    } else {
-        Ok(t)
+impl<T, Env, Policy, Marker> BaseMutex<T, (), Env, Policy, Marker>
+where
+    T: Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    const_fn! {
+        pub const fn new_unhooked(data: T) -> Self {
+            Self::with_hook(data, ())
+        }
     }
 }
 
-impl<T, Env> BaseMutex<T, (), Env>
+impl<T, Hook, Env, Policy, Marker> BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: Sized,
+    Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    pub const fn new_unhooked(data: T) -> Self {
-        Self {
-            lock: AtomicBool::new(false),
-            poison: AtomicBool::new(false),
-            hook: (),
-            thread_env: PhantomData,
-            data: UnsafeCell::new(data),
+    const_fn! {
+        /// Renamed to [`with_hook`](BaseMutex::with_hook).
+        #[deprecated = "use `with_hook` instead"]
+        pub const fn new_with_hook(data: T, hook: Hook) -> Self {
+            Self::with_hook(data, hook)
+        }
+    }
+
+    const_fn! {
+        /// Creates a new mutex with a `hook` configured by the caller, e.g. with thresholds or a
+        /// logger handle, that [`MutexHook::new`] couldn't provide.
+        ///
+        /// Unlike [`new`](BaseMutex::new), this is a `const fn`, so hooked mutexes can be placed in
+        /// `static`s as long as the hook itself can be constructed in a `const` context.
+        pub const fn with_hook(data: T, hook: Hook) -> Self {
+            Self {
+                lock: AtomicU8::new(UNLOCKED),
+                waiters: WaitQueue::new(),
+                poison: Policy::UNPOISONED,
+                hook,
+                label: Label::NONE,
+                #[cfg(feature = "multi")]
+                id: LockId::new(),
+                thread_env: PhantomData,
+                guard_marker: PhantomData,
+                data: UnsafeCell::new(data),
+            }
         }
     }
 }
 
-impl<T, Hook, Env> BaseMutex<T, Hook, Env>
+#[cfg(feature = "alloc")]
+impl<T, Hook, Env, Policy, Marker> BaseMutex<[T], Hook, Env, Policy, Marker>
+where
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Moves a boxed slice into a new boxed mutex, for slices whose length is only known at
+    /// runtime.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::mutex::CoreMutex;
+    /// let data = vec![1, 2, 3].into_boxed_slice();
+    /// let mutex: Box<CoreMutex<[i32]>> = CoreMutex::from_boxed(data);
+    /// mutex.lock().unwrap()[0] = 4;
+    /// assert_eq!(*mutex.lock().unwrap(), [4, 2, 3]);
+    /// ```
+    ///
+    pub fn from_boxed(data: Box<[T]>) -> Box<Self> {
+        // An empty mutex has the same layout as the start of a mutex of any length, since the one
+        // coerces to the other.
+        type Empty<T, Hook, Env, Policy, Marker> = BaseMutex<[T; 0], Hook, Env, Policy, Marker>;
+
+        let len = data.len();
+        let offset = mem::offset_of!(Empty<T, Hook, Env, Policy, Marker>, data);
+        let layout = offset
+            .checked_add(mem::size_of_val(&*data))
+            .and_then(|size| {
+                Layout::from_size_align(
+                    size,
+                    mem::align_of::<Empty<T, Hook, Env, Policy, Marker>>(),
+                )
+                .ok()
+            })
+            .expect("mutex is too large")
+            .pad_to_align();
+
+        // SAFETY: `layout` is never zero-sized, as the mutex has a lock flag.
+        let raw = unsafe { alloc::alloc::alloc(layout) };
+        if raw.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
+        }
+        // SAFETY: `raw` is valid for `layout`, which starts with an empty mutex, and has room for
+        // `len` elements of `T` at `offset`. The elements are moved out of `data`, whose
+        // allocation is then freed without dropping them again.
+        unsafe {
+            ptr::write(raw.cast(), Empty::<T, Hook, Env, Policy, Marker>::new([]));
+            let data = Box::into_raw(data);
+            ptr::copy_nonoverlapping(data.cast::<T>(), raw.add(offset).cast::<T>(), len);
+            drop(Box::from_raw(data as *mut [mem::ManuallyDrop<T>]));
+            Box::from_raw(ptr::slice_from_raw_parts_mut(raw.cast::<T>(), len) as *mut Self)
+        }
+    }
+}
+
+impl<T, Hook, Env, Policy, Marker> BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     pub fn new(data: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::with_hook(data, Hook::new())
+    }
+
+    /// Creates a new mutex with a `label`, which shows up in its [`Debug`] output, in the
+    /// [`PoisonError`](crate::primitives::PoisonError)s it reports, in the [`HookContext`]s passed
+    /// to its hook, and in [`debug::dump`](crate::debug::dump).
+    #[cfg(feature = "labels")]
+    pub fn new_named(label: &'static str, data: T) -> Self
     where
         Self: Sized,
         T: Sized,
     {
         Self {
-            lock: AtomicBool::new(false),
-            poison: AtomicBool::new(false),
-            hook: Hook::new(),
-            thread_env: PhantomData,
-            data: UnsafeCell::new(data),
+            label: Label::new(label),
+            ..Self::new(data)
         }
     }
 
-    pub fn into_inner(self) -> LockResult<T>
+    /// Returns the label of this mutex, if it was created with
+    /// [`new_named`](BaseMutex::new_named).
+    #[cfg(feature = "labels")]
+    pub fn label(&self) -> Option<&'static str> {
+        self.label.get()
+    }
+
+    /// Returns `true` if this mutex hands itself over to the thread that has waited the longest,
+    /// see [`MutexBuilder::fair`].
+    pub fn is_fair(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) & FAIR != 0
+    }
+
+    /// Returns a [`MutexBuilder`] for a mutex of this type, to configure several options at once.
+    pub fn builder() -> MutexBuilder<T, Hook, Env, Policy, Marker>
+    where
+        T: Sized,
+    {
+        MutexBuilder::new()
+    }
+
+    /// Creates a new mutex inside an [`Arc`], ready to be shared between threads.
+    #[cfg(feature = "alloc")]
+    pub fn new_arc(data: T) -> Arc<Self>
     where
         Self: Sized,
         T: Sized,
     {
-        wrap_lock_result(self.is_poisoned(), self.data.into_inner())
+        Arc::new(Self::new(data))
     }
 
-    pub fn get_mut(&mut self) -> LockResult<&mut T> {
-        wrap_lock_result(self.is_poisoned(), self.data.get_mut())
+    pub fn into_inner(self) -> Policy::Result<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        let (poison, label) = (self.poison, self.label.get());
+        poison.wrap_labeled(self.data.into_inner(), label)
+    }
+
+    /// Returns the id that [`multi::lock_all`](crate::multi::lock_all) orders this lock by.
+    #[cfg(feature = "multi")]
+    pub(crate) fn lock_id(&self) -> u64 {
+        self.id.get()
+    }
+
+    /// Returns a raw pointer to the underlying data.
+    ///
+    /// The pointer is always valid, but dereferencing it is only sound while the lock is held
+    /// appropriately, or when no other references to the data exist.
+    ///
+    /// See also: [`std::sync::Mutex::data_ptr`]
+    pub fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+
+    pub fn get_mut(&mut self) -> Policy::Result<&mut T> {
+        self.poison
+            .wrap_labeled(self.data.get_mut(), self.label.get())
+    }
+
+    /// Locks the mutex just long enough to clone the protected data.
+    pub fn get_cloned(&self) -> Policy::Result<T>
+    where
+        T: Clone,
+    {
+        Policy::map_result(self.lock(), |guard| T::clone(&guard))
+    }
+
+    /// Locks the mutex just long enough to replace the protected data with `value`, dropping the
+    /// old data.
+    ///
+    /// Like [`replace`](BaseMutex::replace) and [`take`](BaseMutex::take), this only holds the lock
+    /// for the swap itself: the old data is dropped or returned once the mutex is unlocked, so that
+    /// a slow `Drop` doesn't keep other threads waiting.
+    pub fn set(&self, value: T) -> Policy::Result<()>
+    where
+        T: Sized,
+    {
+        Policy::map_result(self.replace(value), drop)
+    }
+
+    /// Locks the mutex just long enough to replace the protected data with `value`, returning the
+    /// old data. See [`set`](BaseMutex::set).
+    pub fn replace(&self, value: T) -> Policy::Result<T>
+    where
+        T: Sized,
+    {
+        Policy::map_result(self.lock(), |mut guard| mem::replace(&mut *guard, value))
+    }
+
+    /// Locks the mutex just long enough to take the protected data, leaving [`Default::default`]
+    /// in its place. See [`set`](BaseMutex::set).
+    pub fn take(&self) -> Policy::Result<T>
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /// Returns a pinned mutable reference to the underlying data, for data that isn't [`Unpin`].
+    ///
+    /// This can't be offered safely: a pinned mutex still derefs to `&Self`, and
+    /// [`lock`](Self::lock) turns that into an unpinned `&mut T` in any thread, so the mutex can't
+    /// keep its data pinned on its own.
+    ///
+    /// # Safety
+    /// Pinning a mutex can't stop its data from being moved through a guard, so the caller must
+    /// uphold the pinning guarantees instead: from now on, the data must not be moved out of this
+    /// mutex (e.g. with [`get_mut`](Self::get_mut), [`into_inner`](Self::into_inner) or
+    /// [`mem::swap`](core::mem::swap) through a guard) until it is dropped.
+    pub unsafe fn get_pin_mut(self: Pin<&mut Self>) -> Policy::Result<Pin<&mut T>> {
+        // SAFETY: We never move the mutex out of `self`.
+        let this = unsafe { self.get_unchecked_mut() };
+        // SAFETY: Caller guarantees that the data is never moved again.
+        let data = unsafe { Pin::new_unchecked(this.data.get_mut()) };
+        this.poison.wrap_labeled(data, this.label.get())
     }
 
     pub fn is_poisoned(&self) -> bool {
-        self.poison.load(Ordering::Acquire)
+        self.poison.is_poisoned()
     }
 
     pub fn clear_poison(&self) {
-        self.poison.store(false, Ordering::Release);
+        self.poison.clear_poison();
     }
 
-    unsafe fn unlock(&self, poison: bool) {
-        self.lock.store(false, Ordering::Release);
-        self.poison.fetch_or(poison, Ordering::Release);
+    /// Returns `true` if the mutex is currently locked, without acquiring it.
+    ///
+    /// This is only a snapshot that may already be out of date by the time it is returned, so it
+    /// is meant for monitoring and assertions rather than synchronization.
+    pub fn is_locked(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) & !FAIR != UNLOCKED
     }
 
-    unsafe fn do_lock(&self) -> LockResult<BaseMutexGuard<T, Hook, Env>> {
-        // SAFETY: Caller promises that we have the exclusive lock.
-        let guard = unsafe { BaseMutexGuard::new(self) };
-        if self.is_poisoned() {
-            Err(PoisonError::new(guard))
+    /// Forcibly unlocks this mutex without going through a guard.
+    ///
+    /// This is intended for FFI and panic-recovery scenarios where a guard has been leaked (e.g.
+    /// via [`BaseMutexGuard::leak`] or [`core::mem::forget`]) or otherwise lost. The mutex is not
+    /// poisoned by this call.
+    ///
+    /// # Safety
+    /// The mutex must currently be locked, and the guard (or leaked reference) that was obtained by
+    /// locking it must no longer be used after this call. Unlocking a mutex that is still
+    /// accessed through a live guard leads to data races and undefined behavior.
+    pub unsafe fn force_unlock(&self) {
+        debug_assert!(
+            self.lock.load(Ordering::Acquire) & !FAIR == LOCKED,
+            "`force_unlock` called on an unlocked mutex"
+        );
+        self.hook
+            .before_unlock(&self.hook_context(!self.waiters.is_empty()));
+        // SAFETY: Caller promises that the mutex is locked and that the previous owner is gone.
+        unsafe { self.unlock(false) };
+        self.hook
+            .after_lock(&self.hook_context(!self.waiters.is_empty()));
+    }
+
+    fn hook_context(&self, contended: bool) -> HookContext {
+        HookContext::new::<Env>(Method::Write, contended, self.label.get())
+    }
+
+    fn acquire_context(&self) -> HookContext {
+        self.hook_context(self.is_locked())
+    }
+
+    /// Unlocks the mutex, poisoning it if `poison` is `true`. Returns whether this newly poisoned
+    /// the mutex.
+    unsafe fn unlock(&self, poison: bool) -> bool {
+        if self.is_fair() {
+            let poisoned = self.poison.poison(poison);
+            self.waiters.release_and_notify_one(|parked| {
+                let state = if parked { HANDED_OFF } else { UNLOCKED };
+                self.lock.store(state | FAIR, Ordering::Release);
+            });
+            poisoned
         } else {
-            Ok(guard)
+            self.lock.store(UNLOCKED, Ordering::Release);
+            let poisoned = self.poison.poison(poison);
+            self.waiters.notify_one();
+            poisoned
         }
     }
 
+    unsafe fn do_lock(&self) -> BaseMutexGuard<'_, T, Hook, Env, Policy, Marker> {
+        // SAFETY: Caller promises that we have the exclusive lock.
+        unsafe { BaseMutexGuard::new(self) }
+    }
+
     fn try_acquire_locker(&self, strong: bool) -> bool {
+        // The flag never changes, so any load reads it right.
+        let fair = self.lock.load(Ordering::Relaxed) & FAIR;
+        let (unlocked, locked) = (UNLOCKED | fair, LOCKED | fair);
         let compare_result = if strong {
             self.lock
-                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .compare_exchange(unlocked, locked, Ordering::AcqRel, Ordering::Acquire)
         } else {
             self.lock
-                .compare_exchange_weak(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .compare_exchange_weak(unlocked, locked, Ordering::AcqRel, Ordering::Acquire)
         };
 
         compare_result.is_ok()
     }
 
-    pub fn lock(&self) -> LockResult<BaseMutexGuard<T, Hook, Env>> {
-        while let ShouldBlock::Block = self.hook.try_lock() {}
+    /// Takes the mutex if a fair release handed it over, which only the woken waiter may do.
+    fn take_handoff(&self) -> bool {
+        self.lock
+            .compare_exchange(
+                HANDED_OFF | FAIR,
+                LOCKED | FAIR,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok()
+    }
+
+    pub fn lock(&self) -> Policy::Result<BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>> {
+        self.lock_registered(true)
+    }
+
+    /// Locks the mutex like [`lock`](BaseMutex::lock), but without registering it in the debug
+    /// registry while blocked. The registry and the locks it inspects lock through this, so that
+    /// `debug::dump` never waits on itself.
+    #[cfg(any(feature = "registry", feature = "rwlock"))]
+    pub(crate) fn lock_unregistered(
+        &self,
+    ) -> Policy::Result<BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>> {
+        self.lock_registered(false)
+    }
+
+    fn lock_registered(
+        &self,
+        register: bool,
+    ) -> Policy::Result<BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>> {
+        let mut backoff = Backoff::new();
+        while let ShouldBlock::Block = self.hook.try_lock(&self.acquire_context()) {
+            backoff.wait::<Env>();
+        }
+        self.hook.before_lock(&self.acquire_context());
 
         const STRONG_ATTEMPT_DIVIDER: usize = 32;
+        const SPIN_ATTEMPTS: usize = 64;
         let mut attempts = 0_usize;
 
         // Try a strong acquire once in a while to prevent being stuck on spurious failures.
         // Otherwise, stay weak in order to conserve efficiency. Guarantee though that the first
         // acquire is strong.
-        while !self.try_acquire_locker(attempts % STRONG_ATTEMPT_DIVIDER == 0) {
+        while !self.try_acquire_locker(attempts.is_multiple_of(STRONG_ATTEMPT_DIVIDER)) {
+            if attempts == 0 {
+                self.hook.on_contended(&self.hook_context(true));
+            }
+            if attempts == SPIN_ATTEMPTS {
+                // The mutex is heavily contended. Park until the owner wakes us up on release.
+                let _waiting = register.then(|| Waiting::register(self));
+                let mut woken = false;
+                self.waiters.wait_until(|| {
+                    let acquired = self.try_acquire_locker(true) || (woken && self.take_handoff());
+                    woken = true;
+                    acquired
+                });
+                break;
+            }
             Env::yield_now();
-            attempts = attempts.wrapping_add(1);
+            attempts += 1;
         }
         // SAFETY: Repeating `try_acquire_locker` until success guarantees us exclusive access.
-        unsafe { self.do_lock() }
+        self.poison
+            .wrap_labeled(unsafe { self.do_lock() }, self.label.get())
+    }
+
+    /// Acquires the mutex like [`lock`](BaseMutex::lock), but gives up and returns
+    /// [`TryLockError::Cancelled`] once `cancel` is set.
+    ///
+    /// While waiting, the thread keeps polling `cancel` instead of parking, so another thread can
+    /// abort the acquisition at any time by storing `true`, e.g. on shutdown.
+    pub fn lock_until(
+        &self,
+        cancel: &CancelFlag,
+    ) -> TryLockResult<BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>> {
+        let cancelled = || cancel.load(Ordering::Acquire);
+
+        let mut backoff = Backoff::new();
+        while let ShouldBlock::Block = self.hook.try_lock(&self.acquire_context()) {
+            if cancelled() {
+                return Err(TryLockError::Cancelled);
+            }
+            backoff.wait::<Env>();
+        }
+        self.hook.before_lock(&self.acquire_context());
+
+        if !self.try_acquire_locker(true) {
+            self.hook.on_contended(&self.hook_context(true));
+            let mut backoff = Backoff::new();
+            while !self.try_acquire_locker(true) {
+                if cancelled() {
+                    return Err(TryLockError::Cancelled);
+                }
+                backoff.wait::<Env>();
+            }
+        }
+        // SAFETY: `try_acquire_locker`'s success guarantees us exclusive access.
+        self.poison
+            .try_wrap_labeled(unsafe { self.do_lock() }, self.label.get())
     }
 
-    pub fn try_lock(&self) -> TryLockResult<BaseMutexGuard<T, Hook, Env>> {
-        self.hook.try_lock().to_result()?;
+    pub fn try_lock(&self) -> TryLockResult<BaseMutexGuard<'_, T, Hook, Env, Policy, Marker>> {
+        self.hook.try_lock(&self.acquire_context()).to_result()?;
+        self.hook.before_lock(&self.acquire_context());
 
         if self.try_acquire_locker(true) {
             // SAFETY: `try_acquire_locker`'s success guarantees us exclusive access.
-            unsafe { self.do_lock() }.map_err(TryLockError::Poisoned)
+            self.poison
+                .try_wrap_labeled(unsafe { self.do_lock() }, self.label.get())
         } else {
+            self.hook.on_contended(&self.hook_context(true));
             Err(TryLockError::WouldBlock)
         }
     }
 }
 
-impl<T, Hook, Env> Default for BaseMutex<T, Hook, Env>
+/// Formats the data like [`std::sync::Mutex`] does: the lock is only tried, and `<locked>` is
+/// printed in place of the data if it is held elsewhere.
+impl<T, Hook, Env, Policy, Marker> Debug for BaseMutex<T, Hook, Env, Policy, Marker>
+where
+    T: ?Sized + Debug,
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        if let Some(label) = self.label.get() {
+            d.field("label", &label);
+        }
+        // Probe the lock directly rather than through `try_lock`, so that formatting the mutex
+        // doesn't run its hooks.
+        if self.try_acquire_locker(true) {
+            // SAFETY: We just took the lock, and release it again once done with the data, even
+            // if formatting it panics. Formatting doesn't poison the mutex.
+            let _unlock = OnDrop(|| {
+                unsafe { self.unlock(false) };
+            });
+            d.field("data", &unsafe { &*self.data.get() });
+        } else {
+            d.field("data", &format_args!("<locked>"));
+        }
+        d.field("poisoned", &self.is_poisoned());
+        d.finish_non_exhaustive()
+    }
+}
+
+impl<T, Hook, Env, Policy, Marker> Default for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: Default,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn default() -> Self {
         Self::new(T::default())
     }
 }
 
-impl<T, Hook, Env> From<T> for BaseMutex<T, Hook, Env>
+#[cfg(feature = "registry")]
+impl<T, Hook, Env, Policy, Marker> crate::debug::Inspect for BaseMutex<T, Hook, Env, Policy, Marker>
+where
+    T: ?Sized,
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let state = match self.is_locked() {
+            true => LockState::Written,
+            false => LockState::Unlocked,
+        };
+        LockInfo {
+            label: self.label.get(),
+            ..LockInfo::new("Mutex", state, self.is_poisoned(), self.waiters.len())
+        }
+    }
+}
+
+impl<T, Hook, Env, Policy, Marker> From<T> for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn from(value: T) -> Self {
         Self::new(value)
     }
 }
 
+impl<T, Hook, Env, Policy, Marker> BaseMutex<Option<T>, Hook, Env, Policy, Marker>
+where
+    Hook: MutexHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Locks the mutex, initializing the data with `f` if it is [`None`], and returns a guard to
+    /// the initialized value.
+    ///
+    /// `f` runs under the lock, so it runs at most once even if several threads race to
+    /// initialize. For data that is never reset, [`OnceLock`](crate::once_lock::BaseOnceLock)
+    /// avoids locking once initialized.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use powerlocks::mutex::Mutex;
+    /// let cache: Mutex<Option<String>> = Mutex::new(None);
+    /// assert_eq!(*cache.get_or_init_with(|| "first".into()).unwrap(), "first");
+    /// assert_eq!(*cache.get_or_init_with(|| "second".into()).unwrap(), "first");
+    /// # }
+    /// ```
+    ///
+    #[allow(
+        clippy::type_complexity,
+        reason = "Only the guard's own type parameters."
+    )]
+    pub fn get_or_init_with(
+        &self,
+        f: impl FnOnce() -> T,
+    ) -> LockResult<MappedGuard<BaseMutexGuard<'_, Option<T>, Hook, Env, Policy, Marker>, T>> {
+        Policy::into_lock_result(self.lock())
+            .map_guard(|guard| MappedGuard::new(guard, |data| data.get_or_insert_with(f)))
+    }
+}
+
 // `T` needs to be `Send` for `BaseMutex` to be `Send`. Otherwise, that means transferring `T`
 // itself across thread boundaries. Like `T` for example being a `MutexGuard`.
-unsafe impl<T, Hook, Env> Send for BaseMutex<T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy, Marker> Send for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized + Send,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-unsafe impl<T, Hook, Env> Sync for BaseMutex<T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy, Marker> Sync for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized + Send,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<T, Hook, Env> UnwindSafe for BaseMutex<T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> UnwindSafe for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-impl<T, Hook, Env> RefUnwindSafe for BaseMutex<T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> RefUnwindSafe for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<'a, T, Hook, Env> MutexGuardApi<'a, T> for BaseMutexGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy, Marker> MutexGuardApi<'a, T>
+    for BaseMutexGuard<'a, T, Hook, Env, Policy, Marker>
 where
     T: 'a + ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<T, Hook, Env> MutexApi<T> for BaseMutex<T, Hook, Env>
+impl<T, Hook, Env, Policy, Marker> MutexApi<T> for BaseMutex<T, Hook, Env, Policy, Marker>
 where
     T: ?Sized,
     Hook: MutexHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
     where
@@ -319,7 +916,7 @@ where
     where
         T: 'a,
     {
-        self.lock()
+        Policy::into_lock_result(self.lock())
     }
 
     fn is_poisoned(&self) -> bool {
@@ -331,7 +928,7 @@ where
     }
 
     fn get_mut(&mut self) -> LockResult<&mut T> {
-        self.get_mut()
+        Policy::into_lock_result(self.get_mut())
     }
 
     fn new(t: T) -> Self
@@ -347,20 +944,37 @@ where
         Self: Sized,
         T: Sized,
     {
-        self.into_inner()
+        Policy::into_lock_result(self.into_inner())
     }
 }
 
 pub type CoreMutex<T> = BaseMutex<T, (), CoreThreadEnv>;
 pub type CoreMutexGuard<'a, T> = BaseMutexGuard<'a, T, (), CoreThreadEnv>;
+pub type CoreUnpoisonedMutex<T> = BaseMutex<T, (), CoreThreadEnv, NoPoison>;
+pub type CoreUnpoisonedMutexGuard<'a, T> = BaseMutexGuard<'a, T, (), CoreThreadEnv, NoPoison>;
+pub type CoreTicketMutex<T> = BaseTicketMutex<T, CoreThreadEnv>;
+pub type CoreTicketMutexGuard<'a, T> = BaseTicketMutexGuard<'a, T, CoreThreadEnv>;
+pub type CoreMcsMutex<T> = BaseMcsMutex<T, CoreThreadEnv>;
+pub type CoreMcsMutexGuard<'a, T> = BaseMcsMutexGuard<'a, T, CoreThreadEnv>;
 
 #[cfg(feature = "std")]
 mod std_types {
-    use super::{BaseMutex, BaseMutexGuard};
-    use crate::primitives::StdThreadEnv;
+    use super::{
+        BaseMcsMutex, BaseMcsMutexGuard, BaseMutex, BaseMutexGuard, BaseReentrantLock,
+        BaseReentrantLockGuard, BaseTicketMutex, BaseTicketMutexGuard,
+    };
+    use crate::primitives::{NoPoison, StdThreadEnv};
 
     pub type StdMutex<T> = BaseMutex<T, (), StdThreadEnv>;
     pub type StdMutexGuard<'a, T> = BaseMutexGuard<'a, T, (), StdThreadEnv>;
+    pub type StdUnpoisonedMutex<T> = BaseMutex<T, (), StdThreadEnv, NoPoison>;
+    pub type StdUnpoisonedMutexGuard<'a, T> = BaseMutexGuard<'a, T, (), StdThreadEnv, NoPoison>;
+    pub type StdTicketMutex<T> = BaseTicketMutex<T, StdThreadEnv>;
+    pub type StdTicketMutexGuard<'a, T> = BaseTicketMutexGuard<'a, T, StdThreadEnv>;
+    pub type StdMcsMutex<T> = BaseMcsMutex<T, StdThreadEnv>;
+    pub type StdMcsMutexGuard<'a, T> = BaseMcsMutexGuard<'a, T, StdThreadEnv>;
+    pub type StdReentrantLock<T> = BaseReentrantLock<T, StdThreadEnv>;
+    pub type StdReentrantLockGuard<'a, T> = BaseReentrantLockGuard<'a, T, StdThreadEnv>;
 }
 
 #[cfg(feature = "std")]
@@ -368,16 +982,41 @@ pub use std_types::*;
 
 #[cfg(not(feature = "std"))]
 mod types {
-    use super::{CoreMutex, CoreMutexGuard};
+    use super::{
+        CoreMcsMutex, CoreMcsMutexGuard, CoreMutex, CoreMutexGuard, CoreTicketMutex,
+        CoreTicketMutexGuard, CoreUnpoisonedMutex, CoreUnpoisonedMutexGuard,
+    };
     pub type Mutex<T> = CoreMutex<T>;
     pub type MutexGuard<'a, T> = CoreMutexGuard<'a, T>;
+    pub type UnpoisonedMutex<T> = CoreUnpoisonedMutex<T>;
+    pub type UnpoisonedMutexGuard<'a, T> = CoreUnpoisonedMutexGuard<'a, T>;
+    pub type TicketMutex<T> = CoreTicketMutex<T>;
+    pub type TicketMutexGuard<'a, T> = CoreTicketMutexGuard<'a, T>;
+    pub type McsMutex<T> = CoreMcsMutex<T>;
+    pub type McsMutexGuard<'a, T> = CoreMcsMutexGuard<'a, T>;
 }
 
 #[cfg(feature = "std")]
 mod types {
-    use super::{StdMutex, StdMutexGuard};
+    use super::{
+        StdMcsMutex, StdMcsMutexGuard, StdMutex, StdMutexGuard, StdReentrantLock,
+        StdReentrantLockGuard, StdTicketMutex, StdTicketMutexGuard, StdUnpoisonedMutex,
+        StdUnpoisonedMutexGuard,
+    };
     pub type Mutex<T> = StdMutex<T>;
     pub type MutexGuard<'a, T> = StdMutexGuard<'a, T>;
+    pub type UnpoisonedMutex<T> = StdUnpoisonedMutex<T>;
+    pub type UnpoisonedMutexGuard<'a, T> = StdUnpoisonedMutexGuard<'a, T>;
+    pub type TicketMutex<T> = StdTicketMutex<T>;
+    pub type TicketMutexGuard<'a, T> = StdTicketMutexGuard<'a, T>;
+    pub type McsMutex<T> = StdMcsMutex<T>;
+    pub type McsMutexGuard<'a, T> = StdMcsMutexGuard<'a, T>;
+    pub type ReentrantLock<T> = StdReentrantLock<T>;
+    pub type ReentrantLockGuard<'a, T> = StdReentrantLockGuard<'a, T>;
 }
 
 pub use types::*;
+
+/// A [`Mutex`] shared through an [`Arc`], as created by [`BaseMutex::new_arc`].
+#[cfg(feature = "alloc")]
+pub type ArcMutex<T> = Arc<Mutex<T>>;
@@ -0,0 +1,296 @@
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    ops::Deref,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicU64, Ordering},
+    const_fn,
+};
+
+use crate::primitives::{
+    ThreadEnv, TryLockError, TryLockResult, WaitQueue, Waiting, forward_guard_traits,
+};
+
+// No thread is ever handed this id, see `HandleId::new_dumb`.
+const UNOWNED: u64 = 0;
+
+#[must_use = "if unused the `BaseReentrantLock` will immediately unlock"]
+pub struct BaseReentrantLockGuard<'a, T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    lock: &'a BaseReentrantLock<T, Env>,
+    // The lock must be released by the thread that owns it.
+    not_send: PhantomData<*const ()>,
+}
+
+impl<'a, T, Env> BaseReentrantLockGuard<'a, T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    /// Returns the lock that this guard was acquired from.
+    pub fn lock(&self) -> &'a BaseReentrantLock<T, Env> {
+        self.lock
+    }
+}
+
+impl<T, Env> Deref for BaseReentrantLockGuard<'_, T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.lock.data
+    }
+}
+
+forward_guard_traits! {
+    impl<T, Env> BaseReentrantLockGuard<'_, T, Env> => T
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+}
+
+impl<T, Env> Drop for BaseReentrantLockGuard<'_, T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    fn drop(&mut self) {
+        // SAFETY: The guard is `!Send`, so we are on the owning thread.
+        unsafe { self.lock.unlock() };
+    }
+}
+
+impl<T, Env> Debug for BaseReentrantLockGuard<'_, T, Env>
+where
+    T: ?Sized + Debug,
+    Env: ThreadEnv,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+// SAFETY: The guard only hands out shared references to the data.
+unsafe impl<T, Env> Sync for BaseReentrantLockGuard<'_, T, Env>
+where
+    T: ?Sized + Sync,
+    Env: ThreadEnv,
+{
+}
+
+/// A mutex that the thread holding it can lock again, like std's unstable
+/// [`ReentrantLock`](https://doc.rust-lang.org/std/sync/struct.ReentrantLock.html).
+///
+/// As several guards of the same thread can be alive at once, the guards only give shared access
+/// to the data. Use a [`Cell`](core::cell::Cell) or [`RefCell`](core::cell::RefCell) inside to
+/// mutate it. The lock is released once the last guard is dropped.
+///
+/// The owning thread is told apart through [`ThreadEnv::current_id`], so `Env` must implement it.
+/// This lock never poisons.
+///
+pub struct BaseReentrantLock<T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    owner: AtomicU64,
+    // Only accessed by the owning thread.
+    count: UnsafeCell<usize>,
+    waiters: WaitQueue<Env::Handle>,
+    thread_env: PhantomData<Env>,
+    data: T,
+}
+
+impl<T, Env> BaseReentrantLock<T, Env>
+where
+    T: Sized,
+    Env: ThreadEnv,
+{
+    const_fn! {
+        pub const fn new(data: T) -> Self {
+            Self {
+                owner: AtomicU64::new(UNOWNED),
+                count: UnsafeCell::new(0),
+                waiters: WaitQueue::new(),
+                thread_env: PhantomData,
+                data,
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T, Env> BaseReentrantLock<T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    fn current_id() -> u64 {
+        match Env::current_id() {
+            Some(id) => *id,
+            None => panic!("`ReentrantLock` requires a `ThreadEnv` that implements `current_id`"),
+        }
+    }
+
+    fn try_own(&self, id: u64) -> bool {
+        self.owner
+            .compare_exchange(UNOWNED, id, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// # Safety
+    /// The current thread must own the lock.
+    unsafe fn enter(&self) -> BaseReentrantLockGuard<'_, T, Env> {
+        // SAFETY: Only the owning thread accesses `count`.
+        let count = unsafe { &mut *self.count.get() };
+        *count = count
+            .checked_add(1)
+            .expect("`ReentrantLock` was locked too many times");
+        BaseReentrantLockGuard {
+            lock: self,
+            not_send: PhantomData,
+        }
+    }
+
+    /// # Safety
+    /// The current thread must own the lock, through a guard that is being dropped.
+    unsafe fn unlock(&self) {
+        // SAFETY: Only the owning thread accesses `count`.
+        let count = unsafe { &mut *self.count.get() };
+        *count -= 1;
+        if *count == 0 {
+            self.owner.store(UNOWNED, Ordering::Release);
+            self.waiters.notify_one();
+        }
+    }
+
+    /// Returns whether the current thread already holds the lock.
+    pub fn is_owned_by_current_thread(&self) -> bool {
+        // Only the current thread can store its own id, so a relaxed load suffices.
+        self.owner.load(Ordering::Relaxed) == Self::current_id()
+    }
+
+    /// Acquires the lock, blocking until no other thread holds it. Returns immediately if the
+    /// current thread already holds it.
+    ///
+    /// # Panics
+    /// Panics if `Env` can't tell threads apart, or if the current thread holds the lock
+    /// `usize::MAX` times.
+    pub fn lock(&self) -> BaseReentrantLockGuard<'_, T, Env> {
+        let id = Self::current_id();
+        if self.owner.load(Ordering::Relaxed) != id && !self.try_own(id) {
+            let _waiting = Waiting::register(self);
+            self.waiters.wait_until(|| self.try_own(id));
+        }
+        // SAFETY: We either owned the lock already, or just took it.
+        unsafe { self.enter() }
+    }
+
+    /// Acquires the lock like [`lock`](BaseReentrantLock::lock), but returns
+    /// [`TryLockError::WouldBlock`] instead of blocking if another thread holds it.
+    pub fn try_lock(&self) -> TryLockResult<BaseReentrantLockGuard<'_, T, Env>> {
+        let id = Self::current_id();
+        if self.owner.load(Ordering::Relaxed) == id || self.try_own(id) {
+            // SAFETY: We either owned the lock already, or just took it.
+            Ok(unsafe { self.enter() })
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<T, Env> Default for BaseReentrantLock<T, Env>
+where
+    T: Default,
+    Env: ThreadEnv,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, Env> From<T> for BaseReentrantLock<T, Env>
+where
+    Env: ThreadEnv,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Formats the data if the lock can be taken, and `<locked>` otherwise.
+impl<T, Env> Debug for BaseReentrantLock<T, Env>
+where
+    T: ?Sized + Debug,
+    Env: ThreadEnv,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("ReentrantLock");
+        match self.try_lock() {
+            Ok(guard) => d.field("data", &&*guard),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, Env> crate::debug::Inspect for BaseReentrantLock<T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let state = match self.owner.load(Ordering::Relaxed) {
+            UNOWNED => LockState::Unlocked,
+            _ => LockState::Written,
+        };
+        LockInfo::new("ReentrantLock", state, false, self.waiters.len())
+    }
+}
+
+// SAFETY: Moving the lock moves the data to another thread.
+unsafe impl<T, Env> Send for BaseReentrantLock<T, Env>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+{
+}
+// SAFETY: Only the owning thread can reach the data, so it is effectively handed between threads.
+// Sharing it further through a guard requires the guard, and so `T`, to be `Sync`.
+unsafe impl<T, Env> Sync for BaseReentrantLock<T, Env>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+{
+}
+
+impl<T, Env> UnwindSafe for BaseReentrantLock<T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+}
+impl<T, Env> RefUnwindSafe for BaseReentrantLock<T, Env>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+{
+}
@@ -0,0 +1,394 @@
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicUsize, Ordering},
+    const_fn,
+};
+
+use super::{MutexApi, MutexGuardApi};
+#[cfg(feature = "multi")]
+use crate::primitives::LockId;
+use crate::primitives::{
+    LockResult, Poison, PoisonPolicy, ThreadEnv, TryLockError, TryLockResult, Waiting,
+    forward_guard_mut_traits, forward_guard_traits, panicking, stable_guard,
+};
+
+#[derive(Debug)]
+#[must_use = "if unused the `BaseTicketMutex` will immediately unlock"]
+pub struct BaseTicketMutexGuard<'a, T, Env, Policy = Poison>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    lock: &'a BaseTicketMutex<T, Env, Policy>,
+    // See `BaseMutexGuard` for why this is a raw pointer.
+    data: *mut T,
+}
+
+impl<'a, T, Env, Policy> BaseTicketMutexGuard<'a, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    unsafe fn new(lock: &'a BaseTicketMutex<T, Env, Policy>) -> Self {
+        Self {
+            lock,
+            data: lock.data.get(),
+        }
+    }
+
+    /// Returns the mutex that this guard was acquired from.
+    pub fn mutex(&self) -> &'a BaseTicketMutex<T, Env, Policy> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the mutex locked forever and returning a mutable reference to the
+    /// protected data that lives as long as the mutex itself.
+    ///
+    /// See also: [`std::sync::MutexGuard::leak`].
+    pub fn leak(self) -> &'a mut T {
+        let data = self.data;
+        core::mem::forget(self);
+        // SAFETY: Forgetting the guard means that the lock is never released, so we keep our
+        // exclusive access to `data` for the rest of `'a`.
+        unsafe { &mut *data }
+    }
+}
+
+impl<T, Env, Policy> Drop for BaseTicketMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn drop(&mut self) {
+        // SAFETY: We're dropping, so we won't use `data` again.
+        unsafe { self.lock.unlock(panicking::<Env>()) };
+    }
+}
+
+impl<T, Env, Policy> Deref for BaseTicketMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `data` is aligned and is guaranteed to point to valid memory via
+        // `UnsafeCell::get`. Caller of `new` must guarantee that we have no writing access.
+        unsafe { &*self.data }
+    }
+}
+
+impl<T, Env, Policy> DerefMut for BaseTicketMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: `data` is aligned and is guaranteed to point to valid memory via
+        // `UnsafeCell::get`. Caller of `new` must guarantee that we have exclusive access.
+        unsafe { &mut *self.data }
+    }
+}
+
+forward_guard_traits! {
+    impl<T, Env, Policy> BaseTicketMutexGuard<'_, T, Env, Policy> => T
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_mut_traits! {
+    impl<T, Env, Policy> BaseTicketMutexGuard<'_, T, Env, Policy> => T
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+stable_guard! {
+    impl<T, Env, Policy> BaseTicketMutexGuard<'_, T, Env, Policy>
+    where
+        T: ?Sized,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+// SAFETY: Like `BaseMutexGuard`, the ticket mutex does not care which thread releases it.
+unsafe impl<T, Env, Policy> Send for BaseTicketMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+unsafe impl<T, Env, Policy> Sync for BaseTicketMutexGuard<'_, T, Env, Policy>
+where
+    T: ?Sized + Sync,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+/// A fair mutex that grants the lock in strict arrival order.
+///
+/// Each call to [`lock`](BaseTicketMutex::lock) draws a ticket, and waits until that ticket is
+/// being served. Unlike [`BaseMutex`](super::BaseMutex), a thread can never be overtaken by threads
+/// that started waiting after it, at the cost of spinning while waiting rather than parking.
+#[derive(Debug)]
+pub struct BaseTicketMutex<T, Env, Policy = Poison>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    next_ticket: AtomicUsize,
+    now_serving: AtomicUsize,
+    poison: Policy,
+    #[cfg(feature = "multi")]
+    id: LockId,
+    thread_env: PhantomData<Env>,
+    data: UnsafeCell<T>,
+}
+
+impl<T, Env, Policy> BaseTicketMutex<T, Env, Policy>
+where
+    T: Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    const_fn! {
+        pub const fn new(data: T) -> Self {
+            Self {
+                next_ticket: AtomicUsize::new(0),
+                now_serving: AtomicUsize::new(0),
+                poison: Policy::UNPOISONED,
+                #[cfg(feature = "multi")]
+                id: LockId::new(),
+                thread_env: PhantomData,
+                data: UnsafeCell::new(data),
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> Policy::Result<T> {
+        let poison = self.poison;
+        poison.wrap(self.data.into_inner())
+    }
+}
+
+impl<T, Env, Policy> BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Returns the id that [`multi::lock_all`](crate::multi::lock_all) orders this lock by.
+    #[cfg(feature = "multi")]
+    pub(crate) fn lock_id(&self) -> u64 {
+        self.id.get()
+    }
+
+    /// Returns a raw pointer to the underlying data.
+    ///
+    /// See also: [`std::sync::Mutex::data_ptr`]
+    pub fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+
+    pub fn get_mut(&mut self) -> Policy::Result<&mut T> {
+        self.poison.wrap(self.data.get_mut())
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.poison.clear_poison();
+    }
+
+    unsafe fn unlock(&self, poison: bool) {
+        self.poison.poison(poison);
+        self.now_serving.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn lock(&self) -> Policy::Result<BaseTicketMutexGuard<'_, T, Env, Policy>> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        if self.now_serving.load(Ordering::Acquire) != ticket {
+            let _waiting = Waiting::register(self);
+            while self.now_serving.load(Ordering::Acquire) != ticket {
+                Env::yield_now();
+            }
+        }
+        // SAFETY: Our ticket is being served, so we have exclusive access until we release it.
+        self.poison.wrap(unsafe { BaseTicketMutexGuard::new(self) })
+    }
+
+    pub fn try_lock(&self) -> TryLockResult<BaseTicketMutexGuard<'_, T, Env, Policy>> {
+        // Only draw a ticket if it would be served immediately, so that we never have to wait.
+        let serving = self.now_serving.load(Ordering::Relaxed);
+        let drawn = self.next_ticket.compare_exchange(
+            serving,
+            serving.wrapping_add(1),
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        );
+
+        if drawn.is_ok() {
+            // SAFETY: We drew the ticket that is currently being served.
+            self.poison
+                .try_wrap(unsafe { BaseTicketMutexGuard::new(self) })
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+}
+
+impl<T, Env, Policy> Default for BaseTicketMutex<T, Env, Policy>
+where
+    T: Default,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, Env, Policy> crate::debug::Inspect for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let now_serving = self.now_serving.load(Ordering::Relaxed);
+        // Every drawn ticket that isn't being served yet belongs to a waiting thread.
+        let drawn = self
+            .next_ticket
+            .load(Ordering::Relaxed)
+            .wrapping_sub(now_serving);
+        let state = match drawn {
+            0 => LockState::Unlocked,
+            _ => LockState::Written,
+        };
+        LockInfo::new(
+            "TicketMutex",
+            state,
+            self.is_poisoned(),
+            drawn.saturating_sub(1),
+        )
+    }
+}
+
+impl<T, Env, Policy> From<T> for BaseTicketMutex<T, Env, Policy>
+where
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+// `T` needs to be `Send` for the mutex to be `Sync`, for the same reasons as `BaseMutex`.
+unsafe impl<T, Env, Policy> Send for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+unsafe impl<T, Env, Policy> Sync for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized + Send,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<T, Env, Policy> UnwindSafe for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+impl<T, Env, Policy> RefUnwindSafe for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<'a, T, Env, Policy> MutexGuardApi<'a, T> for BaseTicketMutexGuard<'a, T, Env, Policy>
+where
+    T: 'a + ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+}
+
+impl<T, Env, Policy> MutexApi<T> for BaseTicketMutex<T, Env, Policy>
+where
+    T: ?Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_lock()
+    }
+
+    fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        Policy::into_lock_result(self.lock())
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.is_poisoned()
+    }
+
+    fn clear_poison(&self) {
+        self.clear_poison();
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        Policy::into_lock_result(self.get_mut())
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::new(t)
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Policy::into_lock_result(self.into_inner())
+    }
+}
@@ -0,0 +1,184 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+extern crate alloc;
+use alloc::{collections::VecDeque, sync::Arc};
+
+use crate::{
+    mutex::CoreUnpoisonedMutex,
+    primitives::{
+        AsyncHandle, Handle,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            const_fn,
+        },
+    },
+};
+
+/// Signals async tasks, like tokio's `Notify`: a task awaits [`notified`](Notify::notified) until
+/// another task calls [`notify_one`](Notify::notify_one) or
+/// [`notify_waiters`](Notify::notify_waiters).
+///
+/// Each waiting task is woken through an [`AsyncHandle`].
+///
+/// # Examples
+/// ```
+/// # use std::{pin::pin, task::{Context, Poll, Waker}};
+/// # use powerlocks::notify::Notify;
+/// let notify = Notify::new();
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// let mut notified = pin!(notify.notified());
+/// assert_eq!(notified.as_mut().poll(&mut cx), Poll::Pending);
+/// notify.notify_one();
+/// assert_eq!(notified.poll(&mut cx), Poll::Ready(()));
+/// ```
+///
+pub struct Notify {
+    waiters: CoreUnpoisonedMutex<VecDeque<Arc<AsyncHandle>>>,
+    // Set by `notify_one` while no task waits, and consumed by the next task to wait.
+    permit: AtomicBool,
+    // Bumped by every `notify_waiters`, which completes every `Notified` created before.
+    generation: AtomicUsize,
+}
+
+impl Notify {
+    const_fn! {
+        pub const fn new() -> Self {
+            Self {
+                waiters: CoreUnpoisonedMutex::new_unhooked(VecDeque::new()),
+                permit: AtomicBool::new(false),
+                generation: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Returns a future that completes once this task is notified.
+    ///
+    /// The future only starts waiting once it is first polled, except for
+    /// [`notify_waiters`](Notify::notify_waiters), which also completes the futures that weren't
+    /// polled yet.
+    pub fn notified(&self) -> Notified<'_> {
+        Notified {
+            notify: self,
+            generation: self.generation.load(Ordering::Acquire),
+            state: NotifiedState::Init,
+        }
+    }
+
+    /// Wakes the task that has waited the longest, or lets the next task that waits through right
+    /// away if none is waiting.
+    ///
+    /// At most one such permit is stored, however often this is called.
+    pub fn notify_one(&self) {
+        let mut waiters = self.waiters.lock();
+        match waiters.pop_front() {
+            Some(waiter) => {
+                drop(waiters);
+                waiter.unpark();
+            }
+            // Under the lock, so that a task can't start waiting without seeing the permit.
+            None => self.permit.store(true, Ordering::Release),
+        }
+    }
+
+    /// Wakes every task that is waiting, along with every [`Notified`] created before this call.
+    /// No permit is stored for tasks that wait later on.
+    pub fn notify_waiters(&self) {
+        let mut waiters = self.waiters.lock();
+        self.generation.fetch_add(1, Ordering::Release);
+        let woken = core::mem::take(&mut *waiters);
+        drop(waiters);
+        AsyncHandle::unpark_all(woken.iter().map(|waiter| &**waiter));
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Notify {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notify")
+            .field("waiters", &self.waiters.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+enum NotifiedState {
+    Init,
+    Waiting(Arc<AsyncHandle>),
+    Done,
+}
+
+/// The future returned by [`Notify::notified`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    generation: usize,
+    state: NotifiedState,
+}
+
+impl Notified<'_> {
+    fn notified_waiters(&self) -> bool {
+        self.notify.generation.load(Ordering::Acquire) != self.generation
+    }
+
+    /// Removes our handle from the waiters, returning `false` if a notifier already took it.
+    fn unregister(&self, handle: &Arc<AsyncHandle>) -> bool {
+        let mut waiters = self.notify.waiters.lock();
+        let position = waiters
+            .iter()
+            .position(|waiter| Arc::ptr_eq(waiter, handle));
+        position.map(|position| waiters.remove(position)).is_some()
+    }
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if let NotifiedState::Init = this.state {
+            let mut waiters = this.notify.waiters.lock();
+            if this.notified_waiters() || this.notify.permit.swap(false, Ordering::Acquire) {
+                this.state = NotifiedState::Done;
+            } else {
+                let handle = Arc::new(AsyncHandle::new());
+                waiters.push_back(Arc::clone(&handle));
+                this.state = NotifiedState::Waiting(handle);
+            }
+        }
+
+        match &this.state {
+            NotifiedState::Init => unreachable!(),
+            NotifiedState::Waiting(handle) => {
+                if handle.poll_park(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                this.state = NotifiedState::Done;
+                Poll::Ready(())
+            }
+            NotifiedState::Done => Poll::Ready(()),
+        }
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        if let NotifiedState::Waiting(handle) = &self.state {
+            // A `notify_one` meant for us would be lost, so hand it on to the next task.
+            if !self.unregister(handle) && !self.notified_waiters() {
+                self.notify.notify_one();
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::primitives::{CoreThreadEnv, ThreadEnv, sync::const_fn};
+
+use super::BaseOnceLock;
+
+/// A value that is initialized on first access, like [`std::sync::LazyLock`].
+///
+/// Unlike std's, `init` is an [`Fn`] rather than an [`FnOnce`], so that no state needs to track
+/// whether it was consumed: if it panics, the next access simply calls it again. It is mostly used
+/// through [`powerlock_static!`](crate::powerlock_static), to place locks that can't be built in a
+/// `const` context in `static`s.
+///
+pub struct BaseLazyLock<T, Env: ThreadEnv, F = fn() -> T> {
+    cell: BaseOnceLock<T, Env>,
+    init: F,
+}
+
+impl<T, Env, F> BaseLazyLock<T, Env, F>
+where
+    Env: ThreadEnv,
+    F: Fn() -> T,
+{
+    const_fn! {
+        pub const fn new(init: F) -> Self {
+            Self {
+                cell: BaseOnceLock::new(),
+                init,
+            }
+        }
+    }
+
+    /// Returns the value, initializing it first if needed.
+    ///
+    /// This is an associated function, so that it doesn't shadow any method of the value.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(&this.init)
+    }
+
+    /// Returns the value, or [`None`] if it was not initialized yet.
+    pub fn get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+}
+
+impl<T, Env, F> Deref for BaseLazyLock<T, Env, F>
+where
+    Env: ThreadEnv,
+    F: Fn() -> T,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        Self::force(self)
+    }
+}
+
+impl<T, Env> Default for BaseLazyLock<T, Env>
+where
+    T: Default,
+    Env: ThreadEnv,
+{
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T, Env, F> Debug for BaseLazyLock<T, Env, F>
+where
+    T: Debug,
+    Env: ThreadEnv,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("LazyLock");
+        match self.cell.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+pub type CoreLazyLock<T, F = fn() -> T> = BaseLazyLock<T, CoreThreadEnv, F>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseLazyLock;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdLazyLock<T, F = fn() -> T> = BaseLazyLock<T, StdThreadEnv, F>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreLazyLock;
+    pub type LazyLock<T, F = fn() -> T> = CoreLazyLock<T, F>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdLazyLock;
+    pub type LazyLock<T, F = fn() -> T> = StdLazyLock<T, F>;
+}
+
+pub use types::*;
+
+/// Declares `static` locks.
+///
+/// `static NAME: Type = data;` builds the lock with its `const` constructor `new_unhooked`, as
+/// provided by [`mutex::BaseMutex`](crate::mutex::BaseMutex) and `rwlock::BaseRwLock`. Locks
+/// whose constructor isn't `const`, such as the strategied or hooked ones, are declared with
+/// `static NAME: lazy Type = data;` instead: they are built with `new` on first access, behind a
+/// [`LazyLock`] that dereferences to the lock.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "rwlock", feature = "std"))] {
+/// use powerlocks::{mutex::Mutex, powerlock_static, strategied_rwlock::RwLock};
+///
+/// powerlock_static! {
+///     static COUNTER: Mutex<u32> = 0;
+///     /// Built on first access.
+///     pub static NAMES: lazy RwLock<Vec<String>> = Vec::new();
+/// }
+///
+/// *COUNTER.lock().unwrap() += 1;
+/// NAMES.write().unwrap().push("first".into());
+/// assert_eq!(NAMES.read().unwrap().len(), 1);
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! powerlock_static {
+    () => {};
+    (
+        $(#[$attr:meta])* $vis:vis static $name:ident: lazy $ty:ty = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis static $name: $crate::once_lock::LazyLock<$ty> =
+            $crate::once_lock::LazyLock::new(|| <$ty>::new($init));
+        $crate::powerlock_static!($($rest)*);
+    };
+    (
+        $(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis static $name: $ty = <$ty>::new_unhooked($init);
+        $crate::powerlock_static!($($rest)*);
+    };
+}
@@ -0,0 +1,215 @@
+mod lazy;
+pub use lazy::*;
+
+mod once;
+pub use once::*;
+
+use core::{
+    cell::UnsafeCell,
+    convert::Infallible,
+    fmt::{self, Debug, Formatter},
+    mem::{self, MaybeUninit},
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicBool, Ordering},
+    const_fn,
+};
+
+use crate::{
+    mutex::BaseMutex,
+    primitives::{CoreThreadEnv, NoPoison, ThreadEnv},
+};
+
+/// A cell that is written at most once, like [`std::sync::OnceLock`].
+///
+/// Initialization is double-checked: once the value is set, reading it is a single atomic load.
+/// Until then, initializers are serialized by a mutex of the crate, so only one of them runs at a
+/// time and the others wait for it. If an initializer panics or fails, the next one gets a go.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::once_lock::OnceLock;
+/// static CONFIG: OnceLock<String> = OnceLock::new();
+///
+/// assert_eq!(CONFIG.get(), None);
+/// assert_eq!(CONFIG.get_or_init(|| "loaded".into()), "loaded");
+/// assert_eq!(CONFIG.get_or_init(|| unreachable!()), "loaded");
+/// # }
+/// ```
+///
+pub struct BaseOnceLock<T, Env: ThreadEnv> {
+    initialized: AtomicBool,
+    init_lock: BaseMutex<(), (), Env, NoPoison>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T, Env: ThreadEnv> BaseOnceLock<T, Env> {
+    const_fn! {
+        /// Creates a new, uninitialized cell.
+        pub const fn new() -> Self {
+            Self {
+                initialized: AtomicBool::new(false),
+                init_lock: BaseMutex::new_unhooked(()),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            }
+        }
+    }
+
+    /// Returns the value, or [`None`] if the cell is not initialized yet.
+    pub fn get(&self) -> Option<&T> {
+        if self.initialized.load(Ordering::Acquire) {
+            // SAFETY: The value was written before `initialized` was set, and is never written
+            // again through a shared reference.
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.initialized.load(Ordering::Relaxed) {
+            // SAFETY: `initialized` is only set once the value was written.
+            Some(unsafe { self.value.get_mut().assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Initializes the cell with `value`, or gives `value` back if it was already initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Returns the value, initializing it with `f` first if the cell is empty.
+    ///
+    /// If several threads call this at once, only one of them runs its `f`, and the others block
+    /// until the value is ready.
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        match self.get_or_try_init(|| Ok::<T, Infallible>(f())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns the value, initializing it with `f` first if the cell is empty. If `f` fails, the
+    /// cell stays empty and the error is returned.
+    pub fn get_or_try_init<E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+
+        let _guard = self.init_lock.lock();
+        // Another thread may have initialized the cell while we were waiting for the lock.
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+        let value = f()?;
+        // SAFETY: Holding `init_lock` with `initialized` unset, nobody else reads or writes the
+        // value.
+        unsafe { (*self.value.get()).write(value) };
+        self.initialized.store(true, Ordering::Release);
+        Ok(self.get().unwrap())
+    }
+
+    /// Takes the value out of the cell, leaving it uninitialized.
+    pub fn take(&mut self) -> Option<T> {
+        mem::take(self).into_inner()
+    }
+
+    pub fn into_inner(mut self) -> Option<T> {
+        if self.initialized.swap(false, Ordering::Relaxed) {
+            // SAFETY: The value is initialized, and `initialized` was cleared so that it isn't
+            // dropped again.
+            Some(unsafe { self.value.get_mut().assume_init_read() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, Env: ThreadEnv> Default for BaseOnceLock<T, Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Env: ThreadEnv> From<T> for BaseOnceLock<T, Env> {
+    fn from(value: T) -> Self {
+        let this = Self::new();
+        // A fresh cell can always be set.
+        let _ = this.set(value);
+        this
+    }
+}
+
+impl<T: Clone, Env: ThreadEnv> Clone for BaseOnceLock<T, Env> {
+    fn clone(&self) -> Self {
+        match self.get() {
+            Some(value) => Self::from(value.clone()),
+            None => Self::new(),
+        }
+    }
+}
+
+impl<T: Debug, Env: ThreadEnv> Debug for BaseOnceLock<T, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("OnceLock");
+        match self.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T, Env: ThreadEnv> Drop for BaseOnceLock<T, Env> {
+    fn drop(&mut self) {
+        if self.initialized.load(Ordering::Relaxed) {
+            // SAFETY: The value is initialized, and never used again.
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+// SAFETY: Sending the cell sends the value.
+unsafe impl<T: Send, Env: ThreadEnv> Send for BaseOnceLock<T, Env> {}
+// SAFETY: The value is shared between threads once set, and may be set by any of them.
+unsafe impl<T: Send + Sync, Env: ThreadEnv> Sync for BaseOnceLock<T, Env> {}
+
+impl<T: UnwindSafe, Env: ThreadEnv> UnwindSafe for BaseOnceLock<T, Env> {}
+impl<T: UnwindSafe + RefUnwindSafe, Env: ThreadEnv> RefUnwindSafe for BaseOnceLock<T, Env> {}
+
+pub type CoreOnceLock<T> = BaseOnceLock<T, CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseOnceLock;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdOnceLock<T> = BaseOnceLock<T, StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreOnceLock;
+    pub type OnceLock<T> = CoreOnceLock<T>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdOnceLock;
+    pub type OnceLock<T> = StdOnceLock<T>;
+}
+
+pub use types::*;
@@ -0,0 +1,153 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    mem,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicU8, Ordering},
+    const_fn,
+};
+
+use crate::{
+    mutex::BaseMutex,
+    primitives::{CoreThreadEnv, NoPoison, OnDrop, ThreadEnv},
+};
+
+const INCOMPLETE: u8 = 0;
+const POISONED: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// Runs a one-time initialization, like [`std::sync::Once`].
+///
+/// Like std's, the `Once` is poisoned if an initializer panics: [`call_once`](Self::call_once)
+/// then panics too, while [`call_once_force`](Self::call_once_force) runs its initializer again,
+/// telling it through [`OnceState::is_poisoned`] that it has to recover from the earlier attempt.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::once_lock::Once;
+/// static INIT: Once = Once::new();
+///
+/// INIT.call_once(|| println!("initialized"));
+/// INIT.call_once(|| unreachable!());
+/// assert!(INIT.is_completed());
+/// # }
+/// ```
+///
+pub struct BaseOnce<Env: ThreadEnv> {
+    state: AtomicU8,
+    init_lock: BaseMutex<(), (), Env, NoPoison>,
+}
+
+/// The state of a [`BaseOnce`], passed to the initializer of
+/// [`call_once_force`](BaseOnce::call_once_force).
+#[derive(Debug)]
+pub struct OnceState {
+    poisoned: bool,
+}
+
+impl OnceState {
+    /// Returns `true` if an earlier initializer panicked, leaving the `Once` poisoned.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
+
+impl<Env: ThreadEnv> BaseOnce<Env> {
+    const_fn! {
+        /// Creates a new `Once` that has not run yet.
+        pub const fn new() -> Self {
+            Self {
+                state: AtomicU8::new(INCOMPLETE),
+                init_lock: BaseMutex::new_unhooked(()),
+            }
+        }
+    }
+
+    /// Runs `f` if no initializer has completed yet.
+    ///
+    /// If several threads call this at once, only one of them runs its `f`, and the others block
+    /// until it is done.
+    ///
+    /// # Panics
+    /// Panics if the `Once` is poisoned by an initializer that panicked.
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if !self.is_completed() {
+            self.call(false, |_| f());
+        }
+    }
+
+    /// Runs `f` if no initializer has completed yet, even if the `Once` is poisoned.
+    ///
+    /// The [`OnceState`] tells `f` whether an earlier initializer panicked. If `f` returns, the
+    /// `Once` is completed and no longer poisoned.
+    pub fn call_once_force(&self, f: impl FnOnce(&OnceState)) {
+        if !self.is_completed() {
+            self.call(true, f);
+        }
+    }
+
+    /// Returns `true` if an initializer has completed.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    fn call(&self, ignore_poison: bool, f: impl FnOnce(&OnceState)) {
+        let _guard = self.init_lock.lock();
+        // Another thread may have completed while we were waiting for the lock.
+        let state = self.state.load(Ordering::Acquire);
+        if state == COMPLETE {
+            return;
+        }
+        if state == POISONED && !ignore_poison {
+            panic!("Once instance has previously been poisoned");
+        }
+
+        let poison = OnDrop(|| self.state.store(POISONED, Ordering::Relaxed));
+        f(&OnceState {
+            poisoned: state == POISONED,
+        });
+        mem::forget(poison);
+        self.state.store(COMPLETE, Ordering::Release);
+    }
+}
+
+impl<Env: ThreadEnv> Default for BaseOnce<Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Env: ThreadEnv> Debug for BaseOnce<Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
+
+pub type CoreOnce = BaseOnce<CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseOnce;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdOnce = BaseOnce<StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreOnce;
+    pub type Once = CoreOnce;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdOnce;
+    pub type Once = StdOnce;
+}
+
+pub use types::*;
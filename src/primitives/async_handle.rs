@@ -0,0 +1,119 @@
+use core::{
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use super::{
+    CoreThreadEnv, Handle, HandleError, HandleId, ThreadEnv,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use crate::mutex::CoreUnpoisonedMutex;
+
+/// A [`Handle`] for async tasks instead of threads: [`poll_park`](AsyncHandle::poll_park)
+/// registers the task's [`Waker`], and [`unpark`](Handle::unpark) wakes it.
+///
+/// Tasks queued by handle poll their handle instead of parking the thread. The strategied locks
+/// queue tasks with the same strategy as threads, e.g. through `BaseRwLock::read_async`, and a
+/// `Notify` wakes its tasks the same way. The blocking [`park`](Handle::park) never blocks, it
+/// only consumes a pending unpark, so a thread waiting on this handle spins.
+///
+#[derive(Debug)]
+pub struct AsyncHandle {
+    id: HandleId,
+    notified: AtomicBool,
+    waker: CoreUnpoisonedMutex<Option<Waker>>,
+}
+
+impl AsyncHandle {
+    fn with_id(id: HandleId) -> Self {
+        Self {
+            id,
+            notified: AtomicBool::new(false),
+            waker: CoreUnpoisonedMutex::new(None),
+        }
+    }
+
+    /// Returns [`Poll::Ready`] if the handle was unparked since the last time it was parked, and
+    /// otherwise registers the waker in `cx` to be woken by the next [`unpark`](Handle::unpark).
+    ///
+    /// Only the waker of the latest call is woken.
+    pub fn poll_park(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.notified.swap(false, Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        let mut waker = self.waker.lock();
+        match &mut *waker {
+            Some(waker) => waker.clone_from(cx.waker()),
+            None => *waker = Some(cx.waker().clone()),
+        }
+        drop(waker);
+
+        // An `unpark` may have slipped in before the waker was registered, and found none to wake.
+        if self.notified.swap(false, Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl ThreadEnv for AsyncHandle {
+    type Handle = Self;
+
+    fn yield_now()
+    where
+        Self: Sized,
+    {
+        CoreThreadEnv::yield_now();
+    }
+
+    fn sleep(duration: Duration)
+    where
+        Self: Sized,
+    {
+        CoreThreadEnv::sleep(duration);
+    }
+}
+
+unsafe impl Handle for AsyncHandle {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_id(HandleId::new())
+    }
+
+    fn try_new() -> Result<Self, HandleError>
+    where
+        Self: Sized,
+    {
+        HandleId::try_new().map(Self::with_id)
+    }
+
+    fn dumb() -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_id(HandleId::new_dumb())
+    }
+
+    fn id(&self) -> HandleId {
+        self.id
+    }
+
+    fn park(&self) {
+        if !self.notified.swap(false, Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+    }
+
+    fn unpark(&self) {
+        self.notified.store(true, Ordering::Release);
+        // Wake outside of the lock, the waker may run arbitrary code.
+        let waker = self.waker.lock().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use core::time::Duration;
+
+use super::ThreadEnv;
+
+/// Waits between the polls of a loop that can't park, first by yielding, and then by sleeping for
+/// exponentially longer durations, so that long waits don't keep a core busy.
+#[derive(Debug, Default)]
+pub(crate) struct Backoff {
+    attempts: u32,
+}
+
+impl Backoff {
+    const YIELD_ATTEMPTS: u32 = 64;
+    const MIN_SLEEP: Duration = Duration::from_micros(1);
+    const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+    pub(crate) const fn new() -> Self {
+        Self { attempts: 0 }
+    }
+
+    pub(crate) fn wait<Env: ThreadEnv>(&mut self) {
+        match self.attempts.checked_sub(Self::YIELD_ATTEMPTS) {
+            None => Env::yield_now(),
+            Some(sleeps) => {
+                let sleep = Self::MIN_SLEEP.saturating_mul(1 << sleeps.min(16));
+                Env::sleep(sleep.min(Self::MAX_SLEEP));
+            }
+        }
+        self.attempts = self.attempts.saturating_add(1);
+    }
+}
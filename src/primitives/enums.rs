@@ -1,3 +1,5 @@
+use core::fmt::{self, Display, Formatter};
+
 use crate::primitives::TryLockError;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -14,3 +16,88 @@ impl ShouldBlock {
         }
     }
 }
+
+///
+/// Denotes the type of operation that a Thread is performing on a lock. Used by the strategies of
+/// a strategied `RwLock`, and by [`HookContext`](super::HookContext) to tell lock hooks what kind
+/// of access is being requested.
+///
+/// Mutexes only ever grant exclusive access, so they always report [`Method::Write`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// Denotes threads that are reading, or planning to read from the lock.
+    Read,
+    /// Denotes threads that are writing, or planning to write to the lock.
+    Write,
+}
+
+impl Method {
+    /// Returns `true` if this `Method` an instance of [`Method::Read`], and returns `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::Method;
+    /// let method = Method::Read;
+    /// assert!(method.is_read());
+    ///
+    /// let method = Method::Write;
+    /// assert!(!method.is_read());
+    /// ```
+    ///
+    pub const fn is_read(&self) -> bool {
+        matches!(self, Method::Read)
+    }
+
+    /// Returns `true` if this `Method` an instance of [`Method::Write`], and returns `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::Method;
+    /// let method = Method::Write;
+    /// assert!(method.is_write());
+    ///
+    /// let method = Method::Read;
+    /// assert!(!method.is_write());
+    /// ```
+    ///
+    pub const fn is_write(&self) -> bool {
+        matches!(self, Method::Write)
+    }
+
+    /// Returns `true` if threads accessing a lock with this `Method` and `other` can't hold the
+    /// lock at the same time, which is the case unless both are [`Method::Read`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::Method;
+    /// assert!(!Method::Read.conflicts_with(Method::Read));
+    /// assert!(Method::Read.conflicts_with(Method::Write));
+    /// assert!(Method::Write.conflicts_with(Method::Write));
+    /// ```
+    ///
+    pub const fn conflicts_with(&self, other: Method) -> bool {
+        self.is_write() || other.is_write()
+    }
+
+    #[cfg(feature = "rwlock")]
+    #[inline]
+    pub(crate) fn switch<T>(&self, read: impl FnOnce() -> T, write: impl FnOnce() -> T) -> T {
+        match self {
+            Method::Read => read(),
+            Method::Write => write(),
+        }
+    }
+}
+
+impl Display for Method {
+    /// Formats the `Method` in lowercase, as `read` or `write`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Method::Read => "read",
+            Method::Write => "write",
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use core::marker::PhantomData;
+
+/// Marks a lock whose guards are [`Send`], so they can be released on another thread than the one
+/// that acquired them. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GuardSend(());
+
+/// Marks a lock whose guards are not [`Send`], just like the guards of the Standard library's
+/// locks, for drop-in compatibility with them.
+///
+/// # Examples
+/// ```compile_fail
+/// # use powerlocks::{mutex::BaseMutex, primitives::{CoreThreadEnv, GuardNoSend, Poison}};
+/// let mutex = BaseMutex::<_, (), CoreThreadEnv, Poison, GuardNoSend>::new(0);
+/// let guard = mutex.lock().unwrap();
+/// std::thread::scope(|scope| {
+///     scope.spawn(move || drop(guard));
+/// });
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GuardNoSend(PhantomData<*mut ()>);
@@ -0,0 +1,196 @@
+/// Implements [`Display`](core::fmt::Display), [`PartialEq`] and [`PartialOrd`] against the
+/// data, [`Hash`](core::hash::Hash), [`AsRef`] and [`Borrow`](core::borrow::Borrow), for a guard
+/// that dereferences to `$target`, by forwarding them to the data. This lets guards be compared,
+/// printed and passed to generic code like std's guards.
+///
+/// The `where` clause must end with a comma.
+macro_rules! forward_guard_traits {
+    (impl<$($gen:tt),*> $guard:ty => $target:ty where $($bound:tt)*) => {
+        impl<$($gen),*> core::fmt::Display for $guard
+        where
+            $($bound)*
+            $target: core::fmt::Display,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Display::fmt(&**self, f)
+            }
+        }
+
+        impl<$($gen),*> PartialEq<$target> for $guard
+        where
+            $($bound)*
+            $target: PartialEq,
+        {
+            fn eq(&self, other: &$target) -> bool {
+                **self == *other
+            }
+        }
+
+        impl<$($gen),*> PartialOrd<$target> for $guard
+        where
+            $($bound)*
+            $target: PartialOrd,
+        {
+            fn partial_cmp(&self, other: &$target) -> Option<core::cmp::Ordering> {
+                (**self).partial_cmp(other)
+            }
+        }
+
+        impl<$($gen),*> core::hash::Hash for $guard
+        where
+            $($bound)*
+            $target: core::hash::Hash,
+        {
+            fn hash<HashState: core::hash::Hasher>(&self, state: &mut HashState) {
+                (**self).hash(state);
+            }
+        }
+
+        impl<$($gen),*> AsRef<$target> for $guard
+        where
+            $($bound)*
+        {
+            fn as_ref(&self) -> &$target {
+                self
+            }
+        }
+
+        impl<$($gen),*> core::borrow::Borrow<$target> for $guard
+        where
+            $($bound)*
+        {
+            fn borrow(&self) -> &$target {
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use forward_guard_traits;
+
+/// Implements [`AsMut`] and [`BorrowMut`](core::borrow::BorrowMut) for a guard with exclusive
+/// access to `$target`, on top of [`forward_guard_traits`].
+///
+/// The `where` clause must end with a comma.
+macro_rules! forward_guard_mut_traits {
+    (impl<$($gen:tt),*> $guard:ty => $target:ty where $($bound:tt)*) => {
+        impl<$($gen),*> AsMut<$target> for $guard
+        where
+            $($bound)*
+        {
+            fn as_mut(&mut self) -> &mut $target {
+                self
+            }
+        }
+
+        impl<$($gen),*> core::borrow::BorrowMut<$target> for $guard
+        where
+            $($bound)*
+        {
+            fn borrow_mut(&mut self) -> &mut $target {
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use forward_guard_mut_traits;
+
+/// Implements [`StableGuard`](crate::primitives::StableGuard) for a guard whose data lives in its
+/// lock, rather than in the guard itself.
+///
+/// The `where` clause must end with a comma.
+macro_rules! stable_guard {
+    (impl<$($gen:tt),*> $guard:ty where $($bound:tt)*) => {
+        impl<$($gen),*> $crate::primitives::SealedGuard for $guard
+        where
+            $($bound)*
+        {
+        }
+
+        // SAFETY: The data is borrowed from the lock, which the guard only points to, so moving
+        // the guard doesn't move the data.
+        unsafe impl<$($gen),*> $crate::primitives::StableGuard for $guard
+        where
+            $($bound)*
+        {
+        }
+    };
+}
+
+pub(crate) use stable_guard;
+
+/// Implements [`std::io::Read`] and [`std::io::Write`] for a guard with exclusive access to data
+/// that implements them, so that a locked reader or writer can be passed on without a newtype.
+///
+/// This expands to nothing without the `std` feature. The `where` clause must end with a comma.
+#[cfg(feature = "mutex")]
+macro_rules! forward_guard_io {
+    (impl<$($gen:tt),*> $guard:ty => $target:ty where $($bound:tt)*) => {
+        #[cfg(feature = "std")]
+        const _: () = {
+            extern crate std;
+            use std::{
+                fmt,
+                io::{self, IoSlice, IoSliceMut, Read, Write},
+                string::String,
+                vec::Vec,
+            };
+
+            impl<$($gen),*> Read for $guard
+            where
+                $($bound)*
+                $target: Read,
+            {
+                fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                    (**self).read(buf)
+                }
+
+                fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+                    (**self).read_vectored(bufs)
+                }
+
+                fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+                    (**self).read_to_end(buf)
+                }
+
+                fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+                    (**self).read_to_string(buf)
+                }
+
+                fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+                    (**self).read_exact(buf)
+                }
+            }
+
+            impl<$($gen),*> Write for $guard
+            where
+                $($bound)*
+                $target: Write,
+            {
+                fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                    (**self).write(buf)
+                }
+
+                fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                    (**self).write_vectored(bufs)
+                }
+
+                fn flush(&mut self) -> io::Result<()> {
+                    (**self).flush()
+                }
+
+                fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+                    (**self).write_all(buf)
+                }
+
+                fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+                    (**self).write_fmt(args)
+                }
+            }
+        };
+    };
+}
+
+#[cfg(feature = "mutex")]
+pub(crate) use forward_guard_io;
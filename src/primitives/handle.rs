@@ -1,41 +1,69 @@
-use core::ops::Deref;
+use core::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+    time::Duration,
+};
+
+use super::sync::native_atomic::Ordering;
 
 #[cfg(not(feature = "mutex"))]
 compile_error!("Internal crate error: `handle.rs` requires the `mutex` feature.");
 
 mod handle_type {
-    pub(super) type HandleIdBase = u128;
-    pub(super) type HandleIdAtomicBase = crate::mutex::CoreMutex<u128>;
+    pub(super) type HandleIdBase = u64;
+    pub(super) type HandleIdAtomicBase = crate::primitives::sync::native_atomic::AtomicU64;
 }
 
 use handle_type::{HandleIdAtomicBase, HandleIdBase};
 
-static HANDLE_COUNTER: HandleIdAtomicBase = HandleIdAtomicBase::new_unhooked(1);
+static HANDLE_COUNTER: HandleIdAtomicBase = HandleIdAtomicBase::new(1);
+
+/// Once the counter passes this value, `HandleId::try_new` refuses to hand out any more ids. Every
+/// refused call still bumps the counter, so it leaves plenty of headroom before the counter could
+/// wrap around and repeat an id that is already in use.
+const HANDLE_COUNTER_LIMIT: HandleIdBase = HandleIdBase::MAX / 2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct HandleId(HandleIdBase);
 impl HandleId {
-    fn new() -> Self {
-        if *HANDLE_COUNTER.lock().unwrap() == u128::MAX {
-            panic!("Exhausted `HandleId::new()`.");
+    /// Allocates a new id that is different from every other id allocated before, or returns a
+    /// [`HandleError`] once every id has been handed out.
+    ///
+    /// This lets [`Handle`] implementations decide for themselves how to deal with running out of
+    /// ids, see [`Handle::try_new`].
+    pub fn try_new() -> Result<Self, HandleError> {
+        // Uniqueness only relies on every call observing a different counter value, so no ordering
+        // with other memory is needed.
+        let val = HANDLE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        if val > HANDLE_COUNTER_LIMIT {
+            return Err(HandleError);
         }
 
-        let val = {
-            let mut guard = HANDLE_COUNTER.lock().unwrap();
-            let val = *guard;
-            *guard += 1;
-            drop(guard);
-            val
-        };
+        Ok(Self(val))
+    }
 
-        Self(val)
+    pub(crate) fn new() -> Self {
+        Self::try_new().unwrap_or_else(|err| panic!("{err}"))
     }
 
-    fn new_dumb() -> Self {
+    pub(super) fn new_dumb() -> Self {
         Self(0)
     }
 }
 
+/// The error returned when no more unique [`HandleId`]s can be allocated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandleError;
+
+impl Display for HandleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt("exhausted all unique `HandleId`s", f)
+    }
+}
+
+impl Error for HandleError {}
+
 impl Deref for HandleId {
     type Target = HandleIdBase;
     fn deref(&self) -> &Self::Target {
@@ -44,6 +72,9 @@ impl Deref for HandleId {
 }
 
 pub trait ThreadEnv {
+    /// The [`Handle`] used to park threads that block on locks in this environment.
+    type Handle: Handle + Sync + Debug;
+
     fn yield_now()
     where
         Self: Sized,
@@ -56,6 +87,39 @@ pub trait ThreadEnv {
     {
         false
     }
+
+    /// Puts the current thread to sleep for about `duration`, for waits that can't park. It may
+    /// return early, so callers must check their condition again afterwards.
+    ///
+    /// The default implementation only calls [`yield_now`](ThreadEnv::yield_now) once, which is
+    /// all that environments without a clock can do.
+    fn sleep(_duration: Duration)
+    where
+        Self: Sized,
+    {
+        Self::yield_now();
+    }
+
+    /// Returns an id that is unique to the current thread, and stays the same for the lifetime of
+    /// the thread. Environments that can't tell threads apart return `None`.
+    fn current_id() -> Option<HandleId>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Returns the time elapsed since a fixed, but arbitrary, point in the past, from a clock that
+    /// never goes backwards. Environments without a clock return `None`.
+    ///
+    /// This is what time-based strategies, such as
+    /// [`throttle_writes`](crate::strategied_rwlock::strategies::throttle_writes), tell time with.
+    fn now() -> Option<Duration>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 /// The core primitive for interacting with a thread environment, independent of the OS.
@@ -80,6 +144,8 @@ pub trait ThreadEnv {
 ///    thread (if any are in progress).
 ///  - `park` ideally should block, but is not required to (due to implementations permitting
 ///    spurious wakeups).
+///  - An `unpark` that happens before the matching `park` should cause that `park` to return
+///    promptly, like [`std::thread::park`]'s token.
 ///
 /// It is a logic error for `unpark` to not satisfy the above properties.
 ///
@@ -88,6 +154,17 @@ pub unsafe trait Handle: ThreadEnv {
     where
         Self: Sized;
 
+    /// Like [`new`](Handle::new), but reports running out of unique [`HandleId`]s as a
+    /// [`HandleError`] instead of panicking.
+    ///
+    /// The default implementation calls `new`, and so inherits whatever `new` does on exhaustion.
+    fn try_new() -> Result<Self, HandleError>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+
     fn dumb() -> Self
     where
         Self: Sized,
@@ -98,11 +175,41 @@ pub unsafe trait Handle: ThreadEnv {
     fn id(&self) -> HandleId;
     fn park(&self);
     fn unpark(&self);
+
+    /// Returns whether a thread is currently parked on this handle. This is a best-effort snapshot
+    /// for diagnostics, which may be outdated by the time it returns, so it must not be used to
+    /// decide whether an [`unpark`](Handle::unpark) is needed.
+    ///
+    /// The default implementation returns `false`, for environments that can't tell.
+    fn is_parked(&self) -> bool {
+        false
+    }
+
+    /// Unparks every handle in `handles`, e.g. all the readers admitted at once.
+    ///
+    /// The default implementation calls [`unpark`](Handle::unpark) on each handle in turn.
+    /// Environments that can wake several threads with a single call should override it.
+    fn unpark_all<'h>(handles: impl IntoIterator<Item = &'h Self>)
+    where
+        Self: Sized + 'h,
+    {
+        handles.into_iter().for_each(Self::unpark);
+    }
+
+    /// Like [`park`](Handle::park), but returns after at most `timeout`, even if not unparked.
+    ///
+    /// The default implementation forwards to `park`, which is allowed as `park` may wake up
+    /// spuriously anyway. Callers must check their deadline themselves after this returns.
+    fn park_timeout(&self, _timeout: Duration) {
+        self.park();
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct CoreThreadEnv;
 impl ThreadEnv for CoreThreadEnv {
+    type Handle = CoreHandle;
+
     fn yield_now()
     where
         Self: Sized,
@@ -122,12 +229,21 @@ impl ThreadEnv for CoreThreadEnv {
 pub struct CoreHandle(HandleId);
 
 impl ThreadEnv for CoreHandle {
+    type Handle = Self;
+
     fn yield_now()
     where
         Self: Sized,
     {
         CoreThreadEnv::yield_now();
     }
+
+    fn sleep(duration: Duration)
+    where
+        Self: Sized,
+    {
+        CoreThreadEnv::sleep(duration);
+    }
 }
 
 unsafe impl Handle for CoreHandle {
@@ -138,6 +254,13 @@ unsafe impl Handle for CoreHandle {
         Self(HandleId::new())
     }
 
+    fn try_new() -> Result<Self, HandleError>
+    where
+        Self: Sized,
+    {
+        HandleId::try_new().map(Self)
+    }
+
     fn dumb() -> Self
     where
         Self: Sized,
@@ -158,16 +281,26 @@ unsafe impl Handle for CoreHandle {
 
 #[cfg(feature = "std")]
 mod std_handle {
-    use super::{Handle, HandleId, ThreadEnv};
+    use super::{Handle, HandleError, HandleId, ThreadEnv};
 
     #[cfg(feature = "std")]
     extern crate std;
 
-    use std::thread::{self, Thread};
+    use core::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+    use std::{
+        sync::OnceLock,
+        thread::{self, Thread},
+        time::Instant,
+    };
 
     #[derive(Debug, Clone, Copy)]
     pub struct StdThreadEnv;
     impl ThreadEnv for StdThreadEnv {
+        type Handle = StdHandle;
+
         fn yield_now() {
             thread::yield_now();
         }
@@ -175,15 +308,54 @@ mod std_handle {
         fn panicking() -> bool {
             thread::panicking()
         }
+
+        fn sleep(duration: Duration) {
+            thread::sleep(duration);
+        }
+
+        fn current_id() -> Option<HandleId> {
+            std::thread_local! {
+                static CURRENT_ID: HandleId = HandleId::new();
+            }
+            Some(CURRENT_ID.with(|id| *id))
+        }
+
+        fn now() -> Option<Duration> {
+            static EPOCH: OnceLock<Instant> = OnceLock::new();
+            Some(EPOCH.get_or_init(Instant::now).elapsed())
+        }
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug)]
     pub struct StdHandle {
         id: HandleId,
         thread: Thread,
+        parked: AtomicBool,
+    }
+
+    impl StdHandle {
+        fn with_id(id: HandleId) -> Self {
+            Self {
+                id,
+                thread: thread::current(),
+                parked: AtomicBool::new(false),
+            }
+        }
+    }
+
+    impl Clone for StdHandle {
+        fn clone(&self) -> Self {
+            Self {
+                id: self.id,
+                thread: self.thread.clone(),
+                parked: AtomicBool::new(self.parked.load(Ordering::Relaxed)),
+            }
+        }
     }
 
     impl ThreadEnv for StdHandle {
+        type Handle = Self;
+
         fn yield_now() {
             StdThreadEnv::yield_now();
         }
@@ -191,9 +363,132 @@ mod std_handle {
         fn panicking() -> bool {
             StdThreadEnv::panicking()
         }
+
+        fn sleep(duration: Duration) {
+            StdThreadEnv::sleep(duration);
+        }
+
+        fn current_id() -> Option<HandleId> {
+            StdThreadEnv::current_id()
+        }
+
+        fn now() -> Option<Duration> {
+            StdThreadEnv::now()
+        }
     }
 
     unsafe impl Handle for StdHandle {
+        fn new() -> Self
+        where
+            Self: Sized,
+        {
+            Self::with_id(HandleId::new())
+        }
+
+        fn try_new() -> Result<Self, HandleError>
+        where
+            Self: Sized,
+        {
+            HandleId::try_new().map(Self::with_id)
+        }
+
+        fn dumb() -> Self
+        where
+            Self: Sized,
+        {
+            Self::with_id(HandleId::new_dumb())
+        }
+
+        fn id(&self) -> HandleId {
+            self.id
+        }
+
+        fn park(&self) {
+            assert_eq!(thread::current().id(), self.thread.id());
+            self.parked.store(true, Ordering::Relaxed);
+            thread::park();
+            self.parked.store(false, Ordering::Relaxed);
+        }
+
+        fn unpark(&self) {
+            self.thread.unpark();
+        }
+
+        fn is_parked(&self) -> bool {
+            self.parked.load(Ordering::Relaxed)
+        }
+
+        fn park_timeout(&self, timeout: Duration) {
+            assert_eq!(thread::current().id(), self.thread.id());
+            self.parked.store(true, Ordering::Relaxed);
+            thread::park_timeout(timeout);
+            self.parked.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use std_handle::*;
+
+/// A thread environment backed by [loom](https://docs.rs/loom)'s threads, for model checking the
+/// locks under `cfg(loom)`.
+#[cfg(loom)]
+mod loom_handle {
+    use super::{Handle, HandleError, HandleId, ThreadEnv};
+
+    extern crate std;
+
+    use core::time::Duration;
+    use loom::thread::{self, Thread};
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct LoomThreadEnv;
+    impl ThreadEnv for LoomThreadEnv {
+        type Handle = LoomHandle;
+
+        fn yield_now() {
+            thread::yield_now();
+        }
+
+        fn panicking() -> bool {
+            std::thread::panicking()
+        }
+
+        fn current_id() -> Option<HandleId> {
+            loom::thread_local! {
+                static CURRENT_ID: HandleId = HandleId::new();
+            }
+            Some(CURRENT_ID.with(|id| *id))
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct LoomHandle {
+        id: HandleId,
+        thread: Thread,
+    }
+
+    impl ThreadEnv for LoomHandle {
+        type Handle = Self;
+
+        fn yield_now() {
+            LoomThreadEnv::yield_now();
+        }
+
+        fn panicking() -> bool {
+            LoomThreadEnv::panicking()
+        }
+
+        fn sleep(duration: Duration) {
+            LoomThreadEnv::sleep(duration);
+        }
+
+        fn current_id() -> Option<HandleId> {
+            LoomThreadEnv::current_id()
+        }
+    }
+
+    unsafe impl Handle for LoomHandle {
         fn new() -> Self
         where
             Self: Sized,
@@ -204,6 +499,16 @@ mod std_handle {
             }
         }
 
+        fn try_new() -> Result<Self, HandleError>
+        where
+            Self: Sized,
+        {
+            Ok(Self {
+                id: HandleId::try_new()?,
+                thread: thread::current(),
+            })
+        }
+
         fn dumb() -> Self
         where
             Self: Sized,
@@ -219,7 +524,6 @@ mod std_handle {
         }
 
         fn park(&self) {
-            assert_eq!(thread::current().id(), self.thread.id());
             thread::park();
         }
 
@@ -229,5 +533,48 @@ mod std_handle {
     }
 }
 
+#[cfg(loom)]
+pub use loom_handle::*;
+
+/// Returns a waiting function that parks the given handle until `timeout` has elapsed from now,
+/// and returns `false` once the deadline has passed.
 #[cfg(feature = "std")]
-pub use std_handle::*;
+pub(crate) fn park_until<H: Handle>(timeout: Duration) -> impl FnMut(&H) -> bool {
+    let mut park = park_capped_until(timeout);
+    move |handle| park(handle, None)
+}
+
+/// Like [`park_until`], but the waiting function also takes the longest it may park for at once,
+/// if any.
+#[cfg(feature = "std")]
+pub(crate) fn park_capped_until<H: Handle>(
+    timeout: Duration,
+) -> impl FnMut(&H, Option<Duration>) -> bool {
+    extern crate std;
+    use std::time::Instant;
+
+    // A deadline that can't be represented is as good as no deadline at all.
+    let deadline = Instant::now().checked_add(timeout);
+    move |handle, cap| match deadline {
+        Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+            Some(remaining) if !remaining.is_zero() => {
+                handle.park_timeout(cap.map_or(remaining, |cap| cap.min(remaining)));
+                true
+            }
+            _ => false,
+        },
+        None => {
+            park_capped(handle, cap);
+            true
+        }
+    }
+}
+
+/// Parks the given handle, for at most `cap` if given.
+#[cfg(any(feature = "std", feature = "rwlock"))]
+pub(crate) fn park_capped<H: Handle>(handle: &H, cap: Option<Duration>) {
+    match cap {
+        Some(cap) => handle.park_timeout(cap),
+        None => handle.park(),
+    }
+}
@@ -0,0 +1,55 @@
+use super::{HandleId, Method, ThreadEnv};
+
+/// Describes a lock operation to the hook of a lock, such as a
+/// [`MutexHook`](crate::mutex::MutexHook).
+///
+/// The same context type is passed to every callback, so a single hook can implement policies
+/// that depend on who is accessing the lock, and how.
+#[derive(Debug, Clone, Copy)]
+pub struct HookContext {
+    method: Method,
+    contended: bool,
+    current_id: fn() -> Option<HandleId>,
+    label: Option<&'static str>,
+}
+
+impl HookContext {
+    pub(crate) fn new<Env: ThreadEnv>(
+        method: Method,
+        contended: bool,
+        label: Option<&'static str>,
+    ) -> Self {
+        Self {
+            method,
+            contended,
+            current_id: Env::current_id,
+            label,
+        }
+    }
+
+    /// The kind of access that is being acquired or released. Mutexes always report
+    /// [`Method::Write`].
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Whether other threads were involved in this operation.
+    ///
+    /// When acquiring, this is `true` if the lock was held by another thread at the time. When
+    /// releasing, this is `true` if other threads were waiting for the lock.
+    pub fn contended(&self) -> bool {
+        self.contended
+    }
+
+    /// The id of the thread performing this operation, or `None` if the thread environment of the
+    /// lock can't tell threads apart. See [`ThreadEnv::current_id`].
+    pub fn handle_id(&self) -> Option<HandleId> {
+        (self.current_id)()
+    }
+
+    /// The label of the lock, if it was given one, e.g. with `BaseMutex::new_named`. Locks can only
+    /// be labeled with the `labels` feature.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+}
@@ -0,0 +1,34 @@
+/// The label of a lock, as given by e.g. `BaseMutex::new_named`.
+///
+/// It is only stored with the `labels` feature. Without it, this is zero-sized, so that locks don't
+/// pay for a label they can't be given.
+#[cfg(feature = "labels")]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Label(Option<&'static str>);
+
+#[cfg(feature = "labels")]
+impl Label {
+    pub(crate) const NONE: Self = Self(None);
+
+    pub(crate) const fn new(label: &'static str) -> Self {
+        Self(Some(label))
+    }
+
+    pub(crate) const fn get(self) -> Option<&'static str> {
+        self.0
+    }
+}
+
+/// The label of a lock, which is only stored with the `labels` feature.
+#[cfg(not(feature = "labels"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Label;
+
+#[cfg(not(feature = "labels"))]
+impl Label {
+    pub(crate) const NONE: Self = Self;
+
+    pub(crate) const fn get(self) -> Option<&'static str> {
+        None
+    }
+}
@@ -0,0 +1,35 @@
+use super::sync::native_atomic::{AtomicU64, Ordering};
+
+static LOCK_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// The id that [`multi::lock_all`](crate::multi::lock_all) orders a lock by.
+///
+/// It is only drawn from the counter on first use, so that locks stay `const`-constructible and
+/// locks that are never passed to `lock_all` don't use up ids. Once drawn, it never changes, even
+/// if the lock is moved.
+#[derive(Debug)]
+pub(crate) struct LockId(AtomicU64);
+
+impl LockId {
+    pub(crate) const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub(crate) fn get(&self) -> u64 {
+        // Uniqueness only relies on every lock keeping the first id stored, so no ordering with
+        // other memory is needed.
+        match self.0.load(Ordering::Relaxed) {
+            0 => {
+                let id = LOCK_COUNTER.fetch_add(1, Ordering::Relaxed);
+                match self
+                    .0
+                    .compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => id,
+                    Err(stored) => stored,
+                }
+            }
+            id => id,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::{LockResult, PoisonError};
+
+/// Convenience methods for working with [`LockResult`]s.
+pub trait LockResultExt<T> {
+    /// Returns the contained value, regardless of whether the lock was poisoned or not.
+    ///
+    /// This is shorthand for `result.unwrap_or_else(PoisonError::into_inner)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::{LockResult, LockResultExt, PoisonError};
+    /// let result: LockResult<i32> = Ok(1);
+    /// assert_eq!(result.ignore_poison(), 1);
+    ///
+    /// let result: LockResult<i32> = Err(PoisonError::new(2));
+    /// assert_eq!(result.ignore_poison(), 2);
+    /// ```
+    ///
+    fn ignore_poison(self) -> T;
+
+    /// Returns the value contained in the [`PoisonError`].
+    ///
+    /// # Panics
+    /// Panics if the lock was not poisoned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::{LockResult, LockResultExt, PoisonError};
+    /// let result: LockResult<i32> = Err(PoisonError::new(2));
+    /// assert_eq!(result.unwrap_poisoned(), 2);
+    /// ```
+    ///
+    #[track_caller]
+    fn unwrap_poisoned(self) -> T;
+
+    /// Maps the contained value with `f`, preserving whether the lock was poisoned or not.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::primitives::{LockResult, LockResultExt, PoisonError};
+    /// let result: LockResult<i32> = Ok(1);
+    /// assert_eq!(result.map_guard(|value| value * 2).unwrap(), 2);
+    ///
+    /// let result: LockResult<i32> = Err(PoisonError::new(2));
+    /// assert_eq!(result.map_guard(|value| value * 2).unwrap_poisoned(), 4);
+    /// ```
+    ///
+    fn map_guard<U>(self, f: impl FnOnce(T) -> U) -> LockResult<U>;
+}
+
+impl<T> LockResultExt<T> for LockResult<T> {
+    fn ignore_poison(self) -> T {
+        self.unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[track_caller]
+    fn unwrap_poisoned(self) -> T {
+        match self {
+            Ok(_) => panic!("called `LockResultExt::unwrap_poisoned()` on an unpoisoned result"),
+            Err(poison) => poison.into_inner(),
+        }
+    }
+
+    fn map_guard<U>(self, f: impl FnOnce(T) -> U) -> LockResult<U> {
+        match self {
+            Ok(t) => Ok(f(t)),
+            Err(poison) => Err(poison.map(f)),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use super::{forward_guard_mut_traits, forward_guard_traits, stable_guard};
+
+pub(crate) mod sealed {
+    /// Keeps [`StableGuard`](super::StableGuard) from being implemented outside of this crate.
+    pub trait Sealed {}
+}
+
+/// A guard of this crate whose data lives in its lock, rather than in the guard itself, so that
+/// borrows of the data stay valid when the guard is moved. [`MappedGuard::new`] only maps these.
+///
+/// This trait is sealed. Guards in general may hold their data inline, as e.g.
+/// [`ManuallyDrop`](core::mem::ManuallyDrop) does, which a `MappedGuard` would then outlive:
+/// ```compile_fail
+/// # use core::mem::ManuallyDrop;
+/// # use powerlocks::primitives::MappedGuard;
+/// let y = MappedGuard::new(ManuallyDrop::new((1, 2)), |point| &mut point.1);
+/// ```
+///
+/// # Safety
+/// Moving the guard must neither move its target, nor invalidate borrows of it.
+pub unsafe trait StableGuard: DerefMut + sealed::Sealed {}
+
+/// A guard that gives access to a part of the data protected by another guard, such as a field of
+/// a struct.
+///
+/// The lock is released when this guard is dropped, just like the original guard.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "mutex", feature = "std"))] {
+/// # use powerlocks::{mutex::Mutex, primitives::MappedGuard};
+/// let point = Mutex::new((1, 2));
+///
+/// let mut y = MappedGuard::new(point.lock().unwrap(), |point| &mut point.1);
+/// *y += 1;
+/// drop(y);
+///
+/// assert_eq!(*point.lock().unwrap(), (1, 3));
+/// # }
+/// ```
+///
+/// See also: [`std::sync::MappedMutexGuard`]
+pub struct MappedGuard<G, U>
+where
+    U: ?Sized,
+{
+    // Never dereferenced, so that `data` stays valid. `guard` only owns the lock from now on, and
+    // being a `StableGuard`, moving it leaves `data` in place.
+    guard: G,
+    data: NonNull<U>,
+}
+
+impl<G, U> MappedGuard<G, U>
+where
+    G: StableGuard,
+    U: ?Sized,
+{
+    /// Maps `guard` to the part of its data returned by `f`.
+    pub fn new(mut guard: G, f: impl FnOnce(&mut G::Target) -> &mut U) -> Self {
+        let data = NonNull::from(f(&mut *guard));
+        Self { guard, data }
+    }
+}
+
+impl<G, U> MappedGuard<G, U>
+where
+    U: ?Sized,
+{
+    /// Maps this guard further, to the part of its data returned by `f`.
+    ///
+    /// This is an associated function, so that it doesn't shadow any method of the data.
+    pub fn map<V: ?Sized>(mut this: Self, f: impl FnOnce(&mut U) -> &mut V) -> MappedGuard<G, V> {
+        let data = NonNull::from(f(&mut *this));
+        MappedGuard {
+            guard: this.guard,
+            data,
+        }
+    }
+}
+
+impl<G, U> Deref for MappedGuard<G, U>
+where
+    U: ?Sized,
+{
+    type Target = U;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `data` was derived from a unique borrow of the data protected by `guard`, which
+        // keeps the lock held for as long as we live.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<G, U> DerefMut for MappedGuard<G, U>
+where
+    U: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As in `deref`, and `&mut self` guarantees that this borrow is unique.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+forward_guard_traits! {
+    impl<G, U> MappedGuard<G, U> => U
+    where
+        U: ?Sized,
+}
+
+forward_guard_mut_traits! {
+    impl<G, U> MappedGuard<G, U> => U
+    where
+        U: ?Sized,
+}
+
+stable_guard! {
+    impl<G, U> MappedGuard<G, U>
+    where
+        U: ?Sized,
+}
+
+// SAFETY: We only hand out borrows of `U` tied to `self`, and `G` is what releases the lock.
+unsafe impl<G, U> Send for MappedGuard<G, U>
+where
+    G: Send,
+    U: ?Sized + Send,
+{
+}
+
+// SAFETY: As above, sharing `self` only shares `&U`.
+unsafe impl<G, U> Sync for MappedGuard<G, U>
+where
+    G: Sync,
+    U: ?Sized + Sync,
+{
+}
+
+impl<G, U> Debug for MappedGuard<G, U>
+where
+    U: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
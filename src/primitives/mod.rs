@@ -1,10 +1,79 @@
 mod poison;
 pub use poison::*;
 
+mod poison_policy;
+pub use poison_policy::*;
+
+mod lock_result_ext;
+pub use lock_result_ext::*;
+
 mod enums;
 pub use enums::*;
 
+mod guard_marker;
+pub use guard_marker::*;
+
 #[cfg(feature = "mutex")]
 mod handle;
 #[cfg(feature = "mutex")]
 pub use handle::*;
+
+#[cfg(feature = "mutex")]
+mod async_handle;
+#[cfg(feature = "mutex")]
+pub use async_handle::*;
+
+#[cfg(all(feature = "mutex", feature = "alloc"))]
+mod parker;
+#[cfg(all(feature = "mutex", feature = "alloc"))]
+pub use parker::*;
+
+#[cfg(feature = "mutex")]
+mod hook_context;
+#[cfg(feature = "mutex")]
+pub use hook_context::*;
+
+#[cfg(feature = "mutex")]
+mod wait_queue;
+#[cfg(feature = "mutex")]
+pub(crate) use wait_queue::*;
+
+#[cfg(feature = "mutex")]
+pub(crate) mod sync;
+
+#[cfg(feature = "mutex")]
+mod backoff;
+#[cfg(feature = "mutex")]
+pub(crate) use backoff::*;
+
+#[cfg(feature = "mutex")]
+mod on_drop;
+#[cfg(feature = "mutex")]
+pub(crate) use on_drop::*;
+
+#[cfg(feature = "mutex")]
+mod label;
+#[cfg(feature = "mutex")]
+pub(crate) use label::*;
+
+#[cfg(feature = "multi")]
+mod lock_id;
+#[cfg(feature = "multi")]
+pub(crate) use lock_id::*;
+
+#[cfg(feature = "mutex")]
+mod waiting;
+#[cfg(feature = "mutex")]
+pub(crate) use waiting::*;
+
+#[cfg(all(feature = "mutex", feature = "std"))]
+mod test_handle;
+#[cfg(all(feature = "mutex", feature = "std"))]
+pub use test_handle::*;
+
+mod mapped_guard;
+pub(crate) use mapped_guard::sealed::Sealed as SealedGuard;
+pub use mapped_guard::*;
+
+mod guard_traits;
+pub(crate) use guard_traits::*;
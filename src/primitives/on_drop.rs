@@ -0,0 +1,8 @@
+/// Runs the wrapped closure when dropped, including while unwinding.
+pub(crate) struct OnDrop<F: FnMut()>(pub(crate) F);
+
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)();
+    }
+}
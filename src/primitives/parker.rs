@@ -0,0 +1,141 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    time::Duration,
+};
+
+extern crate alloc;
+use alloc::sync::Arc;
+
+use super::{
+    Handle,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+struct Inner<H: Handle> {
+    handle: H,
+    token: AtomicBool,
+}
+
+/// Creates a [`Parker`] for the current thread, and the [`Unparker`] that wakes it up.
+///
+/// This is the parking token that the locks of the crate are built on, for building other
+/// synchronization primitives on any [`Handle`]. Like [`std::thread::park`], the pair holds a
+/// single token: [`unpark`](Unparker::unpark) makes it available, and
+/// [`park`](Parker::park) blocks until it is, and consumes it. Unlike the raw [`Handle`], `park`
+/// never returns spuriously.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use std::thread;
+/// # use powerlocks::primitives::{StdHandle, parker};
+/// let (parker, unparker) = parker::<StdHandle>();
+/// thread::spawn(move || unparker.unpark());
+/// parker.park();
+/// # }
+/// ```
+///
+pub fn parker<H: Handle>() -> (Parker<H>, Unparker<H>) {
+    let inner = Arc::new(Inner {
+        handle: H::new(),
+        token: AtomicBool::new(false),
+    });
+    let unparker = Unparker {
+        inner: Arc::clone(&inner),
+    };
+    (
+        Parker {
+            inner,
+            not_send: PhantomData,
+        },
+        unparker,
+    )
+}
+
+/// Blocks the thread that created it until its [`Unparker`] is used, see [`parker`].
+///
+/// The parker stays on the thread that created it, as its [`Handle`] wakes that thread.
+pub struct Parker<H: Handle> {
+    inner: Arc<Inner<H>>,
+    not_send: PhantomData<*const ()>,
+}
+
+impl<H: Handle> Parker<H> {
+    /// Blocks until the token is available, and consumes it.
+    pub fn park(&self) {
+        while !self.try_consume() {
+            self.inner.handle.park();
+        }
+    }
+
+    /// Blocks until the token is available, and consumes it, or until `timeout` has passed.
+    /// Returns `true` if the token was consumed.
+    ///
+    /// The timeout is told with [`ThreadEnv::now`](super::ThreadEnv::now) of `H`. In
+    /// environments without a clock, this parks once, and may return `false` early.
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        let deadline = H::now().map(|now| now.saturating_add(timeout));
+        loop {
+            if self.try_consume() {
+                return true;
+            }
+            let remaining = match (deadline, H::now()) {
+                (Some(deadline), Some(now)) if now < deadline => deadline - now,
+                (Some(_), _) => return false,
+                (None, _) => {
+                    self.inner.handle.park_timeout(timeout);
+                    return self.try_consume();
+                }
+            };
+            self.inner.handle.park_timeout(remaining);
+        }
+    }
+
+    /// Returns another [`Unparker`] for this parker.
+    pub fn unparker(&self) -> Unparker<H> {
+        Unparker {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    fn try_consume(&self) -> bool {
+        self.inner.token.swap(false, Ordering::Acquire)
+    }
+}
+
+impl<H: Handle> Debug for Parker<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parker").finish_non_exhaustive()
+    }
+}
+
+/// Wakes up the thread of a [`Parker`], see [`parker`].
+pub struct Unparker<H: Handle> {
+    inner: Arc<Inner<H>>,
+}
+
+impl<H: Handle> Unparker<H> {
+    /// Makes the token available, waking up the parker if it is parked. If it isn't, its next
+    /// [`park`](Parker::park) returns straight away.
+    pub fn unpark(&self) {
+        // A read-modify-write, like the one of `park`, so that it is ordered after the last token
+        // taken.
+        self.inner.token.swap(true, Ordering::Release);
+        self.inner.handle.unpark();
+    }
+}
+
+impl<H: Handle> Clone for Unparker<H> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<H: Handle> Debug for Unparker<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unparker").finish_non_exhaustive()
+    }
+}
@@ -16,17 +16,25 @@ use core::{
 /// See also: [`std::sync::PoisonError`].
 pub struct PoisonError<T> {
     data: T,
+    label: Option<&'static str>,
 }
 
 impl<T> Debug for PoisonError<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("PoisonError").finish_non_exhaustive()
+        let mut debug = f.debug_struct("PoisonError");
+        if let Some(label) = self.label {
+            debug.field("label", &label);
+        }
+        debug.finish_non_exhaustive()
     }
 }
 
 impl<T> Display for PoisonError<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt("poisoned lock: another task failed inside", f)
+        match self.label {
+            Some(label) => write!(f, "poisoned lock {label:?}: another task failed inside"),
+            None => Display::fmt("poisoned lock: another task failed inside", f),
+        }
     }
 }
 
@@ -38,7 +46,7 @@ impl<T> PoisonError<T> {
     /// See also: [`std::sync::PoisonError::new`].
     pub fn new(data: T) -> PoisonError<T> {
         if cfg!(panic = "unwind") {
-            PoisonError { data }
+            PoisonError { data, label: None }
         } else {
             panic!("`PoisonError` created in `primitives` built with panic=\"abort\"");
         }
@@ -67,6 +75,24 @@ impl<T> PoisonError<T> {
     pub fn get_mut(&mut self) -> &mut T {
         &mut self.data
     }
+
+    /// Returns the label of the poisoned lock, if it was given one, e.g. with
+    /// `BaseMutex::new_named`. Locks can only be labeled with the `labels` feature.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    pub(crate) fn with_label(self, label: Option<&'static str>) -> Self {
+        Self { label, ..self }
+    }
+
+    /// Maps the associated data with `f`, keeping the label.
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> PoisonError<U> {
+        PoisonError {
+            data: f(self.data),
+            label: self.label,
+        }
+    }
 }
 
 /// An enumeration of possible errors associated with a [`TryLockResult`] which
@@ -80,6 +106,25 @@ pub enum TryLockError<T> {
     /// The lock could not be acquired at this time because the operation would
     /// otherwise block.
     WouldBlock,
+    /// The lock could not be acquired because the acquisition was cancelled while it was waiting,
+    /// e.g. by [`BaseMutex::lock_until`](crate::mutex::BaseMutex::lock_until).
+    Cancelled,
+    /// The lock could not be acquired before the deadline of the acquisition passed, e.g. in
+    /// [`BaseRwLock::read_timeout`](crate::strategied_rwlock::BaseRwLock::read_timeout).
+    TimedOut,
+}
+
+impl<T> TryLockError<T> {
+    /// Maps the guard of a [`Poisoned`](TryLockError::Poisoned) error with `f`.
+    #[cfg(feature = "rwlock")]
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> TryLockError<U> {
+        match self {
+            TryLockError::Poisoned(poison) => TryLockError::Poisoned(poison.map(f)),
+            TryLockError::WouldBlock => TryLockError::WouldBlock,
+            TryLockError::Cancelled => TryLockError::Cancelled,
+            TryLockError::TimedOut => TryLockError::TimedOut,
+        }
+    }
 }
 
 impl<T> From<PoisonError<T>> for TryLockError<T> {
@@ -93,6 +138,8 @@ impl<T> Debug for TryLockError<T> {
         match *self {
             TryLockError::Poisoned(..) => Debug::fmt("Poisoned(..)", f),
             TryLockError::WouldBlock => Debug::fmt("WouldBlock", f),
+            TryLockError::Cancelled => Debug::fmt("Cancelled", f),
+            TryLockError::TimedOut => Debug::fmt("TimedOut", f),
         }
     }
 }
@@ -100,9 +147,11 @@ impl<T> Debug for TryLockError<T> {
 impl<T> Display for TryLockError<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         Display::fmt(
-            match *self {
-                TryLockError::Poisoned(..) => "poisoned lock: another task failed inside",
+            match self {
+                TryLockError::Poisoned(poison) => return Display::fmt(poison, f),
                 TryLockError::WouldBlock => "try_lock failed because the operation would block",
+                TryLockError::Cancelled => "lock acquisition was cancelled",
+                TryLockError::TimedOut => "lock acquisition timed out",
             },
             f,
         )
@@ -151,7 +200,11 @@ pub mod conversions {
         fn from(value: super::TryLockError<T>) -> Self {
             match value {
                 super::TryLockError::Poisoned(guard) => Self::Poisoned(guard.into()),
-                super::TryLockError::WouldBlock => Self::WouldBlock,
+                // `std` has no notion of cancellation or deadlines. The lock was not acquired,
+                // which is the closest to having to block.
+                super::TryLockError::WouldBlock
+                | super::TryLockError::Cancelled
+                | super::TryLockError::TimedOut => Self::WouldBlock,
             }
         }
     }
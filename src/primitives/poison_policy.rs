@@ -0,0 +1,289 @@
+// Not `sync::atomic`, as `UNPOISONED` has to be built in a `const` context even under `cfg(loom)`.
+#[cfg(all(not(feature = "portable-atomic"), not(feature = "no-poison")))]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(all(feature = "portable-atomic", not(feature = "no-poison")))]
+use portable_atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "mutex")]
+use super::ThreadEnv;
+use super::{LockResult, LockResultExt, PoisonError, TryLockError, TryLockResult};
+
+/// The flag behind the policies that track poisoning. With the `no-poison` feature it stores
+/// nothing and is never set, so locks shrink and their poison checks fold away.
+#[derive(Debug)]
+struct PoisonFlag(#[cfg(not(feature = "no-poison"))] AtomicBool);
+
+impl PoisonFlag {
+    const fn new() -> Self {
+        Self(
+            #[cfg(not(feature = "no-poison"))]
+            AtomicBool::new(false),
+        )
+    }
+
+    fn get(&self) -> bool {
+        #[cfg(not(feature = "no-poison"))]
+        return self.0.load(Ordering::Acquire);
+        #[cfg(feature = "no-poison")]
+        false
+    }
+
+    /// Sets the flag if `poison` is `true`, returning whether it was clear before.
+    #[cfg_attr(
+        feature = "no-poison",
+        allow(unused_variables, reason = "Nothing is poisoned with `no-poison`.")
+    )]
+    fn set(&self, poison: bool) -> bool {
+        #[cfg(not(feature = "no-poison"))]
+        return poison && !self.0.fetch_or(poison, Ordering::AcqRel);
+        #[cfg(feature = "no-poison")]
+        false
+    }
+
+    fn clear(&self) {
+        #[cfg(not(feature = "no-poison"))]
+        self.0.store(false, Ordering::Release);
+    }
+
+    /// Clears the flag, returning whether it was set.
+    fn take(&self) -> bool {
+        #[cfg(not(feature = "no-poison"))]
+        return self.0.swap(false, Ordering::AcqRel);
+        #[cfg(feature = "no-poison")]
+        false
+    }
+}
+
+/// Returns whether the current thread is panicking, for a lock to poison itself when released.
+///
+/// Always `false` with the `no-poison` feature, which spares the check on every release.
+#[cfg(feature = "mutex")]
+pub(crate) fn panicking<Env: ThreadEnv>() -> bool {
+    !cfg!(feature = "no-poison") && Env::panicking()
+}
+
+/// Controls how a lock tracks and reports poisoning.
+///
+/// Locks store their `PoisonPolicy` inline, so policies that don't track poisoning (such as
+/// [`NoPoison`]) add no state to the lock at all.
+pub trait PoisonPolicy: Send + Sync {
+    /// The type returned by blocking acquisitions (and accessors such as `get_mut`) under this
+    /// policy.
+    type Result<G>;
+
+    /// The initial, unpoisoned state of this policy.
+    const UNPOISONED: Self;
+
+    /// Returns `true` if the lock is currently poisoned.
+    fn is_poisoned(&self) -> bool;
+
+    /// Marks the lock as poisoned if `poison` is `true`, returning `true` if this newly poisoned
+    /// it, rather than it being poisoned already.
+    fn poison(&self, poison: bool) -> bool;
+
+    /// Clears the poisoned state of the lock.
+    fn clear_poison(&self);
+
+    /// Wraps `guard` according to the current poisoned state of the lock.
+    fn wrap<G>(&self, guard: G) -> Self::Result<G>;
+
+    /// Converts a result of this policy into a [`LockResult`], for use in the generic lock APIs.
+    fn into_lock_result<G>(result: Self::Result<G>) -> LockResult<G>;
+
+    /// Maps the guard or value held by a result of this policy with `f`, preserving whether the
+    /// lock was reported as poisoned.
+    fn map_result<G, U>(result: Self::Result<G>, f: impl FnOnce(G) -> U) -> Self::Result<U>;
+
+    /// Wraps `guard` according to the current poisoned state of the lock, as a [`TryLockResult`].
+    fn try_wrap<G>(&self, guard: G) -> TryLockResult<G> {
+        Self::into_lock_result(self.wrap(guard)).map_err(TryLockError::Poisoned)
+    }
+
+    /// Wraps `guard` like [`wrap`](PoisonPolicy::wrap), attaching the `label` of the lock to any
+    /// [`PoisonError`] reported. Policies that report poisoning should override this.
+    fn wrap_labeled<G>(&self, guard: G, _label: Option<&'static str>) -> Self::Result<G> {
+        self.wrap(guard)
+    }
+
+    /// Wraps `guard` like [`try_wrap`](PoisonPolicy::try_wrap), attaching the `label` of the lock
+    /// to any [`PoisonError`] reported.
+    fn try_wrap_labeled<G>(&self, guard: G, label: Option<&'static str>) -> TryLockResult<G> {
+        Self::into_lock_result(self.wrap_labeled(guard, label)).map_err(TryLockError::Poisoned)
+    }
+}
+
+/// The default [`PoisonPolicy`], which poisons the lock whenever a thread panics while holding
+/// exclusive access to it, mirroring the behaviour of the Standard library's locks.
+#[derive(Debug)]
+pub struct Poison(PoisonFlag);
+
+impl PoisonPolicy for Poison {
+    type Result<G> = LockResult<G>;
+
+    #[allow(
+        clippy::declare_interior_mutable_const,
+        reason = "Only ever moved into a new lock."
+    )]
+    const UNPOISONED: Self = Self(PoisonFlag::new());
+
+    fn is_poisoned(&self) -> bool {
+        self.0.get()
+    }
+
+    fn poison(&self, poison: bool) -> bool {
+        self.0.set(poison)
+    }
+
+    fn clear_poison(&self) {
+        self.0.clear();
+    }
+
+    fn wrap<G>(&self, guard: G) -> Self::Result<G> {
+        if self.is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    fn wrap_labeled<G>(&self, guard: G, label: Option<&'static str>) -> Self::Result<G> {
+        self.wrap(guard).map_err(|poison| poison.with_label(label))
+    }
+
+    fn into_lock_result<G>(result: Self::Result<G>) -> LockResult<G> {
+        result
+    }
+
+    fn map_result<G, U>(result: Self::Result<G>, f: impl FnOnce(G) -> U) -> Self::Result<U> {
+        result.map_guard(f)
+    }
+}
+
+/// A [`PoisonPolicy`] that never poisons the lock. Acquisitions return their guard directly rather
+/// than a [`LockResult`], and no poison flag is stored in the lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoPoison;
+
+impl PoisonPolicy for NoPoison {
+    type Result<G> = G;
+
+    const UNPOISONED: Self = Self;
+
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+
+    fn poison(&self, _poison: bool) -> bool {
+        false
+    }
+
+    fn clear_poison(&self) {}
+
+    fn wrap<G>(&self, guard: G) -> Self::Result<G> {
+        guard
+    }
+
+    fn into_lock_result<G>(result: Self::Result<G>) -> LockResult<G> {
+        Ok(result)
+    }
+
+    fn map_result<G, U>(result: Self::Result<G>, f: impl FnOnce(G) -> U) -> Self::Result<U> {
+        f(result)
+    }
+}
+
+/// A [`PoisonPolicy`] that records poisoning like [`Poison`], but never reports it from an
+/// acquisition. Acquisitions return their guard directly, while
+/// [`is_poisoned`](PoisonPolicy::is_poisoned) still tells whether a thread panicked while holding
+/// the lock.
+///
+/// This suits data that stays valid even if an update is cut short, where handling the poison at
+/// every call site would only be noise.
+#[derive(Debug)]
+pub struct Ignore(PoisonFlag);
+
+impl PoisonPolicy for Ignore {
+    type Result<G> = G;
+
+    #[allow(
+        clippy::declare_interior_mutable_const,
+        reason = "Only ever moved into a new lock."
+    )]
+    const UNPOISONED: Self = Self(PoisonFlag::new());
+
+    fn is_poisoned(&self) -> bool {
+        self.0.get()
+    }
+
+    fn poison(&self, poison: bool) -> bool {
+        self.0.set(poison)
+    }
+
+    fn clear_poison(&self) {
+        self.0.clear();
+    }
+
+    fn wrap<G>(&self, guard: G) -> Self::Result<G> {
+        guard
+    }
+
+    fn into_lock_result<G>(result: Self::Result<G>) -> LockResult<G> {
+        Ok(result)
+    }
+
+    fn map_result<G, U>(result: Self::Result<G>, f: impl FnOnce(G) -> U) -> Self::Result<U> {
+        f(result)
+    }
+}
+
+/// A [`PoisonPolicy`] that reports poisoning like [`Poison`], but only to the first acquisition
+/// after the panic, which clears the poison. Later acquisitions succeed as usual, until a thread
+/// panics while holding the lock again.
+///
+/// This lets exactly one thread notice the panic and repair the data, without the lock staying
+/// poisoned forever.
+#[derive(Debug)]
+pub struct ClearOnNextLock(PoisonFlag);
+
+impl PoisonPolicy for ClearOnNextLock {
+    type Result<G> = LockResult<G>;
+
+    #[allow(
+        clippy::declare_interior_mutable_const,
+        reason = "Only ever moved into a new lock."
+    )]
+    const UNPOISONED: Self = Self(PoisonFlag::new());
+
+    fn is_poisoned(&self) -> bool {
+        self.0.get()
+    }
+
+    fn poison(&self, poison: bool) -> bool {
+        self.0.set(poison)
+    }
+
+    fn clear_poison(&self) {
+        self.0.clear();
+    }
+
+    fn wrap<G>(&self, guard: G) -> Self::Result<G> {
+        // Only swap when poisoned, to keep the common path a plain load.
+        if self.is_poisoned() && self.0.take() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    fn wrap_labeled<G>(&self, guard: G, label: Option<&'static str>) -> Self::Result<G> {
+        self.wrap(guard).map_err(|poison| poison.with_label(label))
+    }
+
+    fn into_lock_result<G>(result: Self::Result<G>) -> LockResult<G> {
+        result
+    }
+
+    fn map_result<G, U>(result: Self::Result<G>, f: impl FnOnce(G) -> U) -> Self::Result<U> {
+        result.map_guard(f)
+    }
+}
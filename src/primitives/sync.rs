@@ -0,0 +1,42 @@
+//! Synchronization primitives that are swapped for [loom](https://docs.rs/loom)'s versions under
+//! `cfg(loom)`, so that loom can exhaustively check the interleavings of the locks.
+
+pub(crate) mod atomic {
+    #[cfg(not(loom))]
+    pub(crate) use super::native_atomic::*;
+
+    #[cfg(loom)]
+    pub(crate) use loom::sync::atomic::*;
+}
+
+/// The atomics of the target, which are kept under `cfg(loom)` for state that has to be built in
+/// a `const` context, such as `static`s. They come from portable-atomic with the `portable-atomic`
+/// feature.
+pub(crate) mod native_atomic {
+    #[cfg(not(feature = "portable-atomic"))]
+    pub(crate) use core::sync::atomic::*;
+
+    #[cfg(feature = "portable-atomic")]
+    pub(crate) use portable_atomic::*;
+}
+
+/// The flag that cancels an acquisition, as in `BaseMutex::lock_until`. It is only ever loaded,
+/// which every target supports natively, so it is `core`'s even with the `portable-atomic` feature.
+#[cfg(not(loom))]
+pub(crate) type CancelFlag = core::sync::atomic::AtomicBool;
+#[cfg(loom)]
+pub(crate) type CancelFlag = loom::sync::atomic::AtomicBool;
+
+/// Declares a `const fn`, which loses its `const` under `cfg(loom)`, as loom's atomics can't be
+/// created in a `const` context.
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis const fn $($rest:tt)*) => {
+        #[cfg(not(loom))]
+        $(#[$attr])* $vis const fn $($rest)*
+
+        #[cfg(loom)]
+        $(#[$attr])* $vis fn $($rest)*
+    };
+}
+
+pub(crate) use const_fn;
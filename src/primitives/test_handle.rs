@@ -0,0 +1,316 @@
+extern crate alloc;
+extern crate std;
+
+use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
+use core::{cell::RefCell, time::Duration};
+use std::{
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, Thread, ThreadId},
+    time::Instant,
+};
+
+use super::{Handle, HandleError, HandleId, StdThreadEnv, ThreadEnv};
+
+#[derive(Debug, Default)]
+struct ControllerState {
+    holding: bool,
+    // Threads blocked in `park`, in the order they parked.
+    parked: Vec<(ThreadId, HandleId)>,
+    // Threads with an unpark token that no `park` has consumed yet.
+    tokens: Vec<ThreadId>,
+    // Unparks held back by `holding`, in the order they happened.
+    pending: VecDeque<(ThreadId, HandleId)>,
+}
+
+impl ControllerState {
+    fn deliver(&mut self, thread: ThreadId) {
+        if !self.tokens.contains(&thread) {
+            self.tokens.push(thread);
+        }
+    }
+
+    fn take_token(&mut self, thread: ThreadId) -> bool {
+        let token = self.tokens.iter().position(|&token| token == thread);
+        token.map(|token| self.tokens.swap_remove(token)).is_some()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Controller {
+    state: Mutex<ControllerState>,
+    changed: Condvar,
+}
+
+/// Drives the parking and unparking of [`TestHandle`]s, so tests can deterministically observe and
+/// control the order in which a lock wakes its waiters.
+///
+/// Threads opt in with [`attach`](TestController::attach). Every `TestHandle` created on an
+/// attached thread reports its parks to the controller, and while the controller is
+/// [`holding`](TestController::set_holding), its unparks are held back until the test releases
+/// them.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "rwlock")] {
+/// # use std::thread;
+/// # use powerlocks::{primitives::{TestController, TestHandle}, strategied_rwlock::BaseRwLock};
+/// let lock = BaseRwLock::<i32, TestHandle>::new(0);
+/// let controller = TestController::new();
+/// controller.attach();
+///
+/// thread::scope(|scope| {
+///     let guard = lock.write().unwrap();
+///     scope.spawn(|| {
+///         controller.attach();
+///         *lock.write().unwrap() += 1;
+///     });
+///     controller.wait_for_parked(1);
+///     let waiter = controller.parked()[0];
+///
+///     controller.set_holding(true);
+///     drop(guard);
+///     assert_eq!(controller.pending(), [waiter]);
+///     controller.release_all();
+/// });
+/// assert_eq!(*lock.read().unwrap(), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TestController(Arc<Controller>);
+
+std::thread_local! {
+    static CURRENT_CONTROLLER: RefCell<Option<TestController>> = const { RefCell::new(None) };
+}
+
+impl TestController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, ControllerState> {
+        self.0.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn current() -> Option<Self> {
+        CURRENT_CONTROLLER.with(|current| current.borrow().clone())
+    }
+
+    /// Attaches the current thread to this controller, so that the `TestHandle`s created on it
+    /// from now on are driven by this controller.
+    pub fn attach(&self) {
+        CURRENT_CONTROLLER.with(|current| *current.borrow_mut() = Some(self.clone()));
+    }
+
+    /// Sets whether unparks are held back until they are released. Turning holding off releases
+    /// every held unpark.
+    pub fn set_holding(&self, holding: bool) {
+        let mut state = self.state();
+        state.holding = holding;
+        if !holding {
+            while let Some((thread, _)) = state.pending.pop_front() {
+                state.deliver(thread);
+            }
+            self.0.changed.notify_all();
+        }
+    }
+
+    /// Returns the handles that are currently parked, in the order they parked.
+    pub fn parked(&self) -> Vec<HandleId> {
+        self.state().parked.iter().map(|&(_, id)| id).collect()
+    }
+
+    /// Blocks until at least `count` handles are parked.
+    pub fn wait_for_parked(&self, count: usize) {
+        let state = self.state();
+        drop(
+            self.0
+                .changed
+                .wait_while(state, |state| state.parked.len() < count)
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+    }
+
+    /// Returns the handles whose unparks are being held back, in the order they were unparked.
+    pub fn pending(&self) -> Vec<HandleId> {
+        self.state().pending.iter().map(|&(_, id)| id).collect()
+    }
+
+    /// Releases the oldest held unpark, returning the handle it was for.
+    pub fn release_next(&self) -> Option<HandleId> {
+        let mut state = self.state();
+        let (thread, id) = state.pending.pop_front()?;
+        state.deliver(thread);
+        self.0.changed.notify_all();
+        Some(id)
+    }
+
+    /// Releases every held unpark, without turning holding off.
+    pub fn release_all(&self) {
+        while self.release_next().is_some() {}
+    }
+
+    fn park(&self, id: HandleId, timeout: Option<Duration>) {
+        let thread = thread::current().id();
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.state();
+        if state.take_token(thread) {
+            return;
+        }
+
+        state.parked.push((thread, id));
+        self.0.changed.notify_all();
+        while !state.take_token(thread) {
+            state = match deadline {
+                Some(deadline) => {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        break;
+                    };
+                    let (state, _) = self
+                        .0
+                        .changed
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(PoisonError::into_inner);
+                    state
+                }
+                None => self
+                    .0
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+        state.parked.retain(|&(parked, _)| parked != thread);
+        self.0.changed.notify_all();
+    }
+
+    fn unpark(&self, thread: ThreadId, id: HandleId) {
+        let mut state = self.state();
+        if state.holding {
+            state.pending.push_back((thread, id));
+        } else {
+            state.deliver(thread);
+            self.0.changed.notify_all();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TestThreadEnv;
+impl ThreadEnv for TestThreadEnv {
+    type Handle = TestHandle;
+
+    fn yield_now() {
+        StdThreadEnv::yield_now();
+    }
+
+    fn panicking() -> bool {
+        StdThreadEnv::panicking()
+    }
+
+    fn sleep(duration: Duration) {
+        StdThreadEnv::sleep(duration);
+    }
+
+    fn current_id() -> Option<HandleId> {
+        StdThreadEnv::current_id()
+    }
+}
+
+/// A [`Handle`] whose parks and unparks are driven by a [`TestController`].
+///
+/// Handles created on threads that aren't attached to a controller, as well as handles from
+/// [`dumb`](Handle::dumb), behave like [`StdHandle`](super::StdHandle)s instead.
+#[derive(Debug, Clone)]
+pub struct TestHandle {
+    id: HandleId,
+    thread: Thread,
+    controller: Option<TestController>,
+}
+
+impl ThreadEnv for TestHandle {
+    type Handle = Self;
+
+    fn yield_now() {
+        TestThreadEnv::yield_now();
+    }
+
+    fn panicking() -> bool {
+        TestThreadEnv::panicking()
+    }
+
+    fn sleep(duration: Duration) {
+        TestThreadEnv::sleep(duration);
+    }
+
+    fn current_id() -> Option<HandleId> {
+        TestThreadEnv::current_id()
+    }
+}
+
+unsafe impl Handle for TestHandle {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            id: HandleId::new(),
+            thread: thread::current(),
+            controller: TestController::current(),
+        }
+    }
+
+    fn try_new() -> Result<Self, HandleError>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            id: HandleId::try_new()?,
+            thread: thread::current(),
+            controller: TestController::current(),
+        })
+    }
+
+    fn dumb() -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            id: HandleId::new_dumb(),
+            thread: thread::current(),
+            controller: None,
+        }
+    }
+
+    fn id(&self) -> HandleId {
+        self.id
+    }
+
+    fn park(&self) {
+        assert_eq!(thread::current().id(), self.thread.id());
+        match &self.controller {
+            Some(controller) => controller.park(self.id, None),
+            None => thread::park(),
+        }
+    }
+
+    fn unpark(&self) {
+        match &self.controller {
+            Some(controller) => controller.unpark(self.thread.id(), self.id),
+            None => self.thread.unpark(),
+        }
+    }
+
+    fn is_parked(&self) -> bool {
+        self.controller
+            .as_ref()
+            .is_some_and(|controller| controller.parked().contains(&self.id))
+    }
+
+    fn park_timeout(&self, timeout: Duration) {
+        assert_eq!(thread::current().id(), self.thread.id());
+        match &self.controller {
+            Some(controller) => controller.park(self.id, Some(timeout)),
+            None => thread::park_timeout(timeout),
+        }
+    }
+}
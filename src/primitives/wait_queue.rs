@@ -0,0 +1,365 @@
+use core::{
+    cell::{Cell, UnsafeCell},
+    fmt,
+    marker::PhantomData,
+    ptr,
+};
+
+use super::sync::{
+    atomic::{AtomicBool, Ordering, fence},
+    const_fn, native_atomic,
+};
+
+use super::Handle;
+
+/// The part of a [`Waiter`] that the buckets link up, which doesn't depend on the handle type, so
+/// that the locks of every thread environment can share the buckets.
+struct Link {
+    // The address of the `WaitQueue` the waiter is parked on.
+    key: usize,
+    next: Cell<*const Link>,
+    notified: AtomicBool,
+    // Unparks the `Waiter` this is the link of.
+    unpark: unsafe fn(*const Link),
+}
+
+#[repr(C)]
+struct Waiter<H: Handle> {
+    // First, so that a pointer to the link is also one to the waiter.
+    link: Link,
+    handle: H,
+}
+
+impl<H: Handle> Waiter<H> {
+    /// # Safety
+    /// `link` must be the link of a live `Waiter<H>`.
+    unsafe fn unpark(link: *const Link) {
+        // SAFETY: The caller guarantees that `link` starts a live `Waiter<H>`.
+        unsafe { (*link.cast::<Self>()).handle.unpark() };
+    }
+}
+
+/// The number of buckets that the wait queues are spread over. Queues that share a bucket only
+/// share its critical section, which is short, so a few collisions don't hurt.
+const BUCKETS: usize = 64;
+
+/// The waiters of every wait queue whose key hashes to this bucket, in the order they parked.
+///
+/// The links are guarded by a ticket lock: threads take a ticket and are served in order, so that
+/// no thread can be overtaken indefinitely under contention, and each acquisition is a single
+/// read-modify-write rather than a loop of failing ones.
+struct Bucket {
+    // The buckets live in a `static`, so they keep the target's atomics under loom, which doesn't
+    // see the critical section. The handshake between parking and releasing goes through the
+    // `parked` flag of each queue instead, which loom does see.
+    next_ticket: native_atomic::AtomicUsize,
+    now_serving: native_atomic::AtomicUsize,
+    head: UnsafeCell<*const Link>,
+    tail: UnsafeCell<*const Link>,
+}
+
+// SAFETY: The links are only accessed within the critical section, and the waiters they point to
+// are only shared by reference, which their `H: Sync` handles permit.
+unsafe impl Sync for Bucket {}
+
+static TABLE: [Bucket; BUCKETS] = [const { Bucket::new() }; BUCKETS];
+
+impl Bucket {
+    const fn new() -> Self {
+        Self {
+            next_ticket: native_atomic::AtomicUsize::new(0),
+            now_serving: native_atomic::AtomicUsize::new(0),
+            head: UnsafeCell::new(ptr::null()),
+            tail: UnsafeCell::new(ptr::null()),
+        }
+    }
+
+    fn of(key: usize) -> &'static Self {
+        // Fibonacci hashing, which spreads out the addresses of neighbouring locks.
+        const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+        let hash = (key as u64).wrapping_mul(MULTIPLIER) >> (u64::BITS - BUCKETS.ilog2());
+        &TABLE[hash as usize]
+    }
+
+    fn critical_section<H: Handle, T>(&self, f: impl FnOnce() -> T) -> T {
+        let ticket = self
+            .next_ticket
+            .fetch_add(1, native_atomic::Ordering::Relaxed);
+        while self.now_serving.load(native_atomic::Ordering::Acquire) != ticket {
+            H::yield_now();
+        }
+        let result = f();
+        self.now_serving
+            .store(ticket.wrapping_add(1), native_atomic::Ordering::Release);
+        result
+    }
+
+    /// Appends `link` to the bucket.
+    ///
+    /// # Safety
+    /// Must be called within `critical_section`, and the waiter of `link` must stay alive until it
+    /// is removed from the bucket again.
+    unsafe fn push(&self, link: &Link) {
+        link.next.set(ptr::null());
+        // SAFETY: The caller guarantees exclusive access to the links.
+        unsafe {
+            let tail = *self.tail.get();
+            if tail.is_null() {
+                *self.head.get() = link;
+            } else {
+                // Every waiter in the bucket is alive until removed.
+                (*tail).next.set(link);
+            }
+            *self.tail.get() = link;
+        }
+    }
+
+    /// Removes the first link that `matches`, returning it, or null if there is none.
+    ///
+    /// # Safety
+    /// Must be called within `critical_section`.
+    unsafe fn remove(&self, mut matches: impl FnMut(&Link) -> bool) -> *const Link {
+        let mut previous: *const Link = ptr::null();
+        // SAFETY: The caller guarantees exclusive access to the links, and every waiter in the
+        // bucket is alive until removed.
+        unsafe {
+            let mut current = *self.head.get();
+            while !current.is_null() {
+                let next = (*current).next.get();
+                if matches(&*current) {
+                    if previous.is_null() {
+                        *self.head.get() = next;
+                    } else {
+                        (*previous).next.set(next);
+                    }
+                    if *self.tail.get() == current {
+                        *self.tail.get() = previous;
+                    }
+                    return current;
+                }
+                previous = current;
+                current = next;
+            }
+        }
+        ptr::null()
+    }
+
+    /// Returns the number of links with the given `key`.
+    ///
+    /// # Safety
+    /// Must be called within `critical_section`.
+    unsafe fn count(&self, key: usize) -> usize {
+        let mut count = 0;
+        // SAFETY: The caller guarantees exclusive access to the links, and every waiter in the
+        // bucket is alive until removed.
+        unsafe {
+            let mut current = *self.head.get();
+            while !current.is_null() {
+                count += usize::from((*current).key == key);
+                current = (*current).next.get();
+            }
+        }
+        count
+    }
+}
+
+/// A FIFO queue of parked threads, used by the locks to block contended acquisitions instead of
+/// spinning.
+///
+/// Only a flag that tells whether any thread is parked is kept in the lock. The waiters themselves
+/// live on the stack of the thread that is waiting, and are linked up in a global table of
+/// buckets, keyed by the address of the queue, as in `parking_lot`. So the queue never allocates,
+/// and costs a lock a single byte, even in environments that can't park.
+pub(crate) struct WaitQueue<H: Handle> {
+    // Only changed within the critical section of the bucket, but read outside of it as a hint.
+    parked: AtomicBool,
+    handle: PhantomData<*const H>,
+}
+
+impl<H: Handle> WaitQueue<H> {
+    const_fn! {
+        pub(crate) const fn new() -> Self {
+            Self {
+                parked: AtomicBool::new(false),
+                handle: PhantomData,
+            }
+        }
+    }
+
+    fn key(&self) -> usize {
+        ptr::from_ref(self).addr()
+    }
+
+    fn critical_section<T>(&self, f: impl FnOnce(&Bucket) -> T) -> T {
+        let bucket = Bucket::of(self.key());
+        bucket.critical_section::<H, T>(|| f(bucket))
+    }
+
+    /// Removes the oldest waiter of this queue and wakes it up. Returns `false` if the queue was
+    /// empty.
+    ///
+    /// # Safety
+    /// Must be called within `critical_section`, on the bucket it passed.
+    unsafe fn wake_front(&self, bucket: &Bucket) -> bool {
+        let key = self.key();
+        // SAFETY: The caller guarantees that we are in the critical section.
+        let link = unsafe { bucket.remove(|link| link.key == key) };
+        if link.is_null() {
+            return false;
+        }
+        // SAFETY: As above.
+        self.parked
+            .store(unsafe { bucket.count(key) } != 0, Ordering::Relaxed);
+
+        // SAFETY: Every waiter in the bucket is alive until it observes `notified` and then leaves
+        // the critical section, which cannot happen before we leave it ourselves.
+        unsafe {
+            (*link).notified.store(true, Ordering::Release);
+            ((*link).unpark)(link);
+        }
+        true
+    }
+
+    /// Returns `true` if no thread is currently parked. This is only a hint, and is not
+    /// synchronized with threads that are concurrently parking.
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.parked.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of parked threads.
+    #[cfg(feature = "registry")]
+    pub(crate) fn len(&self) -> usize {
+        // SAFETY: Called within the critical section.
+        self.critical_section(|bucket| unsafe { bucket.count(self.key()) })
+    }
+
+    fn has_waiters(&self) -> bool {
+        // Pairs with the fence in `wait_until`, so that either the waiter sees the lock released,
+        // or we see the waiter in the queue.
+        fence(Ordering::SeqCst);
+        self.parked.load(Ordering::Relaxed)
+    }
+
+    /// Parks the current thread until `try_acquire` succeeds.
+    ///
+    /// `try_acquire` is always retried after the thread is enqueued, so a release that happens
+    /// concurrently with enqueueing is never missed, as long as the releasing thread calls
+    /// [`notify_one`](WaitQueue::notify_one) or [`notify_all`](WaitQueue::notify_all) after
+    /// releasing.
+    pub(crate) fn wait_until(&self, try_acquire: impl FnMut() -> bool) {
+        self.wait_until_or(try_acquire, |handle| {
+            handle.park();
+            true
+        });
+    }
+
+    /// Like [`wait_until`](WaitQueue::wait_until), but parks through `park`, and gives up once
+    /// `park` returns `false`. Returns whether `try_acquire` succeeded.
+    pub(crate) fn wait_until_or(
+        &self,
+        mut try_acquire: impl FnMut() -> bool,
+        mut park: impl FnMut(&H) -> bool,
+    ) -> bool {
+        let waiter = Waiter {
+            link: Link {
+                key: self.key(),
+                next: Cell::new(ptr::null()),
+                notified: AtomicBool::new(false),
+                unpark: Waiter::<H>::unpark,
+            },
+            handle: H::dumb(),
+        };
+        let link = &waiter.link;
+        // SAFETY: Called within the critical section. If a notifier removed `link` already, it
+        // has left the critical section before we entered it.
+        let remove = |bucket: &Bucket| unsafe {
+            let removed = !bucket.remove(|other| ptr::eq(other, link)).is_null();
+            if removed {
+                self.parked
+                    .store(bucket.count(link.key) != 0, Ordering::Relaxed);
+            }
+            removed
+        };
+
+        loop {
+            link.notified.store(false, Ordering::Relaxed);
+            let acquired = self.critical_section(|bucket| {
+                // SAFETY: We are in the critical section, and `waiter` is either removed below or
+                // stays alive until a notifier removes it.
+                unsafe { bucket.push(link) };
+                self.parked.store(true, Ordering::Relaxed);
+                fence(Ordering::SeqCst);
+                let acquired = try_acquire();
+                if acquired {
+                    remove(bucket);
+                }
+                acquired
+            });
+            if acquired {
+                return true;
+            }
+
+            while !link.notified.load(Ordering::Acquire) {
+                if !park(&waiter.handle) {
+                    let popped = !self.critical_section(remove);
+                    let acquired = try_acquire();
+                    if popped && !acquired {
+                        // The wake-up was meant for us, so hand it on to the next waiter rather
+                        // than losing it.
+                        self.notify_one();
+                    }
+                    return acquired;
+                }
+            }
+            // The notifier may still be unparking `waiter`. Wait for it to leave the critical
+            // section before reusing or dropping `waiter`.
+            self.critical_section(|_| {});
+        }
+    }
+
+    /// Wakes up the oldest parked thread, if any.
+    pub(crate) fn notify_one(&self) {
+        if self.has_waiters() {
+            // SAFETY: Called within the critical section.
+            self.critical_section(|bucket| unsafe { self.wake_front(bucket) });
+        }
+    }
+
+    /// Releases a lock through `release`, which is told whether a thread is parked, and then wakes
+    /// up the oldest parked thread, if any.
+    ///
+    /// Both happen within the critical section, so that no thread can enqueue in between. A lock
+    /// can thus be handed over to the woken thread, without any other waiter taking it first.
+    pub(crate) fn release_and_notify_one(&self, release: impl FnOnce(bool)) {
+        self.critical_section(|bucket| {
+            let parked = self.parked.load(Ordering::Relaxed);
+            release(parked);
+            if parked {
+                // SAFETY: Called within the critical section.
+                unsafe { self.wake_front(bucket) };
+            }
+        });
+    }
+
+    /// Wakes up every parked thread.
+    pub(crate) fn notify_all(&self) {
+        if self.has_waiters() {
+            // SAFETY: Called within the critical section.
+            self.critical_section(|bucket| while unsafe { self.wake_front(bucket) } {});
+        }
+    }
+}
+
+// SAFETY: The waiters are only reached through the buckets, within their critical section, and
+// only shared by reference, which `H: Sync` permits.
+unsafe impl<H: Handle + Sync> Send for WaitQueue<H> {}
+// SAFETY: See above.
+unsafe impl<H: Handle + Sync> Sync for WaitQueue<H> {}
+
+impl<H: Handle> fmt::Debug for WaitQueue<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitQueue")
+            .field("has_waiters", &self.parked.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,14 @@
+#[cfg(feature = "registry")]
+pub(crate) use crate::debug::Waiting;
+
+/// Stands in for the registration of a lock that a thread is blocked on, which only the `registry`
+/// feature keeps track of.
+#[cfg(not(feature = "registry"))]
+pub(crate) struct Waiting;
+
+#[cfg(not(feature = "registry"))]
+impl Waiting {
+    pub(crate) fn register<L: ?Sized>(_lock: &L) -> Self {
+        Self
+    }
+}
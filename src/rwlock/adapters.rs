@@ -0,0 +1,207 @@
+use core::ops::{Deref, DerefMut};
+
+use super::{RwLockApi, RwLockReadGuardApi, RwLockWriteGuardApi};
+use crate::{
+    mutex::{MutexApi, MutexGuardApi},
+    primitives::{LockResult, LockResultExt, TryLockResult},
+};
+
+/// A guard of one lock API, dressed up as a guard of the other.
+struct AdaptedGuard<G>(G);
+
+impl<G: Deref> Deref for AdaptedGuard<G> {
+    type Target = G::Target;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<G: DerefMut> DerefMut for AdaptedGuard<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T, G> RwLockReadGuardApi<'a, T> for AdaptedGuard<G>
+where
+    T: 'a + ?Sized,
+    G: MutexGuardApi<'a, T>,
+{
+}
+impl<'a, T, G> RwLockWriteGuardApi<'a, T> for AdaptedGuard<G>
+where
+    T: 'a + ?Sized,
+    G: MutexGuardApi<'a, T>,
+{
+}
+impl<'a, T, G> MutexGuardApi<'a, T> for AdaptedGuard<G>
+where
+    T: 'a + ?Sized,
+    G: RwLockWriteGuardApi<'a, T>,
+{
+}
+
+/// Implements [`RwLockApi`] on top of a [`MutexApi`], where reads and writes both take the mutex.
+///
+/// This lets code that is generic over `RwLockApi` run on a plain mutex, which is cheaper when
+/// reads are rare. As reads are exclusive too, a read guard dropped during a panic poisons the
+/// lock, unlike on a real read-write lock.
+///
+#[derive(Debug, Default)]
+pub struct MutexAsRwLock<M> {
+    mutex: M,
+}
+
+impl<M> MutexAsRwLock<M> {
+    pub const fn from_mutex(mutex: M) -> Self {
+        Self { mutex }
+    }
+
+    pub fn mutex(&self) -> &M {
+        &self.mutex
+    }
+
+    pub fn into_mutex(self) -> M {
+        self.mutex
+    }
+}
+
+impl<T, M> RwLockApi<T> for MutexAsRwLock<M>
+where
+    T: ?Sized,
+    M: MutexApi<T>,
+{
+    fn try_read<'a>(&'a self) -> TryLockResult<impl RwLockReadGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        M::try_lock(&self.mutex)
+            .map(AdaptedGuard)
+            .map_err(|err| err.map(AdaptedGuard))
+    }
+
+    fn read<'a>(&'a self) -> LockResult<impl RwLockReadGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        M::lock(&self.mutex).map_guard(AdaptedGuard)
+    }
+
+    fn try_write<'a>(&'a self) -> TryLockResult<impl RwLockWriteGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        M::try_lock(&self.mutex)
+            .map(AdaptedGuard)
+            .map_err(|err| err.map(AdaptedGuard))
+    }
+
+    fn write<'a>(&'a self) -> LockResult<impl RwLockWriteGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        M::lock(&self.mutex).map_guard(AdaptedGuard)
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        M::get_mut(&mut self.mutex)
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::from_mutex(M::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        M::into_inner(self.mutex)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        M::is_poisoned(&self.mutex)
+    }
+
+    fn clear_poison(&self) {
+        M::clear_poison(&self.mutex);
+    }
+}
+
+/// Implements [`MutexApi`] on top of a [`RwLockApi`], where locking takes the write lock.
+///
+/// This lets a read-write lock be passed to code that is generic over `MutexApi`, e.g. when the
+/// same lock is also read elsewhere through [`rwlock`](RwLockAsMutex::rwlock).
+///
+#[derive(Debug, Default)]
+pub struct RwLockAsMutex<R> {
+    rwlock: R,
+}
+
+impl<R> RwLockAsMutex<R> {
+    pub const fn from_rwlock(rwlock: R) -> Self {
+        Self { rwlock }
+    }
+
+    pub fn rwlock(&self) -> &R {
+        &self.rwlock
+    }
+
+    pub fn into_rwlock(self) -> R {
+        self.rwlock
+    }
+}
+
+impl<T, R> MutexApi<T> for RwLockAsMutex<R>
+where
+    T: ?Sized,
+    R: RwLockApi<T>,
+{
+    fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        R::try_write(&self.rwlock)
+            .map(AdaptedGuard)
+            .map_err(|err| err.map(AdaptedGuard))
+    }
+
+    fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        R::write(&self.rwlock).map_guard(AdaptedGuard)
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        R::get_mut(&mut self.rwlock)
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::from_rwlock(R::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        R::into_inner(self.rwlock)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        R::is_poisoned(&self.rwlock)
+    }
+
+    fn clear_poison(&self) {
+        R::clear_poison(&self.rwlock);
+    }
+}
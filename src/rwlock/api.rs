@@ -1,22 +1,32 @@
 use core::ops::{Deref, DerefMut};
 
-use crate::primitives::{LockResult, ShouldBlock, TryLockError, TryLockResult};
+extern crate alloc;
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::primitives::{
+    HookContext, LockResult, LockResultExt, ShouldBlock, TryLockError, TryLockResult,
+};
 
 pub trait RwLockHook {
     fn new() -> Self
     where
         Self: Sized;
 
-    fn try_read(&self) -> ShouldBlock {
+    fn try_read(&self, _context: &HookContext) -> ShouldBlock {
         ShouldBlock::Ok
     }
 
-    fn try_write(&self) -> ShouldBlock {
+    fn try_write(&self, _context: &HookContext) -> ShouldBlock {
         ShouldBlock::Ok
     }
 
-    fn after_read(&self) {}
-    fn after_write(&self) {}
+    fn after_read(&self, _context: &HookContext) {}
+    fn after_write(&self, _context: &HookContext) {}
+
+    /// Called when a write guard that is dropped while its thread panics poisons the lock, right
+    /// after the lock has been released and before [`after_write`](RwLockHook::after_write).
+    /// Panics while the lock is poisoned already don't call it again.
+    fn on_poison(&self, _context: &HookContext) {}
 }
 
 // `()` means a basic hook that does nothing.
@@ -47,7 +57,9 @@ pub trait RwLockApi<T: ?Sized> {
             match self.try_read() {
                 Ok(guard) => break Ok(guard),
                 Err(TryLockError::Poisoned(poison)) => break Err(poison),
-                Err(TryLockError::WouldBlock) => continue,
+                Err(
+                    TryLockError::WouldBlock | TryLockError::Cancelled | TryLockError::TimedOut,
+                ) => continue,
             };
         }
     }
@@ -64,11 +76,31 @@ pub trait RwLockApi<T: ?Sized> {
             match self.try_write() {
                 Ok(guard) => break Ok(guard),
                 Err(TryLockError::Poisoned(poison)) => break Err(poison),
-                Err(TryLockError::WouldBlock) => continue,
+                Err(
+                    TryLockError::WouldBlock | TryLockError::Cancelled | TryLockError::TimedOut,
+                ) => continue,
             };
         }
     }
 
+    /// Read-locks the lock, runs `f` on the protected data, and releases the lock again, returning
+    /// the result of `f`.
+    ///
+    /// As the guard never escapes `f`, the lock is held for exactly the duration of the call. If
+    /// the lock is poisoned, `f` still runs, and its result is returned inside the
+    /// [`PoisonError`](crate::primitives::PoisonError).
+    fn with_read<U>(&self, f: impl FnOnce(&T) -> U) -> LockResult<U> {
+        self.read().map_guard(|guard| f(&guard))
+    }
+
+    /// Write-locks the lock, runs `f` on the protected data, and releases the lock again,
+    /// returning the result of `f`.
+    ///
+    /// See [`with_read`](RwLockApi::with_read) for details.
+    fn with_write<U>(&self, f: impl FnOnce(&mut T) -> U) -> LockResult<U> {
+        self.write().map_guard(|mut guard| f(&mut guard))
+    }
+
     fn get_mut(&mut self) -> LockResult<&mut T>;
 
     fn new(t: T) -> Self
@@ -88,6 +120,109 @@ pub trait RwLockApi<T: ?Sized> {
     fn clear_poison(&self) {}
 }
 
+/// Forwards the locking methods of [`RwLockApi`] to the lock `L` behind a pointer.
+macro_rules! forward_rwlock_api {
+    () => {
+        fn try_read<'a>(&'a self) -> TryLockResult<impl RwLockReadGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::try_read(self)
+        }
+
+        fn read<'a>(&'a self) -> LockResult<impl RwLockReadGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::read(self)
+        }
+
+        fn try_write<'a>(&'a self) -> TryLockResult<impl RwLockWriteGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::try_write(self)
+        }
+
+        fn write<'a>(&'a self) -> LockResult<impl RwLockWriteGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            L::write(self)
+        }
+
+        fn is_poisoned(&self) -> bool {
+            L::is_poisoned(self)
+        }
+
+        fn clear_poison(&self) {
+            L::clear_poison(self)
+        }
+    };
+}
+
+// References don't implement `RwLockApi`, as they can't own the lock for the owning methods such
+// as `new` and `into_inner`. Generic code can take `&impl RwLockApi<T>` instead.
+impl<T, L> RwLockApi<T> for Box<L>
+where
+    T: ?Sized,
+    L: RwLockApi<T>,
+{
+    forward_rwlock_api!();
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        L::get_mut(self)
+    }
+
+    fn new(t: T) -> Self
+    where
+        T: Sized,
+    {
+        Box::new(L::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        L::into_inner(*self)
+    }
+}
+
+/// Locks through shared ownership.
+///
+/// # Panics
+/// [`get_mut`](RwLockApi::get_mut) and [`into_inner`](RwLockApi::into_inner) panic if the lock is
+/// shared with other `Arc`s.
+impl<T, L> RwLockApi<T> for Arc<L>
+where
+    T: ?Sized,
+    L: RwLockApi<T>,
+{
+    forward_rwlock_api!();
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        let lock = Arc::get_mut(self).expect("`RwLockApi::get_mut` was called on a shared `Arc`");
+        L::get_mut(lock)
+    }
+
+    fn new(t: T) -> Self
+    where
+        T: Sized,
+    {
+        Arc::new(L::new(t))
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        let lock =
+            Arc::into_inner(self).expect("`RwLockApi::into_inner` was called on a shared `Arc`");
+        L::into_inner(lock)
+    }
+}
+
 #[cfg(feature = "std")]
 pub mod std_rwlock_api {
     #[cfg(feature = "std")]
@@ -157,3 +292,63 @@ pub mod std_rwlock_api {
         }
     }
 }
+
+#[cfg(feature = "parking_lot")]
+pub mod parking_lot_rwlock_api {
+    use super::{RwLockApi, RwLockReadGuardApi, RwLockWriteGuardApi};
+    use crate::primitives::{LockResult, TryLockError, TryLockResult};
+
+    impl<'a, T: 'a + ?Sized> RwLockReadGuardApi<'a, T> for parking_lot::RwLockReadGuard<'a, T> {}
+    impl<'a, T: 'a + ?Sized> RwLockWriteGuardApi<'a, T> for parking_lot::RwLockWriteGuard<'a, T> {}
+
+    /// `parking_lot`'s read-write locks never poison, so `read` and `write` always succeed.
+    impl<T: ?Sized> RwLockApi<T> for parking_lot::RwLock<T> {
+        fn try_read<'a>(&'a self) -> TryLockResult<impl RwLockReadGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            self.try_read().ok_or(TryLockError::WouldBlock)
+        }
+
+        fn read<'a>(&'a self) -> LockResult<impl RwLockReadGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            Ok(self.read())
+        }
+
+        fn try_write<'a>(&'a self) -> TryLockResult<impl RwLockWriteGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            self.try_write().ok_or(TryLockError::WouldBlock)
+        }
+
+        fn write<'a>(&'a self) -> LockResult<impl RwLockWriteGuardApi<'a, T>>
+        where
+            T: 'a,
+        {
+            Ok(self.write())
+        }
+
+        fn get_mut(&mut self) -> LockResult<&mut T> {
+            Ok(self.get_mut())
+        }
+
+        fn new(t: T) -> Self
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Self::new(t)
+        }
+
+        fn into_inner(self) -> LockResult<T>
+        where
+            Self: Sized,
+            T: Sized,
+        {
+            Ok(self.into_inner())
+        }
+    }
+}
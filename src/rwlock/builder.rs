@@ -0,0 +1,114 @@
+use core::marker::PhantomData;
+
+use crate::primitives::{Label, Poison, PoisonPolicy, ThreadEnv};
+
+use super::{BaseRwLock, RwLockHook, checked_max_readers};
+
+/// Configures a [`BaseRwLock`] one option at a time, as created by
+/// [`BaseRwLock::builder`].
+///
+/// Options that change the type of the lock, such as the hook, the thread environment, and the
+/// poison policy, return a builder for the new type, so that e.g. a hooked lock doesn't have to be
+/// named with all of its generic parameters up front.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::{primitives::NoPoison, rwlock::RwLock};
+/// let lock = RwLock::builder()
+///     .max_readers(2)
+///     .poison_policy::<NoPoison>()
+///     .build(0);
+/// assert_eq!(lock.max_readers(), 2);
+/// assert_eq!(*lock.read(), 0);
+/// # }
+/// ```
+///
+#[must_use = "a builder does nothing until it is built"]
+pub struct RwLockBuilder<T, Hook, Env, Policy = Poison>
+where
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    hook: Option<Hook>,
+    label: Label,
+    max_readers: Option<usize>,
+    marker: PhantomData<fn(T) -> (Env, Policy)>,
+}
+
+impl<T, Hook, Env, Policy> RwLockBuilder<T, Hook, Env, Policy>
+where
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    pub(super) fn new() -> Self {
+        Self {
+            hook: None,
+            label: Label::NONE,
+            max_readers: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Uses `hook` instead of [`RwLockHook::new`], like [`BaseRwLock::with_hook`].
+    pub fn hook<NewHook: RwLockHook>(
+        self,
+        hook: NewHook,
+    ) -> RwLockBuilder<T, NewHook, Env, Policy> {
+        RwLockBuilder {
+            hook: Some(hook),
+            label: self.label,
+            max_readers: self.max_readers,
+            marker: PhantomData,
+        }
+    }
+
+    /// Labels the lock, like [`BaseRwLock::new_named`].
+    #[cfg(feature = "labels")]
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Label::new(label);
+        self
+    }
+
+    /// Admits at most `max_readers` readers at once, like [`BaseRwLock::with_max_readers`].
+    ///
+    /// # Panics
+    /// [`build`](RwLockBuilder::build) panics if `max_readers` is out of range, see
+    /// [`BaseRwLock::with_max_readers`].
+    pub fn max_readers(mut self, max_readers: usize) -> Self {
+        self.max_readers = Some(max_readers);
+        self
+    }
+
+    /// Blocks threads according to `NewEnv` instead.
+    pub fn thread_env<NewEnv: ThreadEnv>(self) -> RwLockBuilder<T, Hook, NewEnv, Policy> {
+        RwLockBuilder {
+            hook: self.hook,
+            label: self.label,
+            max_readers: self.max_readers,
+            marker: PhantomData,
+        }
+    }
+
+    /// Handles panics of writers according to `NewPolicy` instead.
+    pub fn poison_policy<NewPolicy: PoisonPolicy>(self) -> RwLockBuilder<T, Hook, Env, NewPolicy> {
+        RwLockBuilder {
+            hook: self.hook,
+            label: self.label,
+            max_readers: self.max_readers,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates the lock, protecting `t`.
+    pub fn build(self, t: T) -> BaseRwLock<T, Hook, Env, Policy> {
+        let mut lock = BaseRwLock::with_hook(t, self.hook.unwrap_or_else(Hook::new));
+        lock.inner.label = self.label;
+        if let Some(max_readers) = self.max_readers {
+            lock.inner.max_readers = checked_max_readers(max_readers);
+        }
+        lock
+    }
+}
@@ -0,0 +1,126 @@
+//! Hardware lock elision through Intel's Restricted Transactional Memory (RTM).
+//!
+//! Inside a transaction, memory accesses are only made visible once it commits. The transaction
+//! aborts as soon as another thread writes memory that it read, or it does something that can't be
+//! rolled back, such as a system call. On abort, every change it made is discarded, and execution
+//! resumes at its `xbegin` with the abort status. The whole transaction therefore runs inside one
+//! `asm!` block, which calls back into Rust for its body, so an abort never makes Rust code return
+//! a second time.
+
+use core::{
+    arch::{
+        asm,
+        x86_64::{__cpuid, __cpuid_count},
+    },
+    mem,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::primitives::OnDrop;
+
+/// Returned by [`transaction`] when the transaction has committed.
+const COMMITTED: u32 = u32::MAX;
+/// Set in an abort status if the transaction may succeed when retried.
+const RETRY: u32 = 1 << 1;
+
+const UNKNOWN: u8 = 0;
+const UNSUPPORTED: u8 = 1;
+const SUPPORTED: u8 = 2;
+
+/// Returns `true` if the CPU can run transactions that don't always abort.
+pub(super) fn supported() -> bool {
+    static CACHED: AtomicU8 = AtomicU8::new(UNKNOWN);
+    match CACHED.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            // RTM is flagged in bit 11 of EBX, and microcode that disables it by always aborting
+            // is flagged in bit 11 of EDX, of the structured extended feature leaf.
+            let supported = __cpuid(0).eax >= 7 && {
+                let features = __cpuid_count(7, 0);
+                features.ebx & (1 << 11) != 0 && features.edx & (1 << 11) == 0
+            };
+            let cached = if supported { SUPPORTED } else { UNSUPPORTED };
+            CACHED.store(cached, Ordering::Relaxed);
+            supported
+        }
+        cached => cached == SUPPORTED,
+    }
+}
+
+/// Passes `f` to `run` inside a transaction, and returns the result once the transaction commits.
+///
+/// An aborted transaction is retried up to `attempts` times in total while its abort status says
+/// that a retry may succeed. If it never commits, or the CPU doesn't support RTM, `f` is handed
+/// back without having had any effect.
+pub(super) fn elide<F, R>(attempts: usize, f: F, run: impl Fn(F) -> R) -> Result<R, F> {
+    if !supported() {
+        return Err(f);
+    }
+    let mut f = Some(f);
+    let mut value = None;
+    let mut body = || {
+        if let Some(f) = f.take() {
+            value = Some(run(f));
+        }
+    };
+    for _ in 0..attempts {
+        // SAFETY: RTM is supported.
+        let status = unsafe { transaction(&mut body) };
+        if status == COMMITTED || status & RETRY == 0 {
+            break;
+        }
+    }
+    // An aborted transaction discards the `take` of `f`, so `f` is only gone once one committed.
+    match (value, f) {
+        (Some(value), _) => Ok(value),
+        (None, Some(f)) => Err(f),
+        (None, None) => unreachable!("a committed transaction stores the value"),
+    }
+}
+
+/// Runs `body` inside a transaction, returning [`COMMITTED`] once it committed, or the abort
+/// status.
+///
+/// # Safety
+/// The CPU must support RTM, see [`supported`].
+unsafe fn transaction<F: FnMut()>(body: &mut F) -> u32 {
+    unsafe extern "sysv64" fn run<F: FnMut()>(body: *mut F) {
+        // Unwinding can't leave this function, nor the `asm!` block that called it. Abort the
+        // transaction instead, which discards the panic along with everything else.
+        // SAFETY: This is only called inside the transaction.
+        let abort_on_unwind = OnDrop(|| unsafe { abort() });
+        // SAFETY: `transaction` passes a pointer to its exclusively borrowed `body`.
+        unsafe { (*body)() };
+        mem::forget(abort_on_unwind);
+    }
+
+    let status: u32;
+    // SAFETY: The transaction either commits after `run` returned, or aborts, which restores the
+    // registers and memory to their state at `xbegin` and jumps to the end of this block with the
+    // abort status in `eax`. Either way the block is left once, through its end. The stack is
+    // aligned for the call, as `nostack` isn't set.
+    unsafe {
+        asm!(
+            "xbegin 2f",
+            "call {run}",
+            "xend",
+            "mov eax, {committed}",
+            "2:",
+            run = in(reg) run::<F> as unsafe extern "sysv64" fn(*mut F),
+            committed = const COMMITTED,
+            in("rdi") body as *mut F,
+            out("eax") status,
+            clobber_abi("sysv64"),
+        );
+    }
+    status
+}
+
+/// Aborts the running transaction, which resumes at the end of [`transaction`].
+///
+/// # Safety
+/// Must be called inside a transaction, as `xabort` does nothing outside of one.
+#[inline(always)]
+pub(super) unsafe fn abort() -> ! {
+    // SAFETY: The caller guarantees that a transaction is running, so this never falls through.
+    unsafe { asm!("xabort 0xff", options(nostack, noreturn)) };
+}
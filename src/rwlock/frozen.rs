@@ -0,0 +1,86 @@
+use core::{
+    borrow::Borrow,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+};
+
+use crate::primitives::{PoisonPolicy, ThreadEnv};
+
+use super::{BaseRwLock, RwLockHook};
+
+impl<T, Hook, Env, Policy> BaseRwLock<T, Hook, Env, Policy>
+where
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Consumes the lock, returning its data behind a [`Frozen`] wrapper that can only be read,
+    /// and that reads without any synchronization.
+    ///
+    /// This suits data that is written during setup and then only read: once published, e.g. in
+    /// an `Arc`, readers no longer pay for the lock.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use std::sync::Arc;
+    /// # use powerlocks::rwlock::RwLock;
+    /// let config = RwLock::new(Vec::new());
+    /// config.write().unwrap().push("verbose");
+    ///
+    /// let config = Arc::new(config.freeze().unwrap());
+    /// let thread = std::thread::spawn({
+    ///     let config = Arc::clone(&config);
+    ///     move || config.contains(&"verbose")
+    /// });
+    /// assert!(thread.join().unwrap());
+    /// # }
+    /// ```
+    ///
+    pub fn freeze(self) -> Policy::Result<Frozen<T>> {
+        let (poison, label) = (self.inner.poison, self.inner.label.get());
+        poison.wrap_labeled(Frozen(self.data.into_inner()), label)
+    }
+}
+
+/// The data of a lock consumed by [`BaseRwLock::freeze`], which can only be read from now on.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frozen<T>(T);
+
+impl<T> Frozen<T> {
+    /// Unfreezes the data, e.g. to put it into a new lock.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Frozen<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for Frozen<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Borrow<T> for Frozen<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Frozen<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frozen").field(&self.0).finish()
+    }
+}
+
+impl<T: Display> Display for Frozen<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
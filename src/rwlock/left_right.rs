@@ -0,0 +1,286 @@
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::{
+    mutex::{BaseMutex, BaseMutexGuard},
+    primitives::{
+        Backoff, CoreThreadEnv, NoPoison, ThreadEnv,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// An operation that a [`BaseLeftRight`] applies to both of its copies.
+///
+/// `absorb` is called once on each copy with the same operation, so it must be deterministic:
+/// applying it to two equal values must leave them equal.
+pub trait Absorb<O> {
+    fn absorb(&mut self, op: &O);
+}
+
+/// A left-right lock, whose readers never wait, not even for a writer.
+///
+/// The lock keeps two copies of the data. Readers read the active copy, while the writer applies
+/// its operations to the other one. Publishing the operations makes the written copy active, waits
+/// until the last readers of the old copy are done, and replays the operations on it, so that both
+/// copies are equal again. Operations are thus applied twice, and the data takes twice the memory.
+///
+/// Writers are serialized by a mutex of the crate, and wait for readers according to `Env`. The
+/// lock doesn't poison: if `absorb` panics, the two copies may differ.
+///
+/// Unlike a [`BaseRwLock`](super::BaseRwLock), a write guard can't hand out `&mut T`, as every
+/// change has to be replayed on the other copy. Writes go through [`Absorb`] operations instead.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::rwlock::{Absorb, LeftRight};
+/// struct Push(u32);
+///
+/// impl Absorb<Push> for Vec<u32> {
+///     fn absorb(&mut self, op: &Push) {
+///         self.push(op.0);
+///     }
+/// }
+///
+/// let lock = LeftRight::new(Vec::new());
+/// let mut writer = lock.write();
+/// writer.append(Push(1)).append(Push(2));
+/// assert!(lock.read().is_empty());
+///
+/// drop(writer);
+/// assert_eq!(*lock.read(), [1, 2]);
+/// # }
+/// ```
+///
+pub struct BaseLeftRight<T, O, Env: ThreadEnv> {
+    copies: [UnsafeCell<T>; 2],
+    active: AtomicUsize,
+    readers: [AtomicUsize; 2],
+    // The operations written but not published yet.
+    log: BaseMutex<Vec<O>, (), Env, NoPoison>,
+}
+
+impl<T, O, Env: ThreadEnv> BaseLeftRight<T, O, Env> {
+    /// Creates a lock whose copies start out as clones of `t`.
+    pub fn new(t: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            copies: [UnsafeCell::new(t.clone()), UnsafeCell::new(t)],
+            active: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            log: BaseMutex::new_unhooked(Vec::new()),
+        }
+    }
+
+    /// Read-locks the active copy. This never waits, but retries if a writer publishes meanwhile.
+    pub fn read(&self) -> BaseLeftRightReadGuard<'_, T, O, Env> {
+        loop {
+            let index = self.active.load(Ordering::SeqCst);
+            self.readers[index].fetch_add(1, Ordering::SeqCst);
+            // A writer that published before our increment may not have seen it, and may be
+            // writing the copy by now. This reads through a read-modify-write, which sees the
+            // latest copy, and orders our increment before any later publish.
+            if self.active.fetch_add(0, Ordering::SeqCst) == index {
+                return BaseLeftRightReadGuard { lock: self, index };
+            }
+            self.readers[index].fetch_sub(1, Ordering::Release);
+        }
+    }
+
+    /// Reads the active copy, runs `f` on it, and releases it again, returning the result of `f`.
+    pub fn with_read<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        f(&self.read())
+    }
+
+    /// Locks the lock for writing, blocking while another writer holds it.
+    pub fn write(&self) -> BaseLeftRightWriteGuard<'_, T, O, Env>
+    where
+        T: Absorb<O>,
+    {
+        self.write_with(self.log.lock())
+    }
+
+    /// Attempts to lock the lock for writing, returning [`None`] if another writer holds it.
+    pub fn try_write(&self) -> Option<BaseLeftRightWriteGuard<'_, T, O, Env>>
+    where
+        T: Absorb<O>,
+    {
+        self.log.try_lock().ok().map(|log| self.write_with(log))
+    }
+
+    fn write_with<'a>(
+        &'a self,
+        log: BaseMutexGuard<'a, Vec<O>, (), Env, NoPoison>,
+    ) -> BaseLeftRightWriteGuard<'a, T, O, Env>
+    where
+        T: Absorb<O>,
+    {
+        BaseLeftRightWriteGuard {
+            lock: self,
+            index: 1 - self.active.load(Ordering::Relaxed),
+            log,
+        }
+    }
+
+    /// Consumes the lock, returning the active copy of the data.
+    pub fn into_inner(self) -> T {
+        let active = self.active.load(Ordering::Relaxed);
+        let [left, right] = self.copies;
+        match active {
+            0 => left.into_inner(),
+            _ => right.into_inner(),
+        }
+    }
+}
+
+impl<T: Clone + Default, O, Env: ThreadEnv> Default for BaseLeftRight<T, O, Env> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug, O, Env: ThreadEnv> Debug for BaseLeftRight<T, O, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeftRight")
+            .field("data", &&*self.read())
+            .finish_non_exhaustive()
+    }
+}
+
+// SAFETY: Readers share the active copy, and the writer mutates the other one from any thread,
+// only once every reader has left it. Operations are moved to the writing thread.
+unsafe impl<T: Send + Sync, O: Send, Env: ThreadEnv> Sync for BaseLeftRight<T, O, Env> {}
+
+/// A read lock of the active copy of a [`BaseLeftRight`].
+#[must_use = "if unused the LeftRight will immediately unlock"]
+pub struct BaseLeftRightReadGuard<'a, T, O, Env: ThreadEnv> {
+    lock: &'a BaseLeftRight<T, O, Env>,
+    index: usize,
+}
+
+impl<T, O, Env: ThreadEnv> Deref for BaseLeftRightReadGuard<'_, T, O, Env> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The writer doesn't write a copy while it has readers.
+        unsafe { &*self.lock.copies[self.index].get() }
+    }
+}
+
+impl<T, O, Env: ThreadEnv> Drop for BaseLeftRightReadGuard<'_, T, O, Env> {
+    fn drop(&mut self) {
+        self.lock.readers[self.index].fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<T: Debug, O, Env: ThreadEnv> Debug for BaseLeftRightReadGuard<'_, T, O, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// The write lock of a [`BaseLeftRight`]. Dropping it publishes the operations appended to it.
+///
+/// It dereferences to the written copy, which already reflects the appended operations.
+#[must_use = "if unused the LeftRight will immediately unlock"]
+pub struct BaseLeftRightWriteGuard<'a, T: Absorb<O>, O, Env: ThreadEnv> {
+    lock: &'a BaseLeftRight<T, O, Env>,
+    index: usize,
+    log: BaseMutexGuard<'a, Vec<O>, (), Env, NoPoison>,
+}
+
+impl<T: Absorb<O>, O, Env: ThreadEnv> BaseLeftRightWriteGuard<'_, T, O, Env> {
+    /// Applies `op` to the written copy. Readers see it once it is published.
+    pub fn append(&mut self, op: O) -> &mut Self {
+        // SAFETY: Readers left the written copy before it was handed to the writer.
+        unsafe { (*self.lock.copies[self.index].get()).absorb(&op) };
+        self.log.push(op);
+        self
+    }
+
+    /// Publishes the appended operations to readers, waiting until the last readers of the old
+    /// copy are done to replay them on it.
+    pub fn publish(&mut self) {
+        if self.log.is_empty() {
+            return;
+        }
+        self.lock.active.swap(self.index, Ordering::SeqCst);
+        let old = 1 - self.index;
+        let mut backoff = Backoff::new();
+        // Like the active copy in `read`, the count is read through a read-modify-write, to see the
+        // increments of every reader that validated the old copy.
+        while self.lock.readers[old].fetch_add(0, Ordering::SeqCst) != 0 {
+            backoff.wait::<Env>();
+        }
+        // SAFETY: New readers go to the published copy, and the readers of the old copy are gone.
+        let copy = unsafe { &mut *self.lock.copies[old].get() };
+        for op in self.log.drain(..) {
+            copy.absorb(&op);
+        }
+        self.index = old;
+    }
+}
+
+impl<T: Absorb<O>, O, Env: ThreadEnv> Deref for BaseLeftRightWriteGuard<'_, T, O, Env> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Readers left the written copy before it was handed to the writer.
+        unsafe { &*self.lock.copies[self.index].get() }
+    }
+}
+
+impl<T: Absorb<O>, O, Env: ThreadEnv> Drop for BaseLeftRightWriteGuard<'_, T, O, Env> {
+    fn drop(&mut self) {
+        self.publish();
+    }
+}
+
+impl<T: Absorb<O> + Debug, O, Env: ThreadEnv> Debug for BaseLeftRightWriteGuard<'_, T, O, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+pub type CoreLeftRight<T, O> = BaseLeftRight<T, O, CoreThreadEnv>;
+pub type CoreLeftRightReadGuard<'a, T, O> = BaseLeftRightReadGuard<'a, T, O, CoreThreadEnv>;
+pub type CoreLeftRightWriteGuard<'a, T, O> = BaseLeftRightWriteGuard<'a, T, O, CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::{BaseLeftRight, BaseLeftRightReadGuard, BaseLeftRightWriteGuard};
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdLeftRight<T, O> = BaseLeftRight<T, O, StdThreadEnv>;
+    pub type StdLeftRightReadGuard<'a, T, O> = BaseLeftRightReadGuard<'a, T, O, StdThreadEnv>;
+    pub type StdLeftRightWriteGuard<'a, T, O> = BaseLeftRightWriteGuard<'a, T, O, StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod main_type {
+    use super::{CoreLeftRight, CoreLeftRightReadGuard, CoreLeftRightWriteGuard};
+
+    pub type LeftRight<T, O> = CoreLeftRight<T, O>;
+    pub type LeftRightReadGuard<'a, T, O> = CoreLeftRightReadGuard<'a, T, O>;
+    pub type LeftRightWriteGuard<'a, T, O> = CoreLeftRightWriteGuard<'a, T, O>;
+}
+
+#[cfg(feature = "std")]
+mod main_type {
+    use super::{StdLeftRight, StdLeftRightReadGuard, StdLeftRightWriteGuard};
+
+    pub type LeftRight<T, O> = StdLeftRight<T, O>;
+    pub type LeftRightReadGuard<'a, T, O> = StdLeftRightReadGuard<'a, T, O>;
+    pub type LeftRightWriteGuard<'a, T, O> = StdLeftRightWriteGuard<'a, T, O>;
+}
+
+pub use main_type::*;
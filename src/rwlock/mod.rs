@@ -1,267 +1,715 @@
 mod api;
 pub use api::*;
 
+mod adapters;
+pub use adapters::*;
+
+mod read_only;
+pub use read_only::*;
+
+mod split;
+pub use split::*;
+
+mod frozen;
+pub use frozen::*;
+
+mod left_right;
+pub use left_right::*;
+
+mod builder;
+pub use builder::*;
+
+#[cfg(all(feature = "hle", target_arch = "x86_64", not(loom)))]
+mod elision;
+
 use core::{
     cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
     marker::PhantomData,
+    mem,
     ops::{Deref, DerefMut},
     panic::{RefUnwindSafe, UnwindSafe},
     ptr::NonNull,
-    sync::atomic::{AtomicBool, Ordering},
 };
 
+use crate::primitives::sync::{CancelFlag, atomic::Ordering, const_fn};
+
+extern crate alloc;
+use alloc::sync::Arc;
+
+#[cfg(feature = "multi")]
+use crate::primitives::LockId;
 use crate::primitives::{
-    CoreThreadEnv, LockResult, PoisonError, ThreadEnv, TryLockError, TryLockResult,
+    Backoff, CoreThreadEnv, HookContext, Label, LockResult, Method, NoPoison, OnDrop, Poison,
+    PoisonPolicy, ShouldBlock, ThreadEnv, TryLockError, TryLockResult, WaitQueue, Waiting,
+    forward_guard_io, forward_guard_mut_traits, forward_guard_traits, panicking, stable_guard,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Method {
-    Read,
-    Write,
-}
-
-impl Method {
-    #[inline]
-    fn switch<T>(&self, read: impl FnOnce() -> T, write: impl FnOnce() -> T) -> T {
-        match self {
-            Method::Read => read(),
-            Method::Write => write(),
-        }
+/// The width of the lock state, which the `rwlock-state-u32` and `rwlock-state-u16` features
+/// narrow for targets where `usize` atomics are wasteful. The narrowest enabled width wins.
+mod state_word {
+    #[cfg(feature = "rwlock-state-u16")]
+    pub(super) use crate::primitives::sync::atomic::AtomicU16 as AtomicWord;
+    #[cfg(feature = "rwlock-state-u16")]
+    pub(super) type Word = u16;
+
+    #[cfg(all(feature = "rwlock-state-u32", not(feature = "rwlock-state-u16")))]
+    pub(super) use crate::primitives::sync::atomic::AtomicU32 as AtomicWord;
+    #[cfg(all(feature = "rwlock-state-u32", not(feature = "rwlock-state-u16")))]
+    pub(super) type Word = u32;
+
+    #[cfg(not(any(feature = "rwlock-state-u32", feature = "rwlock-state-u16")))]
+    pub(super) use crate::primitives::sync::atomic::AtomicUsize as AtomicWord;
+    #[cfg(not(any(feature = "rwlock-state-u32", feature = "rwlock-state-u16")))]
+    pub(super) type Word = usize;
+
+    /// Widens a reader count, which always fits in a `usize` on the targets these widths suit.
+    #[allow(
+        clippy::unnecessary_cast,
+        reason = "`Word` is only `usize` without the narrowing features"
+    )]
+    pub(super) const fn widen(word: Word) -> usize {
+        word as usize
     }
 }
 
+use state_word::{AtomicWord, Word, widen};
+
+/// The lock state, packed into a single atomic: the top bit is set while a writer holds the lock,
+/// and the remaining bits count the active readers.
 #[repr(transparent)]
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
-struct State(usize);
+#[derive(Debug, Default)]
+struct State(AtomicWord);
 
 impl State {
-    const fn new() -> Self {
-        Self(usize::MIN)
+    const WRITER: Word = !(Word::MAX >> 1);
+    const MAX_READERS: Word = Self::WRITER - 1;
+
+    const_fn! {
+        const fn new() -> Self {
+            Self(AtomicWord::new(0))
+        }
+    }
+
+    fn alloc(&self, method: Method, max_readers: Word) -> bool {
+        method.switch(
+            || {
+                self.0
+                    .fetch_update(Ordering::Acquire, Ordering::Relaxed, |readers| {
+                        (readers < max_readers).then_some(readers + 1)
+                    })
+                    .is_ok()
+            },
+            || {
+                self.0
+                    .compare_exchange(0, Self::WRITER, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            },
+        )
+    }
+
+    fn is_available(&self, method: Method, max_readers: Word) -> bool {
+        let state = self.0.load(Ordering::Relaxed);
+        method.switch(|| state < max_readers, || state == 0)
+    }
+
+    fn is_read_locked(&self) -> bool {
+        let state = self.0.load(Ordering::Relaxed);
+        0 < state && state < Self::WRITER
+    }
+
+    #[cfg(feature = "registry")]
+    fn inspect(&self) -> crate::debug::LockState {
+        use crate::debug::LockState;
+        match self.0.load(Ordering::Relaxed) {
+            0 => LockState::Unlocked,
+            Self::WRITER => LockState::Written,
+            readers => LockState::Read(widen(readers)),
+        }
+    }
+
+    fn is_write_locked(&self) -> bool {
+        self.0.load(Ordering::Relaxed) == Self::WRITER
     }
 
-    fn alloc(&mut self, method: Method) -> bool {
-        let available = method.switch(|| self.0 < usize::MAX - 1, || self.0 == usize::MIN);
-        if available {
-            self.0 = method.switch(|| self.0 + 1, || usize::MAX);
+    fn readers(&self) -> usize {
+        match self.0.load(Ordering::Relaxed) {
+            Self::WRITER => 0,
+            readers => widen(readers),
         }
-        available
     }
 
-    fn free(&mut self, method: Method) {
+    /// Releases the lock, returning `true` if blocked threads may now be able to acquire it.
+    fn free(&self, method: Method, max_readers: Word) -> bool {
         method.switch(
-            || assert!(usize::MIN < self.0 && self.0 < usize::MAX),
-            || assert_eq!(self.0, usize::MAX),
-        );
-        self.0 = method.switch(|| self.0 - 1, || usize::MIN);
+            || {
+                // Checked before updating, so that a stray release never corrupts the state.
+                let previous = self
+                    .0
+                    .fetch_update(Ordering::Release, Ordering::Relaxed, |readers| {
+                        (0 < readers && readers < Self::WRITER).then(|| readers - 1)
+                    })
+                    .expect("read lock released while not read-locked");
+                previous == 1 || previous == max_readers
+            },
+            || {
+                self.0
+                    .compare_exchange(Self::WRITER, 0, Ordering::Release, Ordering::Relaxed)
+                    .expect("write lock released while not write-locked");
+                true
+            },
+        )
     }
 }
 
+fn checked_max_readers(max_readers: usize) -> Word {
+    Word::try_from(max_readers)
+        .ok()
+        .filter(|&max| 0 < max && max <= State::MAX_READERS)
+        .expect("`max_readers` must be between 1 and the capacity of the lock state")
+}
+
 #[derive(Debug)]
-struct BaseRwLockInner<Hook: RwLockHook, Env: ThreadEnv> {
-    mutex: AtomicBool,
-    state: UnsafeCell<State>,
-    poison: AtomicBool,
+struct BaseRwLockInner<Hook: RwLockHook, Env: ThreadEnv, Policy: PoisonPolicy> {
+    state: State,
+    max_readers: Word,
+    waiters: WaitQueue<Env::Handle>,
+    poison: Policy,
     hook: Hook,
+    label: Label,
     thread_env: PhantomData<Env>,
 }
 
-impl<Env: ThreadEnv> BaseRwLockInner<(), Env> {
-    const fn new_unhooked() -> Self {
-        Self {
-            mutex: AtomicBool::new(false),
-            state: UnsafeCell::new(State::new()),
-            poison: AtomicBool::new(false),
-            hook: (),
-            thread_env: PhantomData,
+impl<Hook: RwLockHook, Env: ThreadEnv, Policy: PoisonPolicy> BaseRwLockInner<Hook, Env, Policy> {
+    const_fn! {
+        const fn with_hook(hook: Hook) -> Self {
+            Self {
+                state: State::new(),
+                max_readers: State::MAX_READERS,
+                waiters: WaitQueue::new(),
+                poison: Policy::UNPOISONED,
+                hook,
+                label: Label::NONE,
+                thread_env: PhantomData,
+            }
         }
     }
-}
 
-impl<Hook: RwLockHook, Env: ThreadEnv> BaseRwLockInner<Hook, Env> {
-    fn new() -> Self {
-        Self {
-            mutex: AtomicBool::new(false),
-            state: UnsafeCell::new(State::new()),
-            poison: AtomicBool::new(false),
-            hook: Hook::new(),
-            thread_env: PhantomData,
-        }
+    fn context(&self, method: Method, contended: bool) -> HookContext {
+        HookContext::new::<Env>(method, contended, self.label.get())
     }
 
-    #[inline]
-    fn is_poisoned(&self) -> bool {
-        self.poison.load(Ordering::Acquire)
+    fn wrap<G>(&self, guard: G) -> Policy::Result<G> {
+        self.poison.wrap_labeled(guard, self.label.get())
     }
 
-    #[inline]
-    fn clear_poison(&self) {
-        self.poison.store(false, Ordering::Release);
+    fn try_wrap<G>(&self, guard: G) -> TryLockResult<G> {
+        self.poison.try_wrap_labeled(guard, self.label.get())
+    }
+
+    fn try_hook(&self, method: Method) -> ShouldBlock {
+        let context = self.context(method, !self.state.is_available(method, self.max_readers));
+        method.switch(
+            || self.hook.try_read(&context),
+            || self.hook.try_write(&context),
+        )
     }
 
-    fn critical_section<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
-        while self
-            .mutex
-            .compare_exchange(false, true, Ordering::Acquire, Ordering::Acquire)
-            .is_err()
-        {
+    fn after_hook(&self, method: Method) {
+        let context = self.context(method, !self.waiters.is_empty());
+        method.switch(
+            || self.hook.after_read(&context),
+            || self.hook.after_write(&context),
+        );
+    }
+
+    fn poison_hook(&self) {
+        let context = self.context(Method::Write, !self.waiters.is_empty());
+        self.hook.on_poison(&context);
+    }
+
+    fn try_lock(&self, method: Method) -> bool {
+        let admitted = self.try_hook(method);
+        admitted == ShouldBlock::Ok && self.state.alloc(method, self.max_readers)
+    }
+
+    /// Acquires the lock with `method`, calling `register` to register the lock in the debug
+    /// registry if the thread has to park.
+    fn lock<W>(&self, method: Method, register: impl FnOnce() -> W) {
+        let mut backoff = Backoff::new();
+        while self.try_hook(method) == ShouldBlock::Block {
+            backoff.wait::<Env>();
+        }
+
+        const SPIN_ATTEMPTS: usize = 64;
+        for _ in 0..SPIN_ATTEMPTS {
+            if self.state.alloc(method, self.max_readers) {
+                return;
+            }
             Env::yield_now();
         }
-        // SAFETY: `critical_section` enforces exclusive access via `mutex`. Box the reference in a
-        // nested scope to prevent theoretical lifetime escape.
-        let result = { f(unsafe { &mut *self.state.get() }) };
-        self.mutex.store(false, Ordering::Release);
-        result
-    }
-
-    fn try_lock(&self, method: Method) -> TryLockResult<()> {
-        match (
-            self.critical_section(|state| state.alloc(method)),
-            !self.is_poisoned(),
-        ) {
-            (false, _) => Err(TryLockError::WouldBlock),
-            (true, false) => Err(TryLockError::Poisoned(PoisonError::new(()))),
-            (true, true) => Ok(()),
+        // The lock is heavily contended. Park until a release wakes us up.
+        let _waiting = register();
+        self.waiters
+            .wait_until(|| self.state.alloc(method, self.max_readers));
+    }
+
+    /// Like `lock`, but polls `cancel` instead of parking. Returns `false` if cancelled.
+    fn lock_until(&self, method: Method, cancel: &CancelFlag) -> bool {
+        let cancelled = || cancel.load(Ordering::Acquire);
+        let mut backoff = Backoff::new();
+        while self.try_hook(method) == ShouldBlock::Block {
+            if cancelled() {
+                return false;
+            }
+            backoff.wait::<Env>();
+        }
+
+        let mut backoff = Backoff::new();
+        while !self.state.alloc(method, self.max_readers) {
+            if cancelled() {
+                return false;
+            }
+            backoff.wait::<Env>();
         }
+        true
     }
 
-    unsafe fn unlock(&self, method: Method, poison: bool) {
-        self.critical_section(|state| state.free(method));
-        self.poison.fetch_or(poison, Ordering::AcqRel);
+    /// Unlocks the lock held with `method`, poisoning it if `poison` is `true`. Returns whether
+    /// this newly poisoned the lock.
+    unsafe fn unlock(&self, method: Method, poison: bool) -> bool {
+        let released = self.state.free(method, self.max_readers);
+        let poisoned = self.poison.poison(poison);
+        if released {
+            // A released write lock can admit every waiting reader at once.
+            method.switch(|| self.waiters.notify_one(), || self.waiters.notify_all());
+        }
+        poisoned
     }
 }
 
-// SAFETY: `critical_section` enforces access to the `state` cell variable.
-unsafe impl<Hook: RwLockHook, Env: ThreadEnv> Sync for BaseRwLockInner<Hook, Env> {}
+// SAFETY: `state` is only ever accessed through atomic operations.
+unsafe impl<Hook: RwLockHook, Env: ThreadEnv, Policy: PoisonPolicy> Sync
+    for BaseRwLockInner<Hook, Env, Policy>
+{
+}
 
-impl<Hook: RwLockHook, Env: ThreadEnv> UnwindSafe for BaseRwLockInner<Hook, Env> {}
-impl<Hook: RwLockHook, Env: ThreadEnv> RefUnwindSafe for BaseRwLockInner<Hook, Env> {}
+impl<Hook: RwLockHook, Env: ThreadEnv, Policy: PoisonPolicy> UnwindSafe
+    for BaseRwLockInner<Hook, Env, Policy>
+{
+}
+impl<Hook: RwLockHook, Env: ThreadEnv, Policy: PoisonPolicy> RefUnwindSafe
+    for BaseRwLockInner<Hook, Env, Policy>
+{
+}
 
-#[derive(Debug)]
-pub struct BaseRwLock<T, Hook, Env>
+pub struct BaseRwLock<T, Hook, Env, Policy = Poison>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    inner: BaseRwLockInner<Hook, Env>,
+    inner: BaseRwLockInner<Hook, Env, Policy>,
+    #[cfg(feature = "multi")]
+    id: LockId,
     data: UnsafeCell<T>,
 }
 
-macro_rules! wrap_poison {
-    ($poisoned:expr, $data:expr) => {{
-        let (poisoned, data) = ($poisoned, $data);
-        if poisoned {
-            Err(PoisonError::new(data))
-        } else {
-            Ok(data)
-        }
-    }};
-}
-
-fn map_ok_and_poisoned<T, U>(r: TryLockResult<T>, f: impl FnOnce(T) -> U) -> TryLockResult<U> {
-    match r {
-        Ok(t) => Ok(f(t)),
-        Err(TryLockError::Poisoned(poison_error)) => Err(TryLockError::Poisoned(PoisonError::new(
-            f(poison_error.into_inner()),
-        ))),
-        Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
-    }
-}
-
-fn block_try_lock<T>(mut routine: impl FnMut() -> TryLockResult<T>) -> LockResult<T> {
-    loop {
-        match routine() {
-            Ok(t) => break Ok(t),
-            Err(TryLockError::Poisoned(poison)) => break Err(poison),
-            Err(TryLockError::WouldBlock) => continue,
+impl<T, Env, Policy> BaseRwLock<T, (), Env, Policy>
+where
+    T: Sized,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    const_fn! {
+        pub const fn new_unhooked(t: T) -> Self {
+            Self::with_hook(t, ())
         }
     }
 }
 
-impl<T, Env> BaseRwLock<T, (), Env>
+impl<T, Hook, Env, Policy> BaseRwLock<T, Hook, Env, Policy>
 where
     T: Sized,
+    Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    pub const fn new_unhooked(t: T) -> Self {
-        Self {
-            inner: BaseRwLockInner::new_unhooked(),
-            data: UnsafeCell::new(t),
+    const_fn! {
+        /// Renamed to [`with_hook`](BaseRwLock::with_hook).
+        #[deprecated = "use `with_hook` instead"]
+        pub const fn new_with_hook(t: T, hook: Hook) -> Self {
+            Self::with_hook(t, hook)
+        }
+    }
+
+    const_fn! {
+        /// Creates a new read-write lock with a `hook` configured by the caller, e.g. with
+        /// thresholds or a logger handle, that [`RwLockHook::new`] couldn't provide.
+        ///
+        /// Unlike [`new`](BaseRwLock::new), this is a `const fn`, so hooked locks can be placed in
+        /// `static`s as long as the hook itself can be constructed in a `const` context.
+        pub const fn with_hook(t: T, hook: Hook) -> Self {
+            Self {
+                inner: BaseRwLockInner::with_hook(hook),
+                #[cfg(feature = "multi")]
+                id: LockId::new(),
+                data: UnsafeCell::new(t),
+            }
         }
     }
 }
 
-impl<T, Hook, Env> BaseRwLock<T, Hook, Env>
+impl<T, Hook, Env, Policy> BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     pub fn new(t: T) -> Self
     where
         Self: Sized,
         T: Sized,
     {
-        Self {
-            inner: BaseRwLockInner::new(),
-            data: UnsafeCell::new(t),
-        }
+        Self::with_hook(t, Hook::new())
     }
 
-    pub fn get_mut(&mut self) -> LockResult<&mut T> {
-        wrap_poison!(self.is_poisoned(), self.data.get_mut())
+    /// Creates a new read-write lock with a `label`, see
+    /// [`mutex::BaseMutex::new_named`](crate::mutex::BaseMutex::new_named).
+    #[cfg(feature = "labels")]
+    pub fn new_named(label: &'static str, t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        let mut lock = Self::new(t);
+        lock.inner.label = Label::new(label);
+        lock
     }
 
-    pub fn into_inner(self) -> LockResult<T>
+    /// Creates a new read-write lock that admits at most `max_readers` readers at once.
+    ///
+    /// Further readers are turned away with [`TryLockError::WouldBlock`] by
+    /// [`try_read`](BaseRwLock::try_read), and wait for a reader to leave in
+    /// [`read`](BaseRwLock::read).
+    ///
+    /// # Panics
+    /// Panics if `max_readers` is zero, or larger than the lock state can count, which is
+    /// `isize::MAX` unless a narrower state is enabled by the `rwlock-state-*` features.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use powerlocks::rwlock::RwLock;
+    /// let lock = RwLock::with_max_readers(2, 0);
+    /// let (_a, _b) = (lock.read().unwrap(), lock.read().unwrap());
+    /// assert!(lock.try_read().is_err());
+    /// # }
+    /// ```
+    ///
+    pub fn with_max_readers(max_readers: usize, t: T) -> Self
     where
         Self: Sized,
         T: Sized,
     {
-        wrap_poison!(self.is_poisoned(), self.data.into_inner())
+        let mut lock = Self::new(t);
+        lock.inner.max_readers = checked_max_readers(max_readers);
+        lock
+    }
+
+    /// Returns a [`RwLockBuilder`] for a lock of this type, to configure several options at once.
+    pub fn builder() -> RwLockBuilder<T, Hook, Env, Policy>
+    where
+        T: Sized,
+    {
+        RwLockBuilder::new()
+    }
+
+    /// Returns the most readers that may hold this lock at once, see
+    /// [`with_max_readers`](BaseRwLock::with_max_readers).
+    pub fn max_readers(&self) -> usize {
+        widen(self.inner.max_readers)
+    }
+
+    /// Returns the label of this lock, if it was created with
+    /// [`new_named`](BaseRwLock::new_named).
+    #[cfg(feature = "labels")]
+    pub fn label(&self) -> Option<&'static str> {
+        self.inner.label.get()
+    }
+
+    /// Creates a new read-write lock inside an [`Arc`], ready to be shared between threads.
+    pub fn new_arc(t: T) -> Arc<Self>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Arc::new(Self::new(t))
+    }
+
+    /// Returns the id that [`multi::lock_all`](crate::multi::lock_all) orders this lock by.
+    #[cfg(feature = "multi")]
+    pub(crate) fn lock_id(&self) -> u64 {
+        self.id.get()
+    }
+
+    /// Returns a raw pointer to the underlying data.
+    ///
+    /// The pointer is always valid, but dereferencing it is only sound while the lock is held
+    /// appropriately, or when no other references to the data exist.
+    ///
+    /// See also: [`std::sync::RwLock::data_ptr`]
+    pub fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+
+    pub fn get_mut(&mut self) -> Policy::Result<&mut T> {
+        self.inner.wrap(self.data.get_mut())
+    }
+
+    /// Read-locks the lock just long enough to clone the protected data.
+    pub fn get_cloned(&self) -> Policy::Result<T>
+    where
+        T: Clone,
+    {
+        Policy::map_result(self.read(), |guard| T::clone(&guard))
+    }
+
+    /// Write-locks the lock to replace the protected data with `value`, dropping the old data, like
+    /// [`Mutex::set`](crate::mutex::BaseMutex::set).
+    pub fn set(&self, value: T) -> Policy::Result<()>
+    where
+        T: Sized,
+    {
+        Policy::map_result(self.replace(value), drop)
+    }
+
+    /// Write-locks the lock to replace the protected data with `value`, returning the old data,
+    /// like [`Mutex::replace`](crate::mutex::BaseMutex::replace).
+    pub fn replace(&self, value: T) -> Policy::Result<T>
+    where
+        T: Sized,
+    {
+        Policy::map_result(self.write(), |mut guard| mem::replace(&mut *guard, value))
+    }
+
+    /// Write-locks the lock to take the protected data, leaving [`Default::default`] in its place,
+    /// like [`Mutex::take`](crate::mutex::BaseMutex::take).
+    pub fn take(&self) -> Policy::Result<T>
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    pub fn into_inner(self) -> Policy::Result<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        let (poison, label) = (self.inner.poison, self.inner.label.get());
+        poison.wrap_labeled(self.data.into_inner(), label)
     }
 
     #[inline]
     pub fn is_poisoned(&self) -> bool {
-        self.inner.is_poisoned()
+        self.inner.poison.is_poisoned()
     }
 
     #[inline]
     pub fn clear_poison(&self) {
-        self.inner.clear_poison();
+        self.inner.poison.clear_poison();
     }
 
-    pub fn try_read(&self) -> TryLockResult<BaseRwLockReadGuard<'_, T, Hook, Env>> {
-        self.inner.hook.try_read().to_result()?;
+    /// Returns `true` if the lock is currently held, by either readers or a writer.
+    ///
+    /// This never blocks, and is only a snapshot that may already be out of date by the time it is
+    /// returned, so it is meant for monitoring and heuristics rather than synchronization.
+    pub fn is_locked(&self) -> bool {
+        self.is_write_locked() || self.reader_count() > 0
+    }
 
-        // SAFETY: The lock is acquired before guard creation by `try_lock`.
-        map_ok_and_poisoned(self.inner.try_lock(Method::Read), |_| unsafe {
-            BaseRwLockReadGuard::new(self)
-        })
+    /// Returns `true` if a writer currently holds the lock.
+    ///
+    /// See [`is_locked`](BaseRwLock::is_locked) for caveats.
+    pub fn is_write_locked(&self) -> bool {
+        self.inner.state.is_write_locked()
     }
 
-    pub fn read(&self) -> LockResult<BaseRwLockReadGuard<'_, T, Hook, Env>> {
-        block_try_lock(|| self.try_read())
+    /// Returns the number of readers currently holding the lock.
+    ///
+    /// See [`is_locked`](BaseRwLock::is_locked) for caveats.
+    pub fn reader_count(&self) -> usize {
+        self.inner.state.readers()
     }
 
-    pub fn try_write(&self) -> TryLockResult<BaseRwLockWriteGuard<'_, T, Hook, Env>> {
-        self.inner.hook.try_write().to_result()?;
+    pub fn try_read(&self) -> TryLockResult<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        if self.inner.try_lock(Method::Read) {
+            // SAFETY: The lock is acquired before guard creation by `try_lock`.
+            self.inner
+                .try_wrap(unsafe { BaseRwLockReadGuard::new(self) })
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
 
-        // SAFETY: The lock is acquired before guard creation by `try_lock`.
-        map_ok_and_poisoned(self.inner.try_lock(Method::Write), |_| unsafe {
-            BaseRwLockWriteGuard::new(self)
-        })
+    pub fn read(&self) -> Policy::Result<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        self.inner.lock(Method::Read, || Waiting::register(self));
+        // SAFETY: `lock` only returns once no writer has access.
+        self.inner.wrap(unsafe { BaseRwLockReadGuard::new(self) })
     }
 
-    pub fn write(&self) -> LockResult<BaseRwLockWriteGuard<'_, T, Hook, Env>> {
-        block_try_lock(|| self.try_write())
+    /// Runs `f` with shared read access to the data, and returns what it returns.
+    ///
+    /// On x86_64 CPUs with Intel's RTM, the lock is elided: `f` runs inside a hardware transaction
+    /// that only reads the lock, so concurrent readers don't contend on it. The transaction aborts
+    /// and discards everything `f` did if a writer takes the lock, or if `f` does something a
+    /// transaction can't, such as a system call or an allocation that reaches the OS. After a few
+    /// aborts, or on other CPUs, `f` runs under a read lock like [`read`](BaseRwLock::read)
+    /// instead. So `f` may start several times, but only its last run counts: the one whose
+    /// transaction commits, or the one under the read lock. If `f` panics inside a transaction, the
+    /// transaction is aborted, and `f` panics again under the read lock.
+    ///
+    /// Elision only pays off for short sections that touch little memory. The [`RwLockHook`] of
+    /// the lock doesn't see elided reads.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::rwlock::RwLock;
+    /// let lock = RwLock::new([1, 2, 3]);
+    /// assert_eq!(lock.read_elided(|data| data[1]).unwrap(), 2);
+    /// ```
+    ///
+    #[cfg(feature = "hle")]
+    pub fn read_elided<R>(&self, f: impl FnOnce(&T) -> R) -> Policy::Result<R> {
+        #[cfg(all(target_arch = "x86_64", not(loom)))]
+        let f = {
+            const ATTEMPTS: usize = 3;
+            let elided = elision::elide(ATTEMPTS, f, |f| {
+                // Reading the state and the poison flag makes the transaction abort as soon as a
+                // writer locks the lock, or poisons it.
+                if self.inner.state.is_write_locked() || self.inner.poison.is_poisoned() {
+                    // SAFETY: `elide` runs this inside a transaction.
+                    unsafe { elision::abort() };
+                }
+                // SAFETY: No writer holds the lock, and any writer that takes it before the
+                // transaction commits aborts it.
+                f(unsafe { &*self.data.get() })
+            });
+            match elided {
+                Ok(value) => return self.inner.wrap(value),
+                Err(elided) => elided,
+            }
+        };
+        self.inner.lock(Method::Read, || Waiting::register(self));
+        // SAFETY: `lock` only returns once no writer has access.
+        let guard = unsafe { BaseRwLockReadGuard::new(self) };
+        let value = f(&guard);
+        drop(guard);
+        self.inner.wrap(value)
+    }
+
+    /// Acquires shared read access like [`read`](BaseRwLock::read), but gives up and returns
+    /// [`TryLockError::Cancelled`] once `cancel` is set.
+    ///
+    /// While waiting, the thread keeps polling `cancel` instead of parking, so another thread can
+    /// abort the acquisition at any time by storing `true`, e.g. on shutdown.
+    pub fn read_until(
+        &self,
+        cancel: &CancelFlag,
+    ) -> TryLockResult<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        if self.inner.lock_until(Method::Read, cancel) {
+            // SAFETY: `lock_until` only returns `true` once no writer has access.
+            self.inner
+                .try_wrap(unsafe { BaseRwLockReadGuard::new(self) })
+        } else {
+            Err(TryLockError::Cancelled)
+        }
+    }
+
+    /// Forcibly releases one read lock without going through a guard.
+    ///
+    /// This is intended for FFI and panic-recovery scenarios where a read guard has been leaked
+    /// (e.g. via [`BaseRwLockReadGuard::leak`] or [`core::mem::forget`]) or otherwise lost.
+    ///
+    /// # Safety
+    /// The lock must currently be read-locked, and the read guard (or leaked reference) being
+    /// released must no longer be used after this call. Releasing a read lock that is still
+    /// accessed through a live guard may let a writer alias it, which is undefined behavior.
+    pub unsafe fn force_unlock_read(&self) {
+        debug_assert!(
+            self.inner.state.is_read_locked(),
+            "`force_unlock_read` called on a lock that is not read-locked"
+        );
+        // SAFETY: Caller promises that the lock is read-locked and that the previous reader is
+        // gone.
+        unsafe { self.inner.unlock(Method::Read, false) };
+        self.inner.after_hook(Method::Read);
+    }
+
+    pub fn try_write(&self) -> TryLockResult<BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>> {
+        if self.inner.try_lock(Method::Write) {
+            // SAFETY: The lock is acquired before guard creation by `try_lock`.
+            self.inner
+                .try_wrap(unsafe { BaseRwLockWriteGuard::new(self) })
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+
+    pub fn write(&self) -> Policy::Result<BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>> {
+        self.inner.lock(Method::Write, || Waiting::register(self));
+        // SAFETY: `lock` only returns once we have exclusive access.
+        self.inner.wrap(unsafe { BaseRwLockWriteGuard::new(self) })
+    }
+
+    /// Acquires exclusive write access like [`write`](BaseRwLock::write), but gives up and returns
+    /// [`TryLockError::Cancelled`] once `cancel` is set.
+    ///
+    /// See [`read_until`](BaseRwLock::read_until) for how cancellation is observed.
+    pub fn write_until(
+        &self,
+        cancel: &CancelFlag,
+    ) -> TryLockResult<BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>> {
+        if self.inner.lock_until(Method::Write, cancel) {
+            // SAFETY: `lock_until` only returns `true` once we have exclusive access.
+            self.inner
+                .try_wrap(unsafe { BaseRwLockWriteGuard::new(self) })
+        } else {
+            Err(TryLockError::Cancelled)
+        }
+    }
+
+    /// Forcibly releases the write lock without going through a guard. The lock is not poisoned
+    /// by this call.
+    ///
+    /// This is intended for FFI and panic-recovery scenarios where a write guard has been leaked
+    /// (e.g. via [`BaseRwLockWriteGuard::leak`] or [`core::mem::forget`]) or otherwise lost.
+    ///
+    /// # Safety
+    /// The lock must currently be write-locked, and the write guard (or leaked reference) must no
+    /// longer be used after this call. Releasing a write lock that is still accessed through a
+    /// live guard leads to data races and undefined behavior.
+    pub unsafe fn force_unlock_write(&self) {
+        debug_assert!(
+            self.inner.state.is_write_locked(),
+            "`force_unlock_write` called on a lock that is not write-locked"
+        );
+        // SAFETY: Caller promises that the lock is write-locked and that the previous writer is
+        // gone.
+        unsafe { self.inner.unlock(Method::Write, false) };
+        self.inner.after_hook(Method::Write);
     }
 }
 
-impl<T, Hook, Env> RwLockApi<T> for BaseRwLock<T, Hook, Env>
+impl<T, Hook, Env, Policy> RwLockApi<T> for BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn is_poisoned(&self) -> bool {
         self.is_poisoned()
@@ -272,7 +720,7 @@ where
     }
 
     fn get_mut(&mut self) -> LockResult<&mut T> {
-        self.get_mut()
+        Policy::into_lock_result(self.get_mut())
     }
 
     fn into_inner(self) -> LockResult<T>
@@ -280,7 +728,7 @@ where
         Self: Sized,
         T: Sized,
     {
-        self.into_inner()
+        Policy::into_lock_result(self.into_inner())
     }
 
     fn new(t: T) -> Self
@@ -302,7 +750,7 @@ where
     where
         T: 'a,
     {
-        self.read()
+        Policy::into_lock_result(self.read())
     }
 
     fn try_write<'a>(&'a self) -> TryLockResult<impl RwLockWriteGuardApi<'a, T>>
@@ -316,55 +764,114 @@ where
     where
         T: 'a,
     {
-        self.write()
+        Policy::into_lock_result(self.write())
     }
 }
 
-unsafe impl<T, Hook, Env> Send for BaseRwLock<T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Send for BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized + Send,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-unsafe impl<T, Hook, Env> Sync for BaseRwLock<T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Sync for BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized + Send + Sync,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<T, Hook, Env> UnwindSafe for BaseRwLock<T, Hook, Env>
+impl<T, Hook, Env, Policy> UnwindSafe for BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-impl<T, Hook, Env> RefUnwindSafe for BaseRwLock<T, Hook, Env>
+impl<T, Hook, Env, Policy> RefUnwindSafe for BaseRwLock<T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<T, Hook, Env> Default for BaseRwLock<T, Hook, Env>
+/// Formats the data like [`std::sync::RwLock`] does: a read lock is only tried, and `<locked>` is
+/// printed in place of the data if it is write-locked elsewhere.
+impl<T, Hook, Env, Policy> Debug for BaseRwLock<T, Hook, Env, Policy>
+where
+    T: ?Sized + Debug,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+        if let Some(label) = self.inner.label.get() {
+            d.field("label", &label);
+        }
+        // Probe the state directly rather than through `try_read`, so that formatting the lock
+        // doesn't run its hooks, or take the poison under `ClearOnNextLock`. The probe may go past
+        // `max_readers`, which only bounds the readers that the lock admits.
+        if self.inner.state.alloc(Method::Read, State::MAX_READERS) {
+            // SAFETY: We just read-locked the lock, and release it again once done with the data,
+            // even if formatting it panics. Reading doesn't poison the lock.
+            let _unlock = OnDrop(|| {
+                unsafe { self.inner.unlock(Method::Read, false) };
+            });
+            d.field("data", &unsafe { &*self.data.get() });
+        } else {
+            d.field("data", &format_args!("<locked>"));
+        }
+        d.field("poisoned", &self.is_poisoned());
+        d.finish_non_exhaustive()
+    }
+}
+
+impl<T, Hook, Env, Policy> Default for BaseRwLock<T, Hook, Env, Policy>
 where
     T: Default,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn default() -> Self {
         Self::new(T::default())
     }
 }
 
-impl<T, Hook, Env> From<T> for BaseRwLock<T, Hook, Env>
+#[cfg(feature = "registry")]
+impl<T, Hook, Env, Policy> crate::debug::Inspect for BaseRwLock<T, Hook, Env, Policy>
+where
+    T: ?Sized,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn inspect(&self) -> crate::debug::LockInfo {
+        crate::debug::LockInfo {
+            label: self.inner.label.get(),
+            ..crate::debug::LockInfo::new(
+                "RwLock",
+                self.inner.state.inspect(),
+                self.is_poisoned(),
+                self.inner.waiters.len(),
+            )
+        }
+    }
+}
+
+impl<T, Hook, Env, Policy> From<T> for BaseRwLock<T, Hook, Env, Policy>
 where
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn from(value: T) -> Self {
         Self::new(value)
@@ -373,38 +880,96 @@ where
 
 #[derive(Debug)]
 #[must_use = "if unused the read-write-lock will immediately unlock"]
-pub struct BaseRwLockReadGuard<'a, T, Hook, Env>
+pub struct BaseRwLockReadGuard<'a, T, Hook, Env, Policy = Poison>
 where
     T: 'a + ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    inner: &'a BaseRwLockInner<Hook, Env>,
+    lock: &'a BaseRwLock<T, Hook, Env, Policy>,
     // Use a raw pointer instead of a reference to prevent aliasing violations during `drop` when
     // the lock is released and then acquired by another thread before `drop` completes.
     data: NonNull<T>,
 }
 
-impl<'a, T, Hook, Env> BaseRwLockReadGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy> BaseRwLockReadGuard<'a, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    unsafe fn new(lock: &'a BaseRwLock<T, Hook, Env>) -> Self {
+    unsafe fn new(lock: &'a BaseRwLock<T, Hook, Env, Policy>) -> Self {
         Self {
-            inner: &lock.inner,
+            lock,
             // SAFETY: `UnsafeCell::get` never returns a null pointer.
             data: unsafe { NonNull::new_unchecked(lock.data.get()) },
         }
     }
+
+    /// Releases the read lock, runs `wait`, and then read-locks the lock again.
+    pub(crate) fn relock_after(self, wait: impl FnOnce()) -> Policy::Result<Self> {
+        let (lock, data) = (self.lock, self.data);
+        drop(self);
+        wait();
+        lock.inner.lock(Method::Read, || Waiting::register(lock));
+        // SAFETY: `lock` only returns once no writer has access to `data` again.
+        lock.inner.wrap(Self { lock, data })
+    }
+
+    /// Temporarily read-unlocks the lock to run `f`, and then read-locks it again, so that other
+    /// threads get a turn while `f` runs. Returns what `f` returns, wrapped according to whether
+    /// the lock was poisoned once it was locked again.
+    ///
+    /// The lock is locked again even if `f` panics.
+    pub fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> Policy::Result<U> {
+        let (lock, inner) = (self.lock, &self.lock.inner);
+        self.release();
+        // Keep our access from being lost for good when `f` panics, since `self` would still be
+        // dropped afterwards.
+        let relock = OnDrop(|| inner.lock(Method::Read, || Waiting::register(lock)));
+        let value = f();
+        drop(relock);
+        inner.wrap(value)
+    }
+
+    /// Temporarily read-unlocks the lock and yields, giving waiting threads a chance to lock it,
+    /// and then read-locks it again.
+    pub fn bump(&mut self) -> Policy::Result<()> {
+        self.unlocked(Env::yield_now)
+    }
+
+    fn release(&self) {
+        // SAFETY: Callers won't use `data` again until the lock is locked again.
+        unsafe { self.lock.inner.unlock(Method::Read, false) };
+        self.lock.inner.after_hook(Method::Read);
+    }
+
+    /// Returns the lock that this guard was acquired from.
+    pub fn rwlock(&self) -> &'a BaseRwLock<T, Hook, Env, Policy> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the lock read-locked forever and returning a shared reference to
+    /// the protected data that lives as long as the lock itself.
+    ///
+    /// Other readers may still acquire the lock, but writers will never be able to.
+    pub fn leak(self) -> &'a T {
+        let data = self.data;
+        core::mem::forget(self);
+        // SAFETY: Forgetting the guard means that the read lock is never released, so no writer
+        // can ever gain access to `data` for the rest of `'a`.
+        unsafe { data.as_ref() }
+    }
 }
 
-impl<T, Hook, Env> Deref for BaseRwLockReadGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy> Deref for BaseRwLockReadGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -412,74 +977,149 @@ where
     }
 }
 
-impl<T, Hook, Env> Drop for BaseRwLockReadGuard<'_, T, Hook, Env>
+forward_guard_traits! {
+    impl<T, Hook, Env, Policy> BaseRwLockReadGuard<'_, T, Hook, Env, Policy> => T
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+impl<T, Hook, Env, Policy> Drop for BaseRwLockReadGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn drop(&mut self) {
-        unsafe { self.inner.unlock(Method::Read, false) };
-        self.inner.hook.after_read();
+        self.release();
     }
 }
 
-unsafe impl<T, Hook, Env> Send for BaseRwLockReadGuard<'_, T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Send for BaseRwLockReadGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized + Send,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-unsafe impl<T, Hook, Env> Sync for BaseRwLockReadGuard<'_, T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Sync for BaseRwLockReadGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized + Sync,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<'a, T, Hook, Env> RwLockReadGuardApi<'a, T> for BaseRwLockReadGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy> RwLockReadGuardApi<'a, T>
+    for BaseRwLockReadGuard<'a, T, Hook, Env, Policy>
 where
     T: 'a + ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
 #[derive(Debug)]
 #[must_use = "if unused the read-write-lock will immediately unlock"]
-pub struct BaseRwLockWriteGuard<'a, T, Hook, Env>
+pub struct BaseRwLockWriteGuard<'a, T, Hook, Env, Policy = Poison>
 where
     T: 'a + ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    inner: &'a BaseRwLockInner<Hook, Env>,
+    lock: &'a BaseRwLock<T, Hook, Env, Policy>,
     // Use a raw pointer instead of a reference to prevent aliasing violations during `drop` when
     // the lock is released and then acquired by another thread before `drop` completes.
     data: *mut T,
 }
 
-impl<'a, T, Hook, Env> BaseRwLockWriteGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy> BaseRwLockWriteGuard<'a, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
-    unsafe fn new(lock: &'a BaseRwLock<T, Hook, Env>) -> Self {
+    unsafe fn new(lock: &'a BaseRwLock<T, Hook, Env, Policy>) -> Self {
         Self {
-            inner: &lock.inner,
+            lock,
             data: lock.data.get(),
         }
     }
+
+    /// Releases the write lock, runs `wait`, and then write-locks the lock again.
+    pub(crate) fn relock_after(self, wait: impl FnOnce()) -> Policy::Result<Self> {
+        let (lock, data) = (self.lock, self.data);
+        drop(self);
+        wait();
+        lock.inner.lock(Method::Write, || Waiting::register(lock));
+        // SAFETY: `lock` only returns once we have exclusive access to `data` again.
+        lock.inner.wrap(Self { lock, data })
+    }
+
+    /// Temporarily write-unlocks the lock to run `f`, and then write-locks it again, so that other
+    /// threads get a turn while `f` runs. Returns what `f` returns, wrapped according to whether
+    /// the lock was poisoned once it was locked again.
+    ///
+    /// The lock is locked again even if `f` panics.
+    pub fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> Policy::Result<U> {
+        let (lock, inner) = (self.lock, &self.lock.inner);
+        self.release();
+        // Keep our access from being lost for good when `f` panics, since `self` would still be
+        // dropped afterwards.
+        let relock = OnDrop(|| inner.lock(Method::Write, || Waiting::register(lock)));
+        let value = f();
+        drop(relock);
+        inner.wrap(value)
+    }
+
+    /// Temporarily write-unlocks the lock and yields, giving waiting threads a chance to lock it,
+    /// and then write-locks it again.
+    pub fn bump(&mut self) -> Policy::Result<()> {
+        self.unlocked(Env::yield_now)
+    }
+
+    fn release(&self) {
+        // SAFETY: Callers won't use `data` again until the lock is locked again.
+        let poisoned = unsafe { self.lock.inner.unlock(Method::Write, panicking::<Env>()) };
+        if poisoned {
+            self.lock.inner.poison_hook();
+        }
+        self.lock.inner.after_hook(Method::Write);
+    }
+
+    /// Returns the lock that this guard was acquired from.
+    pub fn rwlock(&self) -> &'a BaseRwLock<T, Hook, Env, Policy> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the lock write-locked forever and returning a mutable reference
+    /// to the protected data that lives as long as the lock itself.
+    ///
+    /// Any further attempt to read or write the lock will block (or fail with
+    /// [`TryLockError::WouldBlock`]).
+    pub fn leak(self) -> &'a mut T {
+        let data = self.data;
+        core::mem::forget(self);
+        // SAFETY: Forgetting the guard means that the write lock is never released, so we keep our
+        // exclusive access to `data` for the rest of `'a`.
+        unsafe { &mut *data }
+    }
 }
 
-impl<T, Hook, Env> Deref for BaseRwLockWriteGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy> Deref for BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -487,65 +1127,116 @@ where
     }
 }
 
-impl<T, Hook, Env> DerefMut for BaseRwLockWriteGuard<'_, T, Hook, Env>
+impl<T, Hook, Env, Policy> DerefMut for BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe { &mut *self.data }
     }
 }
 
-impl<T, Hook, Env> Drop for BaseRwLockWriteGuard<'_, T, Hook, Env>
+forward_guard_traits! {
+    impl<T, Hook, Env, Policy> BaseRwLockWriteGuard<'_, T, Hook, Env, Policy> => T
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_mut_traits! {
+    impl<T, Hook, Env, Policy> BaseRwLockWriteGuard<'_, T, Hook, Env, Policy> => T
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+stable_guard! {
+    impl<T, Hook, Env, Policy> BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+forward_guard_io! {
+    impl<T, Hook, Env, Policy> BaseRwLockWriteGuard<'_, T, Hook, Env, Policy> => T
+    where
+        T: ?Sized,
+        Hook: RwLockHook,
+        Env: ThreadEnv,
+        Policy: PoisonPolicy,
+}
+
+impl<T, Hook, Env, Policy> Drop for BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
     fn drop(&mut self) {
-        unsafe { self.inner.unlock(Method::Write, Env::panicking()) };
-        self.inner.hook.after_write();
+        self.release();
     }
 }
 
-unsafe impl<T, Hook, Env> Send for BaseRwLockWriteGuard<'_, T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Send for BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized + Send,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
-unsafe impl<T, Hook, Env> Sync for BaseRwLockWriteGuard<'_, T, Hook, Env>
+unsafe impl<T, Hook, Env, Policy> Sync for BaseRwLockWriteGuard<'_, T, Hook, Env, Policy>
 where
     T: ?Sized + Sync,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
-impl<'a, T, Hook, Env> RwLockWriteGuardApi<'a, T> for BaseRwLockWriteGuard<'a, T, Hook, Env>
+impl<'a, T, Hook, Env, Policy> RwLockWriteGuardApi<'a, T>
+    for BaseRwLockWriteGuard<'a, T, Hook, Env, Policy>
 where
     T: 'a + ?Sized,
     Hook: RwLockHook,
     Env: ThreadEnv,
+    Policy: PoisonPolicy,
 {
 }
 
 pub type CoreRwLock<T> = BaseRwLock<T, (), CoreThreadEnv>;
 pub type CoreRwLockReadGuard<'a, T> = BaseRwLockReadGuard<'a, T, (), CoreThreadEnv>;
 pub type CoreRwLockWriteGuard<'a, T> = BaseRwLockWriteGuard<'a, T, (), CoreThreadEnv>;
+pub type CoreUnpoisonedRwLock<T> = BaseRwLock<T, (), CoreThreadEnv, NoPoison>;
+pub type CoreUnpoisonedRwLockReadGuard<'a, T> =
+    BaseRwLockReadGuard<'a, T, (), CoreThreadEnv, NoPoison>;
+pub type CoreUnpoisonedRwLockWriteGuard<'a, T> =
+    BaseRwLockWriteGuard<'a, T, (), CoreThreadEnv, NoPoison>;
 
 #[cfg(feature = "std")]
 mod std_types {
-    use crate::primitives::StdThreadEnv;
+    use crate::primitives::{NoPoison, StdThreadEnv};
 
     use super::{BaseRwLock, BaseRwLockReadGuard, BaseRwLockWriteGuard};
 
     pub type StdRwLock<T> = BaseRwLock<T, (), StdThreadEnv>;
     pub type StdRwLockReadGuard<'a, T> = BaseRwLockReadGuard<'a, T, (), StdThreadEnv>;
     pub type StdRwLockWriteGuard<'a, T> = BaseRwLockWriteGuard<'a, T, (), StdThreadEnv>;
+    pub type StdUnpoisonedRwLock<T> = BaseRwLock<T, (), StdThreadEnv, NoPoison>;
+    pub type StdUnpoisonedRwLockReadGuard<'a, T> =
+        BaseRwLockReadGuard<'a, T, (), StdThreadEnv, NoPoison>;
+    pub type StdUnpoisonedRwLockWriteGuard<'a, T> =
+        BaseRwLockWriteGuard<'a, T, (), StdThreadEnv, NoPoison>;
 }
 
 #[cfg(feature = "std")]
@@ -553,19 +1244,34 @@ pub use std_types::*;
 
 #[cfg(not(feature = "std"))]
 mod main_type {
-    use super::{CoreRwLock, CoreRwLockReadGuard, CoreRwLockWriteGuard};
+    use super::{
+        CoreRwLock, CoreRwLockReadGuard, CoreRwLockWriteGuard, CoreUnpoisonedRwLock,
+        CoreUnpoisonedRwLockReadGuard, CoreUnpoisonedRwLockWriteGuard,
+    };
 
     pub type RwLock<T> = CoreRwLock<T>;
     pub type RwLockReadGuard<'a, T> = CoreRwLockReadGuard<'a, T>;
     pub type RwLockWriteGuard<'a, T> = CoreRwLockWriteGuard<'a, T>;
+    pub type UnpoisonedRwLock<T> = CoreUnpoisonedRwLock<T>;
+    pub type UnpoisonedRwLockReadGuard<'a, T> = CoreUnpoisonedRwLockReadGuard<'a, T>;
+    pub type UnpoisonedRwLockWriteGuard<'a, T> = CoreUnpoisonedRwLockWriteGuard<'a, T>;
 }
 #[cfg(feature = "std")]
 mod main_type {
-    use super::{StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard};
+    use super::{
+        StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard, StdUnpoisonedRwLock,
+        StdUnpoisonedRwLockReadGuard, StdUnpoisonedRwLockWriteGuard,
+    };
 
     pub type RwLock<T> = StdRwLock<T>;
     pub type RwLockReadGuard<'a, T> = StdRwLockReadGuard<'a, T>;
     pub type RwLockWriteGuard<'a, T> = StdRwLockWriteGuard<'a, T>;
+    pub type UnpoisonedRwLock<T> = StdUnpoisonedRwLock<T>;
+    pub type UnpoisonedRwLockReadGuard<'a, T> = StdUnpoisonedRwLockReadGuard<'a, T>;
+    pub type UnpoisonedRwLockWriteGuard<'a, T> = StdUnpoisonedRwLockWriteGuard<'a, T>;
 }
 
 pub use main_type::*;
+
+/// A [`RwLock`] shared through an [`Arc`], as created by [`BaseRwLock::new_arc`].
+pub type ArcRwLock<T> = Arc<RwLock<T>>;
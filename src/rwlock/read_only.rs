@@ -0,0 +1,114 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::primitives::{PoisonPolicy, ThreadEnv, TryLockResult, sync::CancelFlag};
+
+extern crate alloc;
+use alloc::sync::Arc;
+
+use super::{BaseRwLock, BaseRwLockReadGuard, RwLockHook};
+
+impl<T, Hook, Env, Policy> BaseRwLock<T, Hook, Env, Policy>
+where
+    T: ?Sized,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Returns a view of the lock that can only read it.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use powerlocks::rwlock::{ReadOnlyRwLock, RwLock};
+    /// fn total(numbers: ReadOnlyRwLock<&RwLock<Vec<i32>>>) -> i32 {
+    ///     numbers.read().unwrap().iter().sum()
+    /// }
+    ///
+    /// let numbers = RwLock::new(vec![1, 2]);
+    /// numbers.write().unwrap().push(3);
+    /// assert_eq!(total(numbers.read_only()), 6);
+    /// # }
+    /// ```
+    ///
+    pub fn read_only(&self) -> ReadOnlyRwLock<&Self> {
+        ReadOnlyRwLock { lock: self }
+    }
+
+    /// Returns a view of the lock that can only read it, and that shares ownership of the lock.
+    pub fn read_only_arc(self: &Arc<Self>) -> ReadOnlyRwLock<Arc<Self>> {
+        ReadOnlyRwLock {
+            lock: Arc::clone(self),
+        }
+    }
+}
+
+/// A view of a [`BaseRwLock`] that only exposes its reading methods, so that it can be handed to
+/// code that must never write.
+///
+/// `P` is a pointer to the lock: a reference for [`BaseRwLock::read_only`], or an [`Arc`] for
+/// [`BaseRwLock::read_only_arc`].
+///
+#[derive(Clone, Copy)]
+pub struct ReadOnlyRwLock<P> {
+    lock: P,
+}
+
+impl<P, T, Hook, Env, Policy> ReadOnlyRwLock<P>
+where
+    P: Deref<Target = BaseRwLock<T, Hook, Env, Policy>>,
+    T: ?Sized,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// See [`BaseRwLock::try_read`].
+    pub fn try_read(&self) -> TryLockResult<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        self.lock.try_read()
+    }
+
+    /// See [`BaseRwLock::read`].
+    pub fn read(&self) -> Policy::Result<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        self.lock.read()
+    }
+
+    /// See [`BaseRwLock::read_until`].
+    pub fn read_until(
+        &self,
+        cancel: &CancelFlag,
+    ) -> TryLockResult<BaseRwLockReadGuard<'_, T, Hook, Env, Policy>> {
+        self.lock.read_until(cancel)
+    }
+
+    /// See [`BaseRwLock::get_cloned`].
+    pub fn get_cloned(&self) -> Policy::Result<T>
+    where
+        T: Clone,
+    {
+        self.lock.get_cloned()
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.lock.is_poisoned()
+    }
+
+    #[cfg(feature = "labels")]
+    pub fn label(&self) -> Option<&'static str> {
+        self.lock.label()
+    }
+}
+
+impl<P, T, Hook, Env, Policy> Debug for ReadOnlyRwLock<P>
+where
+    P: Deref<Target = BaseRwLock<T, Hook, Env, Policy>>,
+    T: ?Sized + Debug,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReadOnlyRwLock").field(&&*self.lock).finish()
+    }
+}
@@ -0,0 +1,81 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::primitives::{PoisonPolicy, ThreadEnv};
+
+extern crate alloc;
+use alloc::sync::Arc;
+
+use super::{BaseRwLock, ReadOnlyRwLock, RwLockHook};
+
+impl<T, Hook, Env, Policy> BaseRwLock<T, Hook, Env, Policy>
+where
+    T: ?Sized,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Splits a lock into a reader half, which can be cloned but only reads, and a writer half,
+    /// which is the only handle left that can write.
+    ///
+    /// # Panics
+    /// Panics if the [`Arc`] is shared, as its other clones could still write.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use powerlocks::rwlock::RwLock;
+    /// let (reader, writer) = RwLock::new_arc(0).split_arc();
+    ///
+    /// let thread = std::thread::spawn(move || *writer.write().unwrap() += 1);
+    /// thread.join().unwrap();
+    /// assert_eq!(*reader.read().unwrap(), 1);
+    /// # }
+    /// ```
+    ///
+    pub fn split_arc(mut self: Arc<Self>) -> (ReadOnlyRwLock<Arc<Self>>, RwLockWriter<Self>) {
+        assert!(
+            Arc::get_mut(&mut self).is_some(),
+            "`split_arc` was called on a shared `Arc`"
+        );
+        let writer = RwLockWriter { lock: self };
+        (writer.reader(), writer)
+    }
+}
+
+/// The writer half of a lock split by [`BaseRwLock::split_arc`].
+///
+/// It dereferences to the lock, so that it can both read and write, but it can't be cloned, so
+/// there is only ever one of it.
+///
+pub struct RwLockWriter<L: ?Sized> {
+    lock: Arc<L>,
+}
+
+impl<T, Hook, Env, Policy> RwLockWriter<BaseRwLock<T, Hook, Env, Policy>>
+where
+    T: ?Sized,
+    Hook: RwLockHook,
+    Env: ThreadEnv,
+    Policy: PoisonPolicy,
+{
+    /// Creates another reader of the lock.
+    pub fn reader(&self) -> ReadOnlyRwLock<Arc<BaseRwLock<T, Hook, Env, Policy>>> {
+        self.lock.read_only_arc()
+    }
+}
+
+impl<L: ?Sized> Deref for RwLockWriter<L> {
+    type Target = L;
+    fn deref(&self) -> &Self::Target {
+        &self.lock
+    }
+}
+
+impl<L: ?Sized + Debug> Debug for RwLockWriter<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RwLockWriter").field(&&*self.lock).finish()
+    }
+}
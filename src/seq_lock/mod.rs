@@ -0,0 +1,260 @@
+//! A sequence lock, for publishing small values that are read far more often than written.
+//!
+//! Readers of a [`BaseSeqLock`] never write to shared memory: they copy the value out and check a
+//! sequence number that every write bumps, retrying if a write happened in between. Unlike the
+//! readers of a read-write lock, they thus don't contend on a reader count, and never hold up the
+//! writer. In exchange, a reader may retry many times while writes keep coming in, and the value
+//! has to be [`Copy`], as a reader may copy it out while it is being written.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicUsize, Ordering, fence},
+    const_fn,
+};
+
+use crate::primitives::{
+    Backoff, CoreThreadEnv, ThreadEnv, forward_guard_mut_traits, forward_guard_traits, stable_guard,
+};
+
+/// A sequence lock, holding a [`Copy`] value that is read optimistically.
+///
+/// The sequence number is odd while a writer holds the lock. Writers are serialized by moving it
+/// from even to odd, and readers retry until they read the same even number before and after
+/// copying the value. Waiting threads back off according to `Env`.
+///
+/// A writer that panics doesn't poison the lock: whatever it wrote so far is published when its
+/// guard is dropped.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::seq_lock::SeqLock;
+/// static POSITION: SeqLock<(f64, f64)> = SeqLock::new((0.0, 0.0));
+///
+/// *POSITION.write() = (1.0, 2.0);
+/// assert_eq!(POSITION.read(), (1.0, 2.0));
+/// # }
+/// ```
+///
+pub struct BaseSeqLock<T: Copy, Env: ThreadEnv> {
+    seq: AtomicUsize,
+    value: UnsafeCell<T>,
+    env: PhantomData<fn() -> Env>,
+}
+
+impl<T: Copy, Env: ThreadEnv> BaseSeqLock<T, Env> {
+    const_fn! {
+        pub const fn new(value: T) -> Self {
+            Self {
+                seq: AtomicUsize::new(0),
+                value: UnsafeCell::new(value),
+                env: PhantomData,
+            }
+        }
+    }
+
+    /// Returns a copy of the value, retrying while a writer holds the lock.
+    pub fn read(&self) -> T {
+        let mut backoff = Backoff::new();
+        loop {
+            if let Some(value) = self.try_read() {
+                return value;
+            }
+            backoff.wait::<Env>();
+        }
+    }
+
+    /// Attempts to copy the value once, returning [`None`] if a writer held the lock meanwhile.
+    pub fn try_read(&self) -> Option<T> {
+        let seq = self.seq.load(Ordering::Acquire);
+        if seq & 1 != 0 {
+            return None;
+        }
+        // SAFETY: The pointer is valid and aligned. A writer may write the value concurrently, so
+        // the copy may be torn, which is why it is only assumed initialized once the sequence
+        // number shows that no write happened.
+        let value = unsafe { ptr::read_volatile(self.value.get().cast::<MaybeUninit<T>>()) };
+        // Keeps the copy before the second load, pairing with the fence in `try_write`.
+        fence(Ordering::Acquire);
+        if self.seq.load(Ordering::Relaxed) == seq {
+            // SAFETY: No writer held the lock while the value was copied, so the copy is whole.
+            Some(unsafe { value.assume_init() })
+        } else {
+            None
+        }
+    }
+
+    /// Locks the value for writing, blocking while another writer holds the lock.
+    pub fn write(&self) -> BaseSeqLockWriteGuard<'_, T, Env> {
+        let mut backoff = Backoff::new();
+        loop {
+            if let Some(guard) = self.try_write() {
+                return guard;
+            }
+            backoff.wait::<Env>();
+        }
+    }
+
+    /// Attempts to lock the value for writing, returning [`None`] if another writer holds the
+    /// lock.
+    pub fn try_write(&self) -> Option<BaseSeqLockWriteGuard<'_, T, Env>> {
+        let seq = self.seq.load(Ordering::Relaxed);
+        if seq & 1 != 0 {
+            return None;
+        }
+        self.seq
+            .compare_exchange(
+                seq,
+                seq.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .ok()?;
+        // Keeps the writes of the guard after the odd sequence number, for readers that see them.
+        fence(Ordering::Release);
+        Some(BaseSeqLockWriteGuard {
+            lock: self,
+            seq,
+            marker: PhantomData,
+        })
+    }
+
+    /// Replaces the value with `value`.
+    pub fn set(&self, value: T) {
+        *self.write() = value;
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Copy + Default, Env: ThreadEnv> Default for BaseSeqLock<T, Env> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy, Env: ThreadEnv> From<T> for BaseSeqLock<T, Env> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Copy + Debug, Env: ThreadEnv> Debug for BaseSeqLock<T, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("SeqLock");
+        match self.try_read() {
+            Some(value) => d.field("data", &value),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+// SAFETY: Readers only get copies of the value, which may be taken on any thread, and writers are
+// serialized.
+unsafe impl<T: Copy + Send, Env: ThreadEnv> Sync for BaseSeqLock<T, Env> {}
+
+/// The write lock of a [`BaseSeqLock`]. The value written through it is published when it is
+/// dropped.
+#[must_use = "if unused the SeqLock will immediately unlock"]
+pub struct BaseSeqLockWriteGuard<'a, T: Copy, Env: ThreadEnv> {
+    lock: &'a BaseSeqLock<T, Env>,
+    seq: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T: Copy, Env: ThreadEnv> Deref for BaseSeqLockWriteGuard<'_, T, Env> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Holding the write lock, only this guard writes the value.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: Copy, Env: ThreadEnv> DerefMut for BaseSeqLockWriteGuard<'_, T, Env> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Holding the write lock, only this guard writes the value. Readers that copy it
+        // meanwhile discard their copy.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: Copy, Env: ThreadEnv> Drop for BaseSeqLockWriteGuard<'_, T, Env> {
+    fn drop(&mut self) {
+        self.lock
+            .seq
+            .store(self.seq.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl<T: Copy + Debug, Env: ThreadEnv> Debug for BaseSeqLockWriteGuard<'_, T, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+forward_guard_traits! {
+    impl<T, Env> BaseSeqLockWriteGuard<'_, T, Env> => T
+    where
+        T: Copy,
+        Env: ThreadEnv,
+}
+
+forward_guard_mut_traits! {
+    impl<T, Env> BaseSeqLockWriteGuard<'_, T, Env> => T
+    where
+        T: Copy,
+        Env: ThreadEnv,
+}
+
+stable_guard! {
+    impl<T, Env> BaseSeqLockWriteGuard<'_, T, Env>
+    where
+        T: Copy,
+        Env: ThreadEnv,
+}
+
+pub type CoreSeqLock<T> = BaseSeqLock<T, CoreThreadEnv>;
+pub type CoreSeqLockWriteGuard<'a, T> = BaseSeqLockWriteGuard<'a, T, CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::{BaseSeqLock, BaseSeqLockWriteGuard};
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdSeqLock<T> = BaseSeqLock<T, StdThreadEnv>;
+    pub type StdSeqLockWriteGuard<'a, T> = BaseSeqLockWriteGuard<'a, T, StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::{CoreSeqLock, CoreSeqLockWriteGuard};
+    pub type SeqLock<T> = CoreSeqLock<T>;
+    pub type SeqLockWriteGuard<'a, T> = CoreSeqLockWriteGuard<'a, T>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::{StdSeqLock, StdSeqLockWriteGuard};
+    pub type SeqLock<T> = StdSeqLock<T>;
+    pub type SeqLockWriteGuard<'a, T> = StdSeqLockWriteGuard<'a, T>;
+}
+
+pub use types::*;
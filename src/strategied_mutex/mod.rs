@@ -0,0 +1,389 @@
+//! A mutex whose waiters are handed the lock in an order picked by a [`MutexStrategy`].
+//!
+//! This is the mutex counterpart of [`strategied_rwlock`](crate::strategied_rwlock), and shares
+//! its queue. Where [`mutex::Mutex`](crate::mutex::Mutex) lets waiters race for the lock, this
+//! mutex hands it over directly to the waiter picked by its strategy.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "std")] {
+//! # use powerlocks::strategied_mutex::{StdMutex, strategies};
+//! let mutex = StdMutex::new_strategied(0, Box::new(strategies::priority));
+//! *mutex.lock_with_priority(10).unwrap() += 1;
+//! assert_eq!(*mutex.lock().unwrap(), 1);
+//! # }
+//! ```
+
+pub mod strategies;
+
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    panic::{RefUnwindSafe, UnwindSafe},
+    ptr::NonNull,
+};
+
+#[cfg(feature = "std")]
+use crate::primitives::park_capped_until;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+extern crate alloc;
+use alloc::{boxed::Box, sync::Arc};
+
+use crate::{
+    mutex::{MutexApi, MutexGuardApi},
+    primitives::{
+        AsyncHandle, CoreHandle, Handle, HandleId, LockResult, TryLockError, TryLockResult,
+        Waiting, forward_guard_mut_traits, forward_guard_traits, stable_guard,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            const_fn,
+        },
+    },
+    strategied_rwlock::{
+        Method,
+        impls::{Queue, Scheduler, wrap_if_poisoned},
+    },
+};
+
+/// The waiters passed to a [`MutexStrategy`], oldest first.
+///
+/// Each item pairs the [`HandleId`] of a waiting thread with the priority it is waiting with, see
+/// [`BaseMutex::lock_with_priority`]. The thread holding the mutex, if any, is not included.
+pub type MutexStrategyInput<'i> = &'i mut dyn Iterator<Item = &'i (HandleId, u32)>;
+
+/// Picks which waiter gets a [`BaseMutex`] next.
+///
+/// A `MutexStrategy` is a [`Fn`] or function pointer that is consulted whenever the mutex is free
+/// while threads are waiting for it, and returns the index of the waiter in its
+/// [`MutexStrategyInput`] that gets the mutex. Returning an index past the last waiter is a logic
+/// error, which panics and leaves the mutex unusable, like a logic error in a
+/// [`Strategy`](crate::strategied_rwlock::Strategy).
+///
+/// # Examples
+///
+/// In crate [`strategies`]:
+/// - [`strategies::fifo`] - hands the mutex to waiters in arrival order.
+/// - [`strategies::priority`] - hands the mutex to the waiter with the highest priority.
+/// - [`strategies::random`] - hands the mutex to a random waiter, for stress testing.
+///
+pub trait MutexStrategy: Fn(MutexStrategyInput) -> usize {}
+impl<F> MutexStrategy for F where F: ?Sized + Fn(MutexStrategyInput) -> usize {}
+
+#[derive(Debug)]
+#[must_use = "if unused the `Mutex` will immediately unlock"]
+pub struct BaseMutexGuard<'a, T: 'a + ?Sized, H: Handle> {
+    // See `BaseRwLockReadGuard` for why this is a raw pointer.
+    data: NonNull<T>,
+    id: HandleId,
+    lock: &'a BaseMutex<T, H>,
+    // Enforce invariance over `T` because `NonNull` is covariant.
+    invariant_t: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: 'a + ?Sized, H: Handle> BaseMutexGuard<'a, T, H> {
+    unsafe fn new(lock: &'a BaseMutex<T, H>, id: HandleId) -> Self {
+        Self {
+            // SAFETY: `data.get()` always returns a non-null pointer.
+            data: unsafe { NonNull::new_unchecked(lock.data.get()) },
+            id,
+            lock,
+            invariant_t: PhantomData,
+        }
+    }
+
+    /// Returns the mutex that this guard was acquired from.
+    pub fn mutex(&self) -> &'a BaseMutex<T, H> {
+        self.lock
+    }
+}
+
+// SAFETY: `BaseMutexGuard` is `Send` for the same reason as `BaseRwLockWriteGuard`.
+unsafe impl<'a, T: 'a + ?Sized + Send, H: Handle> Send for BaseMutexGuard<'a, T, H> {}
+unsafe impl<'a, T: 'a + ?Sized + Sync, H: Handle> Sync for BaseMutexGuard<'a, T, H> {}
+
+impl<'a, T: 'a + ?Sized, H: Handle> UnwindSafe for BaseMutexGuard<'a, T, H> {}
+impl<'a, T: 'a + ?Sized, H: Handle> RefUnwindSafe for BaseMutexGuard<'a, T, H> {}
+
+impl<'a, T: 'a + ?Sized, H: Handle> Deref for BaseMutexGuard<'a, T, H> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<'a, T: 'a + ?Sized, H: Handle> DerefMut for BaseMutexGuard<'a, T, H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.data.as_mut() }
+    }
+}
+
+forward_guard_traits! {
+    impl<'a, T, H> BaseMutexGuard<'a, T, H> => T
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
+forward_guard_mut_traits! {
+    impl<'a, T, H> BaseMutexGuard<'a, T, H> => T
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
+stable_guard! {
+    impl<'a, T, H> BaseMutexGuard<'a, T, H>
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
+impl<'a, T: 'a + ?Sized, H: Handle> Drop for BaseMutexGuard<'a, T, H> {
+    fn drop(&mut self) {
+        // Poison before releasing, so the next holder is sure to see it.
+        self.lock
+            .poisoned
+            .fetch_or(H::panicking(), Ordering::AcqRel);
+        self.lock.queue.release(self.id);
+    }
+}
+
+#[derive(Debug)]
+pub struct BaseMutex<T: ?Sized, H: Handle> {
+    queue: Queue<H>,
+    poisoned: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+impl<T: Sized, H: Handle> BaseMutex<T, H> {
+    const_fn! {
+        pub const fn new_strategied(t: T, strategy: Box<dyn MutexStrategy>) -> Self {
+            Self {
+                queue: Queue::new(Scheduler::Mutex(strategy)),
+                poisoned: AtomicBool::new(false),
+                data: UnsafeCell::new(t),
+            }
+        }
+    }
+
+    pub fn new(t: T) -> Self {
+        BaseMutex::new_strategied(t, Box::new(strategies::fifo))
+    }
+
+    /// Creates a new mutex inside an [`Arc`], ready to be shared between threads.
+    pub fn new_arc(t: T) -> Arc<Self> {
+        Arc::new(Self::new(t))
+    }
+
+    pub fn into_inner(self) -> LockResult<T> {
+        wrap_if_poisoned(self.is_poisoned(), self.data.into_inner())
+    }
+}
+
+impl<T: ?Sized, H: Handle> BaseMutex<T, H> {
+    fn guard(&self, id: HandleId) -> LockResult<BaseMutexGuard<'_, T, H>> {
+        // SAFETY: Callers only pass handles that were just handed the mutex.
+        wrap_if_poisoned(self.is_poisoned(), unsafe { BaseMutexGuard::new(self, id) })
+    }
+
+    pub fn lock(&self) -> LockResult<BaseMutexGuard<'_, T, H>> {
+        self.lock_with_priority(0)
+    }
+
+    /// Locks the mutex like [`lock`](BaseMutex::lock), waiting with the given `priority`.
+    ///
+    /// The priority is passed on to the [`MutexStrategy`], which is free to ignore it, as
+    /// [`strategies::fifo`] does.
+    pub fn lock_with_priority(&self, priority: u32) -> LockResult<BaseMutexGuard<'_, T, H>> {
+        let handle = self
+            .queue
+            .acquire_tagged(Method::Write, u64::from(priority), || {
+                Waiting::register(self)
+            });
+        self.guard(handle)
+    }
+
+    pub fn try_lock(&self) -> TryLockResult<BaseMutexGuard<'_, T, H>> {
+        if let Ok(handle) = self.queue.try_acquire(Method::Write) {
+            self.guard(handle).map_err(TryLockError::Poisoned)
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+
+    /// Locks the mutex like [`lock`](BaseMutex::lock), but gives up and returns
+    /// [`TryLockError::TimedOut`] if this thread hasn't been handed the mutex within `timeout`.
+    #[cfg(feature = "std")]
+    pub fn lock_timeout(&self, timeout: Duration) -> TryLockResult<BaseMutexGuard<'_, T, H>> {
+        if let Ok(handle) =
+            self.queue
+                .acquire_with(Method::Write, 0, false, park_capped_until(timeout))
+        {
+            self.guard(handle).map_err(TryLockError::Poisoned)
+        } else {
+            Err(TryLockError::TimedOut)
+        }
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        wrap_if_poisoned(self.is_poisoned(), self.data.get_mut())
+    }
+
+    /// Replaces the [`MutexStrategy`] of this mutex, keeping every thread that is waiting on it.
+    ///
+    /// The thread holding the mutex keeps it until it unlocks it.
+    pub fn set_strategy(&self, strategy: Box<dyn MutexStrategy>) {
+        self.queue.set_strategy(Scheduler::Mutex(strategy));
+    }
+
+    /// Returns the number of threads that are waiting for this mutex, as a snapshot like the
+    /// metrics of [`StrategiedRwLockApi`](crate::strategied_rwlock::StrategiedRwLockApi).
+    pub fn waiters(&self) -> usize {
+        self.queue.queued(Method::Write)
+    }
+}
+
+impl<T: ?Sized> BaseMutex<T, AsyncHandle> {
+    /// Locks the mutex like [`lock`](BaseMutex::lock), but awaits its turn instead of parking the
+    /// thread, see [`BaseRwLock::read_async`](crate::strategied_rwlock::BaseRwLock::read_async).
+    pub async fn lock_async(&self) -> LockResult<BaseMutexGuard<'_, T, AsyncHandle>> {
+        self.lock_with_priority_async(0).await
+    }
+
+    /// Locks the mutex like [`lock_async`](BaseMutex::lock_async), waiting with the given
+    /// `priority`, see [`lock_with_priority`](BaseMutex::lock_with_priority).
+    pub async fn lock_with_priority_async(
+        &self,
+        priority: u32,
+    ) -> LockResult<BaseMutexGuard<'_, T, AsyncHandle>> {
+        let handle = self
+            .queue
+            .acquire_async(Method::Write, u64::from(priority))
+            .await;
+        self.guard(handle)
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T: ?Sized, H: Handle> crate::debug::Inspect for BaseMutex<T, H> {
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let state = match self.queue.holders() {
+            0 => LockState::Unlocked,
+            _ => LockState::Written,
+        };
+        LockInfo::new("StrategiedMutex", state, self.is_poisoned(), self.waiters())
+    }
+}
+
+impl<T: Sized, H: Handle> From<T> for BaseMutex<T, H> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Default, H: Handle> Default for BaseMutex<T, H> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+// SAFETY: As for `BaseRwLock`, `T` only needs to be `Send` to send the mutex.
+unsafe impl<T: ?Sized + Send, H: Handle> Send for BaseMutex<T, H> {}
+
+// SAFETY: The queue hands the mutex to a single thread at a time, so sharing the mutex only ever
+// sends `&mut T` to other threads, one at a time.
+unsafe impl<T: ?Sized + Send, H: Handle> Sync for BaseMutex<T, H> {}
+
+impl<T: ?Sized, H: Handle> UnwindSafe for BaseMutex<T, H> {}
+impl<T: ?Sized, H: Handle> RefUnwindSafe for BaseMutex<T, H> {}
+
+impl<'a, T: ?Sized, H: Handle> MutexGuardApi<'a, T> for BaseMutexGuard<'a, T, H> {}
+
+impl<T: ?Sized, H: Handle> MutexApi<T> for BaseMutex<T, H> {
+    fn try_lock<'a>(&'a self) -> TryLockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.try_lock()
+    }
+
+    fn lock<'a>(&'a self) -> LockResult<impl MutexGuardApi<'a, T>>
+    where
+        T: 'a,
+    {
+        self.lock()
+    }
+
+    fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.get_mut()
+    }
+
+    fn new(t: T) -> Self
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        Self::new(t)
+    }
+
+    fn into_inner(self) -> LockResult<T>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        self.into_inner()
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.is_poisoned()
+    }
+
+    fn clear_poison(&self) {
+        self.clear_poison();
+    }
+}
+
+pub type CoreMutexGuard<'a, T> = BaseMutexGuard<'a, T, CoreHandle>;
+pub type CoreMutex<T> = BaseMutex<T, CoreHandle>;
+
+pub type AsyncMutexGuard<'a, T> = BaseMutexGuard<'a, T, AsyncHandle>;
+/// A mutex that async tasks lock with [`lock_async`](BaseMutex::lock_async).
+pub type AsyncMutex<T> = BaseMutex<T, AsyncHandle>;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::{BaseMutex, BaseMutexGuard};
+    use crate::primitives::CoreHandle;
+
+    pub type MutexGuard<'a, T> = BaseMutexGuard<'a, T, CoreHandle>;
+    pub type Mutex<T> = BaseMutex<T, CoreHandle>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::{BaseMutex, BaseMutexGuard};
+    use crate::primitives::StdHandle;
+
+    pub type StdMutexGuard<'a, T> = BaseMutexGuard<'a, T, StdHandle>;
+    pub type StdMutex<T> = BaseMutex<T, StdHandle>;
+
+    pub type MutexGuard<'a, T> = BaseMutexGuard<'a, T, StdHandle>;
+    pub type Mutex<T> = BaseMutex<T, StdHandle>;
+}
+
+pub use types::*;
+
+/// A [`Mutex`] shared through an [`Arc`], as created by [`BaseMutex::new_arc`].
+pub type ArcMutex<T> = Arc<Mutex<T>>;
@@ -0,0 +1,35 @@
+use crate::{mutex::CoreUnpoisonedMutex, strategied_rwlock::strategies::SplitMix64};
+
+use super::MutexStrategyInput;
+
+/// A strategy that hands the lock to the waiter that has been waiting the longest.
+pub fn fifo(_waiters: MutexStrategyInput) -> usize {
+    0
+}
+
+/// A strategy that hands the lock to the waiter with the highest priority, as given to
+/// [`lock_with_priority`](super::BaseMutex::lock_with_priority). Waiters with the same priority
+/// are served in arrival order.
+///
+/// Low priority waiters are starved for as long as higher priority ones keep arriving.
+///
+pub fn priority(waiters: MutexStrategyInput) -> usize {
+    waiters
+        .enumerate()
+        // `max_by_key` keeps the last of equal elements, so reverse the order of arrival.
+        .max_by_key(|&(index, &(_handle_id, priority))| (priority, usize::MAX - index))
+        .map_or(0, |(index, _)| index)
+}
+
+/// A strategy that hands the lock to a random waiter, for stress testing.
+///
+/// The decisions are fully determined by `seed` and the order in which the strategy is consulted,
+/// so a failing seed can be replayed. Waiters may be starved for as long as others keep arriving.
+///
+pub fn random(seed: u64) -> impl Fn(MutexStrategyInput) -> usize + Send + Sync + 'static {
+    let rng = CoreUnpoisonedMutex::new(SplitMix64(seed));
+    move |waiters| {
+        let waiters = waiters.count();
+        rng.lock().below(waiters)
+    }
+}
@@ -5,9 +5,30 @@ use alloc::boxed::Box;
 
 use crate::rwlock::RwLockApi;
 
+/// The API of the read-write locks that queue their threads according to a [`Strategy`].
+///
+/// Its metrics, such as [`queued_readers`](StrategiedRwLockApi::queued_readers), are only
+/// snapshots: the queue keeps changing while they are read, so they may already be out of date by
+/// the time they are returned.
 pub trait StrategiedRwLockApi<T: ?Sized>: RwLockApi<T> {
     fn new_strategied(t: T, strategy: Box<dyn Strategy>) -> Self
     where
         Self: Sized,
         T: Sized;
+
+    fn set_strategy(&self, strategy: Box<dyn Strategy>);
+
+    /// Returns the number of threads that are waiting to read from the lock.
+    fn queued_readers(&self) -> usize;
+
+    /// Returns the number of threads that are waiting to write to the lock.
+    fn queued_writers(&self) -> usize;
+
+    /// Returns the number of threads that are waiting for the lock, either to read or to write.
+    fn queue_len(&self) -> usize {
+        self.queued_readers() + self.queued_writers()
+    }
+
+    /// Returns the number of readers holding the lock, and whether a writer holds it.
+    fn active(&self) -> (usize, bool);
 }
@@ -0,0 +1,109 @@
+use core::{marker::PhantomData, time::Duration};
+
+extern crate alloc;
+use alloc::boxed::Box;
+
+use crate::primitives::Handle;
+
+#[cfg(feature = "strategy-observer")]
+use super::StrategyObserver;
+use super::{BaseRwLock, OnRelease, Strategy, TaggedStrategy, impls::Scheduler, strategies};
+
+/// Configures a strategied [`BaseRwLock`] one option at a time, as created by
+/// [`BaseRwLock::builder`].
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use std::time::Duration;
+/// # use powerlocks::strategied_rwlock::{StdRwLock, strategies};
+/// let lock = StdRwLock::builder()
+///     .strategy(Box::new(strategies::batched(4)))
+///     .recheck_interval(Duration::from_millis(1))
+///     .on_release(Box::new(|_handle_id, method| println!("released a {method:?}")))
+///     .build(0);
+/// assert_eq!(*lock.read().unwrap(), 0);
+/// # }
+/// ```
+///
+#[must_use = "a builder does nothing until it is built"]
+pub struct RwLockBuilder<T, H: Handle> {
+    strategy: Scheduler,
+    recheck: Option<Duration>,
+    on_release: Option<Box<dyn OnRelease>>,
+    max_queued: Option<usize>,
+    #[cfg(feature = "strategy-observer")]
+    observer: Option<Box<dyn StrategyObserver>>,
+    marker: PhantomData<fn(T) -> H>,
+}
+
+impl<T, H: Handle> RwLockBuilder<T, H> {
+    pub(super) fn new() -> Self {
+        Self {
+            strategy: Scheduler::RwLock(Box::new(strategies::fair)),
+            recheck: None,
+            on_release: None,
+            max_queued: None,
+            #[cfg(feature = "strategy-observer")]
+            observer: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Schedules the lock with `strategy`, instead of [`strategies::fair`].
+    pub fn strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.strategy = Scheduler::RwLock(strategy);
+        self
+    }
+
+    /// Schedules the lock with a [`TaggedStrategy`], instead of [`strategies::fair`].
+    pub fn tagged_strategy(mut self, strategy: Box<dyn TaggedStrategy>) -> Self {
+        self.strategy = Scheduler::TaggedRwLock(strategy);
+        self
+    }
+
+    /// See [`BaseRwLock::set_recheck_interval`].
+    pub fn recheck_interval(mut self, interval: Duration) -> Self {
+        self.recheck = Some(interval);
+        self
+    }
+
+    /// See [`BaseRwLock::set_on_release`].
+    pub fn on_release(mut self, on_release: Box<dyn OnRelease>) -> Self {
+        self.on_release = Some(on_release);
+        self
+    }
+
+    /// See [`BaseRwLock::set_max_queued`].
+    pub fn max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = Some(max_queued);
+        self
+    }
+
+    /// See [`BaseRwLock::set_strategy_observer`].
+    #[cfg(feature = "strategy-observer")]
+    pub fn strategy_observer(mut self, observer: Box<dyn StrategyObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Creates the lock, protecting `t`.
+    pub fn build(self, t: T) -> BaseRwLock<T, H> {
+        let lock = BaseRwLock::with_scheduler(t, self.strategy);
+        let queue = lock.inner.queue();
+        if self.recheck.is_some() {
+            queue.set_recheck_interval(self.recheck);
+        }
+        if self.on_release.is_some() {
+            queue.set_on_release(self.on_release);
+        }
+        if self.max_queued.is_some() {
+            queue.set_max_queued(self.max_queued);
+        }
+        #[cfg(feature = "strategy-observer")]
+        if self.observer.is_some() {
+            queue.set_strategy_observer(self.observer);
+        }
+        lock
+    }
+}
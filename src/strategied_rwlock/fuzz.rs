@@ -0,0 +1,80 @@
+//! Generators of random scripts for [proptest](https://docs.rs/proptest), to fuzz a [`Strategy`]
+//! for the logic errors that [`verify::run`] reports.
+//!
+//! # Examples
+//! ```
+//! # use proptest::test_runner::TestRunner;
+//! # use powerlocks::strategied_rwlock::{fuzz, strategies};
+//! TestRunner::default()
+//!     .run(&fuzz::scripts(32), |script| {
+//!         fuzz::check(&strategies::fair, &script, usize::MAX)
+//!     })
+//!     .unwrap();
+//! ```
+
+extern crate alloc;
+use alloc::{format, vec::Vec};
+
+use proptest::{
+    collection, prop_oneof,
+    sample::Index,
+    strategy::{Just, Strategy as _},
+    test_runner::TestCaseError,
+};
+
+use super::{
+    Method, Strategy,
+    verify::{self, Step},
+};
+
+#[derive(Debug, Clone)]
+enum Action {
+    Acquire(Method),
+    Release(Index),
+}
+
+/// Generates scripts of up to `max_steps` acquisitions and releases, followed by a
+/// [`Step::Drain`], so every thread is expected to get through in the end.
+///
+/// Releases only ever refer to threads that are still queued, whether they hold the lock or not.
+pub fn scripts(max_steps: usize) -> impl proptest::strategy::Strategy<Value = Vec<Step>> {
+    let action = prop_oneof![
+        Just(Action::Acquire(Method::Read)),
+        Just(Action::Acquire(Method::Write)),
+        proptest::arbitrary::any::<Index>().prop_map(Action::Release),
+    ];
+
+    collection::vec(action, 0..=max_steps).prop_map(|actions| {
+        let mut queued = Vec::new();
+        let mut acquisitions = 0;
+        let mut script = actions
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::Acquire(method) => {
+                    queued.push(acquisitions);
+                    acquisitions += 1;
+                    Some(Step::Acquire(method))
+                }
+                Action::Release(_) if queued.is_empty() => None,
+                Action::Release(index) => {
+                    Some(Step::Release(queued.remove(index.index(queued.len()))))
+                }
+            })
+            .collect::<Vec<_>>();
+        script.push(Step::Drain);
+        script
+    })
+}
+
+/// Runs `strategy` through `script` with [`verify::run`], and fails the test case on the first
+/// violation.
+pub fn check(
+    strategy: &dyn Strategy,
+    script: &[Step],
+    max_blocked_steps: usize,
+) -> Result<(), TestCaseError> {
+    match verify::run(strategy, script, max_blocked_steps).first() {
+        Some(violation) => Err(TestCaseError::fail(format!("{violation}"))),
+        None => Ok(()),
+    }
+}
@@ -1,20 +1,38 @@
 use core::{
-    cell::UnsafeCell,
+    cell::Cell,
     error::Error,
     fmt::{Debug, Display},
+    future::Future,
     hash::Hash,
-    sync::atomic::{AtomicBool, Ordering},
+    mem,
+    pin::Pin,
+    ptr::NonNull,
+    task::{self, Context},
+    time::Duration,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    const_fn,
 };
 
 extern crate alloc;
-use alloc::{boxed::Box, collections::VecDeque, string::ToString, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, collections::VecDeque, string::ToString, vec::Vec};
 
 use crate::{
-    mutex::Mutex,
-    primitives::{Handle, LockResult, PoisonError},
+    mutex::BaseMutex,
+    primitives::{
+        AsyncHandle, Handle, HandleId, LockResult, LockResultExt, OnDrop, PoisonError, park_capped,
+    },
 };
 
-use super::{BaseRwLockReadGuard, BaseRwLockWriteGuard, Method, State, Strategy};
+#[cfg(feature = "strategy-observer")]
+use super::StrategyObserver;
+use super::{
+    BaseRwLock, BaseRwLockReadGuard, BaseRwLockWriteGuard, Method, OnRelease, QueueEntryInfo,
+    State, Strategy, StrategyResult, TaggedStrategy,
+};
+use crate::strategied_mutex::MutexStrategy;
 
 pub(super) enum LogicErrorHandlingMethod {
     Panic,
@@ -63,6 +81,10 @@ error_type!(pub(super) StrategyLogicError {
         "The provided `Strategy` wanted to re-block a `State::Ok`ed thread.",
         LogicErrorHandlingMethod::BreakAndPanic
     ),
+    NoSuchWaiter(
+        "The provided `MutexStrategy` picked a thread that isn't waiting.",
+        LogicErrorHandlingMethod::BreakAndPanic
+    ),
     BrokenLock(
         "There is a logic error in the provided `Strategy`. Can't continue.",
         LogicErrorHandlingMethod::Panic
@@ -76,31 +98,242 @@ fn cold<F>(f: F) -> F {
     f
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+const NOT_WOKEN: u8 = 0;
+const WOKEN: u8 = 1;
+const ADMITTED: u8 = 2;
+
+/// The node of a thread that may park until its entry is admitted.
+///
+/// Waiters live on the stack of the thread that is waiting, in [`Queue::acquire_with`], so queueing
+/// never allocates. Only async tasks box theirs, see [`Acquire`]. An entry only points to its
+/// waiter while the thread may park, and whoever takes the pointer out of the entry to wake the
+/// thread may use the node until it calls [`wake`](Waiter::wake).
+struct Waiter<H: Handle> {
+    handle: H,
+    woken: AtomicU8,
+}
+
+impl<H: Handle> Waiter<H> {
+    fn new() -> Self {
+        Self {
+            handle: H::new(),
+            woken: AtomicU8::new(NOT_WOKEN),
+        }
+    }
+
+    /// Tells the waiting thread that it was woken, and whether it was admitted on the way. The
+    /// node may be gone as soon as this returns.
+    fn wake(&self, admitted: bool) {
+        let woken = if admitted { ADMITTED } else { WOKEN };
+        // A read-modify-write, like the ones of the waiting thread, so that it is ordered after
+        // them.
+        self.woken.swap(woken, Ordering::Release);
+    }
+
+    /// Takes the news left by [`wake`](Waiter::wake), if any.
+    fn take_woken(&self) -> u8 {
+        self.woken.swap(NOT_WOKEN, Ordering::Acquire)
+    }
+
+    /// Waits for the thread that took this node from its entry to be done with it, and takes its
+    /// news.
+    fn wait_woken(&self) -> u8 {
+        loop {
+            // A read-modify-write, which can't keep reading a stale value while it spins.
+            match self.take_woken() {
+                NOT_WOKEN => H::yield_now(),
+                woken => return woken,
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
 struct LockEntry<H: Handle> {
-    handle: Arc<H>,
+    id: HandleId,
     method: Method,
     state: State,
+    // Set for entries that were admitted by a previous `Strategy`, before it was replaced, and for
+    // recursive reads. These keep their access regardless of what the current `Strategy` says.
+    pinned: bool,
+    // Set once the thread that queued this entry has been told that it is blocked, after which it
+    // may park. Only these entries need an unpark once they are admitted.
+    waiter: Option<NonNull<Waiter<H>>>,
+    // The thread that queued this entry, if the thread environment can tell threads apart.
+    owner: Option<HandleId>,
+    // Handed to `TaggedStrategy`s, and the priority of a mutex waiter for `MutexStrategy`s.
+    tag: u64,
+    // When this entry was queued, as told by `ThreadEnv::now`.
+    queued_at: Option<Duration>,
 }
 
 impl<H: Handle> LockEntry<H> {
-    pub(super) fn new(handle: Arc<H>, method: Method, state: State) -> Self {
+    pub(super) fn new(id: HandleId, method: Method, state: State) -> Self {
         Self {
-            handle,
+            id,
             method,
             state,
+            pinned: false,
+            waiter: None,
+            owner: H::current_id(),
+            tag: 0,
+            queued_at: H::now(),
         }
     }
 
     pub(super) fn state(&self) -> State {
         self.state
     }
+
+    /// How long ago this entry was queued, given the current time.
+    fn waited(&self, now: Option<Duration>) -> Option<Duration> {
+        Some(now?.saturating_sub(self.queued_at?))
+    }
+}
+
+/// A queued entry whose state [`enforce_preconditions`] sets.
+pub(super) trait QueuedEntry {
+    fn id(&self) -> HandleId;
+    fn method(&self) -> Method;
+    /// Whether the entry keeps its access regardless of what the current [`Strategy`] says.
+    fn pinned(&self) -> bool;
+    fn state_mut(&mut self) -> &mut State;
+}
+
+impl<H: Handle> QueuedEntry for LockEntry<H> {
+    fn id(&self) -> HandleId {
+        self.id
+    }
+
+    fn method(&self) -> Method {
+        self.method
+    }
+
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+}
+
+/// The logic errors found by [`enforce_preconditions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(super) struct Violations {
+    pub(super) blocked_after_ok_state: bool,
+    pub(super) concurrent_read_and_write: bool,
+    pub(super) concurrent_multiple_writes: bool,
+}
+
+/// Sets the states of the entries of `queue` to `new_states`, as returned by a [`Strategy`],
+/// correcting any that break the rules of [`Strategy`] and reporting them.
+///
+/// Corrected entries keep the lock consistent: admitted entries that would be re-blocked stay
+/// admitted, and entries that would conflict with entries admitted before them stay blocked. The
+/// entry of `current`, the thread that is acquiring, may be re-blocked, since it hasn't been told
+/// that it was admitted yet.
+pub(super) fn enforce_preconditions<Q, E>(
+    queue: &mut Q,
+    current: Option<HandleId>,
+    new_states: &mut dyn Iterator<Item = State>,
+) -> Violations
+where
+    Q: ?Sized,
+    for<'q> &'q mut Q: IntoIterator<Item = &'q mut E>,
+    E: QueuedEntry,
+{
+    // Entries admitted by a previous `Strategy` can't be known to the current one, so anything
+    // conflicting with them is simply kept blocked rather than treated as a logic error.
+    let (pinned_read, pinned_write) = (&mut *queue)
+        .into_iter()
+        .filter(|entry| entry.pinned())
+        .fold((false, false), |(read, write), entry| {
+            (
+                read || entry.method().is_read(),
+                write || entry.method().is_write(),
+            )
+        });
+
+    let (mut has_ok_read, mut has_ok_write) = (false, false);
+    let mut violations = Violations::default();
+    for (entry, mut new_state) in queue.into_iter().zip(new_states) {
+        // The current handle's state may initially be set to `State::Ok` while the `Strategy`
+        // mandates a `State::Blocked` state. Permit this since the current thread is always
+        // attempting an acquire here, and can be blocked via the results of this function. The
+        // current thread never appears here during a release of the lock since it's removed from
+        // the queue before calling this function.
+        if entry.pinned() {
+            *entry.state_mut() = State::Ok;
+            continue;
+        }
+
+        let state = *entry.state_mut();
+        if Some(entry.id()) != current && state.is_ok() && new_state.is_blocked() {
+            violations.blocked_after_ok_state = true;
+            new_state = State::Ok;
+        }
+
+        let conflicts_with_pinned = match entry.method() {
+            Method::Read => pinned_write,
+            Method::Write => pinned_read || pinned_write,
+        };
+        if new_state.is_ok() && conflicts_with_pinned && state.is_blocked() {
+            new_state = State::Blocked;
+        }
+
+        if new_state.is_ok() {
+            match entry.method() {
+                Method::Read => {
+                    violations.concurrent_read_and_write |= has_ok_write;
+                    has_ok_read = true;
+                }
+                Method::Write => {
+                    violations.concurrent_read_and_write |= has_ok_read;
+                    violations.concurrent_multiple_writes |= has_ok_write;
+                    has_ok_write = true;
+                }
+            }
+
+            if violations.concurrent_read_and_write || violations.concurrent_multiple_writes {
+                new_state = State::Blocked;
+            }
+        }
+
+        *entry.state_mut() = new_state;
+    }
+
+    violations
+}
+
+/// Decides which queued entries are admitted, for either kind of strategied lock.
+pub(crate) enum Scheduler {
+    /// Consulted on every change to the queue, see [`Strategy`].
+    RwLock(Box<dyn Strategy>),
+    /// Like [`Scheduler::RwLock`], but also sees the tag of every entry, see [`TaggedStrategy`].
+    TaggedRwLock(Box<dyn TaggedStrategy>),
+    /// Only consulted when the lock is free, to pick the single waiter that gets it next. Every
+    /// entry is a [`Method::Write`].
+    Mutex(Box<dyn MutexStrategy>),
 }
 
 struct LockedQueue<H: Handle> {
     queue: VecDeque<LockEntry<H>>,
-    strategy: Box<dyn Strategy>,
+    strategy: Scheduler,
     broken: bool,
+    // Waiters taken from their entries while the queue was locked, to be unparked once it is
+    // unlocked, along with whether they were admitted.
+    woken: Vec<(NonNull<Waiter<H>>, bool)>,
+    // How often blocked threads consult the strategy again, for strategies that depend on time.
+    recheck: Option<Duration>,
+    // Told about every release, before the strategy is consulted about it.
+    on_release: Option<Box<dyn OnRelease>>,
+    // The most entries that may be blocked at once, past which acquisitions that can fail are
+    // turned away instead of queued.
+    max_queued: Option<usize>,
+    // Shown every queue the strategy is consulted about, along with its decisions.
+    #[cfg(feature = "strategy-observer")]
+    observer: Option<Box<dyn StrategyObserver>>,
 }
 
 impl<H: Handle> Debug for LockedQueue<H> {
@@ -113,8 +346,14 @@ impl<H: Handle> Debug for LockedQueue<H> {
 // locked. So this should only be held on for the shortest amount of time possible.
 struct LockedQueueView<'a, H: Handle> {
     queue: &'a mut VecDeque<LockEntry<H>>,
-    strategy: &'a mut dyn Strategy,
+    strategy: &'a mut Scheduler,
     broken: &'a mut bool,
+    woken: &'a mut Vec<(NonNull<Waiter<H>>, bool)>,
+    recheck: &'a mut Option<Duration>,
+    on_release: &'a mut Option<Box<dyn OnRelease>>,
+    max_queued: &'a mut Option<usize>,
+    #[cfg(feature = "strategy-observer")]
+    observer: &'a mut Option<Box<dyn StrategyObserver>>,
 }
 
 impl<H: Handle> Debug for LockedQueueView<'_, H> {
@@ -127,8 +366,14 @@ impl<'a, H: Handle> LockedQueueView<'a, H> {
     fn new(queue: &'a mut LockedQueue<H>) -> Self {
         Self {
             queue: &mut queue.queue,
-            strategy: &mut *queue.strategy,
+            strategy: &mut queue.strategy,
             broken: &mut queue.broken,
+            woken: &mut queue.woken,
+            recheck: &mut queue.recheck,
+            on_release: &mut queue.on_release,
+            max_queued: &mut queue.max_queued,
+            #[cfg(feature = "strategy-observer")]
+            observer: &mut queue.observer,
         }
     }
 
@@ -158,210 +403,728 @@ impl<'a, H: Handle> LockedQueueView<'a, H> {
 
     fn set_and_enforce_preconditions(
         &mut self,
-        current_handle: &H,
+        current: HandleId,
         new_states: &mut dyn Iterator<Item = State>,
     ) -> Result<(), StrategyLogicError> {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-        struct Violations {
-            has_ok_read: bool,
-            has_ok_write: bool,
-            err_blocked_after_ok_state: bool,
-            err_concurrent_read_and_write: bool,
-            err_concurent_multiple_writes: bool,
-        }
-
-        let violations = self.queue.iter_mut().zip(new_states).fold(
-            Violations {
-                has_ok_read: false,
-                has_ok_write: false,
-                err_blocked_after_ok_state: false,
-                err_concurrent_read_and_write: false,
-                err_concurent_multiple_writes: false,
-            },
-            |mut violations, (entry, mut new_state)| {
-                // The current handle's state may initially be set to `State::Ok` while the
-                // `Strategy` mandates a `State::Blocked` state. Permit this since the
-                // current thread is always attempting an acquire here, and can be blocked via the
-                // results of this function. The current thread never appears here during a release
-                // of the lock since it's removed from the queue before calling this function.
-                if entry.handle.id() != current_handle.id()
-                    && entry.state().is_ok()
-                    && new_state.is_blocked()
-                {
-                    violations.err_blocked_after_ok_state = true;
-                    new_state = State::Ok;
-                }
-
-                if new_state.is_ok() {
-                    match entry.method {
-                        Method::Read => {
-                            violations.err_concurrent_read_and_write |= violations.has_ok_write;
-                            violations.has_ok_read = true;
-                        }
-                        Method::Write => {
-                            violations.err_concurrent_read_and_write |= violations.has_ok_read;
-                            violations.err_concurent_multiple_writes |= violations.has_ok_write;
-                            violations.has_ok_write = true;
-                        }
-                    }
-
-                    if violations.err_concurrent_read_and_write
-                        || violations.err_concurent_multiple_writes
-                    {
-                        new_state = State::Blocked;
-                    }
-                }
-
-                entry.state = new_state;
-                violations
-            },
-        );
+        let violations = enforce_preconditions(&mut *self.queue, Some(current), new_states);
 
-        if violations.err_blocked_after_ok_state {
+        if violations.blocked_after_ok_state {
             cold(Err(StrategyLogicError::BlockedAfterOkState))
-        } else if violations.err_concurrent_read_and_write {
+        } else if violations.concurrent_read_and_write {
             cold(Err(StrategyLogicError::ConcurrentReadAndWrite))
-        } else if violations.err_concurent_multiple_writes {
+        } else if violations.concurrent_multiple_writes {
             cold(Err(StrategyLogicError::ConcurrentMultipleWrites))
         } else {
             Ok(())
         }
     }
 
-    fn run_queue_logic(&mut self, current_handle: &H) -> Result<(), StrategyLogicError> {
+    fn run_queue_logic(&mut self, current: HandleId) -> Result<(), StrategyLogicError> {
         // Run the strategy and enforce preconditions.
-        let handles_and_methods = self
-            .queue
-            .iter()
-            .map(|entry| (entry.handle.id(), entry.method))
-            .collect::<Vec<_>>();
+        match &self.strategy {
+            Scheduler::RwLock(strategy) => {
+                let handles_and_methods = self
+                    .queue
+                    .iter()
+                    .map(|entry| (entry.id, entry.method))
+                    .collect::<Vec<_>>();
 
-        let mut handles_and_methods_iter = handles_and_methods.iter();
-        let mut raw_results = (self.strategy)(&mut handles_and_methods_iter);
+                let mut handles_and_methods_iter = handles_and_methods.iter();
+                let mut raw_results = self.observe(strategy(&mut handles_and_methods_iter));
+
+                self.set_and_enforce_preconditions(current, &mut raw_results)?;
+            }
+            Scheduler::TaggedRwLock(strategy) => {
+                let tagged_handles_and_methods = self
+                    .queue
+                    .iter()
+                    .map(|entry| (entry.id, entry.method, entry.tag))
+                    .collect::<Vec<_>>();
 
-        self.set_and_enforce_preconditions(current_handle, &mut raw_results)?;
+                let mut tagged_handles_and_methods_iter = tagged_handles_and_methods.iter();
+                let mut raw_results = self.observe(strategy(&mut tagged_handles_and_methods_iter));
 
-        // Then unpark handles as needed
-        self.queue.iter_mut().for_each(|entry| {
-            if entry.handle.id() != current_handle.id() && entry.state().is_ok() {
-                entry.handle.unpark();
+                self.set_and_enforce_preconditions(current, &mut raw_results)?;
             }
-        });
+            Scheduler::Mutex(strategy) => {
+                // The lock is handed over only once it is free, so a holder is never re-blocked.
+                if self.queue.is_empty() || self.queue.iter().any(|entry| entry.state().is_ok()) {
+                    return Ok(());
+                }
+
+                let handles_and_priorities = self
+                    .queue
+                    .iter()
+                    // Mutex entries are only ever tagged with a `u32` priority.
+                    .map(|entry| (entry.id, entry.tag as u32))
+                    .collect::<Vec<_>>();
 
+                let mut handles_and_priorities_iter = handles_and_priorities.iter();
+                let next = strategy(&mut handles_and_priorities_iter);
+
+                let entry = self
+                    .queue
+                    .get_mut(next)
+                    .ok_or(StrategyLogicError::NoSuchWaiter)?;
+                entry.state = State::Ok;
+            }
+        }
+
+        self.wake_admitted(current);
         Ok(())
     }
 
-    fn current_entry(&self, current_handle: &H) -> Option<&LockEntry<H>> {
+    /// Shows the queue and the decisions of the strategy about it to the observer, if any. Only
+    /// as many decisions as there are entries are taken, since no more are ever used.
+    fn observe<'i>(&self, raw_results: StrategyResult<'i>) -> StrategyResult<'i> {
+        #[cfg(feature = "strategy-observer")]
+        if let Some(observer) = &*self.observer {
+            let queue = self
+                .queue
+                .iter()
+                .map(|entry| (entry.id, entry.method, entry.tag))
+                .collect::<Vec<_>>();
+            let decisions = raw_results.take(queue.len()).collect::<Vec<_>>();
+            observer(&queue, &decisions);
+            return Box::new(decisions.into_iter());
+        }
+
+        raw_results
+    }
+
+    /// Picks the newly admitted entries to unpark once the queue is unlocked: the next writer
+    /// alone, or the readers admitted along with the next reader.
+    ///
+    /// Entries that never saw themselves blocked, such as the current holders, aren't parked and
+    /// are left alone.
+    fn wake_admitted(&mut self, current: HandleId) {
+        let mut admitted = self
+            .queue
+            .iter_mut()
+            .filter(|entry| entry.waiter.is_some() && entry.state().is_ok() && entry.id != current);
+        let Some(next) = admitted.next() else {
+            return;
+        };
+        let method = next.method;
+        // The preconditions never admit a writer along with anything else, so this only stops
+        // short of entries with a different method if a strategy is broken.
+        let batch = core::iter::once(next).chain(
+            match method {
+                Method::Read => Some(admitted.filter(|entry| entry.method.is_read())),
+                Method::Write => None,
+            }
+            .into_iter()
+            .flatten(),
+        );
+        self.woken.extend(
+            batch
+                .filter_map(|entry| entry.waiter.take())
+                .map(|waiter| (waiter, true)),
+        );
+    }
+
+    fn set_strategy(&mut self, strategy: Scheduler) {
+        self.assert_not_broken();
+
+        // Current holders were admitted by the old `Strategy`, and must keep their access.
+        self.queue
+            .iter_mut()
+            .filter(|entry| entry.state().is_ok())
+            .for_each(|entry| entry.pinned = true);
+        *self.strategy = strategy;
+
+        // No entry belongs to the current thread here, so use a handle that matches none of them.
+        self.run_queue_logic(H::dumb().id())
+            .unwrap_or_else(|err| self.handle_logic_err(err));
+    }
+
+    fn set_recheck_interval(&mut self, interval: Option<Duration>) {
+        *self.recheck = interval;
+
+        // Blocked threads may be parked without a timeout, so wake them to pick up the interval.
+        self.woken.extend(
+            self.queue
+                .iter_mut()
+                .filter_map(|entry| entry.waiter.take())
+                .map(|waiter| (waiter, false)),
+        );
+    }
+
+    fn count(&self, predicate: impl Fn(&LockEntry<H>) -> bool) -> usize {
+        self.queue.iter().filter(|entry| predicate(entry)).count()
+    }
+
+    fn entry_infos(&self) -> Vec<QueueEntryInfo> {
+        let now = H::now();
         self.queue
             .iter()
-            .find(|entry| entry.handle.id() == current_handle.id())
+            .map(|entry| QueueEntryInfo {
+                handle_id: entry.id,
+                method: entry.method,
+                state: entry.state(),
+                tag: entry.tag,
+                // SAFETY: An entry only points to its waiter while the waiter is alive.
+                parked: entry
+                    .waiter
+                    .is_some_and(|waiter| unsafe { waiter.as_ref() }.handle.is_parked()),
+                waited: entry.waited(now),
+            })
+            .collect()
     }
 
-    fn poll(&mut self, current_handle: &H) -> State {
-        self.current_entry(current_handle)
+    fn longest_wait(&self) -> Option<Duration> {
+        let now = H::now();
+        self.queue
+            .iter()
+            .filter(|entry| entry.state().is_blocked())
+            .filter_map(|entry| entry.waited(now))
+            .max()
+    }
+
+    fn entry_mut(&mut self, current: HandleId) -> &mut LockEntry<H> {
+        self.queue
+            .iter_mut()
+            .find(|entry| entry.id == current)
             // The `None` case should never happen, as there's no way for us to remove a lock entry
             // without going through `try_acquire` or `release`
             .unwrap_or_else(|| unreachable!())
-            .state()
     }
 
-    fn do_acquire(&mut self, method: Method) -> (Arc<H>, State) {
+    /// Returns what the current thread's entry is up to. If it is blocked, it is pointed to
+    /// `waiter`, and the thread may park.
+    ///
+    /// `queued` tells whether the entry was pointed to `waiter` before, and the thread hasn't heard
+    /// from whoever took it since.
+    fn poll(&mut self, current: HandleId, waiter: &Waiter<H>, queued: bool) -> Poll {
+        let entry = self.entry_mut(current);
+        if queued && entry.waiter.is_none() {
+            return Poll::Waking;
+        }
+        match entry.state() {
+            State::Ok => {
+                entry.waiter = None;
+                Poll::Admitted
+            }
+            State::Blocked => {
+                entry.waiter = Some(NonNull::from(waiter));
+                Poll::Blocked
+            }
+        }
+    }
+
+    /// Like [`poll`](Self::poll), but first consults the strategy again if the lock was told to
+    /// re-check it periodically. Also returns how long the current thread may park before
+    /// re-checking.
+    fn recheck(
+        &mut self,
+        current: HandleId,
+        waiter: &Waiter<H>,
+        queued: bool,
+    ) -> (Poll, Option<Duration>) {
+        if self.recheck.is_some() && !self.is_broken() {
+            self.run_queue_logic(current)
+                .unwrap_or_else(|err| self.handle_logic_err(err));
+        }
+        (self.poll(current, waiter, queued), *self.recheck)
+    }
+
+    fn acquire(&mut self, current: HandleId, method: Method, tag: u64) -> State {
         self.assert_not_broken();
-        let current_handle = Arc::new(H::new());
 
         // Will be enforced by the `Strategy`
-        self.queue.push_back(LockEntry::<H>::new(
-            Arc::clone(&current_handle),
-            method,
-            State::Blocked,
-        ));
-        self.run_queue_logic(&current_handle)
+        let mut entry = LockEntry::<H>::new(current, method, State::Blocked);
+        entry.tag = tag;
+        self.queue.push_back(entry);
+        self.run_queue_logic(current)
             .unwrap_or_else(|err| self.handle_logic_err(err));
-        let state = self.poll(&current_handle);
+        // The thread only parks once `poll` has told it that it is blocked.
+        self.entry_mut(current).state()
+    }
+
+    /// Removes the entry of the current thread again if the strategy blocked it while the queue
+    /// already held as many blocked entries as it may.
+    fn reject_if_full(&mut self, current: HandleId) -> Result<(), AcquireError> {
+        let Some(max_queued) = *self.max_queued else {
+            return Ok(());
+        };
+        if self.entry_mut(current).state().is_ok()
+            || self.count(|entry| entry.state().is_blocked()) <= max_queued
+        {
+            return Ok(());
+        }
 
-        (current_handle, state)
+        // Entries queued behind us may have been blocked only because of our entry.
+        self.release(current);
+        Err(AcquireError::QueueFull)
     }
 
-    fn acquire(&mut self, method: Method) -> Arc<H> {
-        self.do_acquire(method).0
+    /// Admits another read right away if the current thread already holds a read, without
+    /// consulting the `Strategy`. Returns `None` otherwise.
+    fn acquire_recursive_read(&mut self) -> Option<HandleId> {
+        self.assert_not_broken();
+        let owner = H::current_id()?;
+        self.queue
+            .iter()
+            .any(|entry| {
+                entry.method.is_read() && entry.state().is_ok() && entry.owner == Some(owner)
+            })
+            .then(|| {
+                let id = H::new().id();
+                // Pinning keeps the `Strategy` from blocking this entry later on, and keeps
+                // writers blocked until it is released.
+                let mut entry = LockEntry::new(id, Method::Read, State::Ok);
+                entry.pinned = true;
+                self.queue.push_back(entry);
+                id
+            })
     }
 
-    fn try_acquire(&mut self, method: Method) -> Result<Arc<H>, ()> {
-        let (handle, state) = self.do_acquire(method);
+    fn try_acquire(&mut self, method: Method) -> Result<HandleId, ()> {
+        let id = H::new().id();
+        let state = self.acquire(id, method, 0);
 
         if state.is_blocked() {
-            // `do_acquire` always puts an entry into `queue` regardless. Since we're only
-            // trying the lock, remove that last entry.
+            // `acquire` always puts an entry into `queue` regardless. Since we're only trying the
+            // lock, remove that last entry.
             let old_entry = self.queue.pop_back();
 
             // Do a sanity check here and make sure...
-            if old_entry.is_none_or(|entry| entry.handle.id() != handle.id()) {
+            if old_entry.is_none_or(|entry| entry.id != id) {
                 // This is unreachable. We've just done a `push_back` of the exact same entry.
                 unreachable!()
             }
         }
 
-        state.is_ok().then_some(handle).ok_or(())
+        state.is_ok().then_some(id).ok_or(())
     }
 
-    fn release(&mut self, current_handle: &H) {
+    /// Gives up on an acquisition that is still queued, see [`poll`](Self::poll) for `queued`.
+    /// Returns [`Poll::Admitted`] if the entry was admitted in the meantime, in which case it is
+    /// kept and the caller holds the lock after all, and [`Poll::Blocked`] once it is removed.
+    fn abandon(&mut self, current: HandleId, queued: bool) -> Poll {
+        let entry = self.entry_mut(current);
+        if queued && entry.waiter.is_none() {
+            return Poll::Waking;
+        }
+        entry.waiter = None;
+        if entry.state().is_ok() {
+            return Poll::Admitted;
+        }
+
+        // Entries queued behind us may have been blocked only because of our entry.
+        self.release(current);
+        Poll::Blocked
+    }
+
+    /// Stops the current entry from pointing to its waiter, for a thread that unwinds while
+    /// queued. Returns `true` if someone took the waiter already, see [`poll`](Self::poll).
+    fn detach(&mut self, current: HandleId) -> bool {
+        match self.queue.iter_mut().find(|entry| entry.id == current) {
+            Some(entry) => entry.waiter.take().is_none(),
+            None => true,
+        }
+    }
+
+    fn release(&mut self, current: HandleId) {
         let result = self
             .queue
             .iter()
-            .position(|entry| entry.handle.id() == current_handle.id())
+            .position(|entry| entry.id == current)
             .and_then(|index| self.queue.remove(index));
 
+        // Abandoned entries never held the lock, so they aren't released as far as the listener is
+        // concerned.
+        if let Some(entry) = &result
+            && entry.state().is_ok()
+            && let Some(on_release) = &self.on_release
+        {
+            on_release(entry.id, entry.method);
+        }
+
         // Try not to panic if we are broken. We want threads releasing the `RwLockReadGuard` and
         // `RwLockWriteGuard` to work gracefully.
         if !self.is_broken() {
             result.unwrap();
-            self.run_queue_logic(current_handle)
+            self.run_queue_logic(current)
                 .unwrap_or_else(|err| self.handle_logic_err(err));
         }
     }
+
+    fn force_release(&mut self, method: Method) {
+        // Release the oldest holder of the lock with the given `method`, since we don't know which
+        // handle the lost guard belonged to.
+        let held = self
+            .queue
+            .iter()
+            .find(|entry| entry.method == method && entry.state().is_ok())
+            .map(|entry| entry.id);
+
+        debug_assert!(
+            held.is_some(),
+            "Forcibly releasing a `Method::{:?}` lock that isn't held.",
+            method
+        );
+
+        if let Some(id) = held {
+            self.release(id);
+        }
+    }
+}
+
+/// Why [`Queue::acquire_with`] didn't acquire the lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AcquireError {
+    /// `wait` returned `false` before the entry was admitted.
+    GaveUp,
+    /// The entry was blocked, and the queue had no room for it, see
+    /// [`LockedQueueView::reject_if_full`].
+    QueueFull,
+}
+
+/// What a queued thread learns about its entry, see [`LockedQueueView::poll`].
+enum Poll {
+    /// The entry holds the lock.
+    Admitted,
+    /// The entry is blocked, and points to the waiter of the thread.
+    Blocked,
+    /// Another thread took the waiter from the entry, and is about to wake it. The waiter must
+    /// stay alive until it is woken.
+    Waking,
 }
 
 #[derive(Debug)]
-pub(super) struct Queue<H: Handle> {
-    inner: Mutex<LockedQueue<H>>,
+pub(crate) struct Queue<H: Handle> {
+    // Locked in the same thread environment as `H`, so the queue blocks the same way the lock does.
+    inner: BaseMutex<LockedQueue<H>, (), H>,
 }
 
 impl<H: Handle> Queue<H> {
-    pub(super) const fn new(strategy: Box<dyn Strategy>) -> Self {
-        Self {
-            inner: Mutex::new_unhooked(LockedQueue {
-                queue: VecDeque::new(),
-                strategy,
-                broken: false,
-            }),
+    const_fn! {
+        pub(crate) const fn new(strategy: Scheduler) -> Self {
+            Self {
+                inner: BaseMutex::new_unhooked(LockedQueue {
+                    queue: VecDeque::new(),
+                    strategy,
+                    broken: false,
+                    woken: Vec::new(),
+                    recheck: None,
+                    on_release: None,
+                    max_queued: None,
+                    #[cfg(feature = "strategy-observer")]
+                    observer: None,
+                }),
+            }
         }
     }
 
     fn lock<T>(&self, callback: impl for<'a> FnOnce(LockedQueueView<'a, H>) -> T) -> T {
-        callback(LockedQueueView::new(
-            &mut self.inner.lock().unwrap_or_else(PoisonError::into_inner),
-        ))
+        // Unregistered, as `debug::dump` inspects the lock through here.
+        let mut queue = self.inner.lock_unregistered().ignore_poison();
+        let result = callback(LockedQueueView::new(&mut queue));
+        let woken = mem::take(&mut queue.woken);
+        drop(queue);
+
+        // Wake the admitted threads in one go after unlocking, so that they don't wake up only to
+        // block on the queue again.
+        if !woken.is_empty() {
+            // SAFETY: A waiter taken from its entry stays alive until it is told that it was woken.
+            H::unpark_all(
+                woken
+                    .iter()
+                    .map(|(waiter, _)| &unsafe { waiter.as_ref() }.handle),
+            );
+            for (waiter, admitted) in woken {
+                // SAFETY: As above. The waiter may be gone once this returns.
+                unsafe { waiter.as_ref() }.wake(admitted);
+            }
+        }
+        result
+    }
+
+    /// Acquires the lock, calling `register` to register it in the debug registry if the thread has
+    /// to park.
+    pub(crate) fn acquire<W>(&self, method: Method, register: impl FnOnce() -> W) -> HandleId {
+        self.acquire_tagged(method, 0, register)
     }
 
-    pub(super) fn acquire(&self, method: Method) -> Arc<H> {
-        let handle = self.lock(|mut queue| queue.acquire(method));
-        while self.lock(|mut queue| queue.poll(&handle)).is_blocked() {
-            handle.park();
+    /// Acquires the lock like [`acquire`](Queue::acquire), queueing with the given tag.
+    pub(crate) fn acquire_tagged<W>(
+        &self,
+        method: Method,
+        tag: u64,
+        register: impl FnOnce() -> W,
+    ) -> HandleId {
+        // The registration lasts until the lock is acquired, not only while parked.
+        let (mut register, mut waiting) = (Some(register), None);
+        self.acquire_with(method, tag, false, |handle, recheck| {
+            if let Some(register) = register.take() {
+                waiting = Some(register());
+            }
+            park_capped(handle, recheck);
+            true
+        })
+        .unwrap_or_else(|_| unreachable!())
+    }
+
+    /// Acquires the lock like [`acquire`](Queue::acquire), unless the entry would be blocked while
+    /// the queue is full, see [`set_max_queued`](Queue::set_max_queued).
+    pub(crate) fn acquire_bounded(&self, method: Method) -> Result<HandleId, AcquireError> {
+        self.acquire_with(method, 0, true, |handle, recheck| {
+            park_capped(handle, recheck);
+            true
+        })
+    }
+
+    /// Acquires the lock, calling `wait` to block while the entry is queued, along with the longest
+    /// it may block for before the strategy must be consulted again. Once `wait` returns `false`,
+    /// the entry is removed from the queue and `Err` is returned, unless the entry was admitted in
+    /// the meantime.
+    ///
+    /// If `bounded`, an entry that would be blocked while the queue is full is removed right away
+    /// instead, see [`set_max_queued`](Queue::set_max_queued).
+    pub(crate) fn acquire_with(
+        &self,
+        method: Method,
+        tag: u64,
+        bounded: bool,
+        mut wait: impl FnMut(&H, Option<Duration>) -> bool,
+    ) -> Result<HandleId, AcquireError> {
+        let waiter = Waiter::<H>::new();
+        let id = waiter.handle.id();
+        // Whether the entry points to `waiter`, or did until a thread that hasn't woken us yet took
+        // it. Either way, `waiter` must not go away.
+        let queued = Cell::new(false);
+        // A strategy or `wait` that panics mustn't leave the entry pointing to a dead waiter.
+        let _detach = OnDrop(|| {
+            if queued.get() && self.lock(|mut queue| queue.detach(id)) {
+                waiter.wait_woken();
+            }
+        });
+
+        self.lock(|mut queue| {
+            queue.acquire(id, method, tag);
+            if bounded {
+                queue.reject_if_full(id)
+            } else {
+                Ok(())
+            }
+        })?;
+        while let Err(recheck) = self.advance(id, &waiter, &queued) {
+            if !wait(&waiter.handle, recheck) {
+                return self.abandon(id, &waiter, &queued);
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Checks on a queued entry until it is admitted, or blocked with its waiter registered. In the
+    /// latter case, returns how long the waiter may wait before the strategy must be consulted
+    /// again. See [`LockedQueueView::poll`] for `queued`.
+    fn advance(
+        &self,
+        id: HandleId,
+        waiter: &Waiter<H>,
+        queued: &Cell<bool>,
+    ) -> Result<(), Option<Duration>> {
+        loop {
+            // Once admitted, a release tells us so when it wakes us, which spares the whole batch
+            // of woken threads from contending for the queue just to learn that.
+            if queued.get() {
+                let woken = waiter.take_woken();
+                if woken != NOT_WOKEN {
+                    queued.set(false);
+                    if woken == ADMITTED {
+                        return Ok(());
+                    }
+                }
+            }
+            let (poll, recheck) = self.lock(|mut queue| queue.recheck(id, waiter, queued.get()));
+            match poll {
+                Poll::Admitted => {
+                    queued.set(false);
+                    return Ok(());
+                }
+                Poll::Waking => {
+                    queued.set(false);
+                    if waiter.wait_woken() == ADMITTED {
+                        return Ok(());
+                    }
+                }
+                Poll::Blocked => {
+                    queued.set(true);
+                    return Err(recheck);
+                }
+            }
         }
+    }
 
-        handle
+    /// Removes the entry of a thread that gave up waiting, see [`LockedQueueView::abandon`].
+    fn abandon(
+        &self,
+        id: HandleId,
+        waiter: &Waiter<H>,
+        queued: &Cell<bool>,
+    ) -> Result<HandleId, AcquireError> {
+        loop {
+            match self.lock(|mut queue| queue.abandon(id, queued.get())) {
+                Poll::Admitted => {
+                    queued.set(false);
+                    return Ok(id);
+                }
+                Poll::Blocked => {
+                    queued.set(false);
+                    return Err(AcquireError::GaveUp);
+                }
+                Poll::Waking => {
+                    queued.set(false);
+                    waiter.wait_woken();
+                }
+            }
+        }
     }
 
-    pub(super) fn try_acquire(&self, method: Method) -> Result<Arc<H>, ()> {
+    pub(crate) fn try_acquire(&self, method: Method) -> Result<HandleId, ()> {
         self.lock(|mut queue| queue.try_acquire(method))
     }
 
-    pub(super) fn release(&self, handle: &H) {
-        self.lock(|mut queue| queue.release(handle));
+    pub(super) fn acquire_recursive_read<W>(&self, register: impl FnOnce() -> W) -> HandleId {
+        self.lock(|mut queue| queue.acquire_recursive_read())
+            .unwrap_or_else(|| self.acquire(Method::Read, register))
+    }
+
+    pub(crate) fn release(&self, id: HandleId) {
+        self.lock(|mut queue| queue.release(id));
+    }
+
+    pub(crate) fn force_release(&self, method: Method) {
+        self.lock(|mut queue| queue.force_release(method));
+    }
+
+    pub(crate) fn set_strategy(&self, strategy: Scheduler) {
+        self.lock(|mut queue| queue.set_strategy(strategy));
+    }
+
+    pub(crate) fn set_recheck_interval(&self, interval: Option<Duration>) {
+        self.lock(|mut queue| queue.set_recheck_interval(interval));
+    }
+
+    pub(crate) fn set_on_release(&self, on_release: Option<Box<dyn OnRelease>>) {
+        self.lock(|queue| *queue.on_release = on_release);
+    }
+
+    /// Limits how many entries may be blocked at once for acquisitions that can fail, or lifts the
+    /// limit if it is `None`. Entries already blocked are left alone.
+    pub(crate) fn set_max_queued(&self, max_queued: Option<usize>) {
+        self.lock(|queue| *queue.max_queued = max_queued);
+    }
+
+    #[cfg(feature = "strategy-observer")]
+    pub(crate) fn set_strategy_observer(&self, observer: Option<Box<dyn StrategyObserver>>) {
+        self.lock(|queue| *queue.observer = observer);
+    }
+
+    pub(crate) fn queued(&self, method: Method) -> usize {
+        self.lock(|queue| queue.count(|entry| entry.method == method && entry.state().is_blocked()))
     }
+
+    /// Returns the number of readers holding the lock, and whether a writer holds it.
+    pub(super) fn active(&self) -> (usize, bool) {
+        self.lock(|queue| {
+            let holding =
+                |method| queue.count(|entry| entry.method == method && entry.state().is_ok());
+            (holding(Method::Read), holding(Method::Write) > 0)
+        })
+    }
+
+    pub(crate) fn holders(&self) -> usize {
+        self.lock(|queue| queue.count(|entry| entry.state().is_ok()))
+    }
+
+    pub(crate) fn entry_infos(&self) -> Vec<QueueEntryInfo> {
+        self.lock(|queue| queue.entry_infos())
+    }
+
+    pub(crate) fn longest_wait(&self) -> Option<Duration> {
+        self.lock(|queue| queue.longest_wait())
+    }
+}
+
+impl Queue<AsyncHandle> {
+    /// Acquires the lock like [`acquire_tagged`](Queue::acquire_tagged), for an async task that
+    /// awaits its entry being admitted instead of parking the thread.
+    pub(crate) fn acquire_async(&self, method: Method, tag: u64) -> Acquire<'_> {
+        Acquire {
+            queue: self,
+            method,
+            tag,
+            state: AcquireState::Init,
+        }
+    }
+}
+
+enum AcquireState {
+    Init,
+    /// The entry is in the queue, see [`LockedQueueView::poll`] for the flag.
+    Queued(Box<Waiter<AsyncHandle>>, Cell<bool>),
+    Done,
 }
 
-pub(super) fn wrap_if_poisoned<U>(poisoned: bool, data: U) -> LockResult<U> {
+/// The future returned by [`Queue::acquire_async`], which completes with the id of the admitted
+/// entry.
+///
+/// The waiter is boxed, as the entry points to it while the future may move between polls. A
+/// future dropped while queued gives up on its entry like a thread that stops waiting.
+#[must_use = "futures do nothing unless polled"]
+pub(crate) struct Acquire<'a> {
+    queue: &'a Queue<AsyncHandle>,
+    method: Method,
+    tag: u64,
+    state: AcquireState,
+}
+
+impl Future for Acquire<'_> {
+    type Output = HandleId;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> task::Poll<HandleId> {
+        let this = self.get_mut();
+        if let AcquireState::Init = this.state {
+            let waiter = Box::new(Waiter::<AsyncHandle>::new());
+            let id = waiter.handle.id();
+            this.queue
+                .lock(|mut queue| queue.acquire(id, this.method, this.tag));
+            this.state = AcquireState::Queued(waiter, Cell::new(false));
+        }
+
+        let AcquireState::Queued(waiter, queued) = &this.state else {
+            panic!("`Acquire` polled after completion");
+        };
+        let id = waiter.handle.id();
+        // The waker is registered after the entry points to the waiter, so a release in between
+        // leaves an unpark behind for `poll_park` to pick up. Periodic re-checks are left out, as
+        // tasks have no timer to be woken by.
+        while this.queue.advance(id, waiter, queued).is_err() {
+            if waiter.handle.poll_park(cx).is_pending() {
+                return task::Poll::Pending;
+            }
+        }
+        this.state = AcquireState::Done;
+        task::Poll::Ready(id)
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let AcquireState::Queued(waiter, queued) = &self.state {
+            // The entry may have been admitted in the meantime, and nobody is left to release it.
+            if let Ok(id) = self.queue.abandon(waiter.handle.id(), waiter, queued) {
+                self.queue.release(id);
+            }
+        }
+    }
+}
+
+pub(crate) fn wrap_if_poisoned<U>(poisoned: bool, data: U) -> LockResult<U> {
     match poisoned {
         true => Err(PoisonError::new(data)),
         false => Ok(data),
@@ -375,10 +1138,12 @@ pub(super) struct RwLockInner<H: Handle> {
 }
 
 impl<H: Handle> RwLockInner<H> {
-    pub(super) const fn new(strategy: Box<dyn Strategy>) -> Self {
-        Self {
-            queue: Queue::new(strategy),
-            poisoned: AtomicBool::new(false),
+    const_fn! {
+        pub(super) const fn new(strategy: Scheduler) -> Self {
+            Self {
+                queue: Queue::new(strategy),
+                poisoned: AtomicBool::new(false),
+            }
         }
     }
 
@@ -388,21 +1153,21 @@ impl<H: Handle> RwLockInner<H> {
 
     pub(super) unsafe fn do_read<'a, T: ?Sized>(
         &'a self,
-        handle: Arc<H>,
-        data: &'a UnsafeCell<T>,
+        id: HandleId,
+        lock: &'a BaseRwLock<T, H>,
     ) -> LockResult<BaseRwLockReadGuard<'a, T, H>> {
         wrap_if_poisoned(self.is_poisoned(), unsafe {
-            BaseRwLockReadGuard::new(data, handle, self)
+            BaseRwLockReadGuard::new(lock, id)
         })
     }
 
     pub(super) unsafe fn do_write<'a, T: ?Sized>(
         &'a self,
-        handle: Arc<H>,
-        data: &'a UnsafeCell<T>,
+        id: HandleId,
+        lock: &'a BaseRwLock<T, H>,
     ) -> LockResult<BaseRwLockWriteGuard<'a, T, H>> {
         wrap_if_poisoned(self.is_poisoned(), unsafe {
-            BaseRwLockWriteGuard::new(data, handle, self)
+            BaseRwLockWriteGuard::new(lock, id)
         })
     }
 
@@ -415,15 +1180,20 @@ impl<H: Handle> RwLockInner<H> {
     }
 
     // `unsafe` enforces the locking invariant in the parent module.
-    pub(super) unsafe fn finish_read(&self, handle: &H) {
-        self.queue.release(handle);
+    pub(super) unsafe fn finish_read(&self, id: HandleId) {
+        self.queue.release(id);
         // The lock is not poisoned as the underlying `T` can't be mutated while `read`ing, which
         // could otherwise expose corrupt state. This is consistent with Rust's `RwLock`.
     }
 
     // `unsafe` enforces the locking invariant in the parent module.
-    pub(super) unsafe fn finish_write(&self, handle: &H, poison: bool) {
-        self.queue.release(handle);
+    pub(super) unsafe fn finish_write(&self, id: HandleId, poison: bool) {
+        self.queue.release(id);
         self.poisoned.fetch_or(poison, Ordering::AcqRel);
     }
+
+    // `unsafe` enforces the locking invariant in the parent module.
+    pub(super) unsafe fn force_finish(&self, method: Method) {
+        self.queue.force_release(method);
+    }
 }
@@ -1,86 +1,55 @@
 pub mod strategies;
+pub mod verify;
+
+#[cfg(feature = "proptest")]
+pub mod fuzz;
 
 mod api;
 pub use api::*;
 
-mod impls;
+mod builder;
+pub use builder::*;
+
+pub(crate) mod impls;
 
 use core::{
     cell::UnsafeCell,
+    fmt::{self, Display, Formatter},
     hash::Hash,
     marker::PhantomData,
+    mem::{self, ManuallyDrop},
     ops::{Deref, DerefMut},
     panic::{RefUnwindSafe, UnwindSafe},
     ptr::NonNull,
+    time::Duration,
 };
 
+#[cfg(feature = "std")]
+use crate::primitives::park_capped_until;
+
 extern crate alloc;
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 
+pub use crate::primitives::Method;
+
+#[cfg(feature = "multi")]
+use crate::primitives::LockId;
 use crate::{
-    primitives::{CoreHandle, Handle, HandleId, LockResult, TryLockError, TryLockResult},
+    primitives::{
+        AsyncHandle, CoreHandle, Handle, HandleId, LockResult, LockResultExt, TryLockError,
+        TryLockResult, Waiting, forward_guard_mut_traits, forward_guard_traits, stable_guard,
+        sync::const_fn,
+    },
     rwlock::{RwLockApi, RwLockReadGuardApi, RwLockWriteGuardApi},
 };
 
-///
-/// Denotes the type of operation that a Thread is performing on a [`RwLock`]. Used by
-/// [`StrategyInput`] as part of the parameter to a [`Strategy`].
-///
-/// When operating with a `Strategy`, implementors must return a `StrategyResult`, which is a boxed
-/// [`Iterator`] of `State`s. Each of the returned `State` objects corresponds to a particular
-/// `Method` of a thread inside `StrategyInput`, which gets passed to `should_block`.
-///
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum Method {
-    /// Denotes threads that are reading, or planning to read from the lock.
-    Read,
-    /// Denotes threads that are writing, or planning to write to the lock.
-    Write,
-}
-
-impl Method {
-    /// Returns `true` if this `Method` an instance of [`Method::Read`], and returns `false`
-    /// otherwise.
-    ///
-    /// # Examples
-    /// ```
-    /// # use powerlocks::strategied_rwlock::Method;
-    /// let method = Method::Read;
-    /// assert!(method.is_read());
-    ///
-    /// let method = Method::Write;
-    /// assert!(!method.is_read());
-    /// ```
-    ///
-    pub fn is_read(&self) -> bool {
-        *self == Method::Read
-    }
-
-    /// Returns `true` if this `Method` an instance of [`Method::Write`], and returns `false`
-    /// otherwise.
-    ///
-    /// # Examples
-    /// ```
-    /// # use powerlocks::strategied_rwlock::Method;
-    /// let method = Method::Write;
-    /// assert!(method.is_write());
-    ///
-    /// let method = Method::Read;
-    /// assert!(!method.is_write());
-    /// ```
-    ///
-    pub fn is_write(&self) -> bool {
-        *self == Method::Write
-    }
-}
-
 ///
 /// Denotes whether a thread accessing a [`RwLock`] is allowed to proceed or is blocked. Used by
 /// [`StrategyResult`] in the return of [`Strategy`].
 ///
 /// When operating with a `Strategy`, implementors must return a `StrategyResult`, which is a boxed
 /// [`Iterator`] of `State`s. Each of the returned `State` objects corresponds to a particular
-/// `Method` of a thread inside `StrategyInput`, which gets passed to `should_block`. An
+/// `Method` of a thread inside `StrategyInput`, which gets passed to the `Strategy`. An
 /// [`Ok`](State::Ok) value returned by `Strategy` indicates that the thread should be able to
 /// access the data inside a `RwLock`, whereas a [`Blocked`](State::Blocked) value indicates that
 /// the thread should remain blocked.
@@ -107,8 +76,8 @@ impl State {
     /// assert!(!state.is_ok());
     /// ```
     ///
-    pub fn is_ok(&self) -> bool {
-        *self == State::Ok
+    pub const fn is_ok(&self) -> bool {
+        matches!(self, State::Ok)
     }
 
     /// Returns `true` if this `State` an instance of [`State::Blocked`], and returns `false`
@@ -124,12 +93,57 @@ impl State {
     /// assert!(!state.is_blocked());
     /// ```
     ///
-    pub fn is_blocked(&self) -> bool {
-        *self == State::Blocked
+    pub const fn is_blocked(&self) -> bool {
+        matches!(self, State::Blocked)
+    }
+}
+
+impl From<bool> for State {
+    /// Converts `true` into [`State::Ok`], and `false` into [`State::Blocked`], which lets
+    /// strategies compute whether each thread may proceed as a plain condition.
+    ///
+    /// # Examples
+    /// ```
+    /// # use powerlocks::strategied_rwlock::State;
+    /// assert_eq!(State::from(true), State::Ok);
+    /// assert_eq!(State::from(false), State::Blocked);
+    /// ```
+    ///
+    fn from(ok: bool) -> Self {
+        match ok {
+            true => State::Ok,
+            false => State::Blocked,
+        }
+    }
+}
+
+impl Display for State {
+    /// Formats the `State` in lowercase, as `ok` or `blocked`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            State::Ok => "ok",
+            State::Blocked => "blocked",
+        })
     }
 }
 
+/// The queue of threads passed to a [`Strategy`], oldest first.
+///
+/// Each item pairs the [`HandleId`] of a thread with the [`Method`] it is accessing the lock with.
+/// Threads that currently hold the lock stay in the queue until they release it.
 pub type StrategyInput<'i> = &'i mut dyn Iterator<Item = &'i (HandleId, Method)>;
+
+/// The [`State`]s returned by a [`Strategy`], one for each item of its [`StrategyInput`], in the
+/// same order.
+///
+/// # Examples
+/// A strategy that only ever admits the oldest thread, which serializes readers as well:
+/// ```
+/// # use powerlocks::strategied_rwlock::{State, StrategyInput, StrategyResult};
+/// fn one_at_a_time(entries: StrategyInput) -> StrategyResult {
+///     Box::new(entries.enumerate().map(|(index, _)| State::from(index == 0)))
+/// }
+/// ```
 pub type StrategyResult<'i> = Box<dyn Iterator<Item = State> + 'i>;
 
 ///
@@ -161,14 +175,114 @@ pub type StrategyResult<'i> = Box<dyn Iterator<Item = State> + 'i>;
 /// The behaviour of a logic error is currently unspecified, but may lead to [`panic`]s and
 /// [`abort`](std::process::abort)s.
 ///
+/// Strategies can be checked for these logic errors ahead of time with [`verify::run`].
+///
 /// # Examples
 ///
 /// In crate [`strategies`]:
 /// - [`strategies::fair`] - a fair strategy that holds Threads in a FIFO queue.
+/// - [`strategies::fifo`] - a strategy that grants the lock strictly in arrival order.
+/// - [`strategies::batched`] - a fair strategy that admits readers in bounded batches.
+/// - [`strategies::random`] - an arbitrary, but safe, strategy for stress testing.
+/// - [`strategies::throttle_writes`] - rate-limits the writers admitted by another strategy.
+/// - [`strategies::time_sliced`] - alternates between windows for readers and for writers.
 ///
 pub trait Strategy: Fn(StrategyInput) -> StrategyResult {}
 impl<F> Strategy for F where F: ?Sized + Fn(StrategyInput) -> StrategyResult {}
 
+/// The queue of threads passed to a [`TaggedStrategy`], oldest first.
+///
+/// Like [`StrategyInput`], but each item also carries the tag the thread is accessing the lock
+/// with, as given to [`read_tagged`](BaseRwLock::read_tagged) and
+/// [`write_tagged`](BaseRwLock::write_tagged). Threads that didn't give a tag are tagged with `0`.
+pub type TaggedStrategyInput<'i> = &'i mut dyn Iterator<Item = &'i (HandleId, Method, u64)>;
+
+/// A [`Strategy`] that also sees the tag of every queued thread, so it can schedule threads by
+/// e.g. tenant or request class, without keeping a side table keyed by [`HandleId`].
+///
+/// A `TaggedStrategy` must follow the same rules as a `Strategy`.
+///
+/// # Examples
+/// A strategy that admits threads in arrival order, like [`strategies::fifo`], but never admits two
+/// readers tagged as heavy at once:
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use powerlocks::strategied_rwlock::{
+/// #     Method, State, StdRwLock, StrategyResult, TaggedStrategyInput,
+/// # };
+/// const HEAVY: u64 = 1;
+///
+/// fn one_heavy_reader(entries: TaggedStrategyInput) -> StrategyResult {
+///     let (mut admitting, mut entries_ahead, mut heavy_ahead) = (true, 0, false);
+///     Box::new(entries.map(move |&(_handle_id, method, tag)| {
+///         admitting &= match method {
+///             Method::Read => !(tag == HEAVY && heavy_ahead),
+///             Method::Write => entries_ahead == 0,
+///         };
+///         let state = State::from(admitting);
+///         admitting &= method.is_read();
+///         heavy_ahead |= tag == HEAVY;
+///         entries_ahead += 1;
+///         state
+///     }))
+/// }
+///
+/// let lock = StdRwLock::new_tagged_strategied(0, Box::new(one_heavy_reader));
+/// let heavy = lock.read_tagged(HEAVY).unwrap();
+/// let light = lock.read().unwrap();
+/// assert_eq!(lock.holders(), 2);
+/// # }
+/// ```
+///
+pub trait TaggedStrategy: Fn(TaggedStrategyInput) -> StrategyResult {}
+impl<F> TaggedStrategy for F where F: ?Sized + Fn(TaggedStrategyInput) -> StrategyResult {}
+
+/// Told about every release of a [`BaseRwLock`], with the [`HandleId`] and [`Method`] of the thread
+/// that released it, see [`set_on_release`](BaseRwLock::set_on_release).
+///
+/// A [`Strategy`] only sees the threads that are still queued, so one that counts e.g. the reads
+/// completed since the last write would otherwise have to infer them from successive queues.
+/// Sharing a counter with an `OnRelease` lets it keep such counts directly.
+///
+/// It is called with the queue of the lock locked, right before the strategy is consulted about
+/// the release, so it must be quick, and must not access the lock.
+pub trait OnRelease: Fn(HandleId, Method) {}
+impl<F> OnRelease for F where F: ?Sized + Fn(HandleId, Method) {}
+
+/// Shown every queue a [`BaseRwLock`] consults its strategy about, along with the [`State`]s the
+/// strategy returned for it, see [`set_strategy_observer`](BaseRwLock::set_strategy_observer).
+///
+/// The queue is oldest first, as a [`TaggedStrategy`] sees it, with a tag of `0` for entries that
+/// weren't tagged. The states are the strategy's own decisions, before the lock enforces its
+/// preconditions on them, and there are fewer of them than entries if the strategy stopped short.
+/// Recording the pairs from a lock in production lets the strategy be replayed on them offline,
+/// to find the queue it misbehaved on.
+///
+/// It is called with the queue of the lock locked, so it must be quick, and must not access the
+/// lock.
+#[cfg(feature = "strategy-observer")]
+pub trait StrategyObserver: Fn(&[(HandleId, Method, u64)], &[State]) {}
+#[cfg(feature = "strategy-observer")]
+impl<F> StrategyObserver for F where F: ?Sized + Fn(&[(HandleId, Method, u64)], &[State]) {}
+
+/// A snapshot of an entry in the queue of a [`BaseRwLock`], as returned by
+/// [`debug_queue`](BaseRwLock::debug_queue).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueueEntryInfo {
+    /// The handle of the thread that queued this entry.
+    pub handle_id: HandleId,
+    pub method: Method,
+    /// Whether this entry holds the lock, or is still waiting for it.
+    pub state: State,
+    /// The tag the entry was queued with, see [`TaggedStrategy`].
+    pub tag: u64,
+    /// Whether the thread of this entry is asleep, as told by [`Handle::is_parked`].
+    pub parked: bool,
+    /// How long ago this entry was queued, if the thread environment has a clock, see
+    /// [`ThreadEnv::now`](crate::primitives::ThreadEnv::now).
+    pub waited: Option<Duration>,
+}
+
 #[derive(Debug)]
 #[must_use = "if unused the `RwLock` will immediately unlock"]
 pub struct BaseRwLockReadGuard<'a, T: 'a + ?Sized, H: Handle> {
@@ -182,30 +296,43 @@ pub struct BaseRwLockReadGuard<'a, T: 'a + ?Sized, H: Handle> {
     // and enables niche optimization over the idiomatic `*const T`.
     // See [`std::sync::RwLockReadGuard`] for more info.
     data: NonNull<T>,
-    handle: Arc<H>,
-    lock: &'a impls::RwLockInner<H>,
+    id: HandleId,
+    lock: &'a BaseRwLock<T, H>,
 }
 
 impl<'a, T: 'a + ?Sized, H: Handle> BaseRwLockReadGuard<'a, T, H> {
-    unsafe fn new(
-        data: &'a UnsafeCell<T>,
-        handle: Arc<H>,
-        lock: &'a impls::RwLockInner<H>,
-    ) -> Self {
+    unsafe fn new(lock: &'a BaseRwLock<T, H>, id: HandleId) -> Self {
         Self {
             // SAFETY: `data.get()` always returns a non-null pointer.
-            data: unsafe { NonNull::new_unchecked(data.get()) },
-            handle,
+            data: unsafe { NonNull::new_unchecked(lock.data.get()) },
+            id,
             lock,
         }
     }
+
+    /// Returns the lock that this guard was acquired from.
+    pub fn rwlock(&self) -> &'a BaseRwLock<T, H> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the lock read-locked forever and returning a shared reference to
+    /// the protected data that lives as long as the lock itself.
+    ///
+    /// Whether other readers may still acquire the lock is up to the [`Strategy`] in use. Writers
+    /// will never be able to.
+    pub fn leak(self) -> &'a T {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: The read lock is never released, so no writer can ever gain access to `data` for
+        // the rest of `'a`.
+        unsafe { this.data.as_ref() }
+    }
 }
 
 // SAFETY: Unlike `RwLockReadGuard`, we are `Send` for similar reasons as why `BaseMutexGuard` is
 // `Send` - we are `Handle`-based and we don't need to release the lock on the same thread, unlike
-// what `pthread_mutex_unlock` requires. The `Handle` structure we have will never `park` after the
-// lock is acquired, and `release` only works with the handle ID, which prevents any threading
-// unsafety or conflicts that arise from `Send`ing this guard.
+// what `pthread_mutex_unlock` requires. The guard only keeps the handle ID, which `release` works
+// with, and which prevents any threading unsafety or conflicts that arise from `Send`ing this
+// guard.
 unsafe impl<'a, T: 'a + ?Sized + Send, H: Handle> Send for BaseRwLockReadGuard<'a, T, H> {}
 unsafe impl<'a, T: 'a + ?Sized + Sync, H: Handle> Sync for BaseRwLockReadGuard<'a, T, H> {}
 
@@ -219,10 +346,17 @@ impl<'a, T: 'a + ?Sized, H: Handle> Deref for BaseRwLockReadGuard<'a, T, H> {
     }
 }
 
+forward_guard_traits! {
+    impl<'a, T, H> BaseRwLockReadGuard<'a, T, H> => T
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
 impl<'a, T: 'a + ?Sized, H: Handle> Drop for BaseRwLockReadGuard<'a, T, H> {
     fn drop(&mut self) {
         // SAFETY: `Queue` ensures that there are no writers currently operating.
-        unsafe { self.lock.finish_read(&self.handle) }
+        unsafe { self.lock.inner.finish_read(self.id) }
     }
 }
 
@@ -230,26 +364,39 @@ impl<'a, T: 'a + ?Sized, H: Handle> Drop for BaseRwLockReadGuard<'a, T, H> {
 #[must_use = "if unused the `RwLock` will immediately unlock"]
 pub struct BaseRwLockWriteGuard<'a, T: 'a + ?Sized, H: Handle> {
     data: NonNull<T>,
-    handle: Arc<H>,
-    lock: &'a impls::RwLockInner<H>,
+    id: HandleId,
+    lock: &'a BaseRwLock<T, H>,
     // Enforce invariance over `T` because `NonNull` is covariant.
     invariant_t: PhantomData<&'a mut T>,
 }
 
 impl<'a, T: 'a + ?Sized, H: Handle> BaseRwLockWriteGuard<'a, T, H> {
-    unsafe fn new(
-        data: &'a UnsafeCell<T>,
-        handle: Arc<H>,
-        lock: &'a impls::RwLockInner<H>,
-    ) -> Self {
+    unsafe fn new(lock: &'a BaseRwLock<T, H>, id: HandleId) -> Self {
         Self {
             // SAFETY: `data.get()` always returns a non-null pointer.
-            data: unsafe { NonNull::new_unchecked(data.get()) },
-            handle,
+            data: unsafe { NonNull::new_unchecked(lock.data.get()) },
+            id,
             lock,
             invariant_t: PhantomData,
         }
     }
+
+    /// Returns the lock that this guard was acquired from.
+    pub fn rwlock(&self) -> &'a BaseRwLock<T, H> {
+        self.lock
+    }
+
+    /// Leaks this guard, keeping the lock write-locked forever and returning a mutable reference
+    /// to the protected data that lives as long as the lock itself.
+    ///
+    /// Any further attempt to read or write the lock will block (or fail with
+    /// [`TryLockError::WouldBlock`]).
+    pub fn leak(self) -> &'a mut T {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: The write lock is never released, so we keep our exclusive access to `data` for
+        // the rest of `'a`.
+        unsafe { this.data.as_mut() }
+    }
 }
 
 // SAFETY: `BaseRwLockWriteGuard` is send for the same reason as `BaseRwLockReadGuard`.
@@ -272,67 +419,287 @@ impl<'a, T: 'a + ?Sized, H: Handle> DerefMut for BaseRwLockWriteGuard<'a, T, H>
     }
 }
 
+forward_guard_traits! {
+    impl<'a, T, H> BaseRwLockWriteGuard<'a, T, H> => T
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
+forward_guard_mut_traits! {
+    impl<'a, T, H> BaseRwLockWriteGuard<'a, T, H> => T
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
+stable_guard! {
+    impl<'a, T, H> BaseRwLockWriteGuard<'a, T, H>
+    where
+        T: 'a + ?Sized,
+        H: Handle,
+}
+
 impl<'a, T: 'a + ?Sized, H: Handle> Drop for BaseRwLockWriteGuard<'a, T, H> {
     fn drop(&mut self) {
         // SAFETY: `Queue` ensures that we have the only access as required here.
-        unsafe { self.lock.finish_write(&self.handle, H::panicking()) }
+        unsafe { self.lock.inner.finish_write(self.id, H::panicking()) }
     }
 }
 
+/// A read-write lock that queues the threads acquiring it, and admits them as its [`Strategy`]
+/// decides.
+///
+/// Its metrics, such as [`queued_readers`](BaseRwLock::queued_readers) and
+/// [`debug_queue`](BaseRwLock::debug_queue), are snapshots like those of [`StrategiedRwLockApi`].
 #[derive(Debug)]
 pub struct BaseRwLock<T: ?Sized, H: Handle> {
     inner: impls::RwLockInner<H>,
+    #[cfg(feature = "multi")]
+    id: LockId,
     data: UnsafeCell<T>,
 }
 
 impl<T: Sized, H: Handle> BaseRwLock<T, H> {
-    pub const fn new_strategied(t: T, strategy: Box<dyn Strategy>) -> Self {
-        Self {
-            inner: impls::RwLockInner::new(strategy),
-            data: UnsafeCell::new(t),
+    const_fn! {
+        const fn with_scheduler(t: T, strategy: impls::Scheduler) -> Self {
+            Self {
+                inner: impls::RwLockInner::new(strategy),
+                #[cfg(feature = "multi")]
+                id: LockId::new(),
+                data: UnsafeCell::new(t),
+            }
+        }
+    }
+
+    const_fn! {
+        pub const fn new_strategied(t: T, strategy: Box<dyn Strategy>) -> Self {
+            Self::with_scheduler(t, impls::Scheduler::RwLock(strategy))
         }
     }
 
+    const_fn! {
+        /// Creates a new lock that is scheduled by a [`TaggedStrategy`].
+        pub const fn new_tagged_strategied(t: T, strategy: Box<dyn TaggedStrategy>) -> Self {
+            Self::with_scheduler(t, impls::Scheduler::TaggedRwLock(strategy))
+        }
+    }
+
+    /// Returns a [`RwLockBuilder`] for a lock of this type, to configure its strategy along with
+    /// the other options at once.
+    pub fn builder() -> RwLockBuilder<T, H> {
+        RwLockBuilder::new()
+    }
+
     pub fn new(t: T) -> Self {
         BaseRwLock::new_strategied(t, Box::new(strategies::fair))
     }
 
+    /// Creates a new read-write lock inside an [`Arc`], ready to be shared between threads.
+    pub fn new_arc(t: T) -> Arc<Self> {
+        Arc::new(Self::new(t))
+    }
+
     pub fn into_inner(self) -> LockResult<T> {
         impls::wrap_if_poisoned(self.is_poisoned(), self.data.into_inner())
     }
 }
 
 impl<T: ?Sized, H: Handle> BaseRwLock<T, H> {
-    pub fn read(&self) -> LockResult<BaseRwLockReadGuard<T, H>> {
-        let handle = self.inner.queue().acquire(Method::Read);
+    pub fn read(&self) -> LockResult<BaseRwLockReadGuard<'_, T, H>> {
+        let handle = self
+            .inner
+            .queue()
+            .acquire(Method::Read, || Waiting::register(self));
         // SAFETY: `acquire` ensures that no write operations are happening.
-        unsafe { self.inner.do_read(handle, &self.data) }
+        unsafe { self.inner.do_read(handle, self) }
     }
 
-    pub fn try_read(&self) -> TryLockResult<BaseRwLockReadGuard<T, H>> {
+    /// Acquires shared read access like [`read`](BaseRwLock::read), queueing with `tag`.
+    ///
+    /// The tag is handed to the [`TaggedStrategy`] of the lock, if it has one, and is ignored
+    /// otherwise.
+    pub fn read_tagged(&self, tag: u64) -> LockResult<BaseRwLockReadGuard<'_, T, H>> {
+        let handle = self
+            .inner
+            .queue()
+            .acquire_tagged(Method::Read, tag, || Waiting::register(self));
+        // SAFETY: `acquire_tagged` ensures that no write operations are happening.
+        unsafe { self.inner.do_read(handle, self) }
+    }
+
+    /// Acquires shared read access like [`read`](BaseRwLock::read), but is admitted right away if
+    /// the current thread already holds a read lock, whatever the [`Strategy`] says.
+    ///
+    /// This prevents the deadlock in which a thread that reads recursively waits behind a queued
+    /// writer, which itself waits for the thread's first read, e.g. under [`strategies::fair`].
+    /// Writers stay blocked until every recursive read is released.
+    ///
+    /// Threads are told apart with
+    /// [`ThreadEnv::current_id`](crate::primitives::ThreadEnv::current_id).
+    ///
+    /// # Deadlocks
+    /// Handles whose environment can't tell threads apart, such as [`CoreHandle`] and
+    /// [`AsyncHandle`], never admit a read as recursive. Reading recursively through them then
+    /// deadlocks behind a queued writer under strategies that block new readers while a writer
+    /// waits, such as [`strategies::fair`], just like with [`read`](BaseRwLock::read).
+    pub fn read_recursive(&self) -> LockResult<BaseRwLockReadGuard<'_, T, H>> {
+        let handle = self
+            .inner
+            .queue()
+            .acquire_recursive_read(|| Waiting::register(self));
+        // SAFETY: `acquire_recursive_read` ensures that no write operations are happening.
+        unsafe { self.inner.do_read(handle, self) }
+    }
+
+    pub fn try_read(&self) -> TryLockResult<BaseRwLockReadGuard<'_, T, H>> {
         if let Ok(handle) = self.inner.queue().try_acquire(Method::Read) {
             // SAFETY: `try_acquire` returning `Ok` ensures that no write operations are happening.
-            unsafe { self.inner.do_read(handle, &self.data) }.map_err(TryLockError::Poisoned)
+            unsafe { self.inner.do_read(handle, self) }.map_err(TryLockError::Poisoned)
         } else {
             Err(TryLockError::WouldBlock)
         }
     }
 
-    pub fn write(&self) -> LockResult<BaseRwLockWriteGuard<T, H>> {
-        let handle = self.inner.queue().acquire(Method::Write);
+    pub fn write(&self) -> LockResult<BaseRwLockWriteGuard<'_, T, H>> {
+        let handle = self
+            .inner
+            .queue()
+            .acquire(Method::Write, || Waiting::register(self));
         // SAFETY: `acquire` ensures that this thread has exclusive access.
-        unsafe { self.inner.do_write(handle, &self.data) }
+        unsafe { self.inner.do_write(handle, self) }
     }
 
-    pub fn try_write(&self) -> TryLockResult<BaseRwLockWriteGuard<T, H>> {
+    /// Acquires exclusive write access like [`write`](BaseRwLock::write), queueing with `tag`.
+    ///
+    /// See [`read_tagged`](BaseRwLock::read_tagged) for details.
+    pub fn write_tagged(&self, tag: u64) -> LockResult<BaseRwLockWriteGuard<'_, T, H>> {
+        let handle = self
+            .inner
+            .queue()
+            .acquire_tagged(Method::Write, tag, || Waiting::register(self));
+        // SAFETY: `acquire_tagged` ensures that this thread has exclusive access.
+        unsafe { self.inner.do_write(handle, self) }
+    }
+
+    pub fn try_write(&self) -> TryLockResult<BaseRwLockWriteGuard<'_, T, H>> {
         if let Ok(handle) = self.inner.queue().try_acquire(Method::Write) {
             // SAFETY: `try_acquire` returning `Ok` ensures that this thread has exclusive access.
-            unsafe { self.inner.do_write(handle, &self.data) }.map_err(TryLockError::Poisoned)
+            unsafe { self.inner.do_write(handle, self) }.map_err(TryLockError::Poisoned)
         } else {
             Err(TryLockError::WouldBlock)
         }
     }
 
+    /// Acquires shared read access like [`read`](BaseRwLock::read), but returns
+    /// [`TryLockError::WouldBlock`] right away instead of waiting if the queue of the lock is
+    /// full, see [`set_max_queued`](BaseRwLock::set_max_queued).
+    pub fn read_bounded(&self) -> TryLockResult<BaseRwLockReadGuard<'_, T, H>> {
+        match self.inner.queue().acquire_bounded(Method::Read) {
+            Ok(handle) => {
+                // SAFETY: `acquire_bounded` returning `Ok` ensures that no write operations are
+                // happening.
+                unsafe { self.inner.do_read(handle, self) }.map_err(TryLockError::Poisoned)
+            }
+            Err(_) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Acquires exclusive write access like [`write`](BaseRwLock::write), but returns
+    /// [`TryLockError::WouldBlock`] right away instead of waiting if the queue of the lock is
+    /// full, see [`set_max_queued`](BaseRwLock::set_max_queued).
+    pub fn write_bounded(&self) -> TryLockResult<BaseRwLockWriteGuard<'_, T, H>> {
+        match self.inner.queue().acquire_bounded(Method::Write) {
+            Ok(handle) => {
+                // SAFETY: `acquire_bounded` returning `Ok` ensures that this thread has exclusive
+                // access.
+                unsafe { self.inner.do_write(handle, self) }.map_err(TryLockError::Poisoned)
+            }
+            Err(_) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Acquires shared read access like [`read`](BaseRwLock::read), but gives up and returns
+    /// [`TryLockError::TimedOut`] if the [`Strategy`] hasn't admitted this thread within
+    /// `timeout`.
+    ///
+    /// A timed out thread leaves the queue, so threads queued behind it may be admitted right
+    /// away. If the queue is already full, see [`set_max_queued`](BaseRwLock::set_max_queued), a
+    /// thread that would wait returns [`TryLockError::WouldBlock`] right away instead.
+    #[cfg(feature = "std")]
+    pub fn read_timeout(&self, timeout: Duration) -> TryLockResult<BaseRwLockReadGuard<'_, T, H>> {
+        match self
+            .inner
+            .queue()
+            .acquire_with(Method::Read, 0, true, park_capped_until(timeout))
+        {
+            Ok(handle) => {
+                // SAFETY: `acquire_with` returning `Ok` ensures that no write operations are
+                // happening.
+                unsafe { self.inner.do_read(handle, self) }.map_err(TryLockError::Poisoned)
+            }
+            Err(impls::AcquireError::GaveUp) => Err(TryLockError::TimedOut),
+            Err(impls::AcquireError::QueueFull) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Acquires exclusive write access like [`write`](BaseRwLock::write), but gives up and returns
+    /// [`TryLockError::TimedOut`] if the [`Strategy`] hasn't admitted this thread within
+    /// `timeout`.
+    ///
+    /// See [`read_timeout`](BaseRwLock::read_timeout) for details.
+    #[cfg(feature = "std")]
+    pub fn write_timeout(
+        &self,
+        timeout: Duration,
+    ) -> TryLockResult<BaseRwLockWriteGuard<'_, T, H>> {
+        match self
+            .inner
+            .queue()
+            .acquire_with(Method::Write, 0, true, park_capped_until(timeout))
+        {
+            Ok(handle) => {
+                // SAFETY: `acquire_with` returning `Ok` ensures that this thread has exclusive
+                // access.
+                unsafe { self.inner.do_write(handle, self) }.map_err(TryLockError::Poisoned)
+            }
+            Err(impls::AcquireError::GaveUp) => Err(TryLockError::TimedOut),
+            Err(impls::AcquireError::QueueFull) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Forcibly releases one read lock without going through a guard.
+    ///
+    /// This is intended for FFI and panic-recovery scenarios where a read guard has been leaked
+    /// (e.g. via [`BaseRwLockReadGuard::leak`] or [`core::mem::forget`]) or otherwise lost. Since
+    /// the lost guard's handle is unknown, the oldest reader holding the lock is released.
+    ///
+    /// # Safety
+    /// The lock must currently be read-locked, and the read guard (or leaked reference) being
+    /// released must no longer be used after this call. Releasing a read lock that is still
+    /// accessed through a live guard may let a writer alias it, which is undefined behavior.
+    pub unsafe fn force_unlock_read(&self) {
+        // SAFETY: Caller promises that the lock is read-locked and that the previous reader is
+        // gone.
+        unsafe { self.inner.force_finish(Method::Read) }
+    }
+
+    /// Forcibly releases the write lock without going through a guard. The lock is not poisoned
+    /// by this call.
+    ///
+    /// This is intended for FFI and panic-recovery scenarios where a write guard has been leaked
+    /// (e.g. via [`BaseRwLockWriteGuard::leak`] or [`core::mem::forget`]) or otherwise lost.
+    ///
+    /// # Safety
+    /// The lock must currently be write-locked, and the write guard (or leaked reference) must no
+    /// longer be used after this call. Releasing a write lock that is still accessed through a
+    /// live guard leads to data races and undefined behavior.
+    pub unsafe fn force_unlock_write(&self) {
+        // SAFETY: Caller promises that the lock is write-locked and that the previous writer is
+        // gone.
+        unsafe { self.inner.force_finish(Method::Write) }
+    }
+
     pub fn is_poisoned(&self) -> bool {
         self.inner.is_poisoned()
     }
@@ -341,9 +708,295 @@ impl<T: ?Sized, H: Handle> BaseRwLock<T, H> {
         self.inner.clear_poison();
     }
 
+    /// Returns the id that [`multi::lock_all`](crate::multi::lock_all) orders this lock by.
+    #[cfg(feature = "multi")]
+    pub(crate) fn lock_id(&self) -> u64 {
+        self.id.get()
+    }
+
+    /// Returns a raw pointer to the underlying data.
+    ///
+    /// The pointer is always valid, but dereferencing it is only sound while the lock is held
+    /// appropriately, or when no other references to the data exist.
+    ///
+    /// See also: [`std::sync::RwLock::data_ptr`]
+    pub fn data_ptr(&self) -> *mut T {
+        self.data.get()
+    }
+
     pub fn get_mut(&mut self) -> LockResult<&mut T> {
         impls::wrap_if_poisoned(self.is_poisoned(), self.data.get_mut())
     }
+
+    /// Read-locks the lock just long enough to clone the protected data.
+    pub fn get_cloned(&self) -> LockResult<T>
+    where
+        T: Clone,
+    {
+        self.read().map_guard(|guard| T::clone(&guard))
+    }
+
+    /// Write-locks the lock to replace the protected data with `value`, dropping the old data, like
+    /// [`Mutex::set`](crate::mutex::BaseMutex::set).
+    pub fn set(&self, value: T) -> LockResult<()>
+    where
+        T: Sized,
+    {
+        self.replace(value).map_guard(drop)
+    }
+
+    /// Write-locks the lock to replace the protected data with `value`, returning the old data,
+    /// like [`Mutex::replace`](crate::mutex::BaseMutex::replace).
+    pub fn replace(&self, value: T) -> LockResult<T>
+    where
+        T: Sized,
+    {
+        self.write()
+            .map_guard(|mut guard| mem::replace(&mut *guard, value))
+    }
+
+    /// Write-locks the lock to take the protected data, leaving [`Default::default`] in its place,
+    /// like [`Mutex::take`](crate::mutex::BaseMutex::take).
+    pub fn take(&self) -> LockResult<T>
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /// Replaces the [`Strategy`] of this lock, keeping every thread that is queued on it.
+    ///
+    /// The new strategy is consulted straight away, and may admit threads that the old one kept
+    /// blocked. Threads that currently hold the lock keep it until they release it, even if the
+    /// new strategy would not have admitted them.
+    pub fn set_strategy(&self, strategy: Box<dyn Strategy>) {
+        self.inner
+            .queue()
+            .set_strategy(impls::Scheduler::RwLock(strategy));
+    }
+
+    /// Replaces the strategy of this lock with a [`TaggedStrategy`], like
+    /// [`set_strategy`](BaseRwLock::set_strategy).
+    pub fn set_tagged_strategy(&self, strategy: Box<dyn TaggedStrategy>) {
+        self.inner
+            .queue()
+            .set_strategy(impls::Scheduler::TaggedRwLock(strategy));
+    }
+
+    /// Makes threads that are blocked on this lock consult its [`Strategy`] again at least every
+    /// `interval`, even if no thread arrives or leaves in the meantime, or stops doing so if
+    /// `interval` is `None`, which is the default.
+    ///
+    /// A strategy is otherwise only consulted when the queue changes, which is enough unless its
+    /// decisions depend on time, as with [`strategies::throttle_writes`]. Shorter intervals let
+    /// such strategies admit threads closer to when they intend to, at the cost of more wake-ups.
+    pub fn set_recheck_interval(&self, interval: Option<Duration>) {
+        self.inner.queue().set_recheck_interval(interval);
+    }
+
+    /// Calls `on_release` every time a thread releases this lock, or stops doing so if it is
+    /// `None`, which is the default. Threads that give up waiting for the lock, e.g. on a timeout,
+    /// never held it, and aren't reported.
+    ///
+    /// # Examples
+    /// Counting the reads completed since the last write, e.g. for a strategy that admits a writer
+    /// every `N` reads to share with:
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    /// # use powerlocks::strategied_rwlock::{Method, StdRwLock};
+    /// let reads = Arc::new(AtomicUsize::new(0));
+    /// let lock = StdRwLock::new(0);
+    ///
+    /// let counting = Arc::clone(&reads);
+    /// lock.set_on_release(Some(Box::new(move |_handle_id, method| match method {
+    ///     Method::Read => drop(counting.fetch_add(1, Ordering::Relaxed)),
+    ///     Method::Write => counting.store(0, Ordering::Relaxed),
+    /// })));
+    ///
+    /// drop(lock.read().unwrap());
+    /// drop(lock.read().unwrap());
+    /// assert_eq!(reads.load(Ordering::Relaxed), 2);
+    /// drop(lock.write().unwrap());
+    /// assert_eq!(reads.load(Ordering::Relaxed), 0);
+    /// # }
+    /// ```
+    pub fn set_on_release(&self, on_release: Option<Box<dyn OnRelease>>) {
+        self.inner.queue().set_on_release(on_release);
+    }
+
+    /// Shows `observer` every queue the strategy of this lock is consulted about, along with its
+    /// decisions, or stops doing so if it is `None`, which is the default.
+    ///
+    /// # Examples
+    /// Capturing the decisions of a strategy, and replaying it on them later:
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use std::sync::{Arc, Mutex};
+    /// # use powerlocks::strategied_rwlock::{StdRwLock, strategies};
+    /// let captured = Arc::new(Mutex::new(Vec::new()));
+    /// let lock = StdRwLock::new(0);
+    ///
+    /// let recording = Arc::clone(&captured);
+    /// lock.set_strategy_observer(Some(Box::new(move |queue, decisions| {
+    ///     recording.lock().unwrap().push((queue.to_vec(), decisions.to_vec()));
+    /// })));
+    /// drop(lock.read().unwrap());
+    /// drop(lock.write().unwrap());
+    ///
+    /// for (queue, decisions) in captured.lock().unwrap().iter() {
+    ///     let entries = queue.iter().map(|&(id, method, _)| (id, method)).collect::<Vec<_>>();
+    ///     let replayed = strategies::fair(&mut entries.iter()).collect::<Vec<_>>();
+    ///     assert_eq!(&replayed, decisions);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "strategy-observer")]
+    pub fn set_strategy_observer(&self, observer: Option<Box<dyn StrategyObserver>>) {
+        self.inner.queue().set_strategy_observer(observer);
+    }
+
+    /// Turns away acquisitions that would wait for this lock while `max_queued` threads already do,
+    /// or lets any number of threads wait if it is `None`, which is the default. Under overload,
+    /// this keeps the queue from growing without bound, and tells the threads turned away to back
+    /// off.
+    ///
+    /// Acquisitions are turned away with [`TryLockError::WouldBlock`]. Under overload, block with
+    /// [`read_bounded`](BaseRwLock::read_bounded) and [`write_bounded`](BaseRwLock::write_bounded),
+    /// or with [`read_timeout`](BaseRwLock::read_timeout) and
+    /// [`write_timeout`](BaseRwLock::write_timeout). [`read`](BaseRwLock::read),
+    /// [`write`](BaseRwLock::write) and their variants can't fail, so they still wait past the
+    /// limit, and count towards it. [`try_read`](BaseRwLock::try_read) and
+    /// [`try_write`](BaseRwLock::try_write) never wait anyway. Threads already waiting when the
+    /// limit is lowered keep waiting.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use powerlocks::{primitives::TryLockError, strategied_rwlock::StdRwLock};
+    /// let lock = StdRwLock::new(0);
+    /// lock.set_max_queued(Some(0));
+    ///
+    /// let write = lock.write().unwrap();
+    /// assert!(matches!(lock.read_bounded(), Err(TryLockError::WouldBlock)));
+    /// drop(write);
+    /// assert!(lock.read_bounded().is_ok());
+    /// # }
+    /// ```
+    pub fn set_max_queued(&self, max_queued: Option<usize>) {
+        self.inner.queue().set_max_queued(max_queued);
+    }
+
+    /// Returns the number of threads that are waiting to read from this lock.
+    pub fn queued_readers(&self) -> usize {
+        self.inner.queue().queued(Method::Read)
+    }
+
+    /// Returns the number of threads that are waiting to write to this lock.
+    pub fn queued_writers(&self) -> usize {
+        self.inner.queue().queued(Method::Write)
+    }
+
+    /// Returns the number of guards currently holding this lock, either a number of readers or a
+    /// single writer.
+    pub fn holders(&self) -> usize {
+        self.inner.queue().holders()
+    }
+
+    /// Returns the number of readers holding this lock, and whether a writer holds it.
+    pub fn active(&self) -> (usize, bool) {
+        self.inner.queue().active()
+    }
+
+    /// Returns a snapshot of every entry in the queue of this lock, oldest first, including the
+    /// entries of the threads that hold it, to diagnose stuck locks at runtime.
+    pub fn debug_queue(&self) -> Vec<QueueEntryInfo> {
+        self.inner.queue().entry_infos()
+    }
+
+    /// Returns how long the thread that has waited the longest for this lock has been waiting,
+    /// e.g. to alert on starving threads. Threads that hold the lock don't count.
+    ///
+    /// Returns `None` if no thread is waiting, or if the thread environment has no clock, see
+    /// [`ThreadEnv::now`](crate::primitives::ThreadEnv::now).
+    pub fn longest_wait(&self) -> Option<Duration> {
+        self.inner.queue().longest_wait()
+    }
+}
+
+impl<T: ?Sized> BaseRwLock<T, AsyncHandle> {
+    /// Acquires shared read access like [`read`](BaseRwLock::read), but awaits its turn instead of
+    /// parking the thread. The task queues with the [`Strategy`] like any thread, and is woken
+    /// through its [`AsyncHandle`] once admitted.
+    ///
+    /// Dropping the future before it completes leaves the queue. The periodic re-checks of
+    /// [`set_recheck_interval`](BaseRwLock::set_recheck_interval) don't apply to tasks, which only
+    /// consult the strategy again once they are woken.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::{pin::pin, task::{Context, Poll, Waker}};
+    /// # use powerlocks::strategied_rwlock::AsyncRwLock;
+    /// let lock = AsyncRwLock::new(0);
+    /// let mut cx = Context::from_waker(Waker::noop());
+    ///
+    /// let writer = lock.write().unwrap();
+    /// let mut read = pin!(lock.read_async());
+    /// assert!(read.as_mut().poll(&mut cx).is_pending());
+    /// drop(writer);
+    /// assert!(matches!(read.poll(&mut cx), Poll::Ready(Ok(_))));
+    /// ```
+    pub async fn read_async(&self) -> LockResult<BaseRwLockReadGuard<'_, T, AsyncHandle>> {
+        self.read_tagged_async(0).await
+    }
+
+    /// Acquires shared read access like [`read_async`](BaseRwLock::read_async), queueing with
+    /// `tag`, see [`read_tagged`](BaseRwLock::read_tagged).
+    pub async fn read_tagged_async(
+        &self,
+        tag: u64,
+    ) -> LockResult<BaseRwLockReadGuard<'_, T, AsyncHandle>> {
+        let handle = self.inner.queue().acquire_async(Method::Read, tag).await;
+        // SAFETY: `acquire_async` ensures that no write operations are happening.
+        unsafe { self.inner.do_read(handle, self) }
+    }
+
+    /// Acquires exclusive write access like [`write`](BaseRwLock::write), but awaits its turn
+    /// instead of parking the thread.
+    ///
+    /// See [`read_async`](BaseRwLock::read_async) for details.
+    pub async fn write_async(&self) -> LockResult<BaseRwLockWriteGuard<'_, T, AsyncHandle>> {
+        self.write_tagged_async(0).await
+    }
+
+    /// Acquires exclusive write access like [`write_async`](BaseRwLock::write_async), queueing
+    /// with `tag`, see [`read_tagged`](BaseRwLock::read_tagged).
+    pub async fn write_tagged_async(
+        &self,
+        tag: u64,
+    ) -> LockResult<BaseRwLockWriteGuard<'_, T, AsyncHandle>> {
+        let handle = self.inner.queue().acquire_async(Method::Write, tag).await;
+        // SAFETY: `acquire_async` ensures that this thread has exclusive access.
+        unsafe { self.inner.do_write(handle, self) }
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T: ?Sized, H: Handle> crate::debug::Inspect for BaseRwLock<T, H> {
+    fn inspect(&self) -> crate::debug::LockInfo {
+        use crate::debug::{LockInfo, LockState};
+        let state = match self.active() {
+            (_, true) => LockState::Written,
+            (0, false) => LockState::Unlocked,
+            (readers, false) => LockState::Read(readers),
+        };
+        LockInfo::new(
+            "StrategiedRwLock",
+            state,
+            self.is_poisoned(),
+            self.queued_readers() + self.queued_writers(),
+        )
+    }
 }
 
 impl<T: Sized, H: Handle> From<T> for BaseRwLock<T, H> {
@@ -443,12 +1096,34 @@ impl<T: ?Sized, H: Handle> StrategiedRwLockApi<T> for BaseRwLock<T, H> {
     {
         Self::new_strategied(t, strategy)
     }
+
+    fn set_strategy(&self, strategy: Box<dyn Strategy>) {
+        self.set_strategy(strategy);
+    }
+
+    fn queued_readers(&self) -> usize {
+        self.queued_readers()
+    }
+
+    fn queued_writers(&self) -> usize {
+        self.queued_writers()
+    }
+
+    fn active(&self) -> (usize, bool) {
+        self.active()
+    }
 }
 
 pub type CoreRwLockReadGuard<'a, T> = BaseRwLockReadGuard<'a, T, CoreHandle>;
 pub type CoreRwLockWriteGuard<'a, T> = BaseRwLockWriteGuard<'a, T, CoreHandle>;
 pub type CoreRwLock<T> = BaseRwLock<T, CoreHandle>;
 
+pub type AsyncRwLockReadGuard<'a, T> = BaseRwLockReadGuard<'a, T, AsyncHandle>;
+pub type AsyncRwLockWriteGuard<'a, T> = BaseRwLockWriteGuard<'a, T, AsyncHandle>;
+/// A lock that async tasks acquire with [`read_async`](BaseRwLock::read_async) and
+/// [`write_async`](BaseRwLock::write_async), while threads may still use the blocking methods.
+pub type AsyncRwLock<T> = BaseRwLock<T, AsyncHandle>;
+
 #[cfg(not(feature = "std"))]
 mod types {
     use super::{BaseRwLock, BaseRwLockReadGuard, BaseRwLockWriteGuard};
@@ -474,3 +1149,6 @@ mod types {
 }
 
 pub use types::*;
+
+/// A [`RwLock`] shared through an [`Arc`], as created by [`BaseRwLock::new_arc`].
+pub type ArcRwLock<T> = Arc<RwLock<T>>;
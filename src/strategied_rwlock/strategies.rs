@@ -1,6 +1,13 @@
+use core::time::Duration;
+
 extern crate alloc;
 use alloc::{boxed::Box, vec, vec::Vec};
 
+use crate::{
+    mutex::CoreUnpoisonedMutex,
+    primitives::{HandleId, ThreadEnv},
+};
+
 use super::{Method, State, StrategyInput, StrategyResult};
 
 pub fn fair(entries: StrategyInput) -> StrategyResult {
@@ -30,3 +37,348 @@ pub fn fair(entries: StrategyInput) -> StrategyResult {
 
     Box::new(state.collection.into_iter())
 }
+
+/// A strategy that grants the lock strictly in arrival order.
+///
+/// A run of readers at the front of the queue is admitted together, and a writer is only admitted
+/// once every thread that arrived before it has released the lock. A thread is never admitted
+/// ahead of a thread that arrived before it.
+///
+/// This currently yields the same schedule as [`fair`], but is simpler to reason about, and its
+/// schedule is guaranteed to stay strictly first-in, first-out.
+///
+pub fn fifo(entries: StrategyInput) -> StrategyResult {
+    let mut only_readers_ahead = true;
+    let mut entries_ahead = 0_usize;
+
+    Box::new(entries.map(move |(_handle_id, method)| {
+        let state = match method {
+            Method::Read if only_readers_ahead => State::Ok,
+            Method::Write if entries_ahead == 0 => State::Ok,
+            _ => State::Blocked,
+        };
+
+        only_readers_ahead &= method.is_read();
+        entries_ahead += 1;
+        state
+    }))
+}
+
+/// A strategy that admits readers in batches of at most `max_readers_per_phase` at a time.
+///
+/// Like [`fair`], threads are served in arrival order, so writers are never starved. On top of
+/// that, a run of queued readers is admitted at most `max_readers_per_phase` at a time, so a long
+/// run of readers can't convoy past the writers queued behind it. The remaining readers are
+/// admitted as readers in the current batch release the lock.
+///
+/// # Panics
+/// Panics if `max_readers_per_phase` is zero, as no reader could ever be admitted.
+///
+pub fn batched(
+    max_readers_per_phase: usize,
+) -> impl Fn(StrategyInput) -> StrategyResult + Clone + Send + Sync + 'static {
+    assert!(
+        max_readers_per_phase > 0,
+        "`max_readers_per_phase` must be greater than zero."
+    );
+
+    move |entries| {
+        let mut entries_ahead = 0_usize;
+        let mut readers_ahead = 0_usize;
+
+        Box::new(entries.map(move |(_handle_id, method)| {
+            let state = match method {
+                Method::Read
+                    if entries_ahead == readers_ahead && readers_ahead < max_readers_per_phase =>
+                {
+                    State::Ok
+                }
+                Method::Write if entries_ahead == 0 => State::Ok,
+                _ => State::Blocked,
+            };
+
+            entries_ahead += 1;
+            readers_ahead += usize::from(method.is_read());
+            state
+        }))
+    }
+}
+
+/// A strategy that makes arbitrary, but safe, admission decisions, for stress testing.
+///
+/// Whenever it is consulted, this strategy keeps the threads it already admitted, and admits a
+/// random selection of the queued threads that don't conflict with them. It never admits a
+/// reader and a writer, or two writers, together, and always admits a thread while the lock is
+/// free, but otherwise gives no guarantees at all: threads may be admitted out of order, and may
+/// be starved for as long as others keep arriving.
+///
+/// The decisions are fully determined by `seed` and the order in which the strategy is consulted,
+/// so a failing seed can be replayed. The returned strategy remembers which threads it admitted,
+/// and so should only be given to a single lock.
+///
+pub fn random(seed: u64) -> impl Fn(StrategyInput) -> StrategyResult + Send + Sync + 'static {
+    struct RandomState {
+        rng: SplitMix64,
+        admitted: Vec<HandleId>,
+    }
+
+    let state = CoreUnpoisonedMutex::new(RandomState {
+        rng: SplitMix64(seed),
+        admitted: vec![],
+    });
+
+    move |entries| {
+        let entries = entries.copied().collect::<Vec<_>>();
+        let mut state = state.lock();
+        let RandomState { rng, admitted } = &mut *state;
+
+        let mut states = entries
+            .iter()
+            .map(|(handle_id, _method)| match admitted.contains(handle_id) {
+                true => State::Ok,
+                false => State::Blocked,
+            })
+            .collect::<Vec<_>>();
+        let admitted_method = entries
+            .iter()
+            .zip(&states)
+            .find(|(_entry, state)| state.is_ok())
+            .map(|((_handle_id, method), _state)| *method);
+
+        let leader = match admitted_method {
+            Some(Method::Write) => None,
+            Some(Method::Read) => entries.iter().position(|(_, method)| method.is_read()),
+            None if entries.is_empty() => None,
+            None => Some(rng.below(entries.len())),
+        };
+        if let Some(leader) = leader {
+            states[leader] = State::Ok;
+            if entries[leader].1.is_read() {
+                for ((_handle_id, method), state) in entries.iter().zip(&mut states) {
+                    if method.is_read() && rng.coin() {
+                        *state = State::Ok;
+                    }
+                }
+            }
+        }
+
+        *admitted = entries
+            .iter()
+            .zip(&states)
+            .filter(|(_entry, state)| state.is_ok())
+            .map(|((handle_id, _method), _state)| *handle_id)
+            .collect();
+        Box::new(states.into_iter())
+    }
+}
+
+/// A strategy that schedules threads like `inner`, but admits a writer at most once every
+/// `min_interval`, e.g. to rate-limit expensive rebuilds of the data behind the lock.
+///
+/// A writer that `inner` admits too soon after the previous one is kept blocked until
+/// `min_interval` has passed since the previous writer was admitted. Time is told with
+/// [`ThreadEnv::now`] of `Env`, and writers aren't throttled at all in environments without a
+/// clock.
+///
+/// The lock only consults its strategy when its queue changes, so a throttled writer may wait for
+/// much longer than `min_interval` if nothing else happens. Set a
+/// [recheck interval](super::BaseRwLock::set_recheck_interval) on the lock to bound that wait.
+///
+/// The returned strategy remembers which writer it admitted, and so should only be given to a
+/// single lock.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use std::time::Duration;
+/// # use powerlocks::{primitives::StdHandle, strategied_rwlock::{StdRwLock, strategies}};
+/// let strategy =
+///     strategies::throttle_writes::<StdHandle, _>(strategies::fair, Duration::from_millis(5));
+/// let lock = StdRwLock::new_strategied(0, Box::new(strategy));
+/// lock.set_recheck_interval(Some(Duration::from_millis(1)));
+///
+/// *lock.write().unwrap() += 1;
+/// assert!(lock.try_write().is_err());
+/// *lock.write().unwrap() += 1;
+/// assert_eq!(*lock.read().unwrap(), 2);
+/// # }
+/// ```
+///
+pub fn throttle_writes<Env, S>(
+    inner: S,
+    min_interval: Duration,
+) -> impl Fn(StrategyInput) -> StrategyResult + Send + Sync + 'static
+where
+    Env: ThreadEnv + 'static,
+    S: Fn(StrategyInput) -> StrategyResult + Send + Sync + 'static,
+{
+    // The writer admitted last, and when it was admitted.
+    let last_write = CoreUnpoisonedMutex::new(None::<(HandleId, Duration)>);
+
+    move |entries| {
+        let entries = entries.copied().collect::<Vec<_>>();
+        let states = inner(&mut entries.iter()).collect::<Vec<_>>();
+        let Some(now) = Env::now() else {
+            return Box::new(states.into_iter());
+        };
+
+        let mut last_write = last_write.lock();
+        let states = entries
+            .iter()
+            .zip(states)
+            .map(|(&(handle_id, method), state)| {
+                if method.is_read() || state.is_blocked() {
+                    return state;
+                }
+                match *last_write {
+                    Some((admitted, _)) if admitted == handle_id => State::Ok,
+                    Some((_, admitted_at)) if now.saturating_sub(admitted_at) < min_interval => {
+                        State::Blocked
+                    }
+                    _ => {
+                        *last_write = Some((handle_id, now));
+                        State::Ok
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        Box::new(states.into_iter())
+    }
+}
+
+/// A strategy that alternates between a read window of `read_window`, in which only readers are
+/// admitted, and a write window of `write_window`, in which writers are admitted one at a time, in
+/// arrival order. This bounds how long a writer waits, however fast readers keep arriving.
+///
+/// So that the lock never idles, the other kind of thread is admitted as well while no thread of
+/// the kind the window is for is queued. Threads that hold the lock when a window ends keep it
+/// until they release it, and threads of the new window wait for them.
+///
+/// Time is told with [`ThreadEnv::now`] of `Env`, and the windows start when the strategy is
+/// first consulted. In environments without a clock, this behaves like [`fair`]. As with
+/// [`throttle_writes`], set a [recheck interval](super::BaseRwLock::set_recheck_interval) on the
+/// lock, so that queued threads are admitted when their window opens.
+///
+/// The returned strategy remembers which threads it admitted, and so should only be given to a
+/// single lock.
+///
+/// # Panics
+/// Panics if either window is zero.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "std")] {
+/// # use std::time::Duration;
+/// # use powerlocks::{primitives::StdHandle, strategied_rwlock::{StdRwLock, strategies}};
+/// let strategy = strategies::time_sliced::<StdHandle>(
+///     Duration::from_millis(8),
+///     Duration::from_millis(2),
+/// );
+/// let lock = StdRwLock::new_strategied(0, Box::new(strategy));
+/// lock.set_recheck_interval(Some(Duration::from_millis(1)));
+///
+/// *lock.write().unwrap() += 1;
+/// assert_eq!(*lock.read().unwrap(), 1);
+/// # }
+/// ```
+///
+pub fn time_sliced<Env>(
+    read_window: Duration,
+    write_window: Duration,
+) -> impl Fn(StrategyInput) -> StrategyResult + Send + Sync + 'static
+where
+    Env: ThreadEnv + 'static,
+{
+    assert!(
+        !read_window.is_zero() && !write_window.is_zero(),
+        "Both windows must be longer than zero."
+    );
+
+    struct SlicedState {
+        started: Option<Duration>,
+        admitted: Vec<HandleId>,
+    }
+
+    let state = CoreUnpoisonedMutex::new(SlicedState {
+        started: None,
+        admitted: vec![],
+    });
+    let cycle = (read_window + write_window).as_nanos();
+
+    move |entries| {
+        let Some(now) = Env::now() else {
+            return fair(entries);
+        };
+        let entries = entries.copied().collect::<Vec<_>>();
+        let mut state = state.lock();
+        let SlicedState { started, admitted } = &mut *state;
+
+        let into_cycle = now.saturating_sub(*started.get_or_insert(now)).as_nanos() % cycle;
+        let window = match into_cycle < read_window.as_nanos() {
+            true => Method::Read,
+            false => Method::Write,
+        };
+        let queued = |method| entries.iter().any(|&(_handle_id, queued)| queued == method);
+        let admitting = match (queued(window), window) {
+            (true, _) => window,
+            (false, Method::Read) => Method::Write,
+            (false, Method::Write) => Method::Read,
+        };
+
+        let mut states = entries
+            .iter()
+            .map(|(handle_id, _method)| State::from(admitted.contains(handle_id)))
+            .collect::<Vec<_>>();
+        let holding = entries
+            .iter()
+            .zip(&states)
+            .find(|(_entry, state)| state.is_ok())
+            .map(|((_handle_id, method), _state)| *method);
+
+        match (admitting, holding) {
+            (Method::Read, None | Some(Method::Read)) => {
+                for ((_handle_id, method), state) in entries.iter().zip(&mut states) {
+                    if method.is_read() {
+                        *state = State::Ok;
+                    }
+                }
+            }
+            (Method::Write, None) => {
+                if let Some(writer) = entries.iter().position(|(_, method)| method.is_write()) {
+                    states[writer] = State::Ok;
+                }
+            }
+            _ => {}
+        }
+
+        *admitted = entries
+            .iter()
+            .zip(&states)
+            .filter(|(_entry, state)| state.is_ok())
+            .map(|((handle_id, _method), _state)| *handle_id)
+            .collect();
+        Box::new(states.into_iter())
+    }
+}
+
+/// A small, fast, seedable pseudo-random number generator, see
+/// <https://prng.di.unimi.it/splitmix64.c>.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    pub(crate) fn coin(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+}
@@ -0,0 +1,218 @@
+//! Checks a [`Strategy`] against scripted scenarios, without running any threads.
+//!
+//! A scenario is a list of [`Step`]s, which acquire and release a simulated lock. After every
+//! step, the strategy is consulted like a real [`RwLock`](super::RwLock) would consult it, and any
+//! logic error it makes is reported as a [`Violation`].
+//!
+//! # Examples
+//! ```
+//! # use powerlocks::strategied_rwlock::{Method, strategies, verify::{self, Step}};
+//! let script = [
+//!     Step::Acquire(Method::Read),
+//!     Step::Acquire(Method::Write),
+//!     Step::Acquire(Method::Read),
+//!     Step::Release(0),
+//!     Step::Release(1),
+//! ];
+//! assert_eq!(verify::run(&strategies::fair, &script, usize::MAX), []);
+//! ```
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use core::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::primitives::HandleId;
+
+use super::{
+    Method, State, Strategy,
+    impls::{QueuedEntry, enforce_preconditions},
+};
+
+/// A step of a scenario run by [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// A new thread starts acquiring the lock with the given [`Method`].
+    Acquire(Method),
+    /// The thread started by the `n`th [`Acquire`](Step::Acquire) of the script releases the lock,
+    /// or gives up on acquiring it if it is still blocked.
+    Release(usize),
+    /// Every thread that holds the lock releases it.
+    ReleaseAll,
+    /// Threads keep releasing the lock as soon as they are admitted, until no thread is left.
+    Drain,
+}
+
+/// A logic error made by a [`Strategy`], see [`Strategy`] for the rules it must follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+    /// The strategy returned a different number of states than there are queued threads.
+    WrongLength,
+    /// The strategy admitted a reader and a writer at the same time.
+    ConcurrentReadAndWrite,
+    /// The strategy admitted two or more writers at the same time.
+    ConcurrentMultipleWrites,
+    /// The strategy blocked a thread that it had already admitted.
+    BlockedAfterOk,
+    /// A thread stayed blocked for more steps than allowed.
+    Starvation,
+    /// The strategy admitted no thread while the lock was free, so no thread could ever make
+    /// progress again.
+    Stalled,
+}
+
+/// A logic error, along with the index of the [`Step`] after which it was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Violation {
+    pub step: usize,
+    pub kind: ViolationKind,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self.kind {
+            ViolationKind::WrongLength => "returned the wrong number of `State`s",
+            ViolationKind::ConcurrentReadAndWrite => {
+                "wanted to `State::Ok` a `Method::Write` and a `Method::Read` together"
+            }
+            ViolationKind::ConcurrentMultipleWrites => {
+                "wanted to `State::Ok` two or more `Method::Write`s"
+            }
+            ViolationKind::BlockedAfterOk => "wanted to re-block a `State::Ok`ed thread",
+            ViolationKind::Starvation => "kept a thread blocked for too long",
+            ViolationKind::Stalled => "admitted no thread while the lock was free",
+        };
+        write!(f, "the `Strategy` {message} after step {}", self.step)
+    }
+}
+
+impl Error for Violation {}
+
+struct Entry {
+    acquisition: usize,
+    id: HandleId,
+    method: Method,
+    state: State,
+    blocked_steps: usize,
+}
+
+impl QueuedEntry for Entry {
+    fn id(&self) -> HandleId {
+        self.id
+    }
+
+    fn method(&self) -> Method {
+        self.method
+    }
+
+    fn pinned(&self) -> bool {
+        false
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+}
+
+/// Runs `strategy` through `script`, and returns every [`Violation`] it made, in order.
+///
+/// A thread that stays blocked for more than `max_blocked_steps` consecutive steps is reported as
+/// starved, once. Pass `usize::MAX` to not check for starvation. Every round of a
+/// [`Step::Drain`] counts as a step of its own.
+///
+/// Like a real lock, the states the strategy returns are corrected after a violation, so the
+/// scenario can carry on: threads it re-blocks stay admitted, and threads that would conflict with
+/// threads admitted before them stay blocked.
+///
+/// # Panics
+/// Panics if a [`Step::Release`] refers to an acquisition that doesn't exist, or that was released
+/// already.
+pub fn run(strategy: &dyn Strategy, script: &[Step], max_blocked_steps: usize) -> Vec<Violation> {
+    let mut queue = Vec::<Entry>::new();
+    let mut violations = Vec::new();
+    let mut acquisitions = 0;
+
+    for (step, &action) in script.iter().enumerate() {
+        match action {
+            Step::Acquire(method) => {
+                queue.push(Entry {
+                    acquisition: acquisitions,
+                    id: HandleId::new(),
+                    method,
+                    state: State::Blocked,
+                    blocked_steps: 0,
+                });
+                acquisitions += 1;
+            }
+            Step::Release(acquisition) => {
+                let index = queue
+                    .iter()
+                    .position(|entry| entry.acquisition == acquisition)
+                    .unwrap_or_else(|| panic!("acquisition {acquisition} isn't queued"));
+                queue.remove(index);
+            }
+            Step::ReleaseAll => queue.retain(|entry| entry.state.is_blocked()),
+            Step::Drain => (),
+        }
+
+        let mut report = |kind| violations.push(Violation { step, kind });
+        consult(strategy, &mut queue, max_blocked_steps, &mut report);
+        if action == Step::Drain {
+            while queue.iter().any(|entry| entry.state.is_ok()) {
+                queue.retain(|entry| entry.state.is_blocked());
+                consult(strategy, &mut queue, max_blocked_steps, &mut report);
+            }
+        }
+    }
+
+    violations
+}
+
+/// Consults `strategy` on `queue`, updating the states of its entries.
+fn consult(
+    strategy: &dyn Strategy,
+    queue: &mut [Entry],
+    max_blocked_steps: usize,
+    report: &mut impl FnMut(ViolationKind),
+) {
+    let input = queue
+        .iter()
+        .map(|entry| (entry.id, entry.method))
+        .collect::<Vec<_>>();
+    let states = strategy(&mut input.iter()).collect::<Vec<_>>();
+    if states.len() != queue.len() {
+        report(ViolationKind::WrongLength);
+    }
+
+    // Checked like a real lock, which has no pinned entries here, and no thread acquiring that
+    // hasn't been told about its state yet.
+    let violations = enforce_preconditions(queue, None, &mut states.into_iter());
+    if violations.blocked_after_ok_state {
+        report(ViolationKind::BlockedAfterOk);
+    }
+    if violations.concurrent_read_and_write {
+        report(ViolationKind::ConcurrentReadAndWrite);
+    }
+    if violations.concurrent_multiple_writes {
+        report(ViolationKind::ConcurrentMultipleWrites);
+    }
+
+    for entry in queue.iter_mut() {
+        match entry.state {
+            State::Ok => entry.blocked_steps = 0,
+            State::Blocked => {
+                entry.blocked_steps += 1;
+                if entry.blocked_steps == max_blocked_steps.saturating_add(1) {
+                    report(ViolationKind::Starvation);
+                }
+            }
+        }
+    }
+
+    if !queue.is_empty() && queue.iter().all(|entry| entry.state.is_blocked()) {
+        report(ViolationKind::Stalled);
+    }
+}
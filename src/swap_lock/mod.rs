@@ -0,0 +1,189 @@
+//! A cell holding an [`Arc`] that is read far more often than replaced, like a configuration.
+//!
+//! Loading from a [`BaseSwapLock`] never waits: it clones the current `Arc`, which stays valid
+//! however long the reader keeps it, even once a new value was stored. Stores are serialized by a
+//! mutex of the crate, and wait until the loads that may still be cloning the old `Arc` are done,
+//! before releasing it.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "std")] {
+//! # use powerlocks::swap_lock::SwapLock;
+//! let config = SwapLock::new(vec!["verbose"]);
+//! let old = config.load();
+//!
+//! config.rcu(|flags| [flags.as_slice(), &["color"]].concat());
+//! assert_eq!(*old, ["verbose"]);
+//! assert_eq!(*config.load(), ["verbose", "color"]);
+//! # }
+//! ```
+
+use core::fmt::{self, Debug, Formatter};
+
+extern crate alloc;
+use alloc::sync::Arc;
+
+use crate::{
+    mutex::BaseMutex,
+    primitives::{
+        Backoff, CoreThreadEnv, NoPoison, ThreadEnv,
+        sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+    },
+};
+
+/// A cell holding an [`Arc<T>`](Arc), which is loaded without locking and replaced under a mutex.
+///
+/// Loads count themselves in one of two slots, picked by an epoch that every store bumps. Once a
+/// store has swapped the pointer, it bumps the epoch and waits for the slot of the old epoch to
+/// drain, as only the loads counted there may have read the old pointer without holding a
+/// reference yet. Loads that race with the bump retry in the new slot, so that a store never waits
+/// for more than the loads that were already running.
+///
+/// See the [module documentation](self) for details.
+pub struct BaseSwapLock<T, Env: ThreadEnv> {
+    ptr: AtomicPtr<T>,
+    epoch: AtomicUsize,
+    loads: [AtomicUsize; 2],
+    store_lock: BaseMutex<(), (), Env, NoPoison>,
+}
+
+impl<T, Env: ThreadEnv> BaseSwapLock<T, Env> {
+    pub fn new(value: T) -> Self {
+        Self::from(Arc::new(value))
+    }
+
+    /// Returns the current value.
+    pub fn load(&self) -> Arc<T> {
+        let slot = loop {
+            let epoch = self.epoch.load(Ordering::SeqCst);
+            let slot = &self.loads[epoch % 2];
+            slot.fetch_add(1, Ordering::SeqCst);
+            // A store that bumped the epoch before our increment may not have seen it. This reads
+            // through a read-modify-write, which sees the latest epoch, and orders our increment
+            // before any later bump.
+            if self.epoch.fetch_add(0, Ordering::SeqCst) == epoch {
+                break slot;
+            }
+            slot.fetch_sub(1, Ordering::Release);
+        };
+        let ptr = self.ptr.load(Ordering::SeqCst);
+        // SAFETY: `ptr` came from `Arc::into_raw`, and the store that swaps it out waits for our
+        // slot to drain before releasing its reference.
+        let value = unsafe {
+            Arc::increment_strong_count(ptr);
+            Arc::from_raw(ptr)
+        };
+        slot.fetch_sub(1, Ordering::Release);
+        value
+    }
+
+    /// Replaces the value with `value`.
+    pub fn store(&self, value: Arc<T>) {
+        drop(self.swap(value));
+    }
+
+    /// Replaces the value with `value`, returning the old one.
+    pub fn swap(&self, value: Arc<T>) -> Arc<T> {
+        let _guard = self.store_lock.lock();
+        self.swap_locked(value)
+    }
+
+    /// Replaces the value with `f` of the current one, returning the old one.
+    ///
+    /// `f` runs while other stores are blocked, so that none of them is lost, but loads go on.
+    pub fn rcu(&self, f: impl FnOnce(&T) -> T) -> Arc<T> {
+        let _guard = self.store_lock.lock();
+        // SAFETY: Holding `store_lock`, nobody else swaps the pointer out and releases it.
+        let new = f(unsafe { &*self.ptr.load(Ordering::Relaxed) });
+        self.swap_locked(Arc::new(new))
+    }
+
+    fn swap_locked(&self, value: Arc<T>) -> Arc<T> {
+        let old = self
+            .ptr
+            .swap(Arc::into_raw(value).cast_mut(), Ordering::SeqCst);
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst);
+        let mut backoff = Backoff::new();
+        // Like the epoch in `load`, the count is read through a read-modify-write, to see the
+        // increments of every load that validated the old epoch.
+        while self.loads[epoch % 2].fetch_add(0, Ordering::SeqCst) != 0 {
+            backoff.wait::<Env>();
+        }
+        // SAFETY: `old` came from `Arc::into_raw`, and every load that read it holds its own
+        // reference by now.
+        unsafe { Arc::from_raw(old) }
+    }
+
+    pub fn into_inner(self) -> Arc<T> {
+        let ptr = self.ptr.swap(core::ptr::null_mut(), Ordering::Relaxed);
+        // SAFETY: `ptr` came from `Arc::into_raw`, and `drop` skips the null pointer left behind.
+        unsafe { Arc::from_raw(ptr) }
+    }
+}
+
+impl<T, Env: ThreadEnv> From<Arc<T>> for BaseSwapLock<T, Env> {
+    fn from(value: Arc<T>) -> Self {
+        Self {
+            ptr: AtomicPtr::new(Arc::into_raw(value).cast_mut()),
+            epoch: AtomicUsize::new(0),
+            loads: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            store_lock: BaseMutex::new_unhooked(()),
+        }
+    }
+}
+
+impl<T: Default, Env: ThreadEnv> Default for BaseSwapLock<T, Env> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug, Env: ThreadEnv> Debug for BaseSwapLock<T, Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwapLock")
+            .field("data", &self.load())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, Env: ThreadEnv> Drop for BaseSwapLock<T, Env> {
+    fn drop(&mut self) {
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        if !ptr.is_null() {
+            // SAFETY: `ptr` came from `Arc::into_raw`, and nobody can load it anymore.
+            drop(unsafe { Arc::from_raw(ptr) });
+        }
+    }
+}
+
+// SAFETY: The cell owns an `Arc<T>`, which is `Send` and `Sync` under these bounds.
+unsafe impl<T: Send + Sync, Env: ThreadEnv> Send for BaseSwapLock<T, Env> {}
+// SAFETY: Loads clone the `Arc<T>` from any thread, and stores drop it on any thread.
+unsafe impl<T: Send + Sync, Env: ThreadEnv> Sync for BaseSwapLock<T, Env> {}
+
+pub type CoreSwapLock<T> = BaseSwapLock<T, CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseSwapLock;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdSwapLock<T> = BaseSwapLock<T, StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreSwapLock;
+    pub type SwapLock<T> = CoreSwapLock<T>;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdSwapLock;
+    pub type SwapLock<T> = StdSwapLock<T>;
+}
+
+pub use types::*;
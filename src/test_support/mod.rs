@@ -0,0 +1,35 @@
+//! The conformance suite that the crate runs against its own locks, for third parties to run
+//! against their own [`MutexApi`](crate::mutex::MutexApi) and
+//! [`RwLockApi`](crate::rwlock::RwLockApi) implementations.
+//!
+//! Each function panics if the lock fails the check. Most of them spawn threads, so the suite
+//! needs `std` on the target, even though it doesn't need the `std` feature.
+
+extern crate alloc;
+extern crate std;
+
+use alloc::boxed::Box;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind, set_hook, take_hook};
+
+pub mod race_checker;
+
+#[cfg(feature = "mutex")]
+pub mod mutex;
+
+#[cfg(feature = "rwlock")]
+pub mod rwlock;
+
+#[cfg(feature = "rwlock")]
+pub mod strategies;
+
+#[cfg(feature = "rwlock")]
+mod try_strategy;
+
+/// Runs `f` without printing the message of any panic it raises, then resumes the panic if there
+/// was one.
+pub fn suppress_panic_message<T>(f: impl FnOnce() -> T) -> T {
+    set_hook(Box::new(|_| {}));
+    let result = catch_unwind(AssertUnwindSafe(f));
+    let _ = take_hook();
+    result.unwrap_or_else(|panic| resume_unwind(panic))
+}
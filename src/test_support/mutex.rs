@@ -1,7 +1,11 @@
-use powerlocks::{
+extern crate alloc;
+extern crate std;
+
+use crate::{
     mutex::MutexApi,
     primitives::{PoisonError, TryLockError},
 };
+use alloc::string::{String, ToString};
 
 use std::{
     fmt::Debug,
@@ -11,7 +15,7 @@ use std::{
     thread,
 };
 
-use crate::utils::race_checker::{CheckerHandles, RaceChecker};
+use super::race_checker::{CheckerHandles, RaceChecker};
 
 pub trait Testable: Clone + PartialEq + Debug + Sync {}
 impl<T: Clone + PartialEq + Debug + Sync> Testable for T {}
@@ -86,6 +90,19 @@ pub fn lock_unsized<A: MutexApi<T> + ?Sized + Sync, T: ?Sized + Sync + PartialEq
     assert_eq!(lock.get_mut().unwrap(), expected);
 }
 
+pub fn with_lock<A: MutexApi<u64> + Sync>() {
+    let lock = A::new(0);
+
+    thread::scope(|scope| {
+        scope.spawn(|| lock.with_lock(|value| *value += 1).unwrap());
+        scope.spawn(|| lock.with_lock(|value| *value += 2).unwrap());
+    });
+
+    assert_eq!(lock.with_lock(|value| *value).unwrap(), 3);
+    // The mutex must be released once the closure returns.
+    assert_eq!(*lock.try_lock().unwrap(), 3);
+}
+
 pub fn race_lock<A: MutexApi<RaceChecker> + Sync>() {
     let lock = A::new(RaceChecker::new());
     let handles = CheckerHandles::new(4);
@@ -183,7 +200,9 @@ pub fn try_lock<A: MutexApi<T> + Sync, T: Testable>(value: &T) {
             drop(guard);
         });
 
-        while !lock_active.load(Ordering::Relaxed) {}
+        while !lock_active.load(Ordering::Relaxed) {
+            std::hint::spin_loop();
+        }
 
         match lock.try_lock() {
             Ok(_) => panic!("Expected `Err(TryLockError::WouldBlock)`, got `Ok`."),
@@ -193,6 +212,9 @@ pub fn try_lock<A: MutexApi<T> + Sync, T: Testable>(value: &T) {
                 )
             }
             Err(TryLockError::WouldBlock) => (),
+            Err(TryLockError::Cancelled | TryLockError::TimedOut) => {
+                panic!("Expected `Err(TryLockError::WouldBlock)`, got a cancelled acquisition.")
+            }
         };
 
         lock_active.store(false, Ordering::Relaxed);
@@ -228,7 +250,7 @@ pub fn do_load_test<A: MutexApi<u64> + Sync>(
                                 .spawn(|| {
                                     let guard =
                                         lock_ref.lock().unwrap_or_else(PoisonError::into_inner);
-                                    black_box(|value| (panic!("Poisoning: {}", value)))(*guard);
+                                    black_box(|value| panic!("Poisoning: {}", value))(*guard);
                                     drop(guard);
                                 })
                                 .join()
@@ -246,7 +268,7 @@ pub fn do_load_test<A: MutexApi<u64> + Sync>(
                         };
 
                         normal();
-                        poisoning_reps.map(|poisoning_reps| {
+                        if let Some(poisoning_reps) = poisoning_reps {
                             match (rep + thread) % (poisoning_reps) {
                                 0 => {
                                     poison();
@@ -256,7 +278,7 @@ pub fn do_load_test<A: MutexApi<u64> + Sync>(
                                 }
                                 _ => (),
                             }
-                        });
+                        }
                     }
                 };
 
@@ -1,3 +1,7 @@
+extern crate alloc;
+extern crate std;
+
+use alloc::vec::Vec;
 use std::{
     iter,
     ops::Deref,
@@ -13,7 +17,10 @@ use std::{
 pub const SUGGESTED_LOCK_WAIT: Duration = Duration::from_secs(10);
 pub const SUGGESTED_NO_LOCK_WAIT: Duration = Duration::from_millis(30);
 
-#[derive(Debug)]
+/// Marks a single thread as inside of a [`RaceChecker`]. A thread that
+/// [`acquire`](CheckerHandle::acquire)s the handle stays inside until another thread
+/// [`release`](CheckerHandle::release)s it, so the test can hold a lock for as long as it needs.
+#[derive(Debug, Default)]
 pub struct CheckerHandle {
     locked: AtomicBool,
 }
@@ -104,6 +111,9 @@ impl Deref for CheckerHandles {
     }
 }
 
+/// Data for a lock under test to protect, which detects whether it is ever read and written, or
+/// written twice, at the same time.
+#[derive(Debug, Default)]
 pub struct RaceChecker {
     checker: RwLock<()>,
 }
@@ -115,35 +125,37 @@ impl RaceChecker {
         }
     }
 
-    // Used by `rwlock` tests, but not by `mutex` tests.
-    #[allow(dead_code)]
-    pub fn try_read(&self, handle: &CheckerHandle) -> Result<(), ()> {
+    /// Reads through `handle` until it is released, or returns `false` right away if a write is
+    /// in progress.
+    pub fn try_read(&self, handle: &CheckerHandle) -> bool {
         let guard = match self.checker.try_read() {
-            Ok(guard) => Ok(guard),
-            Err(TryLockError::Poisoned(guard)) => Ok(guard.into_inner()),
-            Err(TryLockError::WouldBlock) => Err(()),
-        }?;
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(guard)) => guard.into_inner(),
+            Err(TryLockError::WouldBlock) => return false,
+        };
         handle.acquire();
-        Ok(drop(guard))
+        drop(guard);
+        true
     }
 
-    // Used by `rwlock` tests, but not by `mutex` tests.
-    #[allow(dead_code)]
     pub fn read(&self, handle: &CheckerHandle) {
-        self.try_read(handle).expect("read failed")
+        assert!(self.try_read(handle), "read failed");
     }
 
-    pub fn try_write(&self, handle: &CheckerHandle) -> Result<(), ()> {
+    /// Writes through `handle` until it is released, or returns `false` right away if a read or
+    /// write is in progress.
+    pub fn try_write(&self, handle: &CheckerHandle) -> bool {
         let guard = match self.checker.try_write() {
-            Ok(guard) => Ok(guard),
-            Err(TryLockError::Poisoned(guard)) => Ok(guard.into_inner()),
-            Err(TryLockError::WouldBlock) => Err(()),
-        }?;
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(guard)) => guard.into_inner(),
+            Err(TryLockError::WouldBlock) => return false,
+        };
         handle.acquire();
-        Ok(drop(guard))
+        drop(guard);
+        true
     }
 
     pub fn write(&self, handle: &CheckerHandle) {
-        self.try_write(handle).expect("write failed")
+        assert!(self.try_write(handle), "write failed");
     }
 }
@@ -1,5 +1,7 @@
-#![allow(dead_code, reason = "Some functions are not used by importing tests.")]
+extern crate alloc;
+extern crate std;
 
+use super::race_checker::{CheckerHandles, RaceChecker};
 use super::{
     strategies, suppress_panic_message,
     try_strategy::{
@@ -8,12 +10,17 @@ use super::{
         try_strategy,
     },
 };
-use crate::utils::race_checker::{CheckerHandles, RaceChecker};
-use powerlocks::{
-    primitives::TryLockError,
+use crate::{
+    primitives::{LockResultExt, TryLockError},
     rwlock::RwLockApi,
     strategied_rwlock::{Method, StrategiedRwLockApi},
 };
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+};
 use std::{fmt::Debug, hint::black_box, thread};
 
 pub fn run_single_thread<A: RwLockApi<T>, T: Debug + Default + PartialEq>() {
@@ -26,6 +33,20 @@ pub fn run_single_thread<A: RwLockApi<T>, T: Debug + Default + PartialEq>() {
     assert_eq!(*locked_unit.write().unwrap(), default_t);
 }
 
+pub fn with_read_and_write<A: RwLockApi<u64> + Sync>() {
+    let lock = A::new(0);
+
+    thread::scope(|scope| {
+        scope.spawn(|| lock.with_write(|value| *value += 1).unwrap());
+        scope.spawn(|| lock.with_write(|value| *value += 2).unwrap());
+        scope.spawn(|| black_box(lock.with_read(|value| *value).unwrap()));
+    });
+
+    assert_eq!(lock.with_read(|value| *value).unwrap(), 3);
+    // The lock must be released once the closure returns.
+    assert_eq!(*lock.try_write().unwrap(), 3);
+}
+
 pub fn race_reads<A: RwLockApi<RaceChecker> + Sync>(lock: &A) {
     let handles = CheckerHandles::new(4);
 
@@ -49,6 +70,31 @@ pub fn race_reads<A: RwLockApi<RaceChecker> + Sync>(lock: &A) {
     });
 }
 
+/// Expects a lock that admits at most two readers at a time.
+pub fn race_batched_reads<A: RwLockApi<RaceChecker> + Sync>(lock: &A) {
+    let handles = CheckerHandles::new(4);
+
+    thread::scope(|scope| {
+        handles.guard(|| {
+            scope.spawn(|| lock.read().unwrap().read(&handles[0]));
+            assert!(handles[0].will_be_locked());
+            scope.spawn(|| lock.read().unwrap().read(&handles[1]));
+            assert!(handles[1].will_be_locked());
+            scope.spawn(|| lock.read().unwrap().read(&handles[2]));
+            assert!(handles[2].will_not_be_locked());
+            scope.spawn(|| lock.write().unwrap().write(&handles[3]));
+            assert!(handles[3].will_not_be_locked());
+            handles[0].release();
+            assert!(handles[2].will_be_locked());
+            assert!(handles[3].will_not_be_locked());
+            handles[1].release();
+            handles[2].release();
+            assert!(handles[3].will_be_locked());
+            handles[3].release();
+        });
+    });
+}
+
 pub fn race_writes<A: RwLockApi<RaceChecker> + Sync>(lock: &A) {
     let handles = CheckerHandles::new(4);
 
@@ -156,8 +202,8 @@ pub fn poison_on_write<A: RwLockApi<()> + Sync>(lock: &A) {
             "Panicking during a `write` must poison the `lock`."
         );
 
-        assert_eq!(*lock.read().err().unwrap().into_inner(), ());
-        assert_eq!(*lock.write().err().unwrap().into_inner(), ());
+        assert_eq!(*lock.read().unwrap_poisoned(), ());
+        assert_eq!(*lock.write().unwrap_poisoned(), ());
         if let Err(TryLockError::Poisoned(poison)) = lock.try_read() {
             assert_eq!(*poison.into_inner(), ());
         } else {
@@ -181,6 +227,62 @@ pub fn poison_on_write<A: RwLockApi<()> + Sync>(lock: &A) {
     })
 }
 
+pub fn set_strategy<A: StrategiedRwLockApi<RaceChecker> + Sync>() {
+    let lock = A::new_strategied(RaceChecker::new(), Box::new(strategies::block_writes));
+    let handles = CheckerHandles::new(4);
+
+    thread::scope(|scope| {
+        handles.guard(|| {
+            scope.spawn(|| lock.read().unwrap().read(&handles[0]));
+            assert!(handles[0].will_be_locked());
+            scope.spawn(|| lock.write().unwrap().write(&handles[1]));
+            assert!(handles[1].will_not_be_locked());
+            scope.spawn(|| lock.read().unwrap().read(&handles[2]));
+            assert!(handles[2].will_be_locked());
+
+            // Readers admitted by the old strategy keep the lock, and the queued writer stays.
+            lock.set_strategy(Box::new(crate::strategied_rwlock::strategies::fair));
+            assert!(handles[1].will_not_be_locked());
+            scope.spawn(|| lock.read().unwrap().read(&handles[3]));
+            assert!(handles[3].will_not_be_locked());
+
+            handles[0].release();
+            assert!(handles[1].will_not_be_locked());
+            handles[2].release();
+            assert!(handles[1].will_be_locked());
+            assert!(handles[3].will_not_be_locked());
+            handles[1].release();
+            assert!(handles[3].will_be_locked());
+            handles[3].release();
+        });
+    });
+}
+
+pub fn queue_metrics<A: StrategiedRwLockApi<i32> + Sync>() {
+    let lock = A::new_strategied(0, Box::new(crate::strategied_rwlock::strategies::fair));
+    assert_eq!((lock.queue_len(), lock.active()), (0, (0, false)));
+
+    thread::scope(|scope| {
+        let read = lock.read().unwrap();
+        assert_eq!(lock.active(), (1, false));
+
+        scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() < 1 {
+            thread::yield_now();
+        }
+        scope.spawn(|| *lock.read().unwrap());
+        while lock.queued_readers() < 1 {
+            thread::yield_now();
+        }
+        assert_eq!((lock.queue_len(), lock.active()), (2, (1, false)));
+        drop(read);
+    });
+
+    let write = lock.write().unwrap();
+    assert_eq!((lock.queue_len(), lock.active()), (0, (0, true)));
+    drop(write);
+}
+
 pub fn broken_strategy_one_read<A: StrategiedRwLockApi<T> + Sync, T: Default + Sync>() {
     try_strategy::<String, _>(
         &A::new_strategied(T::default(), Box::new(strategies::broken_always_allow)),
@@ -457,7 +559,7 @@ pub fn load_test_with<A: RwLockApi<usize> + Sync>(
                         actions.append(&mut vec![TestActions::Write; writes / 2]);
                         actions.append(&mut vec![TestActions::Read; reads / 2]);
 
-                        rng.shuffle(&mut *actions);
+                        rng.shuffle(&mut actions);
 
                         for action in actions {
                             match action {
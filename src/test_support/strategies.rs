@@ -0,0 +1,24 @@
+extern crate alloc;
+extern crate std;
+
+use alloc::boxed::Box;
+use std::iter;
+
+use crate::strategied_rwlock::{Method, State, StrategyInput, StrategyResult};
+
+pub fn broken_always_allow(entries: StrategyInput) -> StrategyResult {
+    Box::new(entries.map(|_| State::Ok))
+}
+
+pub fn broken_block_on_second(entries: StrategyInput) -> StrategyResult {
+    let len = entries.count();
+    let state = if len >= 2 { State::Blocked } else { State::Ok };
+    Box::new(iter::repeat_n(state, len))
+}
+
+pub fn block_writes(entries: StrategyInput) -> StrategyResult {
+    Box::new(entries.map(|(_handle_id, method)| match method {
+        Method::Read => State::Ok,
+        Method::Write => State::Blocked,
+    }))
+}
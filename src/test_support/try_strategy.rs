@@ -1,3 +1,7 @@
+extern crate alloc;
+extern crate std;
+
+use alloc::format;
 use std::{
     any::Any,
     error::Error,
@@ -6,7 +10,7 @@ use std::{
     thread::{self, Builder, Scope},
 };
 
-use powerlocks::{rwlock::RwLockApi, strategied_rwlock::Method};
+use crate::{rwlock::RwLockApi, strategied_rwlock::Method};
 
 macro_rules! error_type {
         ($vis:vis $name:ident { $($option:ident($message:literal)),* $(,)? }) => {
@@ -130,10 +134,7 @@ where
 
     thread::scope(|scope| {
         attempts
-            .split(|attempt| match attempt {
-                TryStrategyAttempt::UnlockAll => true,
-                _ => false,
-            })
+            .split(|attempt| matches!(attempt, TryStrategyAttempt::UnlockAll))
             .for_each(|attempt_set| try_strategy_inner(lock, scope, 0, attempt_set));
     });
 }
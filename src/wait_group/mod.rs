@@ -0,0 +1,119 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::primitives::sync::{
+    atomic::{AtomicUsize, Ordering},
+    const_fn,
+};
+
+use crate::primitives::{CoreThreadEnv, ThreadEnv, WaitQueue};
+
+/// Waits for a dynamically sized group of tasks to finish, like Go's `sync.WaitGroup`.
+///
+/// Each task is registered with [`add`](BaseWaitGroup::add) and marks itself finished with
+/// [`done`](BaseWaitGroup::done). [`wait`](BaseWaitGroup::wait) blocks until every registered task
+/// is done. Unlike a barrier, the number of tasks doesn't need to be known up front.
+pub struct BaseWaitGroup<Env: ThreadEnv> {
+    count: AtomicUsize,
+    waiters: WaitQueue<Env::Handle>,
+    thread_env: PhantomData<Env>,
+}
+
+impl<Env: ThreadEnv> BaseWaitGroup<Env> {
+    const_fn! {
+        pub const fn new() -> Self {
+            Self {
+                count: AtomicUsize::new(0),
+                waiters: WaitQueue::new(),
+                thread_env: PhantomData,
+            }
+        }
+    }
+
+    /// Registers `tasks` more tasks with the group.
+    ///
+    /// # Panics
+    /// Panics if the number of pending tasks overflows a `usize`.
+    pub fn add(&self, tasks: usize) {
+        // Checked before updating, so that an overflow never wraps the count for other threads to
+        // see.
+        self.count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                count.checked_add(tasks)
+            })
+            .expect("too many tasks in `WaitGroup`");
+    }
+
+    /// Marks one registered task as done, waking up the waiting threads if it was the last one.
+    ///
+    /// # Panics
+    /// Panics if there are no pending tasks.
+    pub fn done(&self) {
+        let previous = self
+            .count
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |count| {
+                count.checked_sub(1)
+            })
+            .expect("`WaitGroup::done` called without a pending task");
+        if previous == 1 {
+            self.waiters.notify_all();
+        }
+    }
+
+    /// Returns the number of tasks that are still pending, which other threads may change at any
+    /// time.
+    pub fn pending(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Blocks the current thread until every registered task is done.
+    pub fn wait(&self) {
+        let finished = || self.count.load(Ordering::Acquire) == 0;
+        if !finished() {
+            self.waiters.wait_until(finished);
+        }
+    }
+}
+
+impl<Env: ThreadEnv> Default for BaseWaitGroup<Env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Env: ThreadEnv> Debug for BaseWaitGroup<Env> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitGroup")
+            .field("pending", &self.pending())
+            .finish_non_exhaustive()
+    }
+}
+
+pub type CoreWaitGroup = BaseWaitGroup<CoreThreadEnv>;
+
+#[cfg(feature = "std")]
+mod std_types {
+    use super::BaseWaitGroup;
+    use crate::primitives::StdThreadEnv;
+
+    pub type StdWaitGroup = BaseWaitGroup<StdThreadEnv>;
+}
+
+#[cfg(feature = "std")]
+pub use std_types::*;
+
+#[cfg(not(feature = "std"))]
+mod types {
+    use super::CoreWaitGroup;
+    pub type WaitGroup = CoreWaitGroup;
+}
+
+#[cfg(feature = "std")]
+mod types {
+    use super::StdWaitGroup;
+    pub type WaitGroup = StdWaitGroup;
+}
+
+pub use types::*;
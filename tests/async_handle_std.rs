@@ -0,0 +1,66 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use powerlocks::primitives::{AsyncHandle, Handle};
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn poll_park() {
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let handle = AsyncHandle::new();
+    assert_eq!(handle.poll_park(&mut cx), Poll::Pending);
+    assert_eq!(handle.poll_park(&mut cx), Poll::Pending);
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 0);
+
+    handle.unpark();
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    assert_eq!(handle.poll_park(&mut cx), Poll::Ready(()));
+    assert_eq!(handle.poll_park(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn unpark_before_poll() {
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let handle = AsyncHandle::new();
+    handle.unpark();
+    handle.unpark();
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 0);
+    assert_eq!(handle.poll_park(&mut cx), Poll::Ready(()));
+    assert_eq!(handle.poll_park(&mut cx), Poll::Pending);
+
+    // The blocking `park` consumes the unpark as well.
+    handle.unpark();
+    handle.park();
+    assert_eq!(handle.poll_park(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn ids() {
+    assert_ne!(AsyncHandle::new().id(), AsyncHandle::new().id());
+    assert_eq!(AsyncHandle::dumb().id(), AsyncHandle::dumb().id());
+}
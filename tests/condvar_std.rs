@@ -0,0 +1,125 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+use std::thread;
+
+use powerlocks::{
+    condvar::StdCondvar, mutex::StdMutex, rwlock::StdRwLock, strategied_mutex::strategies,
+};
+
+#[test]
+fn wait() {
+    let ready = StdMutex::new(false);
+    let condvar = StdCondvar::new();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            *ready.lock().unwrap() = true;
+            condvar.notify_one();
+        });
+
+        let mut guard = ready.lock().unwrap();
+        while !*guard {
+            guard = condvar.wait(guard).unwrap();
+        }
+    });
+}
+
+#[test]
+fn wait_write() {
+    let state = StdRwLock::new(0);
+    let condvar = StdCondvar::new();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut guard = state.write().unwrap();
+            while *guard != 1 {
+                guard = condvar.wait_write(guard).unwrap();
+            }
+            *guard = 2;
+            condvar.notify_all();
+        });
+
+        *state.write().unwrap() = 1;
+        condvar.notify_all();
+
+        let mut guard = state.write().unwrap();
+        while *guard != 2 {
+            guard = condvar.wait_write(guard).unwrap();
+        }
+    });
+}
+
+#[test]
+fn wait_read() {
+    const READERS: usize = 4;
+    let state = StdRwLock::new(false);
+    let condvar = StdCondvar::new();
+
+    thread::scope(|scope| {
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                let mut guard = state.read().unwrap();
+                while !*guard {
+                    guard = condvar.wait_read(guard).unwrap();
+                }
+            });
+        }
+
+        *state.write().unwrap() = true;
+        condvar.notify_all();
+    });
+}
+
+#[test]
+fn wake_strategy() {
+    // How many threads are waiting, and the priorities of the threads woken so far.
+    let state = StdMutex::new((0, Vec::new()));
+    let condvar = StdCondvar::new_strategied(Box::new(strategies::priority));
+
+    thread::scope(|scope| {
+        for priority in [1, 3, 2] {
+            let (state, condvar) = (&state, &condvar);
+            scope.spawn(move || {
+                let mut guard = state.lock().unwrap();
+                guard.0 += 1;
+                guard = condvar.wait_with_priority(guard, priority).unwrap();
+                guard.1.push(priority);
+            });
+        }
+
+        // Waiters are queued before they release the mutex.
+        while state.lock().unwrap().0 < 3 {
+            thread::yield_now();
+        }
+
+        for woken in 1..=3 {
+            condvar.notify_one();
+            while state.lock().unwrap().1.len() < woken {
+                thread::yield_now();
+            }
+        }
+    });
+
+    assert_eq!(state.lock().unwrap().1, [3, 2, 1]);
+}
+
+#[test]
+fn set_wake_strategy() {
+    let ready = StdMutex::new(false);
+    let condvar = StdCondvar::new();
+    condvar.set_wake_strategy(Box::new(strategies::fifo));
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let mut guard = ready.lock().unwrap();
+                while !*guard {
+                    guard = condvar.wait(guard).unwrap();
+                }
+            });
+        }
+
+        *ready.lock().unwrap() = true;
+        condvar.notify_all();
+    });
+}
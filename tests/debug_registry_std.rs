@@ -0,0 +1,179 @@
+#![cfg(all(feature = "registry", feature = "rwlock", feature = "std"))]
+
+use std::{sync::Barrier, thread, time::Duration};
+
+use powerlocks::{
+    debug::{self, LockInfo, LockState},
+    mutex::{StdMcsMutex, StdMutex, StdTicketMutex},
+    rwlock::StdRwLock,
+    strategied_rwlock::StdRwLock as StdStrategiedRwLock,
+};
+
+// The registry is global, so only report the locks registered by the test at hand.
+fn find(label: &str) -> LockInfo {
+    debug::dump()
+        .into_iter()
+        .find(|info| info.label == Some(label))
+        .unwrap()
+}
+
+#[test]
+fn register() {
+    static MUTEX: StdMutex<()> = StdMutex::new_unhooked(());
+
+    assert!(!debug::unregister(&MUTEX));
+    debug::register(&MUTEX, Some("register"));
+    assert_eq!(
+        find("register"),
+        LockInfo {
+            label: Some("register"),
+            kind: "Mutex",
+            state: LockState::Unlocked,
+            poisoned: false,
+            waiters: 0,
+        }
+    );
+    assert_eq!(
+        find("register").to_string(),
+        "Mutex \"register\": unlocked, 0 waiting"
+    );
+
+    assert!(debug::unregister(&MUTEX));
+    assert!(
+        debug::dump()
+            .iter()
+            .all(|info| info.label != Some("register"))
+    );
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn mutex_state() {
+    static MUTEX: StdMutex<()> = StdMutex::new_unhooked(());
+    debug::register(&MUTEX, Some("mutex_state"));
+
+    let barrier = Barrier::new(2);
+    thread::scope(|scope| {
+        let guard = MUTEX.lock().unwrap();
+        scope.spawn(|| {
+            barrier.wait();
+            drop(MUTEX.lock());
+        });
+        barrier.wait();
+        while find("mutex_state").waiters == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(find("mutex_state").state, LockState::Written);
+        drop(guard);
+    });
+
+    let _ = thread::spawn(|| {
+        let _guard = MUTEX.lock();
+        panic!();
+    })
+    .join();
+    let info = find("mutex_state");
+    assert_eq!(
+        (info.state, info.poisoned, info.waiters),
+        (LockState::Unlocked, true, 0)
+    );
+    debug::unregister(&MUTEX);
+}
+
+#[test]
+fn ticket_mutex_state() {
+    let mutex: &'static StdTicketMutex<()> = Box::leak(Box::new(StdTicketMutex::new(())));
+    debug::register(mutex, Some("ticket_mutex_state"));
+
+    let guard = mutex.lock().unwrap();
+    assert_eq!(find("ticket_mutex_state").state, LockState::Written);
+    drop(guard);
+    assert_eq!(find("ticket_mutex_state").state, LockState::Unlocked);
+    debug::unregister(mutex);
+}
+
+#[test]
+fn rwlock_state() {
+    static LOCK: StdRwLock<()> = StdRwLock::new_unhooked(());
+    let strategied: &'static StdStrategiedRwLock<()> =
+        Box::leak(Box::new(StdStrategiedRwLock::new(())));
+    debug::register(&LOCK, Some("rwlock_state"));
+    debug::register(strategied, Some("strategied_rwlock_state"));
+
+    for label in ["rwlock_state", "strategied_rwlock_state"] {
+        assert_eq!(find(label).state, LockState::Unlocked);
+    }
+
+    let guards = (LOCK.read().unwrap(), LOCK.read().unwrap());
+    let strategied_guards = (strategied.read().unwrap(), strategied.read().unwrap());
+    for label in ["rwlock_state", "strategied_rwlock_state"] {
+        assert_eq!(find(label).state, LockState::Read(2));
+    }
+    drop((guards, strategied_guards));
+
+    let guards = (LOCK.write().unwrap(), strategied.write().unwrap());
+    for label in ["rwlock_state", "strategied_rwlock_state"] {
+        assert_eq!(find(label).state, LockState::Written);
+    }
+    drop(guards);
+
+    debug::unregister(&LOCK);
+    debug::unregister(strategied);
+}
+
+#[test]
+#[cfg(feature = "labels")]
+fn named() {
+    let mutex: &'static StdMutex<()> = Box::leak(Box::new(StdMutex::new_named("named", ())));
+    debug::register(mutex, None);
+    assert_eq!(find("named").kind, "Mutex");
+
+    let rwlock: &'static StdRwLock<()> = Box::leak(Box::new(StdRwLock::new_named("named_rw", ())));
+    debug::register(rwlock, Some("overridden"));
+    assert_eq!(find("overridden").kind, "RwLock");
+    assert!(debug::unregister(rwlock));
+    assert!(debug::unregister(mutex));
+}
+
+#[test]
+fn mcs_mutex_state() {
+    let mutex: &'static StdMcsMutex<()> = Box::leak(Box::new(StdMcsMutex::new(())));
+    debug::register(mutex, Some("mcs_mutex_state"));
+
+    let guard = mutex.lock().unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| drop(mutex.lock()));
+        while find("mcs_mutex_state").waiters == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(find("mcs_mutex_state").state, LockState::Written);
+        drop(guard);
+    });
+    let info = find("mcs_mutex_state");
+    assert_eq!((info.state, info.waiters), (LockState::Unlocked, 0));
+    debug::unregister(mutex);
+}
+
+#[test]
+// Blocked locks are told apart by their labels.
+#[cfg(feature = "labels")]
+fn registers_while_blocked() {
+    // Neither lock is `'static`, so they can only be reported while threads are blocked on them.
+    let mutex = StdMutex::new_named("blocked_mutex", ());
+    let rwlock = StdRwLock::new_named("blocked_rwlock", ());
+    let blocked = |label| debug::dump().iter().any(|info| info.label == Some(label));
+
+    let guards = (mutex.lock().unwrap(), rwlock.write().unwrap());
+    assert!(!blocked("blocked_mutex") && !blocked("blocked_rwlock"));
+    thread::scope(|scope| {
+        scope.spawn(|| drop(mutex.lock()));
+        scope.spawn(|| drop(rwlock.read()));
+        while !(blocked("blocked_mutex") && blocked("blocked_rwlock")) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(find("blocked_mutex").state, LockState::Written);
+        assert_eq!(find("blocked_rwlock").state, LockState::Written);
+        drop(guards);
+    });
+    assert!(!blocked("blocked_mutex") && !blocked("blocked_rwlock"));
+}
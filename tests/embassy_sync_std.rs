@@ -0,0 +1,59 @@
+#![cfg(all(feature = "embassy-sync", feature = "std"))]
+
+use std::{cell::Cell, sync::Arc, thread};
+
+use embassy_sync::{blocking_mutex::Mutex, channel::Channel};
+use powerlocks::mutex::{StdMutex, StdUnpoisonedMutex};
+
+#[test]
+fn blocking_mutex() {
+    let counter = Arc::new(Mutex::<StdUnpoisonedMutex<()>, _>::new(Cell::new(0)));
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    counter.lock(|counter| counter.set(counter.get() + 1));
+                }
+            })
+        })
+        .collect();
+    threads.into_iter().for_each(|t| t.join().unwrap());
+
+    assert_eq!(counter.lock(Cell::get), 4000);
+}
+
+#[test]
+fn poisoned_mutex_still_locks() {
+    let mutex = Mutex::<StdMutex<()>, _>::new(Cell::new(0));
+    thread::scope(|s| {
+        s.spawn(|| mutex.lock(|_| panic!())).join().unwrap_err();
+    });
+
+    mutex.lock(|value| value.set(1));
+    assert_eq!(mutex.lock(Cell::get), 1);
+}
+
+#[test]
+fn channel() {
+    static CHANNEL: Channel<StdUnpoisonedMutex<()>, u32, 4> = Channel::new();
+
+    let sender = thread::spawn(|| {
+        for i in 0..100 {
+            while CHANNEL.try_send(i).is_err() {
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut received = Vec::new();
+    while received.len() < 100 {
+        match CHANNEL.try_receive() {
+            Ok(value) => received.push(value),
+            Err(_) => thread::yield_now(),
+        }
+    }
+    sender.join().unwrap();
+
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+}
@@ -0,0 +1,56 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+use std::{thread, time::Duration};
+
+use powerlocks::event::StdEvent;
+
+#[test]
+fn set_and_reset() {
+    let event = StdEvent::new();
+    assert!(!event.is_set());
+
+    event.set();
+    assert!(event.is_set());
+    event.wait();
+
+    event.reset();
+    assert!(!event.is_set());
+}
+
+#[test]
+fn wait() {
+    const THREADS: usize = 4;
+    let event = StdEvent::new();
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| event.wait());
+        }
+        thread::sleep(Duration::from_millis(20));
+        event.set();
+    });
+}
+
+#[test]
+fn wait_timeout() {
+    let event = StdEvent::new();
+    assert!(!event.wait_timeout(Duration::from_millis(20)));
+
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| event.wait_timeout(Duration::from_secs(60)));
+        event.set();
+        assert!(waiter.join().unwrap());
+    });
+
+    assert!(event.wait_timeout(Duration::ZERO));
+
+    // A waiter that times out must leave the other waiters queued.
+    event.reset();
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| event.wait());
+        let timed_out = scope.spawn(|| event.wait_timeout(Duration::from_millis(20)));
+        assert!(!timed_out.join().unwrap());
+        event.set();
+        waiter.join().unwrap();
+    });
+}
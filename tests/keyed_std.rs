@@ -0,0 +1,73 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use powerlocks::keyed::{KeyedMutex, KeyedRwLock};
+
+#[test]
+fn lock_serializes_key() {
+    let keyed = KeyedMutex::new();
+    let holders = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let _guard = keyed.lock(7);
+                    assert_eq!(holders.fetch_add(1, Ordering::Relaxed), 0);
+                    holders.fetch_sub(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    assert!(keyed.is_empty());
+}
+
+#[test]
+fn keys_lock_independently() {
+    let keyed = KeyedMutex::new();
+    let guard = keyed.lock("alice");
+    assert_eq!(*guard.key(), "alice");
+    assert!(keyed.try_lock("alice").is_none());
+
+    thread::scope(|s| {
+        s.spawn(|| drop(keyed.lock("bob")));
+    });
+    assert_eq!(keyed.len(), 1);
+
+    drop(guard);
+    assert!(keyed.try_lock("alice").is_some());
+}
+
+#[test]
+fn unused_keys_are_dropped() {
+    let keyed = KeyedMutex::new();
+    let first = keyed.lock(1);
+    let second = keyed.lock(2);
+    assert!(keyed.try_lock(2).is_none());
+    assert_eq!(keyed.len(), 2);
+
+    drop(first);
+    assert_eq!(keyed.len(), 1);
+    drop(second);
+    assert!(keyed.is_empty());
+}
+
+#[test]
+fn rwlock() {
+    let keyed = KeyedRwLock::new();
+    let first = keyed.read(1);
+    let second = keyed.read(1);
+    assert!(keyed.try_write(1).is_none());
+    assert!(keyed.try_write(2).is_some());
+
+    drop((first, second));
+    let writer = keyed.write(1);
+    assert!(keyed.try_read(1).is_none());
+    assert_eq!(keyed.len(), 1);
+
+    drop(writer);
+    assert!(keyed.is_empty());
+}
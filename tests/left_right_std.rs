@@ -0,0 +1,90 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+mod utils;
+
+use std::thread;
+
+use powerlocks::rwlock::{Absorb, StdLeftRight};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Counters {
+    writes: u64,
+    sum: u64,
+}
+
+struct Add(u64);
+
+impl Absorb<Add> for Counters {
+    fn absorb(&mut self, op: &Add) {
+        self.writes += 1;
+        self.sum += op.0;
+    }
+}
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(StdLeftRight<Counters, Add>, Send, Sync);
+    assert_is_trait!(StdLeftRight<std::cell::Cell<u64>, Add>, !Sync);
+}
+
+#[test]
+fn publish() {
+    let lock = StdLeftRight::new(Counters::default());
+    let mut writer = lock.write();
+    writer.append(Add(2)).append(Add(3));
+    assert_eq!(writer.sum, 5);
+    assert_eq!(*lock.read(), Counters::default());
+    assert!(lock.try_write().is_none());
+
+    writer.publish();
+    assert_eq!(lock.read().sum, 5);
+    writer.append(Add(1));
+    drop(writer);
+
+    assert_eq!(*lock.read(), Counters { writes: 3, sum: 6 });
+    let mut writer = lock.try_write().unwrap();
+    writer.append(Add(4));
+    assert_eq!(writer.sum, 10);
+    drop(writer);
+    assert_eq!(lock.into_inner(), Counters { writes: 4, sum: 10 });
+}
+
+#[test]
+fn readers_see_whole_writes() {
+    let lock = StdLeftRight::new(Counters::default());
+    thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..1000 {
+                lock.write().append(Add(2)).append(Add(2));
+            }
+        });
+        for _ in 0..4 {
+            s.spawn(|| {
+                let mut last = 0;
+                while last < 2000 {
+                    let counters = lock.read();
+                    assert_eq!(counters.sum, counters.writes * 2);
+                    assert_eq!(counters.writes % 2, 0);
+                    assert!(counters.writes >= last);
+                    last = counters.writes;
+                }
+            });
+        }
+    });
+    assert_eq!(lock.with_read(|counters| counters.sum), 4000);
+}
+
+#[test]
+fn debug() {
+    let lock = StdLeftRight::<_, Add>::new(Counters::default());
+    assert_eq!(
+        format!("{lock:?}"),
+        "LeftRight { data: Counters { writes: 0, sum: 0 }, .. }"
+    );
+    assert_eq!(
+        format!("{:?}", lock.read()),
+        "Counters { writes: 0, sum: 0 }"
+    );
+}
@@ -0,0 +1,84 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+mod utils;
+
+use std::panic::{self, AssertUnwindSafe};
+
+use powerlocks::{
+    local_lock::LocalLock, mutex::MutexApi, primitives::TryLockError, rwlock::RwLockApi,
+};
+
+use powerlocks::test_support::{self, rwlock as tests};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(LocalLock<i32>, Send, Unpin);
+    assert_is_trait!(LocalLock<i32>, !Sync);
+    assert_is_trait!(LocalLock<*const ()>, !Send, !Sync);
+}
+
+#[test]
+fn run_single_thread() {
+    tests::run_single_thread::<LocalLock<_>, ()>();
+    tests::run_single_thread::<LocalLock<_>, i32>();
+    tests::run_single_thread::<LocalLock<_>, Vec<i32>>();
+}
+
+#[test]
+fn mutex_api() {
+    fn increment<A: MutexApi<u64>>(lock: &A) {
+        *lock.lock().unwrap() += 1;
+    }
+
+    let mut lock = LocalLock::new(0);
+    increment(&lock);
+    assert_eq!(MutexApi::with_lock(&lock, |value| *value).unwrap(), 1);
+
+    let guard = lock.lock();
+    assert!(matches!(
+        MutexApi::try_lock(&lock),
+        Err(TryLockError::WouldBlock)
+    ));
+    drop(guard);
+
+    *MutexApi::get_mut(&mut lock).unwrap() += 1;
+    assert!(!MutexApi::is_poisoned(&lock));
+    assert_eq!(MutexApi::into_inner(lock).unwrap(), 2);
+}
+
+#[test]
+fn rwlock_api() {
+    let lock = LocalLock::new(0);
+
+    let first = lock.read();
+    let second = RwLockApi::try_read(&lock).unwrap();
+    assert_eq!(*first + *second, 0);
+    assert!(matches!(
+        RwLockApi::try_write(&lock),
+        Err(TryLockError::WouldBlock)
+    ));
+    drop((first, second));
+
+    *RwLockApi::write(&lock).unwrap() += 1;
+    assert_eq!(lock.with_read(|value| *value).unwrap(), 1);
+}
+
+#[test]
+fn conflicting_borrow_panics() {
+    let lock = LocalLock::new(0);
+    let _guard = lock.read();
+    test_support::suppress_panic_message(|| {
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(lock.lock()))).is_err());
+    });
+    assert_eq!(*lock.read(), 0);
+}
+
+#[test]
+fn debug() {
+    let lock = LocalLock::new(1);
+    assert_eq!(format!("{lock:?}"), "LocalLock { data: 1, .. }");
+    let _guard = lock.lock();
+    assert_eq!(format!("{lock:?}"), "LocalLock { data: <locked>, .. }");
+}
@@ -0,0 +1,57 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+use powerlocks::{
+    mutex::{MutexApi, StdMutex},
+    rwlock::{MutexAsRwLock, RwLockApi, RwLockAsMutex, StdRwLock},
+    test_support::{self, mutex as mutex_tests, race_checker::RaceChecker, rwlock as rwlock_tests},
+};
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn mutex_as_rwlock() {
+    rwlock_tests::run_single_thread::<MutexAsRwLock<StdMutex<_>>, i32>();
+    rwlock_tests::with_read_and_write::<MutexAsRwLock<StdMutex<_>>>();
+    rwlock_tests::race_writes(&MutexAsRwLock::from_mutex(
+        StdMutex::new(RaceChecker::new()),
+    ));
+    rwlock_tests::poison_on_write(&MutexAsRwLock::from_mutex(StdMutex::new(())));
+}
+
+#[test]
+fn mutex_as_rwlock_exclusive_reads() {
+    let lock = MutexAsRwLock::from_mutex(StdMutex::new(1));
+    let read = lock.read().unwrap();
+    assert!(lock.try_read().is_err());
+    assert!(lock.mutex().try_lock().is_err());
+    drop(read);
+
+    *lock.write().unwrap() += 1;
+    assert_eq!(lock.into_mutex().into_inner().unwrap(), 2);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn rwlock_as_mutex() {
+    mutex_tests::lock::<RwLockAsMutex<StdRwLock<_>>, _>(&0_u64);
+    mutex_tests::lock_writing::<RwLockAsMutex<StdRwLock<_>>, _>(&0_u64, 0xac7e4d30_951f268b);
+    mutex_tests::with_lock::<RwLockAsMutex<StdRwLock<_>>>();
+    mutex_tests::race_lock::<RwLockAsMutex<StdRwLock<_>>>();
+    mutex_tests::poison::<RwLockAsMutex<StdRwLock<_>>, _>(&0_u64, true);
+    mutex_tests::try_lock::<RwLockAsMutex<StdRwLock<_>>, _>(&0_u64);
+    test_support::suppress_panic_message(|| {
+        mutex_tests::do_load_test::<RwLockAsMutex<StdRwLock<_>>>(4, 64, 4, Some(4))
+    });
+}
+
+#[test]
+fn rwlock_as_mutex_shares_reads() {
+    let lock = RwLockAsMutex::from_rwlock(StdRwLock::new(1));
+    *lock.lock().unwrap() += 1;
+
+    let read = lock.rwlock().read().unwrap();
+    assert!(lock.try_lock().is_err());
+    assert_eq!(*lock.rwlock().try_read().unwrap(), 2);
+    drop(read);
+
+    assert_eq!(MutexApi::into_inner(lock).unwrap(), 2);
+}
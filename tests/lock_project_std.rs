@@ -0,0 +1,44 @@
+#![cfg(all(feature = "powerlocks-derive", feature = "rwlock", feature = "std"))]
+
+use powerlocks::{LockProject, mutex::Mutex, primitives::MappedGuard, rwlock::RwLock};
+
+#[derive(Debug, Default, PartialEq, LockProject)]
+struct Config {
+    name: String,
+    retries: u32,
+}
+
+#[derive(Debug, Default, PartialEq, LockProject)]
+struct Pair<T>(T, T);
+
+#[test]
+fn project_named() {
+    let config = Mutex::new(Config::default());
+
+    config.lock().unwrap().project_name().push_str("server");
+    *config.lock().unwrap().project_retries() += 3;
+
+    assert_eq!(
+        *config.lock().unwrap(),
+        Config {
+            name: "server".into(),
+            retries: 3
+        }
+    );
+}
+
+#[test]
+fn project_unnamed() {
+    let pair = RwLock::new(Pair(1, 2));
+
+    let mut first = pair.write().unwrap().project_0();
+    *first += 10;
+    assert!(pair.try_read().is_err());
+    drop(first);
+
+    let second = MappedGuard::map(pair.write().unwrap().project_1(), |second| second);
+    assert_eq!(*second, 2);
+    drop(second);
+
+    assert_eq!(*pair.read().unwrap(), Pair(11, 2));
+}
@@ -0,0 +1,69 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+use std::{collections::HashMap, hash::RandomState, thread};
+
+use powerlocks::lock_table::{LockTable, StdLockTable};
+
+#[test]
+fn read_write() {
+    let table: StdLockTable<u32, HashMap<u32, u32>, 8> = StdLockTable::new();
+    for key in 0..100 {
+        table.write(&key).unwrap().insert(key, key * 2);
+    }
+
+    for key in 0..100 {
+        assert_eq!(table.read(&key).unwrap().get(&key), Some(&(key * 2)));
+    }
+    let entries: usize = table
+        .shards()
+        .iter()
+        .map(|shard| shard.read().unwrap().len())
+        .sum();
+    assert_eq!(entries, 100);
+}
+
+#[test]
+fn spreads_keys() {
+    let table: LockTable<u64, (), 8> = LockTable::new();
+    let mut used = [false; 8];
+    for key in 0..64 {
+        let index = table.shard_index(&key);
+        assert_eq!(index, table.shard_index(&key));
+        used[index] = true;
+    }
+    assert!(used.iter().all(|&used| used));
+}
+
+#[test]
+fn shards_lock_independently() {
+    let table: StdLockTable<str, u32, 4> = StdLockTable::from_fn(|index| index as u32);
+    let key = "a";
+    let other = (0..)
+        .map(|i| i.to_string())
+        .find(|other| table.shard_index(other) != table.shard_index(key))
+        .unwrap();
+
+    let guard = table.write(key).unwrap();
+    assert!(table.try_read(key).is_err());
+    assert!(table.try_write(&other).is_ok());
+    drop(guard);
+
+    assert_eq!(table.into_inner().iter().sum::<u32>(), (0..4).sum::<u32>());
+}
+
+#[test]
+fn with_hasher() {
+    let table: StdLockTable<str, u32, 16, RandomState> =
+        StdLockTable::with_hasher(RandomState::new());
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    *table.write("counter").unwrap() += 1;
+                }
+            });
+        }
+    });
+    assert_eq!(*table.read("counter").unwrap(), 400);
+}
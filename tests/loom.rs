@@ -0,0 +1,177 @@
+//! Model checks the locks with loom. Run with:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg loom" cargo test --release --features rwlock --test loom
+//! ```
+#![cfg(all(loom, feature = "rwlock"))]
+
+use loom::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use powerlocks::{
+    mutex::BaseMutex,
+    primitives::{LoomHandle, LoomThreadEnv, ThreadEnv, parker},
+    rwlock::{Absorb, BaseLeftRight, BaseRwLock},
+    seq_lock::BaseSeqLock,
+    strategied_rwlock::BaseRwLock as StrategiedRwLock,
+    swap_lock::BaseSwapLock,
+};
+
+type LoomMutex<T> = BaseMutex<T, (), LoomThreadEnv>;
+type LoomRwLock<T> = BaseRwLock<T, (), LoomThreadEnv>;
+type LoomSeqLock<T> = BaseSeqLock<T, LoomThreadEnv>;
+type LoomLeftRight<T, O> = BaseLeftRight<T, O, LoomThreadEnv>;
+type LoomSwapLock<T> = BaseSwapLock<T, LoomThreadEnv>;
+type LoomStrategiedRwLock<T> = StrategiedRwLock<T, LoomHandle>;
+
+#[test]
+fn mutex() {
+    loom::model(|| {
+        let mutex = Arc::new(LoomMutex::new(0));
+        let other = {
+            let mutex = mutex.clone();
+            thread::spawn(move || *mutex.lock().unwrap() += 1)
+        };
+        *mutex.lock().unwrap() += 1;
+        other.join().unwrap();
+        assert_eq!(*mutex.lock().unwrap(), 2);
+    });
+}
+
+#[test]
+fn fair_mutex() {
+    loom::model(|| {
+        let mutex = Arc::new(LoomMutex::builder().fair().build(0));
+        let other = {
+            let mutex = mutex.clone();
+            thread::spawn(move || *mutex.lock().unwrap() += 1)
+        };
+        *mutex.lock().unwrap() += 1;
+        other.join().unwrap();
+        assert_eq!(*mutex.lock().unwrap(), 2);
+    });
+}
+
+#[test]
+fn rwlock() {
+    loom::model(|| {
+        let lock = Arc::new(LoomRwLock::new(0));
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.write().unwrap() += 1)
+        };
+        let read = *lock.read().unwrap();
+        assert!(read == 0 || read == 1);
+        writer.join().unwrap();
+        assert_eq!(*lock.read().unwrap(), 1);
+    });
+}
+
+#[test]
+fn strategied_rwlock() {
+    // The queue is guarded by a mutex of its own, which multiplies the interleavings, so the
+    // preemptions are bounded to keep the model tractable.
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(2);
+    model.check(|| {
+        let lock = Arc::new(LoomStrategiedRwLock::new(0));
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.write().unwrap() += 1)
+        };
+        *lock.write().unwrap() += 1;
+        writer.join().unwrap();
+        assert_eq!(*lock.read().unwrap(), 2);
+    });
+}
+
+#[test]
+fn current_id() {
+    loom::model(|| {
+        let id = LoomThreadEnv::current_id().unwrap();
+        assert_eq!(LoomHandle::current_id(), Some(id));
+        let other = thread::spawn(LoomThreadEnv::current_id).join().unwrap();
+        assert_ne!(other, Some(id));
+    });
+}
+
+#[test]
+fn park_unpark() {
+    loom::model(|| {
+        let (parker, unparker) = parker::<LoomHandle>();
+        let ready = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let ready = ready.clone();
+            thread::spawn(move || {
+                ready.store(true, Ordering::Relaxed);
+                unparker.unpark();
+            })
+        };
+        parker.park();
+        assert!(ready.load(Ordering::Relaxed));
+        thread.join().unwrap();
+    });
+}
+
+#[test]
+fn seq_lock() {
+    loom::model(|| {
+        let lock = Arc::new(LoomSeqLock::new((0, 0)));
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.set((1, 1)))
+        };
+        if let Some((a, b)) = lock.try_read() {
+            assert_eq!(a, b);
+        }
+        writer.join().unwrap();
+        assert_eq!(lock.read(), (1, 1));
+    });
+}
+
+#[test]
+fn left_right() {
+    struct Increment;
+
+    impl Absorb<Increment> for (u32, u32) {
+        fn absorb(&mut self, _: &Increment) {
+            self.0 += 1;
+            self.1 += 1;
+        }
+    }
+
+    loom::model(|| {
+        let lock = Arc::new(LoomLeftRight::new((0, 0)));
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                lock.write().append(Increment);
+                lock.write().append(Increment);
+            })
+        };
+        let (a, b) = *lock.read();
+        assert_eq!(a, b);
+        writer.join().unwrap();
+        assert_eq!(*lock.read(), (2, 2));
+    });
+}
+
+#[test]
+fn swap_lock() {
+    loom::model(|| {
+        let lock = Arc::new(LoomSwapLock::new(0));
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.store(std::sync::Arc::new(1)))
+        };
+        let value = lock.load();
+        assert!(*value == 0 || *value == 1);
+        writer.join().unwrap();
+        assert_eq!(*lock.load(), 1);
+    });
+}
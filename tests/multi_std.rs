@@ -0,0 +1,126 @@
+#![cfg(all(feature = "rwlock", feature = "std", feature = "multi"))]
+
+use std::{thread, time::Duration};
+
+use powerlocks::{
+    multi::{self, Read},
+    mutex::{StdMutex, StdTicketMutex},
+    rwlock::StdRwLock,
+    strategied_rwlock,
+};
+
+#[test]
+fn lock_all() {
+    let mutex = StdMutex::new(1);
+    let ticket = StdTicketMutex::new(2);
+    let rwlock = StdRwLock::new(3);
+    let strategied = strategied_rwlock::StdRwLock::new(4);
+
+    {
+        let (mut a, b, c, mut d) =
+            multi::lock_all((&mutex, &ticket, Read(&rwlock), &strategied)).unwrap();
+        *a += *b + *c;
+        *d += 1;
+
+        // Read locks are shared, write locks are exclusive.
+        assert!(rwlock.try_read().is_ok());
+        assert!(strategied.try_read().is_err());
+    }
+
+    assert_eq!(*mutex.lock().unwrap(), 6);
+    assert_eq!(*strategied.read().unwrap(), 5);
+    assert!(mutex.try_lock().is_ok() && ticket.try_lock().is_ok() && rwlock.try_write().is_ok());
+}
+
+#[test]
+fn opposite_orders() {
+    const REPS: usize = if cfg!(miri) { 16 } else { 4096 };
+    let first = StdMutex::new(0);
+    let second = StdMutex::new(0);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..REPS {
+                let (mut a, mut b) = multi::lock_all((&first, &second)).unwrap();
+                *a += 1;
+                *b += 1;
+            }
+        });
+        scope.spawn(|| {
+            for _ in 0..REPS {
+                let (mut b, mut a) = multi::lock_all((&second, &first)).unwrap();
+                *a += 1;
+                *b += 1;
+            }
+        });
+        // Threads that don't use `lock_all` must not deadlock with it either.
+        scope.spawn(|| {
+            for _ in 0..REPS {
+                let mut b = second.lock().unwrap();
+                let mut a = first.lock().unwrap();
+                *a += 1;
+                *b += 1;
+            }
+        });
+    });
+
+    assert_eq!(*first.lock().unwrap(), 3 * REPS);
+    assert_eq!(*second.lock().unwrap(), 3 * REPS);
+}
+
+#[test]
+fn waits_on_unavailable_lock() {
+    let first = StdMutex::new(0);
+    let second = StdMutex::new(0);
+
+    let held = second.lock().unwrap();
+    thread::scope(|scope| {
+        let locker = scope.spawn(|| {
+            let (mut a, mut b) = multi::lock_all((&first, &second)).unwrap();
+            *a += 1;
+            *b += 1;
+        });
+
+        // Once `second` turned out to be held, `lock_all` waits on it alone, without holding on
+        // to `first`.
+        thread::sleep(Duration::from_millis(100));
+        for _ in 0..16 {
+            drop(first.try_lock().unwrap());
+            thread::sleep(Duration::from_millis(1));
+        }
+        drop(held);
+        locker.join().unwrap();
+    });
+
+    assert_eq!(*first.lock().unwrap(), 1);
+    assert_eq!(*second.lock().unwrap(), 1);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn poison() {
+    let healthy = StdMutex::new(0);
+    let poisoned = StdMutex::new(0);
+    thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let _guard = poisoned.lock().unwrap();
+                panic!("Poisoning the mutex");
+            })
+            .join()
+            .expect_err("Spawned thread must panic");
+    });
+
+    let (mut a, mut b) = multi::lock_all((&healthy, &poisoned))
+        .unwrap_err()
+        .into_inner();
+    *a += 1;
+    *b += 1;
+}
+
+#[test]
+#[should_panic(expected = "same lock more than once")]
+fn same_lock_twice() {
+    let mutex = StdMutex::new(0);
+    let _ = multi::lock_all((&mutex, &mutex));
+}
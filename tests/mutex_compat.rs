@@ -1,6 +1,9 @@
 #![cfg(all(feature = "mutex", feature = "std"))]
+#![allow(
+    clippy::if_same_then_else,
+    reason = "Keeps `miri` parameters side by side."
+)]
 
-mod mutex_utils;
 mod utils;
 
 use std::{
@@ -9,7 +12,7 @@ use std::{
     sync::{Mutex, MutexGuard},
 };
 
-use mutex_utils::tests;
+use powerlocks::test_support::{self, mutex as tests};
 
 #[test]
 fn assert_trait() {
@@ -64,6 +67,11 @@ fn lock() {
     tests::lock_unsized(unsized_lock, &array_i32);
 }
 
+#[test]
+fn with_lock() {
+    tests::with_lock::<Mutex<_>>();
+}
+
 #[test]
 fn race_lock() {
     tests::race_lock::<Mutex<_>>();
@@ -81,6 +89,26 @@ fn try_lock() {
     tests::try_lock::<Mutex<_>, _>(&0_u64);
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn pointers() {
+    use powerlocks::mutex::MutexApi;
+    use std::sync::Arc;
+
+    tests::lock::<Box<Mutex<_>>, _>(&0_u64);
+    tests::lock::<Arc<Mutex<_>>, _>(&0_u64);
+    tests::poison::<Arc<Mutex<_>>, _>(&(), true);
+    tests::try_lock::<Box<Mutex<_>>, _>(&0_u64);
+    tests::with_lock::<Arc<Mutex<_>>>();
+
+    assert_eq!(MutexApi::into_inner(Box::new(Mutex::new(2))).unwrap(), 2);
+
+    let shared = Arc::new(Mutex::new(3));
+    let mut other = Arc::clone(&shared);
+    assert!(std::panic::catch_unwind(move || drop(MutexApi::get_mut(&mut other))).is_err());
+    assert_eq!(MutexApi::into_inner(shared).unwrap(), 3);
+}
+
 #[test]
 fn load_test() {
     const THREADS: usize = if cfg!(miri) { 8 } else { 8 };
@@ -96,7 +124,7 @@ fn poisoning_load_test() {
     const REPS: usize = if cfg!(miri) { 16 } else { 16384 };
     const CYCLES: usize = if cfg!(miri) { 8 } else { 64 };
     const POISONING_REPS: usize = if cfg!(miri) { 4 } else { 64 };
-    mutex_utils::suppress_panic_message(|| {
+    test_support::suppress_panic_message(|| {
         tests::do_load_test::<Mutex<_>>(THREADS, REPS, CYCLES, Some(POISONING_REPS))
     });
 }
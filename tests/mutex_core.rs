@@ -1,16 +1,23 @@
 #![cfg(feature = "mutex")]
+#![allow(
+    clippy::if_same_then_else,
+    reason = "Keeps `miri` parameters side by side."
+)]
 
-mod mutex_utils;
 mod utils;
 
 use std::{
     cell::UnsafeCell,
     panic::{RefUnwindSafe, UnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
-use powerlocks::mutex::{CoreMutex, CoreMutexGuard};
+use powerlocks::{
+    mutex::{BaseMutex, CoreMutex, CoreMutexGuard, MutexHook},
+    primitives::{CoreHandle, CoreThreadEnv, Handle, HandleId, HookContext, Method, ShouldBlock},
+};
 
-use mutex_utils::tests;
+use powerlocks::test_support::{self, mutex as tests};
 
 #[test]
 fn assert_trait() {
@@ -52,6 +59,14 @@ fn assert_trait() {
     assert_is_trait!(CoreMutexGuard<'_, *const ()>, !Send, !Sync);
 }
 
+#[test]
+#[cfg(not(any(feature = "labels", feature = "multi")))]
+fn size() {
+    // Parked threads are queued out of line, so a mutex that only spins doesn't pay for parking
+    // beyond a flag.
+    assert!(size_of::<CoreMutex<()>>() <= 3);
+}
+
 #[test]
 fn lock() {
     tests::lock::<CoreMutex<_>, _>(&());
@@ -71,6 +86,11 @@ fn lock() {
     tests::lock_unsized(unsized_lock, &array_i32);
 }
 
+#[test]
+fn with_lock() {
+    tests::with_lock::<CoreMutex<_>>();
+}
+
 #[test]
 fn race_lock() {
     tests::race_lock::<CoreMutex<_>>();
@@ -88,6 +108,187 @@ fn try_lock() {
     tests::try_lock::<CoreMutex<_>, _>(&0_u64);
 }
 
+#[test]
+fn leak() {
+    let lock: &'static CoreMutex<u64> = Box::leak(Box::new(CoreMutex::new(0)));
+    let leaked: &'static mut u64 = lock.lock().unwrap().leak();
+    *leaked = 0xac7e4d30;
+
+    assert!(lock.try_lock().is_err());
+    assert_eq!(*leaked, 0xac7e4d30);
+}
+
+#[test]
+fn force_unlock() {
+    let lock = CoreMutex::new(0_u64);
+    std::mem::forget(lock.lock().unwrap());
+    assert!(lock.try_lock().is_err());
+
+    // SAFETY: The guard was forgotten and is never used again.
+    unsafe { lock.force_unlock() };
+    assert_eq!(*lock.try_lock().unwrap(), 0);
+    assert!(!lock.is_poisoned());
+}
+
+#[test]
+fn static_hooked() {
+    // Counted outside of the hook, which can't be reached through the lock.
+    static LOCKS: AtomicUsize = AtomicUsize::new(0);
+    static UNLOCKS: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountingHook;
+    impl MutexHook for CountingHook {
+        fn before_lock(&self, _context: &HookContext) {
+            LOCKS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn after_lock(&self, _context: &HookContext) {
+            UNLOCKS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    static LOCK: BaseMutex<u64, CountingHook, CoreThreadEnv> =
+        BaseMutex::with_hook(0, CountingHook);
+
+    *LOCK.lock().unwrap() += 1;
+    *LOCK.lock().unwrap() += 1;
+    assert_eq!(*LOCK.lock().unwrap(), 2);
+    assert_eq!(LOCKS.load(Ordering::Relaxed), 3);
+    assert_eq!(UNLOCKS.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn with_hook() {
+    struct SharedCountingHook(&'static AtomicUsize);
+    impl MutexHook for SharedCountingHook {
+        fn after_lock(&self, _context: &HookContext) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            unreachable!("the hook is passed to `with_hook`")
+        }
+    }
+
+    static UNLOCKS: AtomicUsize = AtomicUsize::new(0);
+    let lock = BaseMutex::<_, _, CoreThreadEnv>::with_hook(0, SharedCountingHook(&UNLOCKS));
+    *lock.lock().unwrap() += 1;
+    assert_eq!(*lock.try_lock().unwrap(), 1);
+    assert_eq!(UNLOCKS.load(Ordering::Relaxed), 2);
+
+    // Formatting the mutex looks at the data without running the hooks.
+    assert!(format!("{lock:?}").contains("data: 1"));
+    assert_eq!(UNLOCKS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn hook_context() {
+    struct CheckingHook;
+    impl MutexHook for CheckingHook {
+        fn try_lock(&self, context: &HookContext) -> ShouldBlock {
+            assert_eq!(context.method(), Method::Write);
+            assert_eq!(context.handle_id(), None);
+            ShouldBlock::Ok
+        }
+
+        fn after_lock(&self, context: &HookContext) {
+            assert_eq!(context.method(), Method::Write);
+            assert!(!context.contended());
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let lock = BaseMutex::<u64, CheckingHook, CoreThreadEnv>::new(0);
+    *lock.lock().unwrap() += 1;
+    assert_eq!(*lock.try_lock().unwrap(), 1);
+}
+
+#[test]
+fn hook_lifecycle() {
+    static EVENTS: std::sync::Mutex<Vec<&str>> = std::sync::Mutex::new(Vec::new());
+
+    struct RecordingHook;
+    impl MutexHook for RecordingHook {
+        fn before_lock(&self, _context: &HookContext) {
+            EVENTS.lock().unwrap().push("before_lock");
+        }
+
+        fn on_contended(&self, context: &HookContext) {
+            assert!(context.contended());
+            EVENTS.lock().unwrap().push("on_contended");
+        }
+
+        fn before_unlock(&self, _context: &HookContext) {
+            EVENTS.lock().unwrap().push("before_unlock");
+        }
+
+        fn after_lock(&self, _context: &HookContext) {
+            EVENTS.lock().unwrap().push("after_lock");
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let lock = BaseMutex::<u64, RecordingHook, CoreThreadEnv>::new(0);
+    {
+        let _guard = lock.lock().unwrap();
+        assert!(lock.try_lock().is_err());
+    }
+    std::mem::forget(lock.try_lock().unwrap());
+    // SAFETY: The guard was forgotten and is never used again.
+    unsafe { lock.force_unlock() };
+
+    assert_eq!(
+        *EVENTS.lock().unwrap(),
+        [
+            "before_lock",
+            "before_lock",
+            "on_contended",
+            "before_unlock",
+            "after_lock",
+            "before_lock",
+            "before_unlock",
+            "after_lock",
+        ]
+    );
+}
+
+#[test]
+fn handle_ids() {
+    let ids = [
+        HandleId::try_new().unwrap(),
+        CoreHandle::try_new().unwrap().id(),
+        CoreHandle::new().id(),
+    ];
+    assert_ne!(ids[0], ids[1]);
+    assert_ne!(ids[1], ids[2]);
+    assert_ne!(ids[0], ids[2]);
+    assert_eq!(CoreHandle::dumb().id(), CoreHandle::dumb().id());
+}
+
+#[test]
+fn data_ptr() {
+    let lock = CoreMutex::new(0_u64);
+    {
+        let mut guard = lock.lock().unwrap();
+        *guard = 1;
+        assert_eq!(lock.data_ptr(), &raw mut *guard);
+    }
+
+    // SAFETY: No guards or other references to the data exist.
+    unsafe { *lock.data_ptr() += 1 };
+    assert_eq!(*lock.lock().unwrap(), 2);
+}
+
 #[test]
 fn load_test() {
     const THREADS: usize = if cfg!(miri) { 8 } else { 8 };
@@ -103,7 +304,7 @@ fn poisoning_load_test() {
     const REPS: usize = if cfg!(miri) { 16 } else { 16384 };
     const CYCLES: usize = if cfg!(miri) { 8 } else { 64 };
     const POISONING_REPS: usize = if cfg!(miri) { 4 } else { 64 };
-    mutex_utils::suppress_panic_message(|| {
+    test_support::suppress_panic_message(|| {
         tests::do_load_test::<CoreMutex<_>>(THREADS, REPS, CYCLES, Some(POISONING_REPS))
     });
 }
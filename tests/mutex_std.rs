@@ -1,16 +1,24 @@
 #![cfg(all(feature = "mutex", feature = "std"))]
+#![allow(
+    clippy::if_same_then_else,
+    reason = "Keeps `miri` parameters side by side."
+)]
 
-mod mutex_utils;
 mod utils;
 
 use std::{
     cell::UnsafeCell,
     panic::{RefUnwindSafe, UnwindSafe},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
 };
 
-use powerlocks::mutex::{StdMutex, StdMutexGuard};
+use powerlocks::{
+    mutex::{StdMutex, StdMutexGuard, StdUnpoisonedMutex},
+    primitives::{NoPoison, TryLockError},
+};
 
-use mutex_utils::tests;
+use powerlocks::test_support::{self, mutex as tests};
 
 #[test]
 fn assert_trait() {
@@ -64,6 +72,11 @@ fn lock() {
     tests::lock_unsized(unsized_lock, &array_i32);
 }
 
+#[test]
+fn with_lock() {
+    tests::with_lock::<StdMutex<_>>();
+}
+
 #[test]
 fn race_lock() {
     tests::race_lock::<StdMutex<_>>();
@@ -71,8 +84,9 @@ fn race_lock() {
 
 #[test]
 fn poison() {
-    tests::poison::<StdMutex<_>, _>(&(), true);
-    tests::poison::<StdMutex<_>, _>(&0_u64, true);
+    let poisons = !cfg!(feature = "no-poison");
+    tests::poison::<StdMutex<_>, _>(&(), poisons);
+    tests::poison::<StdMutex<_>, _>(&0_u64, poisons);
 }
 
 #[test]
@@ -81,6 +95,88 @@ fn try_lock() {
     tests::try_lock::<StdMutex<_>, _>(&0_u64);
 }
 
+#[cfg(feature = "no-poison")]
+#[test]
+fn no_poison() {
+    assert_eq!(size_of::<powerlocks::primitives::Poison>(), 0);
+    assert_eq!(
+        size_of::<StdMutex<()>>(),
+        size_of::<StdUnpoisonedMutex<()>>()
+    );
+
+    let lock = StdMutex::new(0_u64);
+    test_support::suppress_panic_message(|| {
+        thread::scope(|scope| {
+            let holder = scope.spawn(|| {
+                let _guard = lock.lock();
+                panic!();
+            });
+            holder.join().unwrap_err();
+        });
+    });
+    assert!(!lock.is_poisoned());
+    assert!(lock.lock().is_ok());
+}
+
+#[cfg(not(feature = "no-poison"))]
+#[test]
+fn unpoisoned() {
+    // Labels and lock ids are word-aligned, which rounds both variants up to the same size.
+    #[cfg(not(any(feature = "labels", feature = "multi")))]
+    assert!(size_of::<StdUnpoisonedMutex<u8>>() < size_of::<StdMutex<u8>>());
+
+    tests::lock::<StdUnpoisonedMutex<_>, _>(&0_u64);
+    tests::lock_writing::<StdUnpoisonedMutex<_>, _>(&0_u64, 0xac7e4d30_951f268b);
+    tests::try_lock::<StdUnpoisonedMutex<_>, _>(&0_u64);
+    tests::race_lock::<StdUnpoisonedMutex<_>>();
+    test_support::suppress_panic_message(|| {
+        tests::poison::<StdUnpoisonedMutex<_>, _>(&0_u64, false);
+    });
+
+    let lock = StdUnpoisonedMutex::new(0_u64);
+    *lock.lock() += 1;
+    assert_eq!(lock.into_inner(), 1);
+}
+
+#[test]
+fn long_contention() {
+    const THREADS: usize = 8;
+    let lock = StdMutex::new(0_usize);
+
+    std::thread::scope(|scope| {
+        let guard = lock.lock().unwrap();
+        for _ in 0..THREADS {
+            scope.spawn(|| *lock.lock().unwrap() += 1);
+        }
+        // Hold the lock long enough for the waiters to give up spinning and park.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        drop(guard);
+    });
+
+    assert_eq!(lock.into_inner().unwrap(), THREADS);
+}
+
+#[test]
+fn lock_until() {
+    let lock = StdMutex::new(0);
+    let cancel = AtomicBool::new(false);
+
+    let guard = lock.lock().unwrap();
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| lock.lock_until(&cancel).map(|_| ()));
+        cancel.store(true, Ordering::Release);
+        assert!(matches!(
+            waiter.join().unwrap(),
+            Err(TryLockError::Cancelled)
+        ));
+    });
+    drop(guard);
+
+    cancel.store(false, Ordering::Release);
+    *lock.lock_until(&cancel).unwrap() += 1;
+    assert_eq!(*lock.lock().unwrap(), 1);
+}
+
 #[test]
 fn load_test() {
     const THREADS: usize = if cfg!(miri) { 8 } else { 8 };
@@ -96,7 +192,7 @@ fn poisoning_load_test() {
     const REPS: usize = if cfg!(miri) { 16 } else { 16384 };
     const CYCLES: usize = if cfg!(miri) { 8 } else { 64 };
     const POISONING_REPS: usize = if cfg!(miri) { 4 } else { 64 };
-    mutex_utils::suppress_panic_message(|| {
+    test_support::suppress_panic_message(|| {
         tests::do_load_test::<StdMutex<_>>(THREADS, REPS, CYCLES, Some(POISONING_REPS))
     });
 }
@@ -110,3 +206,462 @@ fn extended_load_test() {
 
     tests::do_load_test::<StdMutex<_>>(THREADS, REPS, CYCLES, None);
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn new_arc() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 16 };
+
+    let mutex: powerlocks::mutex::ArcMutex<usize> = StdMutex::new_arc(0);
+    let threads = (0..THREADS)
+        .map(|_| {
+            let mutex = mutex.clone();
+            thread::spawn(move || *mutex.lock().unwrap() += 1)
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(*mutex.lock().unwrap(), THREADS);
+}
+
+#[test]
+fn pin_mut() {
+    use std::{marker::PhantomPinned, pin::Pin};
+
+    struct SelfRef {
+        value: i32,
+        ptr: *const i32,
+        _pinned: PhantomPinned,
+    }
+
+    impl SelfRef {
+        fn init(self: Pin<&mut Self>) {
+            // SAFETY: `ptr` is not structurally pinned, and nothing is moved.
+            let this = unsafe { self.get_unchecked_mut() };
+            this.ptr = &this.value;
+        }
+    }
+
+    let mut mutex = Box::pin(StdMutex::new(SelfRef {
+        value: 7,
+        ptr: std::ptr::null(),
+        _pinned: PhantomPinned,
+    }));
+    // SAFETY: The data is never moved out of `mutex`.
+    unsafe { mutex.as_mut().get_pin_mut() }.unwrap().init();
+
+    let mut guard = mutex.lock().unwrap();
+    // SAFETY: As above.
+    let data = unsafe { guard.as_pin_mut() };
+    // SAFETY: `ptr` points to `value`, which is still in place.
+    assert_eq!(unsafe { *data.ptr }, 7);
+}
+
+#[test]
+fn mapped_guard() {
+    use powerlocks::primitives::MappedGuard;
+
+    let mutex = StdMutex::new((1, [2, 3]));
+    let array = MappedGuard::new(mutex.lock().unwrap(), |data| &mut data.1);
+    let mut last = MappedGuard::map(array, |array| &mut array[1]);
+    *last += 1;
+    assert!(mutex.try_lock().is_err());
+    drop(last);
+
+    assert_eq!(*mutex.lock().unwrap(), (1, [2, 4]));
+}
+
+#[test]
+fn guard_mutex() {
+    let mutex = StdMutex::new(0);
+    let guard = mutex.lock().unwrap();
+    assert!(std::ptr::eq(guard.mutex(), &mutex));
+    assert!(!guard.mutex().is_poisoned());
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn unlocked() {
+    let mutex = StdMutex::new(0);
+    thread::scope(|scope| {
+        let mut guard = mutex.lock().unwrap();
+        let other = scope.spawn(|| *mutex.lock().unwrap() += 1);
+        guard.unlocked(|| other.join().unwrap()).unwrap();
+        assert_eq!(*guard, 1);
+        *guard += 1;
+        guard.bump().unwrap();
+        assert_eq!(*guard, 2);
+    });
+
+    let result = std::panic::catch_unwind(|| {
+        let mut guard = mutex.lock().unwrap();
+        guard.unlocked(|| panic!("unlocked")).unwrap();
+    });
+    assert!(result.is_err());
+    assert!(mutex.is_poisoned());
+    assert!(matches!(mutex.try_lock(), Err(TryLockError::Poisoned(_))));
+}
+
+#[test]
+fn guard_no_send() {
+    use powerlocks::{
+        mutex::{BaseMutex, BaseMutexGuard},
+        primitives::{GuardNoSend, Poison, StdThreadEnv},
+    };
+    use utils::assert_is_trait;
+
+    type NoSendMutex<T> = BaseMutex<T, (), StdThreadEnv, Poison, GuardNoSend>;
+    type NoSendMutexGuard<'a, T> = BaseMutexGuard<'a, T, (), StdThreadEnv, Poison, GuardNoSend>;
+
+    assert_is_trait!(
+        NoSendMutex<i32>,
+        Send,
+        Sync,
+        UnwindSafe,
+        RefUnwindSafe,
+        Unpin
+    );
+    assert_is_trait!(NoSendMutexGuard<'_, i32>, !Send);
+    assert_is_trait!(NoSendMutexGuard<'_, i32>, Sync);
+
+    let mutex = NoSendMutex::new(1);
+    *mutex.lock().unwrap() += 1;
+    assert_eq!(mutex.into_inner().unwrap(), 2);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn on_poison_hook() {
+    use powerlocks::{
+        mutex::{BaseMutex, MutexHook},
+        primitives::{HookContext, Method, Poison, PoisonError, StdThreadEnv},
+    };
+    use std::sync::atomic::AtomicUsize;
+
+    static POISONS: AtomicUsize = AtomicUsize::new(0);
+
+    struct PoisonHook;
+    impl MutexHook for PoisonHook {
+        fn on_poison(&self, context: &HookContext) {
+            assert_eq!(context.method(), Method::Write);
+            POISONS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let lock = BaseMutex::<i32, PoisonHook, StdThreadEnv, Poison>::new(0);
+    drop(lock.lock().unwrap());
+    assert_eq!(POISONS.load(Ordering::Relaxed), 0);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.lock().unwrap();
+        panic!("poisoning");
+    });
+    assert!(lock.is_poisoned());
+    assert_eq!(POISONS.load(Ordering::Relaxed), 1);
+
+    // Only the panic that poisons the mutex is reported.
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        panic!("poisoning again");
+    });
+    assert_eq!(POISONS.load(Ordering::Relaxed), 1);
+
+    let lock = BaseMutex::<i32, PoisonHook, StdThreadEnv, NoPoison>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.lock();
+        panic!("not poisoning");
+    });
+    assert_eq!(POISONS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn poison_policies() {
+    use powerlocks::{
+        mutex::BaseMutex,
+        primitives::{ClearOnNextLock, Ignore, StdThreadEnv},
+    };
+
+    let lock = BaseMutex::<i32, (), StdThreadEnv, Ignore>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let mut guard = lock.lock();
+        *guard += 1;
+        panic!("poisoning");
+    });
+    assert!(lock.is_poisoned());
+    assert_eq!(*lock.lock(), 1);
+    assert!(lock.is_poisoned());
+
+    let lock = BaseMutex::<i32, (), StdThreadEnv, ClearOnNextLock>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let mut guard = lock.lock().unwrap();
+        *guard += 1;
+        panic!("poisoning");
+    });
+    assert!(lock.is_poisoned());
+    assert_eq!(*lock.lock().unwrap_err().into_inner(), 1);
+    assert!(!lock.is_poisoned());
+    assert_eq!(*lock.lock().unwrap(), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_boxed() {
+    use std::sync::Arc;
+
+    let values = (0..5).map(Arc::new).collect::<Vec<_>>();
+    let mutex = StdMutex::from_boxed(values.iter().cloned().collect());
+    assert_eq!(Arc::strong_count(&values[0]), 2);
+    mutex.lock().unwrap()[1] = Arc::new(10);
+    assert_eq!(Arc::strong_count(&values[1]), 1);
+    let guard = mutex.lock().unwrap();
+    assert_eq!(
+        guard.iter().map(|value| **value).collect::<Vec<_>>(),
+        [0, 10, 2, 3, 4]
+    );
+    drop(guard);
+    drop(mutex);
+    assert_eq!(Arc::strong_count(&values[0]), 1);
+
+    let empty = StdMutex::<[u8]>::from_boxed(Box::new([]));
+    assert!(empty.lock().unwrap().is_empty());
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn get_cloned() {
+    let mutex = StdMutex::new(vec![1, 2]);
+    let snapshot = mutex.get_cloned().unwrap();
+    mutex.lock().unwrap().push(3);
+    assert_eq!(snapshot, [1, 2]);
+    assert_eq!(mutex.get_cloned().unwrap(), [1, 2, 3]);
+
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = mutex.lock().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(mutex.get_cloned().unwrap_err().into_inner(), [1, 2, 3]);
+}
+
+#[test]
+fn set_replace_take() {
+    let mutex = StdMutex::new(String::from("a"));
+    mutex.set(String::from("b")).unwrap();
+    assert_eq!(mutex.replace(String::from("c")).unwrap(), "b");
+    assert_eq!(mutex.take().unwrap(), "c");
+    assert_eq!(*mutex.lock().unwrap(), "");
+
+    // Policies that don't report poisoning return the values directly.
+    let mutex = StdUnpoisonedMutex::new(String::from("a"));
+    mutex.set(String::from("b"));
+    assert_eq!(mutex.replace(String::from("c")), "b");
+    assert_eq!(mutex.get_cloned(), "c");
+    assert_eq!(mutex.take(), "c");
+}
+
+#[test]
+fn get_or_init_with() {
+    let mutex = StdMutex::new(None);
+    let mut value = mutex.get_or_init_with(|| vec![1]).unwrap();
+    value.push(2);
+    drop(value);
+    assert_eq!(*mutex.get_or_init_with(|| unreachable!()).unwrap(), [1, 2]);
+
+    *mutex.lock().unwrap() = None;
+    assert_eq!(*mutex.get_or_init_with(|| vec![3]).unwrap(), [3]);
+}
+
+#[test]
+fn is_locked() {
+    let mutex = StdMutex::new(0);
+    assert!(!mutex.is_locked());
+    let guard = mutex.lock().unwrap();
+    assert!(mutex.is_locked());
+    drop(guard);
+    assert!(!mutex.is_locked());
+}
+
+#[test]
+fn builder() {
+    use powerlocks::{
+        mutex::{BaseMutex, MutexHook},
+        primitives::{HookContext, StdThreadEnv},
+    };
+    use std::sync::atomic::AtomicUsize;
+
+    static LOCKS: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountingHook;
+    impl MutexHook for CountingHook {
+        fn before_lock(&self, _context: &HookContext) {
+            LOCKS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            unreachable!("the builder was given a hook")
+        }
+    }
+
+    let mutex: BaseMutex<i32, CountingHook, StdThreadEnv, NoPoison> = StdMutex::builder()
+        .hook(CountingHook)
+        .poison_policy::<NoPoison>()
+        .build(7);
+    assert!(!mutex.is_fair());
+    *mutex.lock() += 1;
+    assert_eq!(mutex.into_inner(), 8);
+    assert_eq!(LOCKS.load(Ordering::Relaxed), 1);
+
+    let mutex = StdMutex::<i32>::builder().build(0);
+    assert!(!mutex.is_fair());
+}
+
+#[test]
+fn fair() {
+    use std::{sync::Mutex, time::Duration};
+
+    const WAITERS: usize = 3;
+    let mutex = StdMutex::builder().fair().build(());
+    let order = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        let guard = mutex.lock().unwrap();
+        for waiter in 0..WAITERS {
+            let (mutex, order) = (&mutex, &order);
+            scope.spawn(move || {
+                let _guard = mutex.lock().unwrap();
+                order.lock().unwrap().push(waiter);
+            });
+            // Give the waiter time to give up spinning and park, behind the ones before it.
+            thread::sleep(Duration::from_millis(50));
+        }
+        drop(guard);
+        // The mutex went straight to the oldest waiter.
+        assert!(mutex.is_locked());
+        assert!(matches!(mutex.try_lock(), Err(TryLockError::WouldBlock)));
+    });
+    assert_eq!(*order.lock().unwrap(), [0, 1, 2]);
+    assert!(!mutex.is_locked());
+}
+
+#[test]
+#[cfg(feature = "labels")]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn new_named() {
+    use powerlocks::{
+        mutex::{BaseMutex, MutexHook},
+        primitives::{HookContext, Poison, StdThreadEnv},
+    };
+    use std::sync::atomic::AtomicUsize;
+
+    static LABELED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct LabelHook;
+    impl MutexHook for LabelHook {
+        fn before_lock(&self, context: &HookContext) {
+            assert_eq!(context.label(), Some("session_map"));
+            LABELED.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let mutex = BaseMutex::<i32, LabelHook, StdThreadEnv, Poison>::new_named("session_map", 0);
+    assert_eq!(mutex.label(), Some("session_map"));
+    assert_eq!(StdMutex::new(0).label(), None);
+
+    let built = StdMutex::<i32>::builder().label("built").build(0);
+    assert_eq!(built.label(), Some("built"));
+
+    test_support::suppress_panic_message(|| {
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = mutex.lock().unwrap();
+            panic!("poisoning");
+        });
+    });
+    assert!(LABELED.load(Ordering::Relaxed) > 0);
+    assert_eq!(
+        format!("{mutex:?}"),
+        "Mutex { label: \"session_map\", data: 0, poisoned: true, .. }"
+    );
+
+    let error = mutex.lock().unwrap_err();
+    assert_eq!(error.label(), Some("session_map"));
+    assert!(error.to_string().contains("\"session_map\""));
+    assert!(format!("{error:?}").contains("session_map"));
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn debug() {
+    let mutex = StdMutex::new(1);
+    assert_eq!(
+        format!("{mutex:?}"),
+        "Mutex { data: 1, poisoned: false, .. }"
+    );
+
+    let guard = mutex.lock().unwrap();
+    assert_eq!(
+        format!("{mutex:?}"),
+        "Mutex { data: <locked>, poisoned: false, .. }"
+    );
+    drop(guard);
+
+    let mutex = StdMutex::new(2);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = mutex.lock().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(
+        format!("{mutex:?}"),
+        "Mutex { data: 2, poisoned: true, .. }"
+    );
+}
+
+#[test]
+fn guard_traits() {
+    use std::hash::{BuildHasher, RandomState};
+
+    let lock = StdMutex::new(2);
+    let guard = lock.lock().unwrap();
+    assert_eq!(guard, 2);
+    assert!(guard < 3);
+    assert_eq!(guard.to_string(), "2");
+
+    let hasher = RandomState::new();
+    assert_eq!(hasher.hash_one(&guard), hasher.hash_one(2));
+}
+
+#[test]
+fn guard_io() {
+    use std::io::{Cursor, Read, Write};
+
+    let lock = StdMutex::new(Cursor::new(Vec::new()));
+    write!(lock.lock().unwrap(), "locked").unwrap();
+
+    let mut guard = lock.lock().unwrap();
+    guard.set_position(0);
+    let mut read = String::new();
+    guard.read_to_string(&mut read).unwrap();
+    assert_eq!(read, "locked");
+}
+
+#[test]
+fn guard_as_ref() {
+    use std::borrow::BorrowMut;
+
+    fn push(mut v: impl AsMut<Vec<i32>>) {
+        v.as_mut().push(1);
+    }
+
+    let lock = StdMutex::new(Vec::new());
+    push(lock.lock().unwrap());
+    BorrowMut::<Vec<_>>::borrow_mut(&mut lock.lock().unwrap()).push(2);
+    assert_eq!(lock.lock().unwrap().as_ref(), &[1, 2]);
+}
@@ -0,0 +1,107 @@
+#![cfg(all(feature = "mutex", feature = "alloc", feature = "std"))]
+
+use std::{
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+use powerlocks::notify::Notify;
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn notify_one() {
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let notify = Notify::new();
+    let mut first = pin!(notify.notified());
+    let mut second = pin!(notify.notified());
+    assert_eq!(first.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(second.as_mut().poll(&mut cx), Poll::Pending);
+
+    notify.notify_one();
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    assert_eq!(second.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(first.as_mut().poll(&mut cx), Poll::Ready(()));
+
+    notify.notify_one();
+    assert_eq!(second.as_mut().poll(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn stored_permit() {
+    let mut cx = Context::from_waker(Waker::noop());
+    let notify = Notify::new();
+    notify.notify_one();
+    notify.notify_one();
+
+    assert_eq!(pin!(notify.notified()).poll(&mut cx), Poll::Ready(()));
+    assert_eq!(pin!(notify.notified()).poll(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn notify_waiters() {
+    let mut cx = Context::from_waker(Waker::noop());
+    let notify = Notify::new();
+
+    let mut polled = pin!(notify.notified());
+    let unpolled = pin!(notify.notified());
+    assert_eq!(polled.as_mut().poll(&mut cx), Poll::Pending);
+
+    notify.notify_waiters();
+    assert_eq!(polled.poll(&mut cx), Poll::Ready(()));
+    assert_eq!(unpolled.poll(&mut cx), Poll::Ready(()));
+
+    // No permit is left behind for later waiters.
+    assert_eq!(pin!(notify.notified()).poll(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn dropped_waiter_forwards_notification() {
+    let mut cx = Context::from_waker(Waker::noop());
+    let notify = Notify::new();
+
+    let mut dropped = Box::pin(notify.notified());
+    let mut kept = pin!(notify.notified());
+    assert_eq!(dropped.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(kept.as_mut().poll(&mut cx), Poll::Pending);
+
+    notify.notify_one();
+    drop(dropped);
+    assert_eq!(kept.poll(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn across_threads() {
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let notify = Arc::new(Notify::new());
+    let mut notified = pin!(notify.notified());
+    assert_eq!(notified.as_mut().poll(&mut cx), Poll::Pending);
+
+    let notifier = Arc::clone(&notify);
+    thread::spawn(move || notifier.notify_one()).join().unwrap();
+
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    assert_eq!(notified.poll(&mut cx), Poll::Ready(()));
+}
@@ -0,0 +1,157 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+mod utils;
+
+use std::{
+    cell::Cell,
+    panic::{self, RefUnwindSafe, UnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use powerlocks::once_lock::{StdOnce, StdOnceLock};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(
+        StdOnceLock<i32>,
+        Send,
+        Sync,
+        UnwindSafe,
+        RefUnwindSafe,
+        Unpin
+    );
+    assert_is_trait!(StdOnceLock<Cell<i32>>, Send);
+    assert_is_trait!(StdOnceLock<Cell<i32>>, !Sync);
+    assert_is_trait!(StdOnceLock<*const ()>, !Send, !Sync);
+    assert_is_trait!(StdOnce, Send, Sync, UnwindSafe, RefUnwindSafe, Unpin);
+}
+
+#[test]
+fn get_or_init() {
+    let cell = StdOnceLock::new();
+    assert_eq!(cell.get(), None);
+    assert_eq!(*cell.get_or_init(|| 1), 1);
+    assert_eq!(*cell.get_or_init(|| 2), 1);
+    assert_eq!(cell.get(), Some(&1));
+}
+
+#[test]
+fn get_or_try_init() {
+    let cell = StdOnceLock::new();
+    assert_eq!(cell.get_or_try_init(|| Err("failed")), Err("failed"));
+    assert_eq!(cell.get(), None);
+
+    assert_eq!(cell.get_or_try_init(|| Ok::<_, &str>(1)), Ok(&1));
+    assert_eq!(cell.get_or_try_init(|| Err("failed")), Ok(&1));
+}
+
+#[test]
+fn set() {
+    let cell = StdOnceLock::new();
+    assert_eq!(cell.set(1), Ok(()));
+    assert_eq!(cell.set(2), Err(2));
+    assert_eq!(cell.get(), Some(&1));
+}
+
+#[test]
+fn panicking_init() {
+    let cell = StdOnceLock::new();
+    let result = panic::catch_unwind(|| cell.get_or_init(|| panic!("init failed")));
+    assert!(result.is_err());
+    assert_eq!(cell.get(), None);
+    assert_eq!(*cell.get_or_init(|| 1), 1);
+}
+
+#[test]
+fn init_once() {
+    const THREADS: usize = 8;
+    let cell = StdOnceLock::new();
+    let calls = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        for i in 0..THREADS {
+            let (cell, calls) = (&cell, &calls);
+            s.spawn(move || {
+                let value = cell.get_or_init(|| {
+                    calls.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(Duration::from_millis(10));
+                    i
+                });
+                assert_eq!(cell.get(), Some(value));
+            });
+        }
+    });
+
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn ownership() {
+    let mut cell = StdOnceLock::from(String::from("a"));
+    cell.get_mut().unwrap().push('b');
+    assert_eq!(cell.clone().into_inner().as_deref(), Some("ab"));
+    assert_eq!(cell.take().as_deref(), Some("ab"));
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.into_inner(), None);
+}
+
+#[test]
+fn debug() {
+    let cell = StdOnceLock::new();
+    assert_eq!(format!("{cell:?}"), "OnceLock(<uninit>)");
+    cell.set(5).unwrap();
+    assert_eq!(format!("{cell:?}"), "OnceLock(5)");
+}
+
+#[test]
+fn call_once() {
+    let once = StdOnce::new();
+    let calls = AtomicUsize::new(0);
+    assert!(!once.is_completed());
+
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                once.call_once(|| {
+                    calls.fetch_add(1, Ordering::Relaxed);
+                });
+            });
+        }
+    });
+
+    assert!(once.is_completed());
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn call_once_poisoned() {
+    let once = StdOnce::new();
+    let result = panic::catch_unwind(|| once.call_once(|| panic!("init failed")));
+    assert!(result.is_err());
+    assert!(!once.is_completed());
+
+    let result = panic::catch_unwind(|| once.call_once(|| {}));
+    assert!(result.is_err());
+    assert!(!once.is_completed());
+}
+
+#[test]
+fn call_once_force() {
+    let once = StdOnce::new();
+    once.call_once_force(|state| assert!(!state.is_poisoned()));
+    once.call_once_force(|_| unreachable!());
+
+    let once = StdOnce::new();
+    let result = panic::catch_unwind(|| once.call_once_force(|_| panic!("init failed")));
+    assert!(result.is_err());
+
+    let mut poisoned = None;
+    once.call_once_force(|state| poisoned = Some(state.is_poisoned()));
+    assert_eq!(poisoned, Some(true));
+    assert!(once.is_completed());
+    once.call_once(|| unreachable!());
+}
@@ -0,0 +1,60 @@
+#![cfg(all(feature = "mutex", feature = "alloc", feature = "std"))]
+
+mod utils;
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use powerlocks::primitives::{Parker, StdHandle, Unparker, parker};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(Unparker<StdHandle>, Send, Sync, Clone);
+    assert_is_trait!(Parker<StdHandle>, !Send, !Sync);
+}
+
+#[test]
+fn unpark_before_park() {
+    let (parker, unparker) = parker::<StdHandle>();
+    unparker.unpark();
+    unparker.unpark();
+    // The token doesn't accumulate.
+    parker.park();
+    assert!(!parker.park_timeout(Duration::from_millis(10)));
+}
+
+#[test]
+fn park_until_unparked() {
+    let (parker, unparker) = parker::<StdHandle>();
+    let done = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            done.store(true, Ordering::Relaxed);
+            unparker.unpark();
+        });
+        parker.park();
+        assert!(done.load(Ordering::Relaxed));
+    });
+
+    let unparker = parker.unparker();
+    thread::scope(|scope| {
+        scope.spawn(|| unparker.unpark());
+        assert!(parker.park_timeout(Duration::from_secs(10)));
+    });
+}
+
+#[test]
+fn park_timeout() {
+    const TIMEOUT: Duration = Duration::from_millis(20);
+    let (parker, _unparker) = parker::<StdHandle>();
+    let start = Instant::now();
+    assert!(!parker.park_timeout(TIMEOUT));
+    assert!(start.elapsed() >= TIMEOUT);
+}
@@ -0,0 +1,91 @@
+#![cfg(all(feature = "parking_lot", feature = "rwlock", feature = "std"))]
+
+mod utils;
+
+use parking_lot::{Mutex, RwLock};
+
+use powerlocks::test_support::{self, race_checker::RaceChecker};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(Mutex<i32>, Send, Sync, Unpin);
+    assert_is_trait!(RwLock<i32>, Send, Sync, Unpin);
+    assert_is_trait!(Mutex<*const ()>, !Send, !Sync);
+    assert_is_trait!(RwLock<*const ()>, !Send, !Sync);
+}
+
+#[test]
+fn lock() {
+    test_support::mutex::lock::<Mutex<_>, _>(&());
+    test_support::mutex::lock::<Mutex<_>, _>(&0_u64);
+    test_support::mutex::lock_writing::<Mutex<_>, _>(&0_u64, 0xac7e4d30_951f268b);
+
+    let array_i32 = [1, 2, 3, 4, 5];
+    let unsized_lock: &mut Mutex<[i32]> = &mut Mutex::new(array_i32);
+    test_support::mutex::lock_unsized(unsized_lock, &array_i32);
+}
+
+#[test]
+fn with_lock() {
+    test_support::mutex::with_lock::<Mutex<_>>();
+}
+
+#[test]
+fn race_lock() {
+    test_support::mutex::race_lock::<Mutex<_>>();
+}
+
+#[test]
+fn poison() {
+    test_support::suppress_panic_message(|| {
+        test_support::mutex::poison::<Mutex<_>, _>(&(), false);
+        test_support::mutex::poison::<Mutex<_>, _>(&0_u64, false);
+    });
+}
+
+#[test]
+fn try_lock() {
+    test_support::mutex::try_lock::<Mutex<_>, _>(&());
+    test_support::mutex::try_lock::<Mutex<_>, _>(&0_u64);
+}
+
+#[test]
+fn mutex_load_test() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 8 };
+    const REPS: usize = if cfg!(miri) { 16 } else { 1024 };
+    const CYCLES: usize = if cfg!(miri) { 4 } else { 16 };
+
+    test_support::mutex::do_load_test::<Mutex<_>>(THREADS, REPS, CYCLES, None);
+}
+
+#[test]
+fn run_single_thread() {
+    test_support::rwlock::run_single_thread::<RwLock<_>, ()>();
+    test_support::rwlock::run_single_thread::<RwLock<_>, i32>();
+}
+
+#[test]
+fn with_read_and_write() {
+    test_support::rwlock::with_read_and_write::<RwLock<_>>();
+}
+
+#[test]
+fn race_reads() {
+    test_support::rwlock::race_reads(&RwLock::new(RaceChecker::new()));
+}
+
+#[test]
+fn race_writes() {
+    test_support::rwlock::race_writes(&RwLock::new(RaceChecker::new()));
+}
+
+#[test]
+fn rwlock_load_test() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 8 };
+    const WRITES: usize = if cfg!(miri) { 16 } else { 1024 };
+    const READS: usize = if cfg!(miri) { 16 } else { 1024 };
+
+    test_support::rwlock::load_test_with(RwLock::new(0), THREADS, WRITES, READS);
+}
@@ -0,0 +1,124 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+mod utils;
+
+use std::{
+    cell::{Cell, RefCell, UnsafeCell},
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::Arc,
+    thread,
+};
+
+use powerlocks::{
+    mutex::{StdReentrantLock, StdReentrantLockGuard},
+    primitives::TryLockError,
+};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(
+        StdReentrantLock<i32>,
+        Send,
+        Sync,
+        UnwindSafe,
+        RefUnwindSafe,
+        Unpin
+    );
+    assert_is_trait!(StdReentrantLock<RefCell<i32>>, Send, Sync);
+    assert_is_trait!(StdReentrantLock<UnsafeCell<i32>>, Send, Sync);
+    assert_is_trait!(StdReentrantLock<*const ()>, !Send, !Sync);
+
+    assert_is_trait!(StdReentrantLockGuard<'_, i32>, Sync);
+    assert_is_trait!(StdReentrantLockGuard<'_, i32>, !Send);
+    assert_is_trait!(StdReentrantLockGuard<'_, Cell<i32>>, !Send, !Sync);
+}
+
+#[test]
+fn nested_lock() {
+    let lock = StdReentrantLock::new(Cell::new(0));
+    assert!(!lock.is_owned_by_current_thread());
+
+    let outer = lock.lock();
+    outer.set(1);
+    {
+        let inner = lock.lock();
+        assert_eq!(inner.get(), 1);
+        inner.set(2);
+        let innermost = lock.try_lock().unwrap();
+        assert_eq!(innermost.get(), 2);
+    }
+    assert!(lock.is_owned_by_current_thread());
+    assert_eq!(outer.get(), 2);
+    drop(outer);
+
+    assert!(!lock.is_owned_by_current_thread());
+    assert_eq!(lock.into_inner().get(), 2);
+}
+
+#[test]
+fn held_by_other_thread() {
+    let lock = StdReentrantLock::new(0);
+    let first = lock.lock();
+    let second = lock.lock();
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            assert!(matches!(lock.try_lock(), Err(TryLockError::WouldBlock)));
+            assert!(!lock.is_owned_by_current_thread());
+        });
+    });
+
+    drop(first);
+    thread::scope(|s| {
+        s.spawn(|| assert!(matches!(lock.try_lock(), Err(TryLockError::WouldBlock))));
+    });
+
+    drop(second);
+    thread::scope(|s| {
+        s.spawn(|| assert_eq!(*lock.try_lock().unwrap(), 0));
+    });
+}
+
+#[test]
+fn contention() {
+    const THREADS: usize = if cfg!(miri) { 3 } else { 8 };
+    const ITERATIONS: usize = if cfg!(miri) { 10 } else { 1000 };
+
+    let lock = Arc::new(StdReentrantLock::new(RefCell::new(0)));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    let outer = lock.lock();
+                    let before = *outer.borrow();
+                    let inner = lock.lock();
+                    *inner.borrow_mut() += 1;
+                    assert_eq!(*outer.borrow(), before + 1);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(*lock.lock().borrow(), THREADS * ITERATIONS);
+}
+
+#[test]
+fn debug() {
+    let lock = StdReentrantLock::new(5);
+    assert_eq!(format!("{lock:?}"), "ReentrantLock { data: 5, .. }");
+
+    let guard = lock.lock();
+    assert_eq!(format!("{guard:?}"), "5");
+    assert_eq!(format!("{lock:?}"), "ReentrantLock { data: 5, .. }");
+
+    thread::scope(|s| {
+        s.spawn(|| assert_eq!(format!("{lock:?}"), "ReentrantLock { data: <locked>, .. }"));
+    });
+}
@@ -3,15 +3,27 @@
 use std::{
     cell::UnsafeCell,
     panic::{RefUnwindSafe, UnwindSafe},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
 };
 
-use powerlocks::rwlock::{StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard};
+use powerlocks::{
+    primitives::{
+        HandleId, HookContext, Method, NoPoison, PoisonError, ShouldBlock, StdHandle, StdThreadEnv,
+        ThreadEnv, TryLockError,
+    },
+    rwlock::{
+        BaseRwLock, RwLockHook, StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard,
+        StdUnpoisonedRwLock,
+    },
+};
 
-mod rwlock_utils;
-use rwlock_utils::tests;
+use powerlocks::test_support::{self, race_checker::RaceChecker, rwlock as tests};
 
 mod utils;
-use utils::{assert_is_trait, race_checker::RaceChecker};
+use utils::assert_is_trait;
 
 #[test]
 fn assert_trait() {
@@ -107,6 +119,11 @@ fn run_single_thread_vec() {
     assert_eq!(*locked_vec.read().unwrap(), [1, 2, 3, 4]);
 }
 
+#[test]
+fn with_read_and_write() {
+    tests::with_read_and_write::<StdRwLock<_>>();
+}
+
 #[test]
 fn race_reads() {
     tests::race_reads(&StdRwLock::new(RaceChecker::new()));
@@ -123,10 +140,288 @@ fn no_poison_on_read() {
 }
 
 #[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
 fn poison_on_write() {
     tests::poison_on_write(&StdRwLock::new(()));
 }
 
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn pointers() {
+    use powerlocks::rwlock::RwLockApi;
+    use std::sync::Arc;
+
+    tests::run_single_thread::<Box<StdRwLock<_>>, i32>();
+    tests::run_single_thread::<Arc<StdRwLock<_>>, i32>();
+    tests::with_read_and_write::<Arc<StdRwLock<_>>>();
+
+    let lock = StdRwLock::new(RaceChecker::new());
+    tests::race_reads(&lock);
+    tests::race_writes(&Arc::new(lock));
+    tests::poison_on_write(&Box::new(StdRwLock::new(())));
+
+    let shared = Arc::new(StdRwLock::new(3));
+    let _other = Arc::clone(&shared);
+    let result = std::panic::catch_unwind(|| RwLockApi::into_inner(shared));
+    assert!(result.is_err());
+}
+
+#[test]
+fn leak() {
+    let lock: &'static StdRwLock<i32> = Box::leak(Box::new(StdRwLock::new(0)));
+    let first: &'static i32 = lock.read().unwrap().leak();
+    let second = lock.read().unwrap();
+    assert_eq!((*first, *second), (0, 0));
+    drop(second);
+    assert!(lock.try_write().is_err());
+
+    let lock: &'static StdRwLock<i32> = Box::leak(Box::new(StdRwLock::new(0)));
+    let leaked: &'static mut i32 = lock.write().unwrap().leak();
+    *leaked = 5;
+    assert!(lock.try_read().is_err());
+    assert!(lock.try_write().is_err());
+    assert_eq!(*leaked, 5);
+}
+
+#[test]
+fn force_unlock() {
+    let lock = StdRwLock::new(0);
+    std::mem::forget(lock.read().unwrap());
+    std::mem::forget(lock.read().unwrap());
+    assert!(lock.try_write().is_err());
+
+    // SAFETY: The guards were forgotten and are never used again.
+    unsafe { lock.force_unlock_read() };
+    assert!(lock.try_write().is_err());
+    unsafe { lock.force_unlock_read() };
+
+    std::mem::forget(lock.write().unwrap());
+    assert!(lock.try_read().is_err());
+
+    // SAFETY: The guard was forgotten and is never used again.
+    unsafe { lock.force_unlock_write() };
+    assert_eq!(*lock.try_write().unwrap(), 0);
+    assert!(!lock.is_poisoned());
+}
+
+#[test]
+fn static_hooked() {
+    // Counted outside of the hook, which can't be reached through the lock.
+    static LOCKS: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+    static UNLOCKS: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+    const READ: usize = 0;
+    const WRITE: usize = 1;
+
+    struct CountingHook;
+    impl RwLockHook for CountingHook {
+        fn new() -> Self {
+            Self
+        }
+
+        fn try_read(&self, _context: &HookContext) -> ShouldBlock {
+            LOCKS[READ].fetch_add(1, Ordering::Relaxed);
+            ShouldBlock::Ok
+        }
+
+        fn try_write(&self, _context: &HookContext) -> ShouldBlock {
+            LOCKS[WRITE].fetch_add(1, Ordering::Relaxed);
+            ShouldBlock::Ok
+        }
+
+        fn after_read(&self, _context: &HookContext) {
+            UNLOCKS[READ].fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn after_write(&self, _context: &HookContext) {
+            UNLOCKS[WRITE].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    static LOCK: BaseRwLock<i32, CountingHook, StdThreadEnv> =
+        BaseRwLock::with_hook(0, CountingHook);
+
+    *LOCK.write().unwrap() += 1;
+    assert_eq!(*LOCK.read().unwrap(), 1);
+    assert_eq!(*LOCK.read().unwrap(), 1);
+    let counts =
+        |counts: &[AtomicUsize; 2]| counts.each_ref().map(|count| count.load(Ordering::Relaxed));
+    assert_eq!(counts(&LOCKS), [2, 1]);
+    assert_eq!(counts(&UNLOCKS), [2, 1]);
+}
+
+#[test]
+fn with_hook() {
+    struct SharedCountingHook(&'static AtomicUsize);
+    impl RwLockHook for SharedCountingHook {
+        fn new() -> Self {
+            unreachable!("the hook is passed to `with_hook`")
+        }
+
+        fn after_read(&self, _context: &HookContext) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn after_write(&self, _context: &HookContext) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    static RELEASES: AtomicUsize = AtomicUsize::new(0);
+    let lock = BaseRwLock::<_, _, StdThreadEnv>::with_hook(0, SharedCountingHook(&RELEASES));
+    *lock.write().unwrap() += 1;
+    assert_eq!(*lock.read().unwrap(), 1);
+    assert_eq!(RELEASES.load(Ordering::Relaxed), 2);
+
+    // Formatting the lock looks at the data without running the hooks.
+    assert!(format!("{lock:?}").contains("data: 1"));
+    assert_eq!(RELEASES.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn hook_context() {
+    static RECORD: Mutex<Vec<(Method, bool, Option<HandleId>)>> = Mutex::new(Vec::new());
+
+    struct RecordingHook;
+    impl RwLockHook for RecordingHook {
+        fn new() -> Self {
+            Self
+        }
+
+        fn try_read(&self, context: &HookContext) -> ShouldBlock {
+            self.record(context);
+            ShouldBlock::Ok
+        }
+
+        fn try_write(&self, context: &HookContext) -> ShouldBlock {
+            self.record(context);
+            ShouldBlock::Ok
+        }
+    }
+
+    impl RecordingHook {
+        fn record(&self, context: &HookContext) {
+            let entry = (context.method(), context.contended(), context.handle_id());
+            RECORD.lock().unwrap().push(entry);
+        }
+    }
+
+    let lock = BaseRwLock::<i32, RecordingHook, StdThreadEnv>::new(0);
+    let id = StdThreadEnv::current_id();
+    assert!(id.is_some());
+    assert_eq!(id, StdHandle::current_id());
+
+    {
+        let _read = lock.read().unwrap();
+        assert!(lock.try_write().is_err());
+        let _read = lock.try_read().unwrap();
+    }
+    let _write = lock.write().unwrap();
+
+    let other = std::thread::spawn(StdThreadEnv::current_id).join().unwrap();
+    assert_ne!(other, id);
+
+    assert_eq!(
+        *RECORD.lock().unwrap(),
+        [
+            (Method::Read, false, id),
+            (Method::Write, true, id),
+            (Method::Read, false, id),
+            (Method::Write, false, id),
+        ]
+    );
+}
+
+#[test]
+fn unpoisoned() {
+    tests::run_single_thread::<StdUnpoisonedRwLock<_>, i32>();
+    tests::race_reads(&StdUnpoisonedRwLock::new(RaceChecker::new()));
+    tests::race_writes(&StdUnpoisonedRwLock::new(RaceChecker::new()));
+    tests::no_poison_on_read(&StdUnpoisonedRwLock::new(()));
+
+    let lock = StdUnpoisonedRwLock::new(0);
+    test_support::suppress_panic_message(|| {
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let _guard = lock.write();
+                    panic!("Not poisoning it");
+                })
+                .join()
+                .expect_err("Spawned thread must panic");
+        })
+    });
+
+    assert!(!lock.is_poisoned());
+    *lock.write() += 1;
+    assert_eq!(*lock.read(), 1);
+    assert_eq!(lock.into_inner(), 1);
+}
+
+#[test]
+fn data_ptr() {
+    let lock = StdRwLock::new(0);
+    {
+        let mut guard = lock.write().unwrap();
+        *guard = 1;
+        assert_eq!(lock.data_ptr(), &raw mut *guard);
+    }
+
+    // SAFETY: No guards or other references to the data exist.
+    unsafe { *lock.data_ptr() += 1 };
+    assert_eq!(*lock.read().unwrap(), 2);
+}
+
+#[test]
+fn lock_until() {
+    let lock = StdRwLock::new(0);
+    let cancel = AtomicBool::new(false);
+
+    let guard = lock.write().unwrap();
+    std::thread::scope(|scope| {
+        let reader = scope.spawn(|| lock.read_until(&cancel).map(|_| ()));
+        let writer = scope.spawn(|| lock.write_until(&cancel).map(|_| ()));
+        cancel.store(true, Ordering::Release);
+        assert!(matches!(
+            reader.join().unwrap(),
+            Err(TryLockError::Cancelled)
+        ));
+        assert!(matches!(
+            writer.join().unwrap(),
+            Err(TryLockError::Cancelled)
+        ));
+    });
+    drop(guard);
+
+    cancel.store(false, Ordering::Release);
+    *lock.write_until(&cancel).unwrap() += 1;
+    assert_eq!(*lock.read_until(&cancel).unwrap(), 1);
+}
+
+#[test]
+fn long_contention() {
+    const THREADS: usize = 8;
+    let lock = StdRwLock::new(0_usize);
+
+    std::thread::scope(|scope| {
+        let guard = lock.write().unwrap();
+        for i in 0..THREADS {
+            let lock = &lock;
+            scope.spawn(move || {
+                if i % 2 == 0 {
+                    *lock.write().unwrap() += 1;
+                } else {
+                    let _ = *lock.read().unwrap();
+                }
+            });
+        }
+        // Hold the lock long enough for the waiters to give up spinning and park.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        drop(guard);
+    });
+
+    assert_eq!(lock.into_inner().unwrap(), THREADS / 2);
+}
+
 #[test]
 fn load_test() {
     const THREADS: usize = if cfg!(miri) { 4 } else { 24 };
@@ -136,3 +431,512 @@ fn load_test() {
     let num = StdRwLock::new(0usize);
     tests::load_test_with(num, THREADS, WRITES, READS);
 }
+
+#[test]
+fn new_arc() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 16 };
+
+    let lock: powerlocks::rwlock::ArcRwLock<usize> = StdRwLock::new_arc(0);
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            std::thread::spawn(move || *lock.write().unwrap() += 1)
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(*lock.read().unwrap(), THREADS);
+}
+
+#[test]
+fn read_only() {
+    use powerlocks::rwlock::ReadOnlyRwLock;
+    use std::sync::Arc;
+
+    assert_is_trait!(ReadOnlyRwLock<&StdRwLock<i32>>, Send, Sync, Copy);
+    assert_is_trait!(ReadOnlyRwLock<Arc<StdRwLock<i32>>>, Send, Sync, Clone);
+
+    let lock = StdRwLock::new(vec![1]);
+    let view = lock.read_only();
+    let guard = view.read().unwrap();
+    assert_eq!(*view.try_read().unwrap(), [1]);
+    assert!(lock.try_write().is_err());
+    drop(guard);
+
+    lock.write().unwrap().push(2);
+    assert_eq!(view.get_cloned().unwrap(), [1, 2]);
+    assert!(!view.is_poisoned());
+    assert_eq!(
+        format!("{view:?}"),
+        "ReadOnlyRwLock(RwLock { data: [1, 2], poisoned: false, .. })"
+    );
+
+    let lock = StdRwLock::new_arc(0);
+    let view = lock.read_only_arc();
+    let thread = std::thread::spawn(move || {
+        while *view.read().unwrap() == 0 {
+            std::thread::yield_now();
+        }
+    });
+    *lock.write().unwrap() = 1;
+    thread.join().unwrap();
+}
+
+#[test]
+fn split_arc() {
+    use powerlocks::rwlock::RwLockWriter;
+    use std::sync::Arc;
+
+    assert_is_trait!(RwLockWriter<StdRwLock<i32>>, Send, Sync);
+    assert_is_trait!(RwLockWriter<StdRwLock<i32>>, !Clone);
+
+    let (reader, writer) = StdRwLock::new_arc(vec![1]).split_arc();
+    let other_reader = writer.reader();
+    std::thread::scope(|s| {
+        s.spawn(|| writer.write().unwrap().push(2));
+    });
+    assert_eq!(*reader.read().unwrap(), [1, 2]);
+    assert_eq!(*other_reader.clone().read().unwrap(), [1, 2]);
+    assert_eq!(*writer.read().unwrap(), [1, 2]);
+
+    let lock = StdRwLock::new_arc(0);
+    let _shared = Arc::clone(&lock);
+    let result = std::panic::catch_unwind(|| lock.split_arc());
+    assert!(result.is_err());
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn freeze() {
+    let lock = StdRwLock::new(String::from("frozen"));
+    lock.write().unwrap().push('!');
+    let frozen = lock.freeze().unwrap();
+    assert_eq!(*frozen, "frozen!");
+    assert_eq!(
+        format!("{frozen:?} {frozen}"),
+        r#"Frozen("frozen!") frozen!"#
+    );
+    assert_eq!(frozen.clone().into_inner(), "frozen!");
+
+    let lock = StdRwLock::new(1);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(*lock.freeze().unwrap_err().into_inner(), 1);
+
+    let frozen = StdUnpoisonedRwLock::new(2).freeze();
+    assert_eq!(*frozen, 2);
+}
+
+#[test]
+fn guard_rwlock() {
+    let lock = StdRwLock::new(0);
+    let guard = lock.read().unwrap();
+    assert!(std::ptr::eq(guard.rwlock(), &lock));
+    drop(guard);
+
+    let guard = lock.write().unwrap();
+    assert!(std::ptr::eq(guard.rwlock(), &lock));
+    assert!(!guard.rwlock().is_poisoned());
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn unlocked() {
+    let lock = StdRwLock::new(0);
+    std::thread::scope(|scope| {
+        let mut guard = lock.write().unwrap();
+        let other = scope.spawn(|| *lock.write().unwrap() += 1);
+        guard.unlocked(|| other.join().unwrap()).unwrap();
+        assert_eq!(*guard, 1);
+        guard.bump().unwrap();
+        drop(guard);
+
+        let mut guard = lock.read().unwrap();
+        let other = scope.spawn(|| *lock.write().unwrap() += 1);
+        guard.unlocked(|| other.join().unwrap()).unwrap();
+        assert_eq!(*guard, 2);
+    });
+
+    let result = std::panic::catch_unwind(|| {
+        let mut guard = lock.write().unwrap();
+        guard.unlocked(|| panic!("unlocked")).unwrap();
+    });
+    assert!(result.is_err());
+    assert!(lock.is_poisoned());
+    assert!(matches!(lock.try_read(), Err(TryLockError::Poisoned(_))));
+}
+
+#[test]
+fn sleep() {
+    let duration = std::time::Duration::from_millis(5);
+    let start = std::time::Instant::now();
+    StdThreadEnv::sleep(duration);
+    assert!(start.elapsed() >= duration);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn on_poison_hook() {
+    static POISONS: AtomicUsize = AtomicUsize::new(0);
+
+    struct PoisonHook;
+    impl RwLockHook for PoisonHook {
+        fn new() -> Self {
+            Self
+        }
+
+        fn on_poison(&self, context: &HookContext) {
+            assert_eq!(context.method(), Method::Write);
+            POISONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let lock = BaseRwLock::<i32, PoisonHook, StdThreadEnv>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.read().unwrap();
+        panic!("not poisoning");
+    });
+    assert_eq!(POISONS.load(Ordering::Relaxed), 0);
+
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert!(lock.is_poisoned());
+    assert_eq!(POISONS.load(Ordering::Relaxed), 1);
+
+    // Only the panic that poisons the lock is reported.
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap_or_else(PoisonError::into_inner);
+        panic!("poisoning again");
+    });
+    assert_eq!(POISONS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn poison_policies() {
+    use powerlocks::primitives::{ClearOnNextLock, Ignore};
+
+    let lock = BaseRwLock::<i32, (), StdThreadEnv, Ignore>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let mut guard = lock.write();
+        *guard += 1;
+        panic!("poisoning");
+    });
+    assert!(lock.is_poisoned());
+    assert_eq!(*lock.read(), 1);
+
+    let lock = BaseRwLock::<i32, (), StdThreadEnv, ClearOnNextLock>::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let mut guard = lock.write().unwrap();
+        *guard += 1;
+        panic!("poisoning");
+    });
+    assert!(lock.read().is_err());
+    assert!(!lock.is_poisoned());
+    assert_eq!(*lock.write().unwrap(), 1);
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn get_cloned() {
+    let lock = StdRwLock::new(vec![1, 2]);
+    let guard = lock.read().unwrap();
+    assert_eq!(lock.get_cloned().unwrap(), [1, 2]);
+    drop(guard);
+    lock.write().unwrap().push(3);
+    assert_eq!(lock.get_cloned().unwrap(), [1, 2, 3]);
+
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(lock.get_cloned().unwrap_err().into_inner(), [1, 2, 3]);
+}
+
+#[test]
+fn set_replace_take() {
+    let lock = StdRwLock::new(String::from("a"));
+    lock.set(String::from("b")).unwrap();
+    assert_eq!(lock.replace(String::from("c")).unwrap(), "b");
+    assert_eq!(lock.take().unwrap(), "c");
+    assert_eq!(*lock.read().unwrap(), "");
+
+    // Policies that don't report poisoning return the values directly.
+    let lock = StdUnpoisonedRwLock::new(String::from("a"));
+    lock.set(String::from("b"));
+    assert_eq!(lock.replace(String::from("c")), "b");
+    assert_eq!(lock.get_cloned(), "c");
+    assert_eq!(lock.take(), "c");
+}
+
+#[test]
+fn observers() {
+    let lock = StdRwLock::new(0);
+    assert!(!lock.is_locked());
+    assert_eq!((lock.is_write_locked(), lock.reader_count()), (false, 0));
+
+    let readers = [lock.read().unwrap(), lock.read().unwrap()];
+    assert!(lock.is_locked());
+    assert_eq!((lock.is_write_locked(), lock.reader_count()), (false, 2));
+    drop(readers);
+
+    let writer = lock.write().unwrap();
+    assert!(lock.is_locked());
+    assert_eq!((lock.is_write_locked(), lock.reader_count()), (true, 0));
+    drop(writer);
+    assert!(!lock.is_locked());
+}
+
+#[test]
+#[cfg(feature = "labels")]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn new_named() {
+    static LABELED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct LabelHook;
+    impl RwLockHook for LabelHook {
+        fn after_write(&self, context: &HookContext) {
+            assert_eq!(context.label(), Some("session_map"));
+            LABELED.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    let lock = BaseRwLock::<i32, LabelHook, StdThreadEnv>::new_named("session_map", 0);
+    assert_eq!(lock.label(), Some("session_map"));
+    assert_eq!(lock.read_only().label(), Some("session_map"));
+    assert_eq!(StdRwLock::new(0).label(), None);
+
+    let built = StdRwLock::<i32>::builder().label("built").build(0);
+    assert_eq!(built.label(), Some("built"));
+
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert!(LABELED.load(Ordering::Relaxed) > 0);
+
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { label: \"session_map\", data: 0, poisoned: true, .. }"
+    );
+
+    let error = lock.read().unwrap_err();
+    assert_eq!(error.label(), Some("session_map"));
+    assert!(error.to_string().contains("\"session_map\""));
+    drop(error);
+    assert_eq!(lock.freeze().unwrap_err().label(), Some("session_map"));
+}
+
+#[test]
+#[cfg_attr(feature = "no-poison", ignore = "Poisons the lock.")]
+fn debug() {
+    let lock = StdRwLock::new(1);
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { data: 1, poisoned: false, .. }"
+    );
+
+    let read = lock.read().unwrap();
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { data: 1, poisoned: false, .. }"
+    );
+    drop(read);
+
+    let write = lock.write().unwrap();
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { data: <locked>, poisoned: false, .. }"
+    );
+    drop(write);
+
+    let lock = StdRwLock::new(2);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { data: 2, poisoned: true, .. }"
+    );
+
+    // Formatting neither stops at `max_readers` nor takes a one-shot poison report.
+    let lock = BaseRwLock::<_, (), StdThreadEnv, powerlocks::primitives::ClearOnNextLock>::with_max_readers(1, 3);
+    let read = lock.read().unwrap();
+    assert_eq!(
+        format!("{lock:?}"),
+        "RwLock { data: 3, poisoned: false, .. }"
+    );
+    drop(read);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert!(format!("{lock:?}").contains("poisoned: true"));
+    assert!(lock.read().is_err());
+}
+
+#[test]
+fn max_readers() {
+    let lock = StdRwLock::with_max_readers(2, 0);
+    assert_eq!(lock.max_readers(), 2);
+
+    let first = lock.read().unwrap();
+    let second = lock.try_read().unwrap();
+    assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+
+    std::thread::scope(|s| {
+        let third = s.spawn(|| *lock.read().unwrap());
+        drop(first);
+        assert_eq!(third.join().unwrap(), 0);
+    });
+    drop(second);
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+#[should_panic = "max_readers"]
+fn zero_max_readers() {
+    StdRwLock::with_max_readers(0, ());
+}
+
+#[test]
+fn builder() {
+    static READS: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountingHook;
+    impl RwLockHook for CountingHook {
+        fn new() -> Self {
+            unreachable!("the builder was given a hook")
+        }
+
+        fn after_read(&self, _context: &HookContext) {
+            READS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let lock: BaseRwLock<i32, CountingHook, StdThreadEnv, NoPoison> = StdRwLock::builder()
+        .hook(CountingHook)
+        .max_readers(1)
+        .poison_policy::<NoPoison>()
+        .build(7);
+    assert_eq!(lock.max_readers(), 1);
+
+    let read = lock.read();
+    assert_eq!(*read, 7);
+    assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+    drop(read);
+    assert_eq!(READS.load(Ordering::Relaxed), 1);
+
+    let lock = StdRwLock::<i32>::builder().build(0);
+    assert_eq!(lock.max_readers(), StdRwLock::new(0).max_readers());
+}
+
+#[test]
+#[should_panic = "max_readers"]
+fn builder_zero_max_readers() {
+    StdRwLock::builder().max_readers(0).build(());
+}
+
+#[test]
+#[cfg(feature = "rwlock-state-u16")]
+fn narrow_state_caps_readers() {
+    let lock = StdRwLock::new(());
+    let readers: Vec<_> = (0..i16::MAX).map(|_| lock.try_read().unwrap()).collect();
+    assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+    drop(readers);
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn guard_traits() {
+    use std::hash::{BuildHasher, RandomState};
+
+    let lock = StdRwLock::new(String::from("powerlocks"));
+    let read = lock.read().unwrap();
+    assert_eq!(read, "powerlocks".to_owned());
+    assert!(read > "locks".to_owned());
+    assert_eq!(format!("{read}!"), "powerlocks!");
+
+    let hasher = RandomState::new();
+    assert_eq!(hasher.hash_one(&read), hasher.hash_one("powerlocks"));
+    drop(read);
+
+    let write = lock.write().unwrap();
+    assert_eq!(write, "powerlocks".to_owned());
+    assert_eq!(write.to_string(), "powerlocks");
+}
+
+#[test]
+fn guard_io() {
+    use std::io::{Read, Write};
+
+    let lock = StdRwLock::new(Vec::new());
+    lock.write().unwrap().write_all(b"locked").unwrap();
+
+    let written = lock.into_inner().unwrap();
+    let lock = StdRwLock::new(&written[..]);
+    let mut read = [0; 4];
+    lock.write().unwrap().read_exact(&mut read).unwrap();
+    assert_eq!(&read, b"lock");
+    assert_eq!(*lock.read().unwrap(), b"ed");
+}
+
+#[test]
+fn guard_as_ref() {
+    use std::borrow::Borrow;
+
+    fn len(s: impl AsRef<String>) -> usize {
+        s.as_ref().len()
+    }
+
+    let lock = StdRwLock::new(String::from("lock"));
+    lock.write().unwrap().as_mut().push('s');
+    assert_eq!(len(lock.read().unwrap()), 5);
+    assert_eq!(Borrow::<String>::borrow(&lock.read().unwrap()), "locks");
+    assert_eq!(AsRef::<String>::as_ref(&lock.write().unwrap()), "locks");
+}
+
+#[cfg(feature = "hle")]
+#[test]
+fn read_elided() {
+    let lock = StdRwLock::new(1);
+    assert_eq!(lock.read_elided(|value| value + 1).unwrap(), 2);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..1000 {
+                *lock.write().unwrap() += 1;
+            }
+        });
+        let mut last = 1;
+        for _ in 0..1000 {
+            let value = lock.read_elided(|value| *value).unwrap();
+            assert!(last <= value && value <= 1001);
+            last = value;
+        }
+    });
+    assert_eq!(lock.read_elided(|value| *value).unwrap(), 1001);
+}
+
+#[cfg(feature = "hle")]
+#[test]
+fn read_elided_panic() {
+    let lock = StdRwLock::new(1);
+    let result = std::panic::catch_unwind(|| lock.read_elided(|_| panic!("read_elided")));
+    assert!(result.is_err());
+    // The panic unwound from under the read lock, which it released.
+    assert!(!lock.is_poisoned());
+    *lock.try_write().unwrap() += 1;
+    assert_eq!(lock.read_elided(|value| *value).unwrap(), 2);
+}
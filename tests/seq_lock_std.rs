@@ -0,0 +1,70 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+mod utils;
+
+use std::thread;
+
+use powerlocks::seq_lock::StdSeqLock;
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(StdSeqLock<i32>, Send, Sync, Unpin);
+    assert_is_trait!(StdSeqLock<*const ()>, !Send, !Sync);
+}
+
+#[test]
+fn read_write() {
+    let lock = StdSeqLock::new(1);
+    assert_eq!(lock.read(), 1);
+
+    let mut guard = lock.write();
+    *guard += 1;
+    assert_eq!(lock.try_read(), None);
+    assert!(lock.try_write().is_none());
+    drop(guard);
+
+    assert_eq!(lock.try_read(), Some(2));
+    lock.set(3);
+    assert_eq!(lock.read(), 3);
+}
+
+#[test]
+fn reads_are_whole() {
+    let lock = StdSeqLock::new([0u64; 8]);
+    thread::scope(|s| {
+        s.spawn(|| {
+            for i in 1..=10_000 {
+                lock.set([i; 8]);
+            }
+        });
+        for _ in 0..4 {
+            s.spawn(|| {
+                let mut last = 0;
+                while last < 10_000 {
+                    let values = lock.read();
+                    assert!(values.iter().all(|&value| value == values[0]));
+                    assert!(values[0] >= last);
+                    last = values[0];
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn ownership() {
+    let mut lock = StdSeqLock::from(1);
+    *lock.get_mut() += 1;
+    assert_eq!(lock.into_inner(), 2);
+}
+
+#[test]
+fn debug() {
+    let lock = StdSeqLock::new(5);
+    assert_eq!(format!("{lock:?}"), "SeqLock { data: 5, .. }");
+    let guard = lock.write();
+    assert_eq!(format!("{lock:?}"), "SeqLock { data: <locked>, .. }");
+    assert_eq!(format!("{guard:?}"), "5");
+}
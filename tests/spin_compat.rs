@@ -0,0 +1,59 @@
+#![cfg(all(feature = "spin", feature = "std"))]
+
+mod utils;
+
+use spin::Mutex;
+
+use powerlocks::test_support::{self, mutex as tests};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(Mutex<i32>, Send, Sync, Unpin);
+    assert_is_trait!(Mutex<*const ()>, !Send, !Sync);
+}
+
+#[test]
+fn lock() {
+    tests::lock::<Mutex<_>, _>(&());
+    tests::lock::<Mutex<_>, _>(&0_u64);
+    tests::lock_writing::<Mutex<_>, _>(&0_u64, 0xac7e4d30_951f268b);
+
+    let array_i32 = [1, 2, 3, 4, 5];
+    let unsized_lock: &mut Mutex<[i32]> = &mut Mutex::new(array_i32);
+    tests::lock_unsized(unsized_lock, &array_i32);
+}
+
+#[test]
+fn with_lock() {
+    tests::with_lock::<Mutex<_>>();
+}
+
+#[test]
+fn race_lock() {
+    tests::race_lock::<Mutex<_>>();
+}
+
+#[test]
+fn poison() {
+    test_support::suppress_panic_message(|| {
+        tests::poison::<Mutex<_>, _>(&(), false);
+        tests::poison::<Mutex<_>, _>(&0_u64, false);
+    });
+}
+
+#[test]
+fn try_lock() {
+    tests::try_lock::<Mutex<_>, _>(&());
+    tests::try_lock::<Mutex<_>, _>(&0_u64);
+}
+
+#[test]
+fn load_test() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 8 };
+    const REPS: usize = if cfg!(miri) { 16 } else { 1024 };
+    const CYCLES: usize = if cfg!(miri) { 4 } else { 16 };
+
+    tests::do_load_test::<Mutex<_>>(THREADS, REPS, CYCLES, None);
+}
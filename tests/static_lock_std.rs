@@ -0,0 +1,86 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+use std::{
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use powerlocks::{
+    mutex::{Mutex, TicketMutex},
+    once_lock::{LazyLock, StdLazyLock},
+    powerlock_static,
+};
+
+powerlock_static! {
+    static COUNTER: Mutex<usize> = 0;
+    /// Built on first access.
+    pub(crate) static LAZY_COUNTER: lazy TicketMutex<usize> = 10;
+}
+
+#[test]
+fn const_static() {
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| *COUNTER.lock().unwrap() += 1);
+        }
+    });
+    assert_eq!(*COUNTER.lock().unwrap(), 4);
+}
+
+#[test]
+fn lazy_static() {
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| *LAZY_COUNTER.lock().unwrap() += 1);
+        }
+    });
+    assert_eq!(*LAZY_COUNTER.lock().unwrap(), 14);
+}
+
+#[cfg(feature = "rwlock")]
+#[test]
+fn rwlock_statics() {
+    use powerlocks::{rwlock::RwLock, strategied_rwlock};
+
+    powerlock_static! {
+        static PLAIN: RwLock<Vec<i32>> = Vec::new();
+        static STRATEGIED: lazy strategied_rwlock::RwLock<Vec<i32>> = vec![1];
+    }
+
+    PLAIN.write().unwrap().push(1);
+    STRATEGIED.write().unwrap().push(2);
+    assert_eq!(*PLAIN.read().unwrap(), [1]);
+    assert_eq!(*STRATEGIED.read().unwrap(), [1, 2]);
+}
+
+#[test]
+fn lazy_lock() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static VALUE: LazyLock<usize> = LazyLock::new(|| CALLS.fetch_add(1, Ordering::Relaxed) + 5);
+
+    assert_eq!(LazyLock::get(&VALUE), None);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| assert_eq!(*VALUE, 5));
+        }
+    });
+    assert_eq!(LazyLock::get(&VALUE), Some(&5));
+    assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    assert_eq!(format!("{VALUE:?}"), "LazyLock(5)");
+}
+
+#[test]
+fn lazy_lock_panicking_init() {
+    let attempts = AtomicUsize::new(0);
+    let lazy = StdLazyLock::new(|| {
+        if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+            panic!("first attempt fails");
+        }
+        1
+    });
+
+    assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| *lazy)).is_err());
+    assert_eq!(format!("{lazy:?}"), "LazyLock(<uninit>)");
+    assert_eq!(*lazy, 1);
+}
@@ -0,0 +1,196 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use powerlocks::{
+    strategied_mutex::{AsyncMutex, strategies},
+    strategied_rwlock::AsyncRwLock,
+};
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn blocking_threads() {
+    let lock = AsyncRwLock::new(0);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..64 {
+                    *lock.write().unwrap() += 1;
+                }
+            });
+        }
+    });
+    assert_eq!(*lock.read().unwrap(), 256);
+}
+
+#[test]
+fn read_async() {
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let lock = AsyncRwLock::new(0);
+    let writer = lock.write().unwrap();
+    let mut read = pin!(lock.read_async());
+    assert!(read.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(lock.queued_readers(), 1);
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 0);
+
+    drop(writer);
+    assert_eq!(wakes.0.load(Ordering::Relaxed), 1);
+    let Poll::Ready(Ok(guard)) = read.poll(&mut cx) else {
+        panic!("the read was not admitted");
+    };
+    assert_eq!(*guard, 0);
+    assert_eq!(lock.holders(), 1);
+}
+
+#[test]
+fn write_async_queues_with_strategy() {
+    let mut cx = Context::from_waker(Waker::noop());
+
+    // The default `fair` strategy keeps readers queued behind a waiting writer.
+    let lock = AsyncRwLock::new(0);
+    let reader = lock.read().unwrap();
+    let mut write = pin!(lock.write_async());
+    let mut read = pin!(lock.read_async());
+    assert!(write.as_mut().poll(&mut cx).is_pending());
+    assert!(read.as_mut().poll(&mut cx).is_pending());
+
+    drop(reader);
+    assert!(read.as_mut().poll(&mut cx).is_pending());
+    let Poll::Ready(Ok(mut writer)) = write.poll(&mut cx) else {
+        panic!("the write was not admitted");
+    };
+    *writer += 1;
+    drop(writer);
+    assert!(matches!(read.poll(&mut cx), Poll::Ready(Ok(guard)) if *guard == 1));
+}
+
+#[test]
+fn dropped_while_queued() {
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let lock = AsyncRwLock::new(0);
+    let reader = lock.read().unwrap();
+    {
+        let mut write = pin!(lock.write_async());
+        assert!(write.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(lock.queued_writers(), 1);
+    }
+    assert_eq!(lock.queued_writers(), 0);
+
+    // Readers aren't held up by the writer that left.
+    assert!(pin!(lock.read_async()).poll(&mut cx).is_ready());
+    drop(reader);
+
+    // A future dropped after being admitted, but before being polled again, releases the lock.
+    let reader = lock.read().unwrap();
+    {
+        let mut write = pin!(lock.write_async());
+        assert!(write.as_mut().poll(&mut cx).is_pending());
+        drop(reader);
+        assert_eq!(lock.active(), (0, true));
+    }
+    assert_eq!(lock.active(), (0, false));
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn tasks_and_threads() {
+    let lock = AsyncRwLock::new(0);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                block_on(async {
+                    for _ in 0..64 {
+                        *lock.write_async().await.unwrap() += 1;
+                        assert!(*lock.read_async().await.unwrap() > 0);
+                    }
+                })
+            });
+            scope.spawn(|| {
+                for _ in 0..64 {
+                    *lock.write().unwrap() += 1;
+                }
+            });
+        }
+    });
+    assert_eq!(*lock.read().unwrap(), 512);
+}
+
+#[test]
+fn lock_async() {
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mutex = AsyncMutex::new_strategied(0, Box::new(strategies::priority));
+    let guard = mutex.lock().unwrap();
+    let mut low = pin!(mutex.lock_with_priority_async(1));
+    let mut high = pin!(mutex.lock_with_priority_async(2));
+    assert!(low.as_mut().poll(&mut cx).is_pending());
+    assert!(high.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(mutex.waiters(), 2);
+
+    drop(guard);
+    assert!(low.as_mut().poll(&mut cx).is_pending());
+    let Poll::Ready(Ok(guard)) = high.poll(&mut cx) else {
+        panic!("the higher priority was not handed the mutex");
+    };
+    drop(guard);
+    assert!(low.poll(&mut cx).is_ready());
+
+    let mutex = AsyncMutex::new(0);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                block_on(async {
+                    for _ in 0..64 {
+                        *mutex.lock_async().await.unwrap() += 1;
+                    }
+                })
+            });
+        }
+    });
+    assert_eq!(*mutex.lock().unwrap(), 256);
+}
@@ -0,0 +1,158 @@
+#![cfg(all(feature = "rwlock", feature = "std"))]
+#![allow(
+    clippy::if_same_then_else,
+    reason = "Keeps `miri` parameters side by side."
+)]
+
+mod utils;
+
+use std::{
+    cell::UnsafeCell,
+    panic::{self, RefUnwindSafe, UnwindSafe},
+    thread,
+    time::Duration,
+};
+
+use powerlocks::{
+    primitives::TryLockError,
+    strategied_mutex::{MutexStrategyInput, StdMutex, StdMutexGuard, strategies},
+};
+
+use powerlocks::test_support::{self, mutex as tests};
+
+/// Spawns a thread per priority, in order, each of which records its priority once it gets
+/// `mutex`, and returns the recorded priorities.
+fn lock_order(mutex: &StdMutex<Vec<u32>>, priorities: &[u32]) -> Vec<u32> {
+    thread::scope(|scope| {
+        let guard = mutex.lock().unwrap();
+        for (waiters, &priority) in priorities.iter().enumerate() {
+            scope.spawn(move || mutex.lock_with_priority(priority).unwrap().push(priority));
+            while mutex.waiters() <= waiters {
+                thread::yield_now();
+            }
+        }
+        drop(guard);
+    });
+    std::mem::take(&mut *mutex.lock().unwrap())
+}
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(StdMutex<()>, Send, Sync, UnwindSafe, RefUnwindSafe, Unpin);
+    assert_is_trait!(StdMutex<UnsafeCell<i32>>, Send, Sync);
+    assert_is_trait!(StdMutex<*const ()>, !Send, !Sync);
+
+    assert_is_trait!(StdMutexGuard<'_, i32>, Send, Sync);
+    assert_is_trait!(StdMutexGuard<'_, UnsafeCell<i32>>, Send);
+    assert_is_trait!(StdMutexGuard<'_, UnsafeCell<i32>>, !Sync);
+    assert_is_trait!(StdMutexGuard<'_, *const ()>, !Send, !Sync);
+}
+
+#[test]
+fn lock() {
+    tests::lock::<StdMutex<_>, _>(&());
+    tests::lock::<StdMutex<_>, _>(&0_u64);
+    tests::lock_writing::<StdMutex<_>, _>(&0_u64, 0xac7e4d30_951f268b);
+
+    let array_i32 = [1, 2, 3, 4, 5];
+    let unsized_lock: &mut StdMutex<[i32]> = &mut StdMutex::new(array_i32);
+    tests::lock_unsized(unsized_lock, &array_i32);
+}
+
+#[test]
+fn with_lock() {
+    tests::with_lock::<StdMutex<_>>();
+}
+
+#[test]
+fn race_lock() {
+    tests::race_lock::<StdMutex<_>>();
+}
+
+#[test]
+fn poison() {
+    tests::poison::<StdMutex<_>, _>(&(), true);
+    tests::poison::<StdMutex<_>, _>(&0_u64, true);
+}
+
+#[test]
+fn try_lock() {
+    tests::try_lock::<StdMutex<_>, _>(&());
+    tests::try_lock::<StdMutex<_>, _>(&0_u64);
+}
+
+#[test]
+fn lock_timeout() {
+    let mutex = StdMutex::new(0);
+    let guard = mutex.lock().unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            assert!(matches!(
+                mutex.lock_timeout(Duration::from_millis(10)),
+                Err(TryLockError::TimedOut)
+            ));
+        });
+    });
+    assert_eq!(mutex.waiters(), 0);
+    drop(guard);
+    *mutex.lock_timeout(Duration::from_secs(60)).unwrap() += 1;
+    assert_eq!(*mutex.lock().unwrap(), 1);
+}
+
+#[test]
+fn fifo() {
+    let mutex = StdMutex::new(Vec::new());
+    assert_eq!(lock_order(&mutex, &[3, 1, 2]), [3, 1, 2]);
+}
+
+#[test]
+fn priority() {
+    let mutex = StdMutex::new_strategied(Vec::new(), Box::new(strategies::priority));
+    assert_eq!(lock_order(&mutex, &[1, 3, 2, 3]), [3, 3, 2, 1]);
+
+    mutex.set_strategy(Box::new(strategies::fifo));
+    assert_eq!(lock_order(&mutex, &[1, 3, 2]), [1, 3, 2]);
+}
+
+#[test]
+fn random() {
+    let priorities = [0, 1, 2, 3, 4, 5];
+    for seed in 0..8 {
+        let mutex = StdMutex::new_strategied(Vec::new(), Box::new(strategies::random(seed)));
+        let mut order = lock_order(&mutex, &priorities);
+        order.sort_unstable();
+        assert_eq!(order, priorities);
+    }
+}
+
+#[test]
+fn broken_strategy() {
+    fn past_the_end(waiters: MutexStrategyInput) -> usize {
+        waiters.count()
+    }
+
+    let mutex = StdMutex::new_strategied((), Box::new(past_the_end));
+    test_support::suppress_panic_message(|| {
+        assert!(panic::catch_unwind(|| drop(mutex.try_lock())).is_err());
+        // The mutex stays unusable from then on.
+        assert!(panic::catch_unwind(|| drop(mutex.lock())).is_err());
+    });
+}
+
+#[test]
+fn load_test() {
+    const THREADS: usize = if cfg!(miri) { 4 } else { 8 };
+    const REPS: usize = if cfg!(miri) { 16 } else { 1024 };
+    const CYCLES: usize = if cfg!(miri) { 4 } else { 16 };
+
+    tests::do_load_test::<StdMutex<_>>(THREADS, REPS, CYCLES, None);
+}
+
+#[test]
+fn guard_mutex() {
+    let mutex = StdMutex::new(0);
+    let guard = mutex.lock().unwrap();
+    assert!(std::ptr::eq(guard.mutex(), &mutex));
+}
@@ -2,18 +2,29 @@
 
 use std::{
     cell::UnsafeCell,
+    mem,
     panic::{RefUnwindSafe, UnwindSafe},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
-use powerlocks::strategied_rwlock::{
-    StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard, strategies,
+use powerlocks::{
+    primitives::{Handle, StdHandle, TestController, TestHandle, TryLockError},
+    strategied_rwlock::{
+        BaseRwLock, Method, State, StdRwLock, StdRwLockReadGuard, StdRwLockWriteGuard,
+        StrategyInput, StrategyResult, TaggedStrategyInput, strategies,
+        verify::{self, Step, Violation, ViolationKind},
+    },
 };
 
-mod rwlock_utils;
-use rwlock_utils::tests;
+use powerlocks::test_support::{race_checker::RaceChecker, rwlock as tests};
 
 mod utils;
-use utils::{assert_is_trait, race_checker::RaceChecker};
+use utils::assert_is_trait;
 
 #[test]
 fn assert_trait() {
@@ -109,6 +120,11 @@ fn run_single_thread_vec() {
     assert_eq!(*locked_vec.read().unwrap(), [1, 2, 3, 4]);
 }
 
+#[test]
+fn with_read_and_write() {
+    tests::with_read_and_write::<StdRwLock<_>>();
+}
+
 #[test]
 fn race_reads() {
     tests::race_reads(&StdRwLock::new_strategied(
@@ -133,6 +149,655 @@ fn race_fair_writes_and_reads() {
     ));
 }
 
+#[test]
+fn display() {
+    assert_eq!(
+        [Method::Read, Method::Write].map(|method| method.to_string()),
+        ["read", "write"]
+    );
+    assert_eq!(
+        [State::Ok, State::Blocked].map(|state| state.to_string()),
+        ["ok", "blocked"]
+    );
+}
+
+#[test]
+fn batched() {
+    let strategy = strategies::batched(2);
+    let [a, b, c, d, e] = [(); 5].map(|()| StdHandle::new().id());
+    let entries = [
+        (a, Method::Read),
+        (b, Method::Read),
+        (c, Method::Read),
+        (d, Method::Write),
+        (e, Method::Read),
+    ];
+    let states = strategy(&mut entries.iter()).collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        [
+            State::Ok,
+            State::Ok,
+            State::Blocked,
+            State::Blocked,
+            State::Blocked
+        ]
+    );
+
+    tests::race_batched_reads(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::batched(2)),
+    ));
+    tests::race_writes(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::batched(2)),
+    ));
+    tests::race_fair_writes_and_reads(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::batched(2)),
+    ));
+}
+
+#[test]
+fn fifo() {
+    let [a, b, c, d] = [(); 4].map(|()| StdHandle::new().id());
+    let schedules = [
+        [
+            (a, Method::Read),
+            (b, Method::Read),
+            (c, Method::Write),
+            (d, Method::Read),
+        ],
+        [
+            (a, Method::Write),
+            (b, Method::Read),
+            (c, Method::Read),
+            (d, Method::Write),
+        ],
+        [
+            (a, Method::Read),
+            (b, Method::Write),
+            (c, Method::Write),
+            (d, Method::Read),
+        ],
+    ];
+    let expected = [
+        [State::Ok, State::Ok, State::Blocked, State::Blocked],
+        [State::Ok, State::Blocked, State::Blocked, State::Blocked],
+        [State::Ok, State::Blocked, State::Blocked, State::Blocked],
+    ];
+    for (entries, expected) in schedules.iter().zip(expected) {
+        let fifo = strategies::fifo(&mut entries.iter()).collect::<Vec<_>>();
+        let fair = strategies::fair(&mut entries.iter()).collect::<Vec<_>>();
+        assert_eq!(fifo, expected);
+        assert_eq!(fifo, fair);
+    }
+
+    let script = [
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Read),
+        Step::Release(0),
+        Step::Acquire(Method::Write),
+        Step::Drain,
+    ];
+    assert_eq!(verify::run(&strategies::fifo, &script, usize::MAX), []);
+
+    tests::race_writes(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::fifo),
+    ));
+    tests::race_fair_writes_and_reads(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::fifo),
+    ));
+}
+
+#[test]
+fn random() {
+    let script = [
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::Acquire(Method::Read),
+        Step::ReleaseAll,
+        Step::Acquire(Method::Read),
+        Step::ReleaseAll,
+        Step::Drain,
+    ];
+    for seed in 0..64 {
+        assert_eq!(
+            verify::run(&strategies::random(seed), &script, usize::MAX),
+            []
+        );
+    }
+
+    // The same seed makes the same decisions.
+    let [a, b, c] = [(); 3].map(|()| StdHandle::new().id());
+    let entries = [(a, Method::Read), (b, Method::Write), (c, Method::Read)];
+    let schedule = |seed| strategies::random(seed)(&mut entries.iter()).collect::<Vec<_>>();
+    for seed in 0..16 {
+        assert_eq!(schedule(seed), schedule(seed));
+    }
+
+    tests::race_writes(&StdRwLock::new_strategied(
+        RaceChecker::new(),
+        Box::new(strategies::random(1)),
+    ));
+    tests::load_test_with(
+        StdRwLock::new_strategied(0, Box::new(strategies::random(2))),
+        4,
+        64,
+        64,
+    );
+}
+
+#[test]
+fn tagged() {
+    const HEAVY: u64 = 1;
+
+    // Admits threads in arrival order, but never two heavy readers at once.
+    fn one_heavy_reader(entries: TaggedStrategyInput) -> StrategyResult {
+        let (mut admitting, mut entries_ahead, mut heavy_ahead) = (true, 0, false);
+        Box::new(entries.map(move |&(_handle_id, method, tag)| {
+            admitting &= match method {
+                Method::Read => !(tag == HEAVY && heavy_ahead),
+                Method::Write => entries_ahead == 0,
+            };
+            let state = State::from(admitting);
+            admitting &= method.is_read();
+            heavy_ahead |= tag == HEAVY;
+            entries_ahead += 1;
+            state
+        }))
+    }
+
+    let lock = StdRwLock::new_tagged_strategied(0, Box::new(one_heavy_reader));
+    thread::scope(|scope| {
+        let heavy = lock.read_tagged(HEAVY).unwrap();
+        let light = lock.read_tagged(0).unwrap();
+        let waiter = scope.spawn(|| *lock.read_tagged(HEAVY).unwrap());
+        while lock.queued_readers() == 0 {
+            thread::yield_now();
+        }
+        drop(light);
+        assert_eq!(lock.queued_readers(), 1);
+        drop(heavy);
+        assert_eq!(waiter.join().unwrap(), 0);
+    });
+    *lock.write_tagged(HEAVY).unwrap() += 1;
+
+    // Untagged strategies ignore tags, and tagged ones see untagged threads as tagged with `0`.
+    lock.set_strategy(Box::new(strategies::fair));
+    let heavy = [(); 2].map(|()| lock.read_tagged(HEAVY).unwrap());
+    assert_eq!(lock.holders(), 2);
+    drop(heavy);
+    lock.set_tagged_strategy(Box::new(one_heavy_reader));
+    let light = [(); 2].map(|()| lock.read().unwrap());
+    assert_eq!(*light[1], 1);
+}
+
+#[test]
+fn debug_queue() {
+    let lock = StdRwLock::new(0);
+    assert_eq!(lock.debug_queue(), []);
+    assert_eq!(lock.longest_wait(), None);
+
+    thread::scope(|scope| {
+        let guard = lock.read_tagged(7).unwrap();
+        // Threads holding the lock aren't waiting.
+        assert_eq!(lock.longest_wait(), None);
+        scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() == 0 {
+            thread::yield_now();
+        }
+        thread::sleep(Duration::from_millis(10));
+
+        let queue = lock.debug_queue();
+        let [reader, writer] = queue.as_slice() else {
+            panic!("unexpected queue {queue:?}");
+        };
+        assert_eq!(
+            (reader.method, reader.state, reader.tag),
+            (Method::Read, State::Ok, 7)
+        );
+        assert_eq!(
+            (writer.method, writer.state, writer.tag),
+            (Method::Write, State::Blocked, 0)
+        );
+        assert_ne!(reader.handle_id, writer.handle_id);
+        assert!(reader.waited.unwrap() >= writer.waited.unwrap());
+        assert!(writer.waited.unwrap() >= Duration::from_millis(10));
+        assert!(lock.longest_wait().unwrap() >= writer.waited.unwrap());
+        drop(guard);
+    });
+    assert_eq!(lock.debug_queue(), []);
+    assert_eq!(lock.longest_wait(), None);
+}
+
+#[test]
+fn set_strategy() {
+    tests::set_strategy::<StdRwLock<_>>();
+}
+
+#[test]
+fn queue_metrics() {
+    tests::queue_metrics::<StdRwLock<_>>();
+}
+
+#[test]
+fn queue_introspection() {
+    let lock = StdRwLock::new(0);
+    assert_eq!(
+        (lock.holders(), lock.queued_readers(), lock.queued_writers()),
+        (0, 0, 0)
+    );
+
+    std::thread::scope(|scope| {
+        let first = lock.read().unwrap();
+        let second = lock.read().unwrap();
+        assert_eq!(lock.holders(), 2);
+
+        scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() < 1 {
+            std::thread::yield_now();
+        }
+        scope.spawn(|| *lock.read().unwrap());
+        while lock.queued_readers() < 1 {
+            std::thread::yield_now();
+        }
+        assert_eq!(
+            (lock.holders(), lock.queued_readers(), lock.queued_writers()),
+            (2, 1, 1)
+        );
+
+        drop(first);
+        drop(second);
+    });
+
+    assert_eq!(
+        (lock.holders(), lock.queued_readers(), lock.queued_writers()),
+        (0, 0, 0)
+    );
+}
+
+#[test]
+fn wakes_reader_batch() {
+    const READERS: usize = 4;
+    let lock = StdRwLock::new(0);
+    let barrier = std::sync::Barrier::new(READERS);
+
+    thread::scope(|scope| {
+        let guard = lock.write().unwrap();
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                let _guard = lock.read().unwrap();
+                // Every reader must be woken up while the others hold the lock.
+                barrier.wait();
+            });
+        }
+        while lock.queued_readers() < READERS {
+            thread::yield_now();
+        }
+        drop(guard);
+    });
+    assert_eq!(lock.holders(), 0);
+}
+
+#[test]
+fn verify() {
+    fn all_ok(entries: StrategyInput) -> StrategyResult {
+        Box::new(entries.map(|_| State::Ok))
+    }
+    fn only_last(entries: StrategyInput) -> StrategyResult {
+        let len = entries.count();
+        Box::new((0..len).map(move |index| match index + 1 == len {
+            true => State::Ok,
+            false => State::Blocked,
+        }))
+    }
+    // Lets readers overtake writers, which starves writers while readers keep arriving.
+    fn readers_first(entries: StrategyInput) -> StrategyResult {
+        let methods = entries.map(|&(_, method)| method).collect::<Vec<_>>();
+        let readers = methods.iter().any(|method| method.is_read());
+        let first_writer = methods.iter().position(|method| method.is_write());
+        Box::new(methods.into_iter().enumerate().map(move |(index, method)| {
+            match method.is_read() || (!readers && Some(index) == first_writer) {
+                true => State::Ok,
+                false => State::Blocked,
+            }
+        }))
+    }
+    fn none(_: StrategyInput) -> StrategyResult {
+        Box::new(std::iter::empty())
+    }
+    let violation = |step, kind| Violation { step, kind };
+
+    let script = [
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::ReleaseAll,
+        Step::Release(2),
+        Step::ReleaseAll,
+        Step::ReleaseAll,
+    ];
+    assert_eq!(verify::run(&strategies::fair, &script, 3), []);
+    assert_eq!(verify::run(&strategies::batched(1), &script, 3), []);
+
+    assert_eq!(
+        verify::run(&all_ok, &script[..4], usize::MAX),
+        [
+            violation(1, ViolationKind::ConcurrentReadAndWrite),
+            violation(2, ViolationKind::ConcurrentReadAndWrite),
+            violation(3, ViolationKind::ConcurrentReadAndWrite),
+            violation(3, ViolationKind::ConcurrentMultipleWrites),
+        ]
+    );
+    assert_eq!(
+        verify::run(&all_ok, &[Step::Acquire(Method::Write); 2], usize::MAX),
+        [violation(1, ViolationKind::ConcurrentMultipleWrites)]
+    );
+    assert_eq!(
+        verify::run(&only_last, &[Step::Acquire(Method::Read); 2], usize::MAX),
+        [violation(1, ViolationKind::BlockedAfterOk)]
+    );
+    assert_eq!(
+        verify::run(&none, &[Step::Acquire(Method::Read)], usize::MAX),
+        [
+            violation(0, ViolationKind::WrongLength),
+            violation(0, ViolationKind::Stalled),
+        ]
+    );
+
+    let starving = [
+        Step::Acquire(Method::Read),
+        Step::Acquire(Method::Write),
+        Step::Acquire(Method::Read),
+        Step::Release(0),
+        Step::Acquire(Method::Read),
+        Step::Release(2),
+        Step::Acquire(Method::Read),
+        Step::Release(4),
+    ];
+    assert_eq!(verify::run(&readers_first, &starving, 7), []);
+    assert_eq!(
+        verify::run(&readers_first, &starving, 6),
+        [violation(7, ViolationKind::Starvation)]
+    );
+
+    // Once the readers stop arriving, the writer gets through after all.
+    let drained = [&starving[..], &[Step::Drain]].concat();
+    assert_eq!(verify::run(&readers_first, &drained, usize::MAX), []);
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn fuzz() {
+    use powerlocks::strategied_rwlock::{Strategy, fuzz};
+    use proptest::test_runner::TestRunner;
+
+    fn all_ok(entries: StrategyInput) -> StrategyResult {
+        Box::new(entries.map(|_| State::Ok))
+    }
+
+    for strategy in [
+        Box::new(strategies::fair) as Box<dyn Strategy>,
+        Box::new(strategies::fifo),
+        Box::new(strategies::batched(2)),
+        Box::new(strategies::random(0)),
+    ] {
+        TestRunner::default()
+            .run(&fuzz::scripts(32), |script| {
+                fuzz::check(&strategy, &script, usize::MAX)
+            })
+            .unwrap();
+    }
+    assert!(
+        TestRunner::default()
+            .run(&fuzz::scripts(32), |script| fuzz::check(
+                &all_ok,
+                &script,
+                usize::MAX
+            ))
+            .is_err()
+    );
+}
+
+#[test]
+fn wakeup_order() {
+    let lock = BaseRwLock::<i32, TestHandle>::new(0);
+    let controller = TestController::new();
+    controller.attach();
+
+    thread::scope(|scope| {
+        let guard = lock.write().unwrap();
+        let writer = scope.spawn(|| {
+            controller.attach();
+            *lock.write().unwrap() += 1;
+        });
+        controller.wait_for_parked(1);
+        let reader = scope.spawn(|| {
+            controller.attach();
+            *lock.read().unwrap()
+        });
+        controller.wait_for_parked(2);
+        let [writer_id, reader_id] = controller.parked()[..] else {
+            unreachable!()
+        };
+
+        // The fair strategy admits the queued writer first, and the reader only once the writer
+        // is done.
+        controller.set_holding(true);
+        drop(guard);
+        assert_eq!(controller.pending(), [writer_id]);
+        assert_eq!(controller.release_next(), Some(writer_id));
+        writer.join().unwrap();
+        assert_eq!(controller.pending(), [reader_id]);
+
+        controller.set_holding(false);
+        assert_eq!(reader.join().unwrap(), 1);
+    });
+}
+
+#[test]
+fn timeout() {
+    const TIMEOUT: Duration = Duration::from_millis(20);
+    let lock = StdRwLock::new(0);
+
+    {
+        let _write = lock.write().unwrap();
+        assert!(matches!(
+            lock.read_timeout(TIMEOUT),
+            Err(TryLockError::TimedOut)
+        ));
+        assert!(matches!(
+            lock.write_timeout(TIMEOUT),
+            Err(TryLockError::TimedOut)
+        ));
+        assert_eq!((lock.queued_readers(), lock.queued_writers()), (0, 0));
+    }
+    *lock.write_timeout(TIMEOUT).unwrap() += 1;
+    assert_eq!(*lock.read_timeout(TIMEOUT).unwrap(), 1);
+
+    // A writer that times out must stop holding back the readers queued behind it.
+    thread::scope(|scope| {
+        let _read = lock.read().unwrap();
+        let writer = scope.spawn(|| lock.write_timeout(TIMEOUT * 5).map(|_| ()));
+        while lock.queued_writers() < 1 {
+            thread::yield_now();
+        }
+        let reader = scope.spawn(|| *lock.read().unwrap());
+        assert!(matches!(
+            writer.join().unwrap(),
+            Err(TryLockError::TimedOut)
+        ));
+        assert_eq!(reader.join().unwrap(), 1);
+    });
+}
+
+#[test]
+fn timeouts_race_releases() {
+    const THREADS: usize = if cfg!(miri) { 2 } else { 8 };
+    const ATTEMPTS: usize = if cfg!(miri) { 4 } else { 256 };
+    let lock = StdRwLock::new(0usize);
+
+    // Waiters that give up as they are being admitted and woken must neither lose the lock nor
+    // leave their entry behind.
+    let acquired: usize = thread::scope(|scope| {
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                scope.spawn(|| {
+                    (0..ATTEMPTS)
+                        .filter(|_| match lock.write_timeout(Duration::from_micros(50)) {
+                            Ok(mut write) => {
+                                *write += 1;
+                                true
+                            }
+                            Err(_) => false,
+                        })
+                        .count()
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .sum()
+    });
+
+    assert_eq!(*lock.read().unwrap(), acquired);
+    assert_eq!(lock.holders(), 0);
+    assert_eq!((lock.queued_readers(), lock.queued_writers()), (0, 0));
+}
+
+#[test]
+fn on_release() {
+    const TIMEOUT: Duration = Duration::from_millis(20);
+    let released = Arc::new(Mutex::new(Vec::new()));
+    let lock = StdRwLock::new(0);
+    let recording = Arc::clone(&released);
+    lock.set_on_release(Some(Box::new(move |_handle_id, method| {
+        recording.lock().unwrap().push(method);
+    })));
+
+    drop((lock.read().unwrap(), lock.read().unwrap()));
+    {
+        let _write = lock.write().unwrap();
+        // Threads that time out never held the lock.
+        assert!(lock.read_timeout(TIMEOUT).is_err());
+    }
+    mem::forget(lock.read().unwrap());
+    unsafe { lock.force_unlock_read() };
+    assert_eq!(
+        *released.lock().unwrap(),
+        [Method::Read, Method::Read, Method::Write, Method::Read]
+    );
+
+    lock.set_on_release(None);
+    drop(lock.write().unwrap());
+    assert_eq!(released.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn builder() {
+    let released = Arc::new(Mutex::new(Vec::new()));
+    let recording = Arc::clone(&released);
+    let lock = StdRwLock::builder()
+        .strategy(Box::new(strategies::batched(1)))
+        .recheck_interval(Duration::from_millis(1))
+        .on_release(Box::new(move |_handle_id, method| {
+            recording.lock().unwrap().push(method);
+        }))
+        .build(0);
+
+    let read = lock.read().unwrap();
+    assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+    drop(read);
+    *lock.write().unwrap() += 1;
+    assert_eq!(*released.lock().unwrap(), [Method::Read, Method::Write]);
+
+    let lock = StdRwLock::builder().build(0);
+    let _reads = (lock.read().unwrap(), lock.read().unwrap());
+    assert_eq!(lock.holders(), 2);
+}
+
+#[test]
+fn max_queued() {
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    let lock = StdRwLock::builder().max_queued(1).build(0);
+
+    thread::scope(|scope| {
+        let write = lock.write().unwrap();
+        let waiter = scope.spawn(|| *lock.write_timeout(TIMEOUT).unwrap() += 1);
+        while lock.queued_writers() == 0 {
+            thread::yield_now();
+        }
+
+        let started = Instant::now();
+        assert!(matches!(
+            lock.read_timeout(TIMEOUT),
+            Err(TryLockError::WouldBlock)
+        ));
+        assert!(matches!(
+            lock.write_timeout(TIMEOUT),
+            Err(TryLockError::WouldBlock)
+        ));
+        assert!(started.elapsed() < TIMEOUT);
+        assert_eq!((lock.queued_readers(), lock.queued_writers()), (0, 1));
+
+        // Blocking acquisitions can't be turned away.
+        let reader = scope.spawn(|| *lock.read().unwrap());
+        while lock.queued_readers() == 0 {
+            thread::yield_now();
+        }
+        drop(write);
+        waiter.join().unwrap();
+        assert_eq!(reader.join().unwrap(), 1);
+    });
+
+    lock.set_max_queued(None);
+    assert_eq!(*lock.read_timeout(TIMEOUT).unwrap(), 1);
+}
+
+#[test]
+fn bounded() {
+    let lock = StdRwLock::builder().max_queued(1).build(0);
+
+    // Uncontended, bounded acquisitions succeed right away.
+    *lock.write_bounded().unwrap() += 1;
+    {
+        let _read = lock.read_bounded().unwrap();
+        assert_eq!(*lock.read_bounded().unwrap(), 1);
+    }
+
+    thread::scope(|scope| {
+        let write = lock.write().unwrap();
+        // Below the limit, they wait like blocking acquisitions.
+        let writer = scope.spawn(|| *lock.write_bounded().unwrap() += 1);
+        while lock.queued_writers() == 0 {
+            thread::yield_now();
+        }
+
+        // Once the queue is full, they are turned away.
+        assert!(matches!(lock.read_bounded(), Err(TryLockError::WouldBlock)));
+        assert!(matches!(
+            lock.write_bounded(),
+            Err(TryLockError::WouldBlock)
+        ));
+        assert_eq!((lock.queued_readers(), lock.queued_writers()), (0, 1));
+
+        drop(write);
+        writer.join().unwrap();
+    });
+
+    assert_eq!(lock.queued_writers(), 0);
+    assert_eq!(*lock.read_bounded().unwrap(), 2);
+}
+
 #[test]
 fn no_poison_on_read() {
     tests::no_poison_on_read(&StdRwLock::new(()));
@@ -197,6 +862,58 @@ fn broken_strategy_try_after_broken() {
     tests::broken_strategy_try_after_broken::<StdRwLock<i32>, _>();
 }
 
+#[test]
+fn leak() {
+    let lock: &'static StdRwLock<i32> = Box::leak(Box::new(StdRwLock::new(0)));
+    let first: &'static i32 = lock.read().unwrap().leak();
+    let second = lock.read().unwrap();
+    assert_eq!((*first, *second), (0, 0));
+    drop(second);
+    assert!(lock.try_write().is_err());
+
+    let lock: &'static StdRwLock<i32> = Box::leak(Box::new(StdRwLock::new(0)));
+    let leaked: &'static mut i32 = lock.write().unwrap().leak();
+    *leaked = 5;
+    assert!(lock.try_read().is_err());
+    assert!(lock.try_write().is_err());
+    assert_eq!(*leaked, 5);
+}
+
+#[test]
+fn force_unlock() {
+    let lock = StdRwLock::new(0);
+    std::mem::forget(lock.read().unwrap());
+    std::mem::forget(lock.read().unwrap());
+    assert!(lock.try_write().is_err());
+
+    // SAFETY: The guards were forgotten and are never used again.
+    unsafe { lock.force_unlock_read() };
+    assert!(lock.try_write().is_err());
+    unsafe { lock.force_unlock_read() };
+
+    std::mem::forget(lock.write().unwrap());
+    assert!(lock.try_read().is_err());
+
+    // SAFETY: The guard was forgotten and is never used again.
+    unsafe { lock.force_unlock_write() };
+    assert_eq!(*lock.try_write().unwrap(), 0);
+    assert!(!lock.is_poisoned());
+}
+
+#[test]
+fn data_ptr() {
+    let lock = StdRwLock::new(0);
+    {
+        let mut guard = lock.write().unwrap();
+        *guard = 1;
+        assert_eq!(lock.data_ptr(), &raw mut *guard);
+    }
+
+    // SAFETY: No guards or other references to the data exist.
+    unsafe { *lock.data_ptr() += 1 };
+    assert_eq!(*lock.read().unwrap(), 2);
+}
+
 #[test]
 fn load_test() {
     const THREADS: usize = if cfg!(miri) { 3 } else { 16 };
@@ -206,3 +923,287 @@ fn load_test() {
     let num = StdRwLock::new_strategied(0usize, Box::new(strategies::fair));
     tests::load_test_with(num, THREADS, WRITES, READS);
 }
+
+#[test]
+fn guard_rwlock() {
+    let lock = StdRwLock::new(0);
+    let guard = lock.read().unwrap();
+    assert!(std::ptr::eq(guard.rwlock(), &lock));
+    drop(guard);
+
+    let guard = lock.write().unwrap();
+    assert!(std::ptr::eq(guard.rwlock(), &lock));
+    assert!(!guard.rwlock().is_poisoned());
+}
+
+#[test]
+fn get_cloned() {
+    let lock = StdRwLock::new(vec![1, 2]);
+    let guard = lock.read().unwrap();
+    assert_eq!(lock.get_cloned().unwrap(), [1, 2]);
+    drop(guard);
+    lock.write().unwrap().push(3);
+    assert_eq!(lock.get_cloned().unwrap(), [1, 2, 3]);
+
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = lock.write().unwrap();
+        panic!("poisoning");
+    });
+    assert_eq!(lock.get_cloned().unwrap_err().into_inner(), [1, 2, 3]);
+}
+
+#[test]
+fn set_replace_take() {
+    let lock = StdRwLock::new(String::from("a"));
+    lock.set(String::from("b")).unwrap();
+    assert_eq!(lock.replace(String::from("c")).unwrap(), "b");
+    assert_eq!(lock.take().unwrap(), "c");
+    assert_eq!(*lock.read().unwrap(), "");
+}
+
+#[test]
+fn read_recursive() {
+    let lock = StdRwLock::new(0);
+    thread::scope(|scope| {
+        let first = lock.read().unwrap();
+        let writer = scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() == 0 {
+            thread::yield_now();
+        }
+
+        assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+        let second = lock.read_recursive().unwrap();
+        assert_eq!(lock.holders(), 2);
+        drop(first);
+        assert_eq!(lock.queued_writers(), 1);
+        assert_eq!(*second, 0);
+        drop(second);
+        writer.join().unwrap();
+    });
+    assert_eq!(*lock.read_recursive().unwrap(), 1);
+}
+
+#[test]
+fn unpark_all() {
+    use powerlocks::primitives::{HandleId, StdThreadEnv, ThreadEnv};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static LARGEST_BATCH: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct BatchHandle(StdHandle);
+
+    impl ThreadEnv for BatchHandle {
+        type Handle = Self;
+
+        fn yield_now() {
+            StdThreadEnv::yield_now();
+        }
+    }
+
+    unsafe impl Handle for BatchHandle {
+        fn new() -> Self {
+            Self(StdHandle::new())
+        }
+
+        fn id(&self) -> HandleId {
+            self.0.id()
+        }
+
+        fn park(&self) {
+            self.0.park();
+        }
+
+        fn unpark(&self) {
+            self.0.unpark();
+        }
+
+        fn unpark_all<'h>(handles: impl IntoIterator<Item = &'h Self>) {
+            let batch = handles.into_iter().map(Self::unpark).count();
+            LARGEST_BATCH.fetch_max(batch, Ordering::Relaxed);
+        }
+    }
+
+    const READERS: usize = 3;
+    let lock = BaseRwLock::<_, BatchHandle>::new(0);
+    thread::scope(|scope| {
+        let guard = lock.write().unwrap();
+        for _ in 0..READERS {
+            scope.spawn(|| drop(lock.read().unwrap()));
+        }
+        while lock.queued_readers() < READERS {
+            thread::yield_now();
+        }
+        // The readers are admitted together once the writer leaves, and woken in one batch.
+        drop(guard);
+    });
+    assert_eq!(LARGEST_BATCH.load(Ordering::Relaxed), READERS);
+}
+
+#[test]
+fn is_parked() {
+    let lock = BaseRwLock::<i32, TestHandle>::new(0);
+    let controller = TestController::new();
+    controller.attach();
+
+    thread::scope(|scope| {
+        let guard = lock.read().unwrap();
+        controller.set_holding(true);
+        scope.spawn(|| {
+            controller.attach();
+            *lock.write().unwrap() += 1;
+        });
+        controller.wait_for_parked(1);
+
+        let queue = lock.debug_queue();
+        let [reader, writer] = queue.as_slice() else {
+            panic!("unexpected queue {queue:?}");
+        };
+        assert!(!reader.parked);
+        assert!(writer.parked);
+        // Queueing the writer didn't unpark the reader, which holds the lock and never parked.
+        assert_eq!(controller.pending(), []);
+
+        drop(guard);
+        assert_eq!(controller.pending(), [writer.handle_id]);
+        controller.set_holding(false);
+    });
+    assert_eq!(*lock.read().unwrap(), 1);
+}
+
+#[test]
+fn throttle_writes() {
+    const INTERVAL: Duration = Duration::from_millis(20);
+    let lock = StdRwLock::new_strategied(
+        0,
+        Box::new(strategies::throttle_writes::<StdHandle, _>(
+            strategies::fair,
+            INTERVAL,
+        )),
+    );
+    lock.set_recheck_interval(Some(Duration::from_millis(1)));
+
+    *lock.write().unwrap() += 1;
+    let throttled_at = Instant::now();
+    assert!(matches!(lock.try_write(), Err(TryLockError::WouldBlock)));
+    // Readers aren't throttled.
+    assert_eq!(*lock.read().unwrap(), 1);
+
+    *lock.write().unwrap() += 1;
+    assert!(throttled_at.elapsed() >= INTERVAL / 2);
+    assert_eq!(*lock.read().unwrap(), 2);
+}
+
+#[test]
+fn recheck_interval_wakes_waiters() {
+    // Admits writers only while `open` is set, which the lock isn't told about.
+    static OPEN: AtomicBool = AtomicBool::new(false);
+    fn gated(entries: StrategyInput) -> StrategyResult {
+        let states = strategies::fair(entries)
+            .map(|state| match OPEN.load(Ordering::Relaxed) {
+                true => state,
+                false => State::Blocked,
+            })
+            .collect::<Vec<_>>();
+        Box::new(states.into_iter())
+    }
+
+    let lock = StdRwLock::new_strategied(0, Box::new(gated));
+    thread::scope(|scope| {
+        let writer = scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() < 1 {
+            thread::yield_now();
+        }
+        OPEN.store(true, Ordering::Relaxed);
+        lock.set_recheck_interval(Some(Duration::from_millis(1)));
+        writer.join().unwrap();
+    });
+    assert_eq!(*lock.read().unwrap(), 1);
+}
+
+#[test]
+fn time_sliced() {
+    let lock = StdRwLock::new_strategied(
+        0,
+        Box::new(strategies::time_sliced::<StdHandle>(
+            Duration::from_secs(60),
+            Duration::from_millis(1),
+        )),
+    );
+    lock.set_recheck_interval(Some(Duration::from_millis(1)));
+
+    thread::scope(|scope| {
+        let read = lock.read().unwrap();
+        let writer = scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() < 1 {
+            thread::yield_now();
+        }
+
+        // Readers keep being admitted past the queued writer during the read window.
+        let another_read = lock.try_read().unwrap();
+        drop((read, another_read));
+
+        // With no reader left, the writer doesn't wait for the write window.
+        writer.join().unwrap();
+    });
+    assert_eq!(*lock.read().unwrap(), 1);
+
+    let lock = StdRwLock::new_strategied(
+        0,
+        Box::new(strategies::time_sliced::<StdHandle>(
+            Duration::from_millis(1),
+            Duration::from_secs(60),
+        )),
+    );
+    drop(lock.try_read());
+    thread::sleep(Duration::from_millis(2));
+
+    thread::scope(|scope| {
+        // With no writer queued, readers are admitted during the write window.
+        let read = lock.read().unwrap();
+        let writer = scope.spawn(|| *lock.write().unwrap() += 1);
+        while lock.queued_writers() < 1 {
+            thread::yield_now();
+        }
+
+        assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+        drop(read);
+        writer.join().unwrap();
+    });
+    assert_eq!(*lock.read().unwrap(), 1);
+}
+
+#[cfg(feature = "strategy-observer")]
+#[test]
+fn strategy_observer() {
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let recording = Arc::clone(&observed);
+    let lock = StdRwLock::builder()
+        .strategy_observer(Box::new(move |queue, decisions| {
+            let queue = queue.iter().map(|&(_, method, tag)| (method, tag));
+            recording
+                .lock()
+                .unwrap()
+                .push((queue.collect::<Vec<_>>(), decisions.to_vec()));
+        }))
+        .build(0);
+
+    let write = lock.write_tagged(7).unwrap();
+    assert!(lock.try_read().is_err());
+    drop(write);
+    assert_eq!(
+        *observed.lock().unwrap(),
+        [
+            (vec![(Method::Write, 7)], vec![State::Ok]),
+            (
+                vec![(Method::Write, 7), (Method::Read, 0)],
+                vec![State::Ok, State::Blocked]
+            ),
+            (vec![], vec![]),
+        ]
+    );
+
+    lock.set_strategy_observer(None);
+    drop(lock.read().unwrap());
+    assert_eq!(observed.lock().unwrap().len(), 3);
+}
@@ -0,0 +1,83 @@
+#![cfg(all(feature = "mutex", feature = "alloc", feature = "std"))]
+
+mod utils;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use powerlocks::swap_lock::StdSwapLock;
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(StdSwapLock<i32>, Send, Sync);
+    assert_is_trait!(StdSwapLock<std::cell::Cell<i32>>, !Send, !Sync);
+}
+
+#[test]
+fn load_store() {
+    let lock = StdSwapLock::new(1);
+    let old = lock.load();
+    lock.store(Arc::new(2));
+    assert_eq!(*old, 1);
+    assert_eq!(*lock.load(), 2);
+
+    assert_eq!(*lock.swap(Arc::new(3)), 2);
+    assert_eq!(*lock.rcu(|value| value + 1), 3);
+    assert_eq!(*lock.into_inner(), 4);
+}
+
+#[test]
+fn rcu_loses_no_update() {
+    let lock = StdSwapLock::new(0);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    lock.rcu(|value| value + 1);
+                }
+            });
+        }
+        s.spawn(|| {
+            let mut last = 0;
+            while last < 4000 {
+                let value = *lock.load();
+                assert!(value >= last);
+                last = value;
+            }
+        });
+    });
+}
+
+#[test]
+fn drops_values() {
+    struct Counted<'a>(&'a AtomicUsize);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = AtomicUsize::new(0);
+    let lock = StdSwapLock::new(Counted(&drops));
+    let old = lock.load();
+    lock.store(Arc::new(Counted(&drops)));
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(old);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    drop(lock);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn debug() {
+    let lock = StdSwapLock::new(5);
+    assert_eq!(format!("{lock:?}"), "SwapLock { data: 5, .. }");
+}
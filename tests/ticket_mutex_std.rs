@@ -0,0 +1,134 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+#![allow(
+    clippy::if_same_then_else,
+    reason = "Keeps `miri` parameters side by side."
+)]
+
+mod utils;
+
+use std::{
+    cell::UnsafeCell,
+    panic::{RefUnwindSafe, UnwindSafe},
+    thread,
+    time::Duration,
+};
+
+use powerlocks::mutex::{StdMutex, StdTicketMutex, StdTicketMutexGuard};
+
+use powerlocks::test_support::{self, mutex as tests};
+
+#[test]
+fn assert_trait() {
+    use utils::assert_is_trait;
+
+    assert_is_trait!(
+        StdTicketMutex<()>,
+        Send,
+        Sync,
+        UnwindSafe,
+        RefUnwindSafe,
+        Unpin
+    );
+    assert_is_trait!(
+        StdTicketMutex<i32>,
+        Send,
+        Sync,
+        UnwindSafe,
+        RefUnwindSafe,
+        Unpin
+    );
+    assert_is_trait!(StdTicketMutex<UnsafeCell<i32>>, Send, Sync);
+    assert_is_trait!(StdTicketMutex<*const ()>, !Send, !Sync);
+
+    assert_is_trait!(StdTicketMutexGuard<'_, i32>, Send, Sync);
+    assert_is_trait!(StdTicketMutexGuard<'_, UnsafeCell<i32>>, Send);
+    assert_is_trait!(StdTicketMutexGuard<'_, UnsafeCell<i32>>, !Sync);
+    assert_is_trait!(StdTicketMutexGuard<'_, *const ()>, !Send, !Sync);
+}
+
+#[test]
+fn lock() {
+    tests::lock::<StdTicketMutex<_>, _>(&());
+    tests::lock::<StdTicketMutex<_>, _>(&0_u64);
+    tests::lock_writing::<StdTicketMutex<_>, _>(&0_u64, 0xac7e4d30_951f268b);
+
+    let array_i32 = [1, 2, 3, 4, 5];
+    let unsized_lock: &mut StdTicketMutex<[i32]> = &mut StdTicketMutex::new(array_i32);
+    tests::lock_unsized(unsized_lock, &array_i32);
+}
+
+#[test]
+fn with_lock() {
+    tests::with_lock::<StdTicketMutex<_>>();
+}
+
+#[test]
+fn race_lock() {
+    tests::race_lock::<StdTicketMutex<_>>();
+}
+
+#[test]
+fn poison() {
+    let poisons = !cfg!(feature = "no-poison");
+    tests::poison::<StdTicketMutex<_>, _>(&(), poisons);
+    tests::poison::<StdTicketMutex<_>, _>(&0_u64, poisons);
+}
+
+#[test]
+fn try_lock() {
+    tests::try_lock::<StdTicketMutex<_>, _>(&());
+    tests::try_lock::<StdTicketMutex<_>, _>(&0_u64);
+}
+
+#[test]
+fn arrival_order() {
+    const THREADS: usize = 4;
+    let lock = StdTicketMutex::new(());
+    let order = StdMutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        let guard = lock.lock().unwrap();
+        for i in 0..THREADS {
+            let (lock, order) = (&lock, &order);
+            scope.spawn(move || {
+                let _guard = lock.lock().unwrap();
+                order.lock().unwrap().push(i);
+            });
+            // Give each thread time to draw its ticket before spawning the next one.
+            thread::sleep(Duration::from_millis(20));
+        }
+        drop(guard);
+    });
+
+    assert_eq!(
+        order.into_inner().unwrap(),
+        (0..THREADS).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn load_test() {
+    const THREADS: usize = if cfg!(miri) { 8 } else { 8 };
+    const REPS: usize = if cfg!(miri) { 32 } else { 4096 };
+    const CYCLES: usize = if cfg!(miri) { 8 } else { 64 };
+
+    tests::do_load_test::<StdTicketMutex<_>>(THREADS, REPS, CYCLES, None);
+}
+
+#[test]
+fn poisoning_load_test() {
+    const THREADS: usize = if cfg!(miri) { 8 } else { 8 };
+    const REPS: usize = if cfg!(miri) { 16 } else { 4096 };
+    const CYCLES: usize = if cfg!(miri) { 8 } else { 64 };
+    const POISONING_REPS: usize = if cfg!(miri) { 4 } else { 64 };
+    test_support::suppress_panic_message(|| {
+        tests::do_load_test::<StdTicketMutex<_>>(THREADS, REPS, CYCLES, Some(POISONING_REPS))
+    });
+}
+
+#[test]
+fn guard_mutex() {
+    let mutex = StdTicketMutex::new(0);
+    let guard = mutex.lock().unwrap();
+    assert!(std::ptr::eq(guard.mutex(), &mutex));
+}
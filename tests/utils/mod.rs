@@ -1,5 +1,3 @@
-pub mod race_checker;
-
 macro_rules! assert_is_trait {
     ($obj:ty, $trait:path) => {{
         fn is_trait<T: $trait>() {}
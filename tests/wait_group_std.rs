@@ -0,0 +1,58 @@
+#![cfg(all(feature = "mutex", feature = "std"))]
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use powerlocks::wait_group::StdWaitGroup;
+
+#[test]
+fn empty() {
+    let group = StdWaitGroup::new();
+    assert_eq!(group.pending(), 0);
+    group.wait();
+}
+
+#[test]
+fn wait() {
+    const TASKS: usize = 8;
+    let group = StdWaitGroup::new();
+    let finished = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..TASKS {
+            group.add(1);
+            scope.spawn(|| {
+                finished.fetch_add(1, Ordering::Relaxed);
+                group.done();
+            });
+        }
+
+        group.wait();
+        assert_eq!(finished.load(Ordering::Relaxed), TASKS);
+        assert_eq!(group.pending(), 0);
+    });
+}
+
+#[test]
+fn add_while_waiting() {
+    let group = StdWaitGroup::new();
+    group.add(2);
+
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| group.wait());
+        group.done();
+        // Tasks may spawn more tasks before the group finishes.
+        group.add(1);
+        group.done();
+        group.done();
+        waiter.join().unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "without a pending task")]
+fn done_without_task() {
+    StdWaitGroup::new().done();
+}